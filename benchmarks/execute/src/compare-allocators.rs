@@ -0,0 +1,52 @@
+//! Compares the guest heap allocators on an allocation-heavy program.
+//!
+//! The `alloc_churn` guest program is built once with the default bump allocator and once with the
+//! free-list allocator (`heap-freelist-alloc`), then executed with metered cost execution. Run:
+//! ```bash
+//! cargo r -r --bin compare-allocators -- --iterations 10000
+//! ```
+
+use clap::Parser;
+use eyre::Result;
+use openvm_benchmarks_utils::{build_elf_with_features, get_programs_dir};
+use openvm_sdk::{Sdk, StdIn};
+
+#[derive(Parser)]
+#[command(author, version, about = "Compare guest heap allocators")]
+struct Cli {
+    /// Number of messages processed by the guest program
+    #[arg(long, default_value_t = 10_000)]
+    iterations: u32,
+
+    /// Cargo profile used to build the guest program
+    #[arg(long, default_value = "release")]
+    profile: String,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let manifest_dir = get_programs_dir().join("alloc_churn");
+    let sdk = Sdk::riscv32();
+
+    println!(
+        "{:<10} {:>14} {:>14} {:>14} {:>16}",
+        "allocator", "instret", "cost", "touched pages", "heap bytes"
+    );
+    for (allocator, features) in [("bump", vec![]), ("freelist", vec!["freelist"])] {
+        let elf = build_elf_with_features(&manifest_dir, &cli.profile, features)?;
+        let mut stdin = StdIn::default();
+        stdin.write(&cli.iterations);
+        let (_, (cost, instret), memory_usage) = sdk.execute_metered_cost(elf, stdin)?;
+        let touched_pages: usize = memory_usage
+            .profile
+            .address_spaces
+            .iter()
+            .map(|usage| usage.touched_pages)
+            .sum();
+        println!(
+            "{allocator:<10} {instret:>14} {cost:>14} {touched_pages:>14} {:>16}",
+            memory_usage.heap_bytes()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use std::collections::BTreeMap;
+
+use openvm::io::{read, reveal_u32};
+
+/// Allocates and frees buffers of varying sizes, the way a program that processes a stream of
+/// messages would. Only a few buffers are live at any time.
+pub fn main() {
+    let n: u32 = read();
+    let mut checksum: u32 = 0;
+    let mut seed: u32 = 1;
+    let mut window: BTreeMap<u32, Vec<u8>> = BTreeMap::new();
+    for i in 0..n {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let len = 16 + (seed >> 16) as usize % 4096;
+        let mut buf = vec![0u8; len];
+        buf[len - 1] = i as u8;
+        let message = format!("message {i} of length {len}");
+        checksum = checksum.wrapping_add(message.len() as u32);
+        window.insert(i, buf);
+        if window.len() > 8 {
+            let (_, oldest) = window.pop_first().unwrap();
+            checksum = checksum.wrapping_add(oldest[oldest.len() - 1] as u32);
+        }
+    }
+    reveal_u32(checksum, 0);
+}
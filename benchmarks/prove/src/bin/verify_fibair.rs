@@ -67,6 +67,7 @@ fn main() -> Result<()> {
             app_vm_config,
             leaf_fri_params: app_fri_params.into(),
             compiler_options,
+            memory_layout: None,
         };
         let (program, input_stream) = build_verification_program(vdata, compiler_options);
         let app_pk = AppProvingKey::keygen(app_config)?;
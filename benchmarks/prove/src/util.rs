@@ -19,7 +19,7 @@ use openvm_sdk::{
         DEFAULT_LEAF_LOG_BLOWUP, DEFAULT_ROOT_LOG_BLOWUP,
     },
     keygen::_leaf_keygen,
-    prover::{verify_app_proof, vm::new_local_prover, LeafProvingController},
+    prover::{verify_app_proof, vm::new_local_prover, LeafProvingController, ProgressReporter},
     types::ExecutableFormat,
     GenericSdk, StdIn,
 };
@@ -109,6 +109,7 @@ impl BenchmarkCli {
                 enable_cycle_tracker: self.profiling,
                 ..Default::default()
             },
+            memory_layout: None,
         }
     }
 
@@ -240,7 +241,12 @@ where
         let leaf_controller = LeafProvingController {
             num_children: AggregationTreeConfig::default().num_children_leaf,
         };
-        let leaf_proofs = leaf_controller.generate_proof(&mut leaf_prover, &app_proof)?;
+        let leaf_proofs = leaf_controller.generate_proof(
+            &mut leaf_prover,
+            &app_proof,
+            None,
+            &ProgressReporter::default(),
+        )?;
         for proof in leaf_proofs {
             verify_single(&leaf_prover.vm.engine, &vk, &proof)?;
         }
@@ -22,19 +22,37 @@ pub fn build_elf(manifest_dir: &PathBuf, profile: impl ToString) -> Result<Elf>
     build_elf_with_path(&pkg, profile, None)
 }
 
+pub fn build_elf_with_features<S: AsRef<str>>(
+    manifest_dir: &PathBuf,
+    profile: impl ToString,
+    features: impl IntoIterator<Item = S>,
+) -> Result<Elf> {
+    let pkg = get_package(manifest_dir);
+    build_elf_with_path_and_features(&pkg, profile, features, None)
+}
+
 pub fn build_elf_with_path(
     pkg: &Package,
     profile: impl ToString,
     elf_path: Option<&PathBuf>,
+) -> Result<Elf> {
+    build_elf_with_path_and_features(pkg, profile, Vec::<String>::new(), elf_path)
+}
+
+pub fn build_elf_with_path_and_features<S: AsRef<str>>(
+    pkg: &Package,
+    profile: impl ToString,
+    features: impl IntoIterator<Item = S>,
+    elf_path: Option<&PathBuf>,
 ) -> Result<Elf> {
     // Use a temporary directory for the build
     let temp_dir = tempdir()?;
     let target_dir = temp_dir.path();
 
-    // Build guest with default features
     let guest_opts = GuestOptions::default()
         .with_target_dir(target_dir)
-        .with_profile(profile.to_string());
+        .with_profile(profile.to_string())
+        .with_features(features);
 
     if let Err(Some(code)) = build_guest_package(pkg, &guest_opts, None, &None) {
         std::process::exit(code);
@@ -1,6 +1,7 @@
 mod builder;
 mod core_chip;
 mod field_variable;
+mod optimize;
 mod symbolic_expr;
 
 #[cfg(test)]
@@ -9,6 +10,7 @@ mod tests;
 pub use builder::*;
 pub use core_chip::*;
 pub use field_variable::*;
+pub use optimize::*;
 pub use symbolic_expr::*;
 pub mod utils;
 
@@ -0,0 +1,403 @@
+use std::{
+    cmp::{max, Ordering},
+    collections::HashMap,
+};
+
+use num_bigint::BigUint;
+
+use crate::{ExprBuilder, SymbolicExpr};
+
+/// Trace width of the columns owned by an [ExprBuilder], before and after
+/// [ExprBuilder::minimize_columns].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExprWidthReport {
+    pub num_variables_before: usize,
+    pub num_variables_after: usize,
+    pub width_before: usize,
+    pub width_after: usize,
+}
+
+/// The constraint and compute of one variable.
+#[derive(Debug)]
+struct VarDef {
+    constraint: SymbolicExpr,
+    compute: SymbolicExpr,
+}
+
+#[derive(Debug)]
+struct Candidate {
+    vars: Vec<VarDef>,
+    outputs: Vec<usize>,
+}
+
+/// Limb parameters needed to size the constraints.
+struct Limits {
+    prime: BigUint,
+    limb_bits: usize,
+    num_limbs: usize,
+    proper_max: BigUint,
+    max_carry_bits: usize,
+}
+
+impl ExprBuilder {
+    /// Number of trace columns of the expression, see `BaseAir::width` of
+    /// [FieldExpr](crate::FieldExpr).
+    pub fn width(&self) -> usize {
+        self.num_limbs * (self.num_input + self.num_variables)
+            + self.q_limbs.iter().sum::<usize>()
+            + self.carry_limbs.iter().sum::<usize>()
+            + self.num_flags
+            + 1
+    }
+
+    /// Rewrites the variables of the expression to reduce the trace width, without changing the
+    /// outputs:
+    /// - variables saved by [FieldVariable::save](crate::FieldVariable::save) are inlined into the
+    ///   constraints and computes using them when the constraints still fit the carry bound,
+    /// - subexpressions which appear several times are saved into a new variable when it makes the
+    ///   trace narrower, usually because the variables using them can then be inlined.
+    ///
+    /// A constraint fits if `SymbolicExpr::constraint_carry_bits_with_pq` is at most
+    /// `max_carry_bits`. Variables defined by division, output variables and variables used in a
+    /// `Select` branch of a different size are kept. This must be called before the builder is
+    /// finalized, i.e. before [FieldExpr::new](crate::FieldExpr::new), and after the last use of
+    /// the builder's `FieldVariable`s since their variable indices are not updated.
+    pub fn minimize_columns(&mut self) -> ExprWidthReport {
+        assert!(!self.is_finalized(), "cannot optimize a finalized builder");
+        let limits = Limits {
+            prime: self.prime.clone(),
+            limb_bits: self.limb_bits,
+            num_limbs: self.num_limbs,
+            proper_max: self.proper_max().clone(),
+            max_carry_bits: self.max_carry_bits,
+        };
+        let num_variables_before = self.num_variables;
+        let width_before = self.width();
+
+        let candidate = Candidate {
+            vars: self
+                .constraints
+                .iter()
+                .zip(&self.computes)
+                .map(|(constraint, compute)| VarDef {
+                    constraint: constraint.clone(),
+                    compute: compute.clone(),
+                })
+                .collect(),
+            outputs: self.output_indices.clone(),
+        };
+        if let Some(width) = limits.width(&candidate.vars) {
+            let best = limits.minimize(candidate, width);
+            self.num_variables = best.vars.len();
+            self.constraints = vec![];
+            self.computes = vec![];
+            self.q_limbs = vec![];
+            self.carry_limbs = vec![];
+            for var in best.vars {
+                let (q_limbs, carry_limbs) = limits.constraint_limbs(&var.constraint);
+                self.constraints.push(var.constraint);
+                self.computes.push(var.compute);
+                self.q_limbs.push(q_limbs);
+                self.carry_limbs.push(carry_limbs);
+            }
+            self.output_indices = best.outputs;
+        }
+
+        let report = ExprWidthReport {
+            num_variables_before,
+            num_variables_after: self.num_variables,
+            width_before,
+            width_after: self.width(),
+        };
+        tracing::debug!(
+            "expression width {} -> {} ({} -> {} variables)",
+            report.width_before,
+            report.width_after,
+            report.num_variables_before,
+            report.num_variables_after
+        );
+        report
+    }
+}
+
+impl Limits {
+    fn constraint_limbs(&self, constraint: &SymbolicExpr) -> (usize, usize) {
+        constraint.constraint_limbs(
+            &self.prime,
+            self.limb_bits,
+            self.num_limbs,
+            &self.proper_max,
+        )
+    }
+
+    /// Returns the number of columns of a variable with this constraint, or `None` if the
+    /// constraint doesn't fit the carry bound.
+    fn var_width(&self, constraint: &SymbolicExpr) -> Option<usize> {
+        if !selects_match(constraint, self.num_limbs) {
+            return None;
+        }
+        let carry_bits = constraint.constraint_carry_bits_with_pq(
+            &self.prime,
+            self.limb_bits,
+            self.num_limbs,
+            &self.proper_max,
+        );
+        if carry_bits > self.max_carry_bits {
+            return None;
+        }
+        let (q_limbs, carry_limbs) = self.constraint_limbs(constraint);
+        Some(self.num_limbs + q_limbs + carry_limbs)
+    }
+
+    /// Width of the variable columns, without inputs and flags.
+    fn width(&self, vars: &[VarDef]) -> Option<usize> {
+        vars.iter().map(|var| self.var_width(&var.constraint)).sum()
+    }
+
+    /// Greedily applies the rewrite reducing the width the most, until none does.
+    fn minimize(&self, mut best: Candidate, mut best_width: usize) -> Candidate {
+        loop {
+            let mut improved = None;
+            let inlined = (0..best.vars.len()).filter_map(|j| inline(&best, j));
+            let extracted = repeated_subexprs(&best.vars)
+                .into_iter()
+                .filter_map(|expr| extract(&best, &expr))
+                .map(|candidate| self.inline_all(candidate));
+            for candidate in inlined.chain(extracted) {
+                if let Some(width) = self.width(&candidate.vars) {
+                    let current = improved.as_ref().map_or(best_width, |(w, _)| *w);
+                    if width < current {
+                        improved = Some((width, candidate));
+                    }
+                }
+            }
+            match improved {
+                Some((width, candidate)) => {
+                    best = candidate;
+                    best_width = width;
+                }
+                None => return best,
+            }
+        }
+    }
+
+    /// Inlines variables as long as it reduces the width.
+    fn inline_all(&self, mut candidate: Candidate) -> Candidate {
+        let Some(mut width) = self.width(&candidate.vars) else {
+            return candidate;
+        };
+        let mut j = 0;
+        while j < candidate.vars.len() {
+            match inline(&candidate, j).and_then(|c| Some((self.width(&c.vars)?, c))) {
+                Some((new_width, new_candidate)) if new_width < width => {
+                    candidate = new_candidate;
+                    width = new_width;
+                }
+                _ => j += 1,
+            }
+        }
+        candidate
+    }
+}
+
+/// Replaces variable `j` by its compute everywhere, if it was saved from an expression without
+/// division and is not an output.
+fn inline(candidate: &Candidate, j: usize) -> Option<Candidate> {
+    if candidate.outputs.contains(&j) {
+        return None;
+    }
+    let VarDef {
+        constraint,
+        compute,
+    } = &candidate.vars[j];
+    let saved = SymbolicExpr::Sub(Box::new(compute.clone()), Box::new(SymbolicExpr::Var(j)));
+    if *constraint != saved || contains_div(compute) || max_var(compute).is_some_and(|v| v >= j) {
+        return None;
+    }
+    let substitute = |i: usize| match i.cmp(&j) {
+        Ordering::Less => SymbolicExpr::Var(i),
+        Ordering::Equal => compute.clone(),
+        Ordering::Greater => SymbolicExpr::Var(i - 1),
+    };
+    let vars = candidate
+        .vars
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != j)
+        .map(|(_, var)| VarDef {
+            constraint: map_vars(&var.constraint, &substitute),
+            compute: map_vars(&var.compute, &substitute),
+        })
+        .collect();
+    let outputs = candidate
+        .outputs
+        .iter()
+        .map(|&i| if i > j { i - 1 } else { i })
+        .collect();
+    Some(Candidate { vars, outputs })
+}
+
+/// Saves `expr` into a new variable, placed after the variables it uses, and replaces its
+/// occurrences by the new variable.
+fn extract(candidate: &Candidate, expr: &SymbolicExpr) -> Option<Candidate> {
+    let pos = max_var(expr).map_or(0, |v| v + 1);
+    // Computes are evaluated in order, so the new variable must come before any compute using it.
+    if candidate.vars[..pos]
+        .iter()
+        .any(|var| contains(&var.compute, expr))
+    {
+        return None;
+    }
+    let shift = |i: usize| SymbolicExpr::Var(if i >= pos { i + 1 } else { i });
+    let new_var = SymbolicExpr::Var(pos);
+    let mut vars: Vec<VarDef> = candidate
+        .vars
+        .iter()
+        .map(|var| VarDef {
+            constraint: replace(&map_vars(&var.constraint, &shift), expr, &new_var),
+            compute: replace(&map_vars(&var.compute, &shift), expr, &new_var),
+        })
+        .collect();
+    vars.insert(
+        pos,
+        VarDef {
+            constraint: SymbolicExpr::Sub(Box::new(expr.clone()), Box::new(new_var)),
+            compute: expr.clone(),
+        },
+    );
+    let outputs = candidate
+        .outputs
+        .iter()
+        .map(|&i| if i >= pos { i + 1 } else { i })
+        .collect();
+    Some(Candidate { vars, outputs })
+}
+
+/// Subexpressions without division which appear at least twice in the constraints. Computes are
+/// not counted since a saved variable repeats its constraint in its compute.
+fn repeated_subexprs(vars: &[VarDef]) -> Vec<SymbolicExpr> {
+    let mut counts = HashMap::<String, (usize, SymbolicExpr)>::new();
+    let mut order = vec![];
+    for var in vars {
+        visit(&var.constraint, &mut |sub| {
+            if is_leaf(sub) || contains_div(sub) {
+                return;
+            }
+            let key = format!("{sub:?}");
+            counts
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    order.push(key);
+                    (0, sub.clone())
+                })
+                .0 += 1;
+        });
+    }
+    order
+        .into_iter()
+        .filter_map(|key| {
+            let (count, expr) = counts.remove(&key)?;
+            (count >= 2).then_some(expr)
+        })
+        .collect()
+}
+
+fn is_leaf(expr: &SymbolicExpr) -> bool {
+    matches!(
+        expr,
+        SymbolicExpr::Input(_) | SymbolicExpr::Var(_) | SymbolicExpr::Const(..)
+    )
+}
+
+fn children(expr: &SymbolicExpr) -> Vec<&SymbolicExpr> {
+    match expr {
+        SymbolicExpr::Input(_) | SymbolicExpr::Var(_) | SymbolicExpr::Const(..) => vec![],
+        SymbolicExpr::Add(lhs, rhs)
+        | SymbolicExpr::Sub(lhs, rhs)
+        | SymbolicExpr::Mul(lhs, rhs)
+        | SymbolicExpr::Div(lhs, rhs)
+        | SymbolicExpr::Select(_, lhs, rhs) => vec![lhs.as_ref(), rhs.as_ref()],
+        SymbolicExpr::IntAdd(lhs, _) | SymbolicExpr::IntMul(lhs, _) => vec![lhs.as_ref()],
+    }
+}
+
+fn visit(expr: &SymbolicExpr, f: &mut impl FnMut(&SymbolicExpr)) {
+    f(expr);
+    for child in children(expr) {
+        visit(child, f);
+    }
+}
+
+fn contains(expr: &SymbolicExpr, target: &SymbolicExpr) -> bool {
+    expr == target || children(expr).into_iter().any(|c| contains(c, target))
+}
+
+fn contains_div(expr: &SymbolicExpr) -> bool {
+    matches!(expr, SymbolicExpr::Div(..)) || children(expr).into_iter().any(contains_div)
+}
+
+fn max_var(expr: &SymbolicExpr) -> Option<usize> {
+    match expr {
+        SymbolicExpr::Var(i) => Some(*i),
+        _ => children(expr).into_iter().filter_map(max_var).max(),
+    }
+}
+
+/// Rebuilds `expr` with each child transformed by `f`.
+fn map_children(expr: &SymbolicExpr, f: &impl Fn(&SymbolicExpr) -> SymbolicExpr) -> SymbolicExpr {
+    let b = |e: &SymbolicExpr| Box::new(f(e));
+    match expr {
+        SymbolicExpr::Input(_) | SymbolicExpr::Var(_) | SymbolicExpr::Const(..) => expr.clone(),
+        SymbolicExpr::Add(lhs, rhs) => SymbolicExpr::Add(b(lhs), b(rhs)),
+        SymbolicExpr::Sub(lhs, rhs) => SymbolicExpr::Sub(b(lhs), b(rhs)),
+        SymbolicExpr::Mul(lhs, rhs) => SymbolicExpr::Mul(b(lhs), b(rhs)),
+        SymbolicExpr::Div(lhs, rhs) => SymbolicExpr::Div(b(lhs), b(rhs)),
+        SymbolicExpr::IntAdd(lhs, s) => SymbolicExpr::IntAdd(b(lhs), *s),
+        SymbolicExpr::IntMul(lhs, s) => SymbolicExpr::IntMul(b(lhs), *s),
+        SymbolicExpr::Select(flag, lhs, rhs) => SymbolicExpr::Select(*flag, b(lhs), b(rhs)),
+    }
+}
+
+fn map_vars(expr: &SymbolicExpr, f: &impl Fn(usize) -> SymbolicExpr) -> SymbolicExpr {
+    match expr {
+        SymbolicExpr::Var(i) => f(*i),
+        _ => map_children(expr, &|child| map_vars(child, f)),
+    }
+}
+
+fn replace(expr: &SymbolicExpr, target: &SymbolicExpr, with: &SymbolicExpr) -> SymbolicExpr {
+    if expr == target {
+        with.clone()
+    } else {
+        map_children(expr, &|child| replace(child, target, with))
+    }
+}
+
+/// Whether both branches of every `Select` have the same number of limbs, as required by
+/// `SymbolicExpr::expr_limbs`.
+fn selects_match(expr: &SymbolicExpr, num_limbs: usize) -> bool {
+    if let SymbolicExpr::Select(_, lhs, rhs) = expr {
+        if expr_limbs(lhs, num_limbs) != expr_limbs(rhs, num_limbs) {
+            return false;
+        }
+    }
+    children(expr)
+        .into_iter()
+        .all(|child| selects_match(child, num_limbs))
+}
+
+/// Same as `SymbolicExpr::expr_limbs`, without the assertion on `Select` branches.
+fn expr_limbs(expr: &SymbolicExpr, num_limbs: usize) -> usize {
+    match expr {
+        SymbolicExpr::Input(_) | SymbolicExpr::Var(_) => num_limbs,
+        SymbolicExpr::Const(_, _, limbs) => *limbs,
+        SymbolicExpr::Add(lhs, rhs)
+        | SymbolicExpr::Sub(lhs, rhs)
+        | SymbolicExpr::Select(_, lhs, rhs) => {
+            max(expr_limbs(lhs, num_limbs), expr_limbs(rhs, num_limbs))
+        }
+        SymbolicExpr::Mul(lhs, rhs) => expr_limbs(lhs, num_limbs) + expr_limbs(rhs, num_limbs) - 1,
+        SymbolicExpr::Div(..) => num_limbs,
+        SymbolicExpr::IntAdd(lhs, _) | SymbolicExpr::IntMul(lhs, _) => expr_limbs(lhs, num_limbs),
+    }
+}
@@ -7,11 +7,11 @@ use openvm_circuit_primitives::{
     bigint::utils::big_uint_to_limbs,
     var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
 };
-use openvm_stark_backend::p3_field::PrimeField64;
+use openvm_stark_backend::{p3_air::BaseAir, p3_field::PrimeField64};
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
 use rand::{rngs::StdRng, RngCore};
 
-use crate::{ExprBuilder, ExprBuilderConfig};
+use crate::{ExprBuilder, ExprBuilderConfig, ExprWidthReport, FieldExpr};
 
 mod bls12381;
 mod bn254;
@@ -37,6 +37,21 @@ pub fn setup(prime: &BigUint) -> (Arc<VariableRangeCheckerChip>, Rc<RefCell<Expr
     (range_checker, Rc::new(RefCell::new(builder)))
 }
 
+/// Asserts that `expr` was built from `builder` after [ExprBuilder::minimize_columns], and returns
+/// the width report of the minimization.
+pub fn assert_minimized_expr(builder: ExprBuilder, expr: &FieldExpr) -> ExprWidthReport {
+    let report = builder.clone().minimize_columns();
+    assert!(report.width_after <= report.width_before);
+    assert_eq!(expr.num_vars(), report.num_variables_after);
+
+    let unminimized = FieldExpr::new(builder, expr.range_bus, expr.builder.needs_setup());
+    assert_eq!(
+        BaseAir::<BabyBear>::width(&unminimized) - BaseAir::<BabyBear>::width(expr),
+        report.width_before - report.width_after
+    );
+    report
+}
+
 pub fn generate_random_biguint(prime: &BigUint) -> BigUint {
     let mut rng = create_seeded_rng();
     let len = 32;
@@ -12,8 +12,8 @@ use openvm_stark_sdk::{
 };
 
 use crate::{
-    test_utils::*, utils::biguint_to_limbs_vec, ExprBuilder, FieldExpr, FieldExprCols,
-    FieldExpressionCoreRecordMut, FieldVariable, SymbolicExpr,
+    test_utils::*, utils::biguint_to_limbs_vec, ExprBuilder, ExprWidthReport, FieldExpr,
+    FieldExprCols, FieldExpressionCoreRecordMut, FieldVariable, SymbolicExpr,
 };
 
 const LIMB_BITS: usize = 8;
@@ -705,3 +705,87 @@ fn test_concurrent_direct_recorded_simulation() {
     let same_inputs = vec![BigUint::from(123u32), BigUint::from(456u32)];
     test_trace_equivalence(&expr, &range_checker, same_inputs, vec![], width);
 }
+
+/// Checks that `builder` and its minimized version have the same outputs, and that the minimized
+/// expression is still satisfied by its generated trace.
+fn test_minimized_equivalence(builder: ExprBuilder, num_inputs: usize) -> ExprWidthReport {
+    let prime = secp256k1_coord_prime();
+    let mut minimized = builder.clone();
+    let report = minimized.minimize_columns();
+    assert!(report.width_after <= report.width_before);
+
+    let (expr, _, width) = create_field_expr_with_setup(builder);
+    assert_eq!(width, report.width_before);
+    let (minimized, range_checker, width) = create_field_expr_with_setup(minimized);
+    assert_eq!(width, report.width_after);
+
+    let inputs: Vec<_> = (0..num_inputs)
+        .map(|_| generate_random_biguint(&prime))
+        .collect();
+    assert_eq!(
+        expr.execute_with_output(inputs.clone(), vec![]),
+        minimized.execute_with_output(inputs.clone(), vec![])
+    );
+    let trace = generate_direct_trace(&minimized, &range_checker, inputs, vec![], width);
+    verify_stark_with_traces(minimized, range_checker, trace, width);
+    report
+}
+
+#[test]
+fn test_minimize_columns_inline() {
+    let prime = secp256k1_coord_prime();
+    let (_, builder) = setup(&prime);
+
+    let x1 = ExprBuilder::new_input(builder.clone());
+    let x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = x1.clone() + x2;
+    x3.save();
+    let mut x4 = x3 * x1;
+    x4.save_output();
+    let builder = builder.borrow().clone();
+    assert_eq!(builder.num_variables, 2);
+
+    let report = test_minimized_equivalence(builder, 2);
+    assert_eq!(report.num_variables_before, 2);
+    assert_eq!(report.num_variables_after, 1);
+    assert!(report.width_after < report.width_before);
+}
+
+#[test]
+fn test_minimize_columns_common_subexpr() {
+    let prime = secp256k1_coord_prime();
+    let (_, builder) = setup(&prime);
+
+    let x1 = ExprBuilder::new_input(builder.clone());
+    let x2 = ExprBuilder::new_input(builder.clone());
+    let product = x1 * x2;
+    for _ in 0..3 {
+        let x = ExprBuilder::new_input(builder.clone());
+        let mut y = product.clone() + x;
+        y.save_output();
+    }
+    let builder = builder.borrow().clone();
+
+    // Saving `x1 * x2` once removes the product from the three output constraints.
+    let report = test_minimized_equivalence(builder, 5);
+    assert_eq!(report.num_variables_before, 3);
+    assert_eq!(report.num_variables_after, 4);
+    assert!(report.width_after < report.width_before);
+}
+
+#[test]
+fn test_minimize_columns_keeps_division() {
+    let prime = secp256k1_coord_prime();
+    let (_, builder) = setup(&prime);
+
+    let x1 = ExprBuilder::new_input(builder.clone());
+    let x2 = ExprBuilder::new_input(builder.clone());
+    let mut x3 = x1.clone() / x2;
+    let mut x4 = &mut x3 * &mut x1.clone();
+    x4.save_output();
+    let builder = builder.borrow().clone();
+
+    let report = test_minimized_equivalence(builder, 2);
+    assert_eq!(report.num_variables_after, 2);
+    assert_eq!(report.width_after, report.width_before);
+}
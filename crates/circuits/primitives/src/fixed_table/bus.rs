@@ -0,0 +1,63 @@
+use openvm_stark_backend::{
+    interaction::{BusIndex, InteractionBuilder, LookupBus},
+    p3_field::FieldAlgebra,
+};
+
+/// Bus for lookups into a [FixedTableLookupChip](super::FixedTableLookupChip) whose rows are
+/// `N`-tuples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedTableLookupBus<const N: usize> {
+    pub inner: LookupBus,
+}
+
+impl<const N: usize> FixedTableLookupBus<N> {
+    pub const fn new(index: BusIndex) -> Self {
+        Self {
+            inner: LookupBus::new(index),
+        }
+    }
+
+    /// Looks up `row` in the table.
+    #[must_use]
+    pub fn send<T>(&self, row: [impl Into<T>; N]) -> FixedTableLookupBusInteraction<T, N> {
+        self.push(row, true)
+    }
+
+    /// Adds `row` to the table. Only the table air should receive on this bus.
+    #[must_use]
+    pub fn receive<T>(&self, row: [impl Into<T>; N]) -> FixedTableLookupBusInteraction<T, N> {
+        self.push(row, false)
+    }
+
+    pub fn push<T>(
+        &self,
+        row: [impl Into<T>; N],
+        is_lookup: bool,
+    ) -> FixedTableLookupBusInteraction<T, N> {
+        FixedTableLookupBusInteraction {
+            row: row.map(Into::into),
+            bus: self.inner,
+            is_lookup,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FixedTableLookupBusInteraction<T, const N: usize> {
+    pub row: [T; N],
+    pub bus: LookupBus,
+    pub is_lookup: bool,
+}
+
+impl<T: FieldAlgebra, const N: usize> FixedTableLookupBusInteraction<T, N> {
+    pub fn eval<AB>(self, builder: &mut AB, count: impl Into<AB::Expr>)
+    where
+        AB: InteractionBuilder<Expr = T>,
+    {
+        if self.is_lookup {
+            self.bus.lookup_key(builder, self.row, count);
+        } else {
+            self.bus.add_key_with_lookups(builder, self.row, count);
+        }
+    }
+}
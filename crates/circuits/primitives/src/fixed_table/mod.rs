@@ -0,0 +1,250 @@
+//! Lookup into an arbitrary fixed table of tuples.
+//! The table is a preprocessed trace, so any small precomputed relation (an S-box, a byte-level
+//! function, a small multiplication table, ...) can be looked up in one interaction by sending
+//! a full row of the table on the [FixedTableLookupBus].
+
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    sync::{atomic::AtomicU32, Arc},
+};
+
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::InteractionBuilder,
+    p3_air::{Air, BaseAir, PairBuilder},
+    p3_field::{Field, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::{cpu::CpuBackend, types::AirProvingContext},
+    rap::{get_air_name, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+
+mod bus;
+pub use bus::*;
+
+#[cfg(test)]
+pub mod tests;
+
+#[repr(C)]
+#[derive(Default, Copy, Clone, AlignedBorrow)]
+pub struct FixedTableLookupCols<T> {
+    /// Number of lookups requested for the row of the table
+    pub mult: T,
+}
+
+#[derive(Default, Clone)]
+pub struct FixedTableLookupPreprocessedCols<T> {
+    /// The row of the table
+    pub row: Vec<T>,
+}
+
+pub const NUM_FIXED_TABLE_LOOKUP_COLS: usize = size_of::<FixedTableLookupCols<u8>>();
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum FixedTableError {
+    #[error("fixed table must not be empty")]
+    Empty,
+    #[error("duplicate row in fixed table: {0:?}")]
+    DuplicateRow(Vec<u32>),
+    #[error("entry {value} of fixed table row {row} is not below the field modulus {modulus}")]
+    EntryNotInField {
+        row: usize,
+        value: u32,
+        modulus: u32,
+    },
+}
+
+/// A table of distinct `N`-tuples of field elements. The trace height is the number of rows
+/// rounded up to the next power of two; padding rows repeat the last row and always have
+/// multiplicity zero.
+#[derive(Clone, Debug)]
+pub struct FixedTable<const N: usize> {
+    rows: Vec<[u32; N]>,
+    index: HashMap<[u32; N], usize>,
+    modulus: u32,
+}
+
+impl<const N: usize> FixedTable<N> {
+    /// `modulus` is the order of the field of the AIR, e.g. `BabyBear::ORDER_U32`. Entries must be
+    /// below it so that distinct rows stay distinct in the preprocessed trace.
+    pub fn new(rows: Vec<[u32; N]>, modulus: u32) -> Result<Self, FixedTableError> {
+        if rows.is_empty() {
+            return Err(FixedTableError::Empty);
+        }
+        let mut index = HashMap::with_capacity(rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            if let Some(&value) = row.iter().find(|&&value| value >= modulus) {
+                return Err(FixedTableError::EntryNotInField {
+                    row: i,
+                    value,
+                    modulus,
+                });
+            }
+            if index.insert(*row, i).is_some() {
+                return Err(FixedTableError::DuplicateRow(row.to_vec()));
+            }
+        }
+        Ok(Self {
+            rows,
+            index,
+            modulus,
+        })
+    }
+
+    /// Table of `f(x)` for `x` in `0..num_rows`, e.g. `|x| [x, SBOX[x]]`.
+    pub fn from_fn(
+        num_rows: u32,
+        modulus: u32,
+        f: impl Fn(u32) -> [u32; N],
+    ) -> Result<Self, FixedTableError> {
+        Self::new((0..num_rows).map(f).collect(), modulus)
+    }
+
+    pub fn rows(&self) -> &[[u32; N]] {
+        &self.rows
+    }
+
+    /// The field modulus the entries were checked against.
+    pub fn modulus(&self) -> u32 {
+        self.modulus
+    }
+
+    /// Index of `row` in the table, if present.
+    pub fn position(&self, row: &[u32; N]) -> Option<usize> {
+        self.index.get(row).copied()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len().next_power_of_two()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FixedTableLookupAir<const N: usize> {
+    pub bus: FixedTableLookupBus<N>,
+    pub table: Arc<FixedTable<N>>,
+}
+
+impl<F: Field, const N: usize> BaseAirWithPublicValues<F> for FixedTableLookupAir<N> {}
+impl<F: Field, const N: usize> PartitionedBaseAir<F> for FixedTableLookupAir<N> {}
+
+impl<F: Field, const N: usize> BaseAir<F> for FixedTableLookupAir<N> {
+    fn width(&self) -> usize {
+        NUM_FIXED_TABLE_LOOKUP_COLS
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let rows = self.table.rows();
+        let padding = rows[rows.len() - 1];
+        let values = rows
+            .iter()
+            .chain(std::iter::repeat_n(
+                &padding,
+                self.table.height() - rows.len(),
+            ))
+            .flat_map(|row| row.map(F::from_canonical_u32))
+            .collect();
+        Some(RowMajorMatrix::new(values, N))
+    }
+}
+
+impl<AB: InteractionBuilder + PairBuilder, const N: usize> Air<AB> for FixedTableLookupAir<N> {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let prep_local = preprocessed.row_slice(0);
+        let prep_local = FixedTableLookupPreprocessedCols {
+            row: (*prep_local).to_vec(),
+        };
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local = FixedTableLookupCols { mult: (*local)[0] };
+
+        self.bus
+            .receive::<AB::Expr>(std::array::from_fn(|i| prep_local.row[i]))
+            .eval(builder, local.mult);
+    }
+}
+
+/// Lookup chip for an arbitrary [FixedTable]. Interactions are of the form `[c_0, ..., c_{N-1}]`
+/// where the tuple is a row of the table.
+#[derive(Debug)]
+pub struct FixedTableLookupChip<const N: usize> {
+    pub air: FixedTableLookupAir<N>,
+    pub count: Vec<Arc<AtomicU32>>,
+}
+
+pub type SharedFixedTableLookupChip<const N: usize> = Arc<FixedTableLookupChip<N>>;
+
+impl<const N: usize> FixedTableLookupChip<N> {
+    pub fn new(bus: FixedTableLookupBus<N>, table: Arc<FixedTable<N>>) -> Self {
+        let count = (0..table.height())
+            .map(|_| Arc::new(AtomicU32::new(0)))
+            .collect();
+        Self {
+            air: FixedTableLookupAir { bus, table },
+            count,
+        }
+    }
+
+    pub fn bus(&self) -> &FixedTableLookupBus<N> {
+        &self.air.bus
+    }
+
+    pub fn table(&self) -> &FixedTable<N> {
+        &self.air.table
+    }
+
+    /// Records a lookup of `row`. Panics if `row` is not in the table.
+    pub fn add_count(&self, row: &[u32; N]) {
+        let index = self
+            .air
+            .table
+            .position(row)
+            .unwrap_or_else(|| panic!("row not in fixed table: {:?}", row));
+        self.count[index].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for val in &self.count {
+            val.store(0, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    /// Generates trace and resets all internal counters to 0.
+    pub fn generate_trace<F: Field>(&self) -> RowMajorMatrix<F> {
+        let rows = self
+            .count
+            .iter()
+            .map(|c| F::from_canonical_u32(c.swap(0, std::sync::atomic::Ordering::Relaxed)))
+            .collect::<Vec<_>>();
+        RowMajorMatrix::new(rows, NUM_FIXED_TABLE_LOOKUP_COLS)
+    }
+}
+
+impl<R, SC: StarkGenericConfig, const N: usize> Chip<R, CpuBackend<SC>> for FixedTableLookupChip<N>
+where
+    Val<SC>: PrimeField32,
+{
+    fn generate_proving_ctx(&self, _: R) -> AirProvingContext<CpuBackend<SC>> {
+        debug_assert_eq!(self.table().modulus(), Val::<SC>::ORDER_U32);
+        let trace = self.generate_trace::<Val<SC>>();
+        AirProvingContext::simple_no_pis(Arc::new(trace))
+    }
+}
+
+impl<const N: usize> ChipUsageGetter for FixedTableLookupChip<N> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+    fn constant_trace_height(&self) -> Option<usize> {
+        Some(self.count.len())
+    }
+    fn current_trace_height(&self) -> usize {
+        self.count.len()
+    }
+    fn trace_width(&self) -> usize {
+        NUM_FIXED_TABLE_LOOKUP_COLS
+    }
+}
@@ -0,0 +1,211 @@
+use std::{iter, sync::Arc};
+
+use openvm_stark_backend::{
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::*,
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    AirRef,
+};
+use openvm_stark_sdk::{
+    any_rap_arc_vec, config::baby_bear_blake3::BabyBearBlake3Engine,
+    dummy_airs::interaction::dummy_interaction_air::DummyInteractionAir, engine::StarkFriEngine,
+    p3_baby_bear::BabyBear, utils::create_seeded_rng,
+};
+use rand::Rng;
+
+use crate::fixed_table::{FixedTable, FixedTableError, FixedTableLookupBus, FixedTableLookupChip};
+
+/// A toy S-box: an affine permutation of the bytes.
+fn sbox(x: u32) -> u32 {
+    (x * 7 + 99) % 256
+}
+
+#[test]
+fn test_fixed_table_lookup_chip() {
+    let mut rng = create_seeded_rng();
+
+    const LIST_LEN: usize = 64;
+
+    let bus_index = 0;
+    let bus = FixedTableLookupBus::<2>::new(bus_index);
+    let table = Arc::new(FixedTable::from_fn(256, BabyBear::ORDER_U32, |x| [x, sbox(x)]).unwrap());
+    let lookup_chip = FixedTableLookupChip::new(bus, table);
+
+    // generates a list of random lookups
+    let num_lists = 10;
+    let lists_vals = (0..num_lists)
+        .map(|_| {
+            (0..LIST_LEN)
+                .map(|_| {
+                    let x = rng.gen_range(0..256);
+                    [x, sbox(x)]
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut all_chips = (0..num_lists)
+        .map(|_| Arc::new(DummyInteractionAir::new(2, true, bus_index)) as AirRef<_>)
+        .collect::<Vec<_>>();
+    all_chips.push(Arc::new(lookup_chip.air.clone()));
+
+    let lists_traces = lists_vals
+        .par_iter()
+        .map(|list| {
+            RowMajorMatrix::new(
+                list.iter()
+                    .flat_map(|row| {
+                        lookup_chip.add_count(row);
+                        iter::once(1).chain(*row)
+                    })
+                    .map(FieldAlgebra::from_wrapped_u32)
+                    .collect(),
+                3,
+            )
+        })
+        .collect::<Vec<RowMajorMatrix<BabyBear>>>();
+
+    let lookup_trace = lookup_chip.generate_trace();
+
+    let all_traces = lists_traces
+        .into_iter()
+        .chain(iter::once(lookup_trace))
+        .collect::<Vec<RowMajorMatrix<BabyBear>>>();
+
+    BabyBearBlake3Engine::run_simple_test_no_pis_fast(all_chips, all_traces)
+        .expect("Verification failed");
+}
+
+#[test]
+fn test_fixed_table_lookup_chip_padded() {
+    // 10 x 10 multiplication table, padded from 100 to 128 rows
+    let bus_index = 0;
+    let bus = FixedTableLookupBus::<3>::new(bus_index);
+    let rows = (0..10)
+        .flat_map(|a| (0..10).map(move |b| [a, b, a * b]))
+        .collect();
+    let lookup_chip = FixedTableLookupChip::new(
+        bus,
+        Arc::new(FixedTable::new(rows, BabyBear::ORDER_U32).unwrap()),
+    );
+    assert_eq!(lookup_chip.table().height(), 128);
+
+    let lookups = [[9, 9, 81], [3, 7, 21], [0, 5, 0], [9, 9, 81]];
+    let dummy_trace = RowMajorMatrix::new(
+        lookups
+            .iter()
+            .flat_map(|row| {
+                lookup_chip.add_count(row);
+                iter::once(1).chain(*row)
+            })
+            .map(BabyBear::from_canonical_u32)
+            .collect(),
+        4,
+    );
+    let lookup_trace = lookup_chip.generate_trace();
+
+    BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+        any_rap_arc_vec![
+            DummyInteractionAir::new(3, true, bus_index),
+            lookup_chip.air
+        ],
+        vec![dummy_trace, lookup_trace],
+    )
+    .expect("Verification failed");
+}
+
+#[test]
+fn negative_test_fixed_table_lookup_chip() {
+    let bus_index = 0;
+    let bus = FixedTableLookupBus::<2>::new(bus_index);
+    let table = Arc::new(FixedTable::from_fn(256, BabyBear::ORDER_U32, |x| [x, sbox(x)]).unwrap());
+    let lookup_chip = FixedTableLookupChip::new(bus, table);
+
+    for x in 0..256 {
+        lookup_chip.add_count(&[x, sbox(x)]);
+    }
+
+    let mut lookup_trace = lookup_chip.generate_trace();
+
+    // Corrupt the trace to make it invalid
+    lookup_trace.values[0] = BabyBear::from_wrapped_u32(99);
+
+    disable_debug_builder();
+    assert_eq!(
+        BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+            any_rap_arc_vec![lookup_chip.air],
+            vec![lookup_trace]
+        )
+        .err(),
+        Some(VerificationError::ChallengePhaseError),
+        "Expected constraint to fail"
+    );
+}
+
+#[test]
+fn negative_test_fixed_table_lookup_row_not_in_table() {
+    let bus_index = 0;
+    let bus = FixedTableLookupBus::<2>::new(bus_index);
+    let table = Arc::new(FixedTable::from_fn(256, BabyBear::ORDER_U32, |x| [x, sbox(x)]).unwrap());
+    let lookup_chip = FixedTableLookupChip::new(bus, table);
+
+    // The lookup is sent but the table has no matching row to receive it.
+    let dummy_trace = RowMajorMatrix::new(
+        [1, 5, sbox(5) + 1]
+            .map(BabyBear::from_canonical_u32)
+            .to_vec(),
+        3,
+    );
+    let lookup_trace = lookup_chip.generate_trace();
+
+    disable_debug_builder();
+    assert_eq!(
+        BabyBearBlake3Engine::run_simple_test_no_pis_fast(
+            any_rap_arc_vec![
+                DummyInteractionAir::new(2, true, bus_index),
+                lookup_chip.air
+            ],
+            vec![dummy_trace, lookup_trace]
+        )
+        .err(),
+        Some(VerificationError::ChallengePhaseError),
+        "Expected constraint to fail"
+    );
+}
+
+#[test]
+#[should_panic(expected = "row not in fixed table")]
+fn test_fixed_table_add_count_missing_row() {
+    let bus = FixedTableLookupBus::<2>::new(0);
+    let table = Arc::new(FixedTable::from_fn(256, BabyBear::ORDER_U32, |x| [x, sbox(x)]).unwrap());
+    FixedTableLookupChip::new(bus, table).add_count(&[5, sbox(5) + 1]);
+}
+
+#[test]
+fn test_fixed_table_duplicate_row() {
+    assert_eq!(
+        FixedTable::new(vec![[1, 2], [3, 4], [1, 2]], BabyBear::ORDER_U32).err(),
+        Some(FixedTableError::DuplicateRow(vec![1, 2]))
+    );
+}
+
+#[test]
+fn test_fixed_table_entry_not_in_field() {
+    let modulus = BabyBear::ORDER_U32;
+    assert_eq!(
+        FixedTable::new(vec![[0, 1], [modulus - 1, modulus]], modulus).err(),
+        Some(FixedTableError::EntryNotInField {
+            row: 1,
+            value: modulus,
+            modulus
+        })
+    );
+    // `modulus` and `0` would be the same row of the preprocessed trace.
+    assert!(FixedTable::from_fn(2, modulus, |x| [x * modulus]).is_err());
+    assert_eq!(
+        FixedTable::<1>::new(vec![], modulus).err(),
+        Some(FixedTableError::Empty)
+    );
+}
@@ -3,6 +3,7 @@
 //! [Air](openvm_stark_backend::p3_air::Air)s and [SubAir]s.
 //!
 //! The following modules contain standalone [Air](openvm_stark_backend::p3_air::Air)s:
+//! - [fixed_table]
 //! - [range]
 //! - [range_gate]
 //! - [range_tuple]
@@ -27,6 +28,7 @@ pub mod assert_less_than;
 pub mod bigint;
 pub mod bitwise_op_lookup;
 pub mod encoder;
+pub mod fixed_table;
 pub mod is_equal;
 pub mod is_equal_array;
 pub mod is_less_than;
@@ -26,6 +26,7 @@ pub struct VmCli {
 pub enum VmCliCommands {
     Build(BuildCmd),
     Commit(CommitCmd),
+    Estimate(EstimateCmd),
     Keygen(KeygenCmd),
     Init(InitCmd),
     Prove(ProveCmd),
@@ -49,6 +50,7 @@ async fn main() -> Result<()> {
     match command {
         VmCliCommands::Build(cmd) => cmd.run(),
         VmCliCommands::Commit(cmd) => cmd.run(),
+        VmCliCommands::Estimate(cmd) => cmd.run(),
         VmCliCommands::Keygen(cmd) => cmd.run(),
         VmCliCommands::Init(cmd) => cmd.run(),
         VmCliCommands::Prove(cmd) => cmd.run(),
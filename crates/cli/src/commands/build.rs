@@ -9,15 +9,24 @@ use eyre::Result;
 use itertools::izip;
 use openvm_build::{
     build_generic, get_package, get_workspace_packages, get_workspace_root, GuestOptions,
+    ROOT_VERIFIER_ASM_DIR_ENV, ROOT_VERIFIER_HASH_ENV,
 };
 use openvm_circuit::arch::{
     instructions::exe::VmExe, InitFileGenerator, OPENVM_DEFAULT_INIT_FILE_NAME,
 };
-use openvm_sdk::{config::TranspilerConfig, fs::write_object_to_file};
-use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE, FromElf};
-
-use crate::util::{
-    get_manifest_path_and_dir, get_target_dir, get_target_output_dir, read_config_toml_or_default,
+use openvm_sdk::{
+    config::TranspilerConfig,
+    fs::{cache_root_verifier_asm, read_object_from_file, write_object_to_file},
+    keygen::AggVerifyingKey,
+};
+use openvm_transpiler::{elf::Elf, FromElf};
+
+use crate::{
+    default::{default_agg_stark_vk_path, default_root_verifier_asm_cache_dir},
+    util::{
+        get_manifest_path_and_dir, get_target_dir, get_target_output_dir,
+        read_config_toml_or_default,
+    },
 };
 
 #[derive(Parser)]
@@ -67,6 +76,13 @@ pub struct BuildArgs {
         help_heading = "OpenVM Options"
     )]
     pub init_file_name: String,
+
+    #[arg(
+        long,
+        help = "Path to the STARK aggregation verifying key to generate the root verifier ASM for define_verify_openvm_stark! from, by default ~/.openvm/agg_stark.vk if it exists",
+        help_heading = "OpenVM Options"
+    )]
+    pub agg_vk: Option<PathBuf>,
 }
 
 impl Default for BuildArgs {
@@ -76,6 +92,7 @@ impl Default for BuildArgs {
             config: None,
             output_dir: None,
             init_file_name: OPENVM_DEFAULT_INIT_FILE_NAME.to_string(),
+            agg_vk: None,
         }
     }
 }
@@ -357,6 +374,32 @@ pub fn build(build_args: &BuildArgs, cargo_args: &BuildCargoArgs) -> Result<Path
     app_config
         .app_vm_config
         .write_to_init_file(&manifest_dir, Some(&build_args.init_file_name))?;
+    let memory_layout = app_config.memory_layout()?;
+    guest_options = guest_options.with_memory_layout(memory_layout);
+
+    // Generate the root verifier ASM used by `define_verify_openvm_stark!` from the local
+    // aggregation key, so guests that verify STARK proofs do not need to vendor it
+    let agg_vk_path = build_args
+        .agg_vk
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(default_agg_stark_vk_path()));
+    if agg_vk_path.exists() {
+        let agg_vk: AggVerifyingKey = read_object_from_file(&agg_vk_path)?;
+        let (asm_dir, hash) =
+            cache_root_verifier_asm(&agg_vk, default_root_verifier_asm_cache_dir())?;
+        println!(
+            "[openvm] Using root verifier ASM with hash {hash} at {}",
+            asm_dir.display()
+        );
+        guest_options = guest_options
+            .with_env(ROOT_VERIFIER_ASM_DIR_ENV, asm_dir.to_string_lossy())
+            .with_env(ROOT_VERIFIER_HASH_ENV, hash);
+    } else if build_args.agg_vk.is_some() {
+        return Err(eyre::eyre!(
+            "Aggregation verifying key not found at {}",
+            agg_vk_path.display()
+        ));
+    }
 
     // Build (allowing passed options to decide what gets built)
     let elf_target_dir = match build_generic(&guest_options) {
@@ -433,7 +476,7 @@ pub fn build(build_args: &BuildArgs, cargo_args: &BuildCargoArgs) -> Result<Path
     for (elf_path, target) in izip!(&elf_paths, &elf_targets) {
         let transpiler = app_config.app_vm_config.transpiler();
         let data = read(elf_path.clone())?;
-        let elf = Elf::decode(&data, MEM_SIZE as u32)?;
+        let elf = Elf::decode(&data, memory_layout.mem_size() as u32)?;
         let exe = VmExe::from_elf(elf, transpiler)?;
 
         let target_name = if target.is_example() {
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+use openvm_sdk::{
+    config::AggregationTreeConfig,
+    estimate::CostModel,
+    fs::{read_from_file_json, read_object_from_file, write_to_file_json},
+    Sdk,
+};
+
+use super::{
+    get_app_config, load_app_pk, load_or_build_exe, RunArgs, RunCargoArgs, SegmentationArgs,
+};
+use crate::{
+    default::{default_agg_stark_pk_path, default_cost_model_path},
+    input::read_to_stdin,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "estimate",
+    about = "Estimate the segments, memory and time needed to prove an OpenVM program"
+)]
+pub struct EstimateCmd {
+    #[arg(
+        long,
+        action,
+        help = "Path to app proving key, by default will be ${target_dir}/openvm/app.pk",
+        help_heading = "OpenVM Options"
+    )]
+    app_pk: Option<PathBuf>,
+
+    #[arg(
+        long,
+        action,
+        help = "Path to the cost model of this machine, by default will be ~/.openvm/cost_model.json if it exists",
+        help_heading = "OpenVM Options"
+    )]
+    cost_model: Option<PathBuf>,
+
+    #[arg(
+        long,
+        action,
+        help = "Generate a STARK proof of the program to calibrate the cost model of this machine and write it to the cost model path",
+        help_heading = "OpenVM Options"
+    )]
+    calibrate: bool,
+
+    #[command(flatten)]
+    run_args: RunArgs,
+
+    #[command(flatten)]
+    cargo_args: RunCargoArgs,
+
+    #[command(flatten)]
+    segmentation_args: SegmentationArgs,
+
+    #[command(flatten)]
+    agg_tree_config: AggregationTreeConfig,
+}
+
+impl EstimateCmd {
+    pub fn run(&self) -> Result<()> {
+        let mut app_pk = load_app_pk(&self.app_pk, &self.cargo_args)?;
+        let (exe, _) = load_or_build_exe(&self.run_args, &self.cargo_args)?;
+        let app_config = get_app_config(
+            &mut app_pk,
+            &self.run_args,
+            &self.cargo_args,
+            &self.segmentation_args,
+        )?;
+        let sdk = Sdk::new(app_config)?
+            .with_agg_tree_config(self.agg_tree_config)
+            .with_app_pk(app_pk);
+        let input = read_to_stdin(&self.run_args.input)?;
+
+        let cost_model_path = self
+            .cost_model
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(default_cost_model_path()));
+        let cost_model = if self.calibrate {
+            let agg_pk = read_object_from_file(default_agg_stark_pk_path()).map_err(|e| {
+                eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
+            })?;
+            sdk.set_agg_pk(agg_pk)
+                .map_err(|_| eyre::eyre!("Failed to set agg pk"))?;
+            println!("Calibrating cost model, this generates a STARK proof of the program...");
+            let cost_model = sdk.calibrate_cost_model(exe.clone(), input.clone())?;
+            write_to_file_json(&cost_model_path, &cost_model)?;
+            println!("Cost model written to {}", cost_model_path.display());
+            cost_model
+        } else if cost_model_path.exists() {
+            read_from_file_json(&cost_model_path)?
+        } else if self.cost_model.is_some() {
+            return Err(eyre::eyre!(
+                "Cost model not found at {}",
+                cost_model_path.display()
+            ));
+        } else {
+            println!(
+                "No cost model found at {}, using default figures. Run with --calibrate to measure them on this machine",
+                cost_model_path.display()
+            );
+            CostModel::default()
+        };
+
+        let estimate = sdk.estimate(exe, input, &cost_model)?;
+        println!("{estimate}");
+        Ok(())
+    }
+}
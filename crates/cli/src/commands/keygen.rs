@@ -5,10 +5,10 @@ use std::{
 
 use clap::Parser;
 use eyre::Result;
-use openvm_sdk::{fs::write_object_to_file, Sdk};
+use openvm_sdk::{fs::write_object_to_file, keygen::cache::KeyCache, Sdk};
 
 use crate::{
-    default::{DEFAULT_APP_PK_NAME, DEFAULT_APP_VK_NAME},
+    default::{default_key_cache_dir, DEFAULT_APP_PK_NAME, DEFAULT_APP_VK_NAME},
     util::{
         get_app_pk_path, get_app_vk_path, get_manifest_path_and_dir, get_target_dir,
         read_config_toml_or_default,
@@ -90,7 +90,9 @@ pub(crate) fn keygen(
     output_dir: Option<impl AsRef<Path>>,
 ) -> Result<()> {
     let app_config = read_config_toml_or_default(config)?;
-    let (app_pk, app_vk) = Sdk::new(app_config)?.app_keygen();
+    let (app_pk, app_vk) = Sdk::new(app_config)?
+        .with_key_cache(KeyCache::new(default_key_cache_dir()))
+        .app_keygen();
     write_object_to_file(&app_vk_path, app_vk)?;
     write_object_to_file(&app_pk_path, app_pk)?;
 
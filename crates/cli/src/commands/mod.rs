@@ -4,6 +4,9 @@ pub use build::*;
 mod commit;
 pub use commit::*;
 
+mod estimate;
+pub use estimate::*;
+
 mod keygen;
 pub use keygen::*;
 
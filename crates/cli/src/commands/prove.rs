@@ -1,4 +1,4 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{fs::remove_dir_all, path::PathBuf, sync::Arc};
 
 use clap::Parser;
 use eyre::Result;
@@ -9,7 +9,7 @@ use openvm_circuit::arch::{
     instructions::exe::VmExe,
 };
 use openvm_sdk::{
-    config::{AggregationTreeConfig, AppConfig, SdkVmConfig},
+    config::{AggregationTreeConfig, AppConfig, MemorySize, SdkVmConfig},
     fs::{encode_to_file, read_object_from_file, write_to_file_json},
     keygen::AppProvingKey,
     types::VersionedVmStarkProof,
@@ -23,7 +23,10 @@ use crate::{
     commands::build,
     default::default_agg_stark_pk_path,
     input::read_to_stdin,
-    util::{get_app_pk_path, get_manifest_path_and_dir, get_single_target_name, get_target_dir},
+    util::{
+        get_app_pk_path, get_manifest_path_and_dir, get_single_target_name, get_target_dir,
+        read_config_toml_or_default,
+    },
 };
 
 #[derive(Parser)]
@@ -89,6 +92,22 @@ enum ProveSubCommand {
 
         #[command(flatten)]
         agg_tree_config: AggregationTreeConfig,
+
+        #[arg(
+            long,
+            action,
+            help = "Directory to persist the app, leaf and internal proofs in as they are produced, by default ./${bin_name}.checkpoint if --resume is set",
+            help_heading = "OpenVM Options"
+        )]
+        checkpoint_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            action,
+            help = "Resume an interrupted proof from the proofs in the checkpoint directory",
+            help_heading = "OpenVM Options"
+        )]
+        resume: bool,
     },
     #[cfg(feature = "evm-prove")]
     Evm {
@@ -141,6 +160,11 @@ pub struct SegmentationArgs {
         help_heading = "OpenVM Options"
     )]
     pub segment_max_cells: usize,
+    /// Memory budget of the prover, e.g. `64G`. If set, the segmentation limits are derived from
+    /// a model of the prover memory usage instead of `--segment-max-height-bits` and
+    /// `--segment-max-cells`.
+    #[arg(long, value_name = "SIZE", help_heading = "OpenVM Options")]
+    pub max_prover_memory: Option<MemorySize>,
 }
 
 impl ProveCmd {
@@ -154,7 +178,8 @@ impl ProveCmd {
                 segmentation_args,
             } => {
                 let mut app_pk = load_app_pk(app_pk, cargo_args)?;
-                let app_config = get_app_config(&mut app_pk, segmentation_args);
+                let app_config =
+                    get_app_config(&mut app_pk, run_args, cargo_args, segmentation_args)?;
                 let sdk = Sdk::new(app_config)?.with_app_pk(app_pk);
                 let (exe, target_name) = load_or_build_exe(run_args, cargo_args)?;
 
@@ -180,6 +205,8 @@ impl ProveCmd {
                 cargo_args,
                 segmentation_args,
                 agg_tree_config,
+                checkpoint_dir,
+                resume,
             } => {
                 let mut app_pk = load_app_pk(app_pk, cargo_args)?;
                 let (exe, target_name) = load_or_build_exe(run_args, cargo_args)?;
@@ -187,7 +214,8 @@ impl ProveCmd {
                 let agg_pk = read_object_from_file(default_agg_stark_pk_path()).map_err(|e| {
                     eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
                 })?;
-                let app_config = get_app_config(&mut app_pk, segmentation_args);
+                let app_config =
+                    get_app_config(&mut app_pk, run_args, cargo_args, segmentation_args)?;
                 let sdk = Sdk::new(app_config)?
                     .with_agg_tree_config(*agg_tree_config)
                     .with_app_pk(app_pk)
@@ -197,7 +225,25 @@ impl ProveCmd {
                 println!("exe commit: {:?}", app_commit.app_exe_commit.to_bn254());
                 println!("vm commit: {:?}", app_commit.app_vm_commit.to_bn254());
 
-                let stark_proof = prover.prove(read_to_stdin(&run_args.input)?)?;
+                let input = read_to_stdin(&run_args.input)?;
+                let checkpoint_dir = match checkpoint_dir {
+                    Some(dir) => Some(dir.clone()),
+                    None if *resume => {
+                        Some(PathBuf::from(&target_name).with_extension("checkpoint"))
+                    }
+                    None => None,
+                };
+                let stark_proof = if let Some(checkpoint_dir) = checkpoint_dir {
+                    if !*resume && checkpoint_dir.exists() {
+                        remove_dir_all(&checkpoint_dir)?;
+                    }
+                    println!("Checkpointing proofs in {}", checkpoint_dir.display());
+                    let stark_proof = prover.prove_with_checkpoint(input, &checkpoint_dir)?;
+                    remove_dir_all(&checkpoint_dir)?;
+                    stark_proof
+                } else {
+                    prover.prove(input)?
+                };
                 let stark_proof_bytes = VersionedVmStarkProof::new(stark_proof)?;
 
                 let proof_path = if let Some(proof) = proof {
@@ -227,7 +273,8 @@ impl ProveCmd {
                 let (agg_pk, halo2_pk) = read_default_agg_and_halo2_pk().map_err(|e| {
                     eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
                 })?;
-                let app_config = get_app_config(&mut app_pk, segmentation_args);
+                let app_config =
+                    get_app_config(&mut app_pk, run_args, cargo_args, segmentation_args)?;
                 let sdk = Sdk::new(app_config)?
                     .with_agg_tree_config(*agg_tree_config)
                     .with_app_pk(app_pk)
@@ -296,18 +343,45 @@ pub(crate) fn load_or_build_exe(
 }
 
 /// Should only be called when `app_pk` has only a single reference internally.
-/// Mutates the `SystemConfig` within `app_pk` and then returns the updated `AppConfig`.
-fn get_app_config(
+/// Reads the `AppConfig` from the OpenVM config file, sets the segmentation limits in it and in
+/// the `SystemConfig` within `app_pk`, and returns it. The SDK refuses to prove if `app_pk` was
+/// generated from a different config.
+pub(crate) fn get_app_config(
     app_pk: &mut AppProvingKey<SdkVmConfig>,
+    run_args: &RunArgs,
+    cargo_args: &RunCargoArgs,
     segmentation_args: &SegmentationArgs,
-) -> AppConfig<SdkVmConfig> {
-    Arc::get_mut(&mut app_pk.app_vm_pk)
-        .unwrap()
-        .vm_config
+) -> Result<AppConfig<SdkVmConfig>> {
+    let (_, manifest_dir) = get_manifest_path_and_dir(&cargo_args.manifest_path)?;
+    let config_path = run_args
+        .config
+        .to_owned()
+        .unwrap_or_else(|| manifest_dir.join("openvm.toml"));
+    let mut app_config = read_config_toml_or_default(config_path)?;
+    let limits = if let Some(max_memory) = segmentation_args.max_prover_memory {
+        let limits = app_pk.set_max_prover_memory(max_memory.0);
+        println!(
+            "Segmentation limits for a prover memory budget of {max_memory}: max trace height = 2^{}, max cells = {}",
+            limits.max_trace_height.ilog2(),
+            limits.max_cells
+        );
+        limits
+    } else {
+        let limits = (*segmentation_args).into();
+        Arc::get_mut(&mut app_pk.app_vm_pk)
+            .unwrap()
+            .vm_config
+            .system
+            .config
+            .set_segmentation_limits(limits);
+        limits
+    };
+    app_config
+        .app_vm_config
         .system
         .config
-        .set_segmentation_limits((*segmentation_args).into());
-    app_pk.app_config()
+        .set_segmentation_limits(limits);
+    Ok(app_config)
 }
 
 impl From<SegmentationArgs> for SegmentationLimits {
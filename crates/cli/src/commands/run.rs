@@ -2,12 +2,17 @@ use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
 use eyre::Result;
+use openvm_build::get_dir_with_profile;
 use openvm_circuit::arch::{instructions::exe::VmExe, OPENVM_DEFAULT_INIT_FILE_NAME};
-use openvm_sdk::{config::SdkVmConfig, fs::read_object_from_file, keygen::AppProvingKey, Sdk, F};
+use openvm_sdk::{
+    config::SdkVmConfig, fs::read_object_from_file, keygen::AppProvingKey, types::GuestMemoryUsage,
+    Sdk, F,
+};
 
 use super::{build, BuildArgs, BuildCargoArgs};
 use crate::{
     commands::keygen::keygen,
+    coverage::CoverageReport,
     input::{read_to_stdin, Input},
     util::{
         get_app_pk_path, get_app_vk_path, get_manifest_path_and_dir, get_single_target_name,
@@ -19,7 +24,8 @@ use crate::{
 pub enum ExecutionMode {
     /// Runs the program normally
     Pure,
-    /// Runs the program and estimates the execution cost in terms of number of cells
+    /// Runs the program and estimates the execution cost in terms of number of cells, and reports
+    /// guest memory usage
     Meter,
     /// Runs the program and calculates the number of segments that the execution will be split
     /// into for proving
@@ -84,6 +90,22 @@ pub struct RunArgs {
         help_heading = "OpenVM Options"
     )]
     pub mode: ExecutionMode,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write guest code coverage to this file, merging with any existing report. Files ending in .xml are written in Cobertura format, all others in lcov format. Requires the guest to be built with debug info and is only supported in pure mode",
+        help_heading = "OpenVM Options"
+    )]
+    pub coverage: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path to the guest ELF used for coverage debug info, if --exe is specified",
+        help_heading = "OpenVM Options"
+    )]
+    pub elf: Option<PathBuf>,
 }
 
 impl From<RunArgs> for BuildArgs {
@@ -253,6 +275,15 @@ impl From<RunCargoArgs> for BuildCargoArgs {
 
 impl RunCmd {
     pub fn run(&self) -> Result<()> {
+        if self.run_args.coverage.is_some() && !matches!(self.run_args.mode, ExecutionMode::Pure) {
+            return Err(eyre::eyre!(
+                "--coverage is only supported in pure execution mode"
+            ));
+        }
+        let (manifest_path, manifest_dir) =
+            get_manifest_path_and_dir(&self.cargo_args.manifest_path)?;
+
+        let mut elf_path = self.run_args.elf.clone();
         let exe_path = if let Some(exe) = &self.run_args.exe {
             exe
         } else {
@@ -261,11 +292,16 @@ impl RunCmd {
             let build_args = self.run_args.clone().into();
             let cargo_args = self.cargo_args.clone().into();
             let output_dir = build(&build_args, &cargo_args)?;
+            if elf_path.is_none() {
+                let target_dir = get_target_dir(&self.cargo_args.target_dir, &manifest_path);
+                elf_path = Some(
+                    get_dir_with_profile(target_dir, &self.cargo_args.profile, false)
+                        .join(&target_name),
+                );
+            }
             &output_dir.join(target_name.with_extension("vmexe"))
         };
 
-        let (manifest_path, manifest_dir) =
-            get_manifest_path_and_dir(&self.cargo_args.manifest_path)?;
         let config_path = self
             .run_args
             .config
@@ -305,26 +341,94 @@ impl RunCmd {
 
         match self.run_args.mode {
             ExecutionMode::Pure => {
-                let output = sdk.execute(exe, inputs)?;
-                println!("Execution output: {:?}", output);
+                if let Some(coverage_path) = &self.run_args.coverage {
+                    let elf_path = elf_path.ok_or_else(|| {
+                        eyre::eyre!("--elf must be specified to collect coverage with --exe")
+                    })?;
+                    let (output, pc_counts) = sdk.execute_with_coverage(exe.clone(), inputs)?;
+                    println!("Execution output: {:?}", output);
+
+                    let report = CoverageReport::from_execution(
+                        &exe,
+                        &elf_path,
+                        &pc_counts,
+                        Some(&manifest_dir),
+                    )?;
+                    println!(
+                        "Lines covered in this run: {}/{}",
+                        report.num_lines_hit(),
+                        report.num_lines()
+                    );
+                    report.write_merged(coverage_path)?;
+                    println!("Coverage written to {}", coverage_path.display());
+                } else {
+                    let output = sdk.execute(exe, inputs)?;
+                    println!("Execution output: {:?}", output);
+                }
             }
             ExecutionMode::Meter => {
-                let (output, (cost, instret)) = sdk.execute_metered_cost(exe, inputs)?;
+                let (output, (cost, instret), memory_usage) =
+                    sdk.execute_metered_cost(exe, inputs)?;
                 println!("Execution output: {:?}", output);
 
                 println!("Number of instructions executed: {}", instret);
                 println!("Total cost: {}", cost);
+                print_memory_usage(&memory_usage);
             }
             ExecutionMode::Segment => {
-                let (output, segments) = sdk.execute_metered(exe, inputs)?;
+                let (output, segments, memory_usage) = sdk.execute_metered(exe, inputs)?;
                 println!("Execution output: {:?}", output);
 
                 let total_instructions: u64 = segments.iter().map(|s| s.num_insns).sum();
                 println!("Number of instructions executed: {}", total_instructions);
                 println!("Total segments: {}", segments.len());
+                print_memory_usage(&memory_usage);
             }
         }
 
         Ok(())
     }
 }
+
+fn print_memory_usage(memory_usage: &GuestMemoryUsage) {
+    println!("Heap start: {:#010x}", memory_usage.heap_start);
+    match (memory_usage.peak_heap_ptr, memory_usage.max_heap_addr) {
+        (Some(peak_heap_ptr), _) => println!(
+            "Peak heap pointer: {:#010x} ({} bytes above heap start, limit {:#010x})",
+            peak_heap_ptr,
+            memory_usage.heap_bytes(),
+            memory_usage.heap_end
+        ),
+        (None, Some(max_heap_addr)) => println!(
+            "Max accessed heap address: {:#010x} ({} bytes above heap start, limit {:#010x})",
+            max_heap_addr,
+            memory_usage.accessed_heap_bytes(),
+            memory_usage.heap_end
+        ),
+        (None, None) => println!("Heap: nothing allocated"),
+    }
+    match memory_usage.stack_low_water {
+        Some(stack_low_water) => println!(
+            "Stack low-water mark: {:#010x} ({} bytes below stack top {:#010x})",
+            stack_low_water,
+            memory_usage.stack_bytes(),
+            memory_usage.stack_top
+        ),
+        None => println!("Stack low-water mark: stack not used"),
+    }
+    let profile = &memory_usage.profile;
+    for usage in &profile.address_spaces {
+        println!(
+            "Address space {}: {} touched pages of {} cells in [{:#010x}, {:#010x})",
+            usage.address_space,
+            usage.touched_pages,
+            profile.page_size,
+            usage.min_ptr,
+            usage.max_ptr
+        );
+    }
+    println!(
+        "Estimated memory boundary/merkle cost: {} cells",
+        profile.boundary_merkle_cells
+    );
+}
@@ -0,0 +1,430 @@
+//! Guest code coverage reports, built from the execution counts of each pc and the DWARF line
+//! tables of the guest ELF.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use addr2line::Loader;
+use eyre::Result;
+use openvm_circuit::arch::{
+    execution_mode::PcCounts,
+    instructions::{exe::VmExe, program::DEFAULT_PC_STEP},
+};
+use openvm_sdk::F;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoverageFormat {
+    Lcov,
+    Cobertura,
+}
+
+impl CoverageFormat {
+    /// Files ending in `.xml` are written in Cobertura format, everything else in lcov format.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("xml") => CoverageFormat::Cobertura,
+            _ => CoverageFormat::Lcov,
+        }
+    }
+}
+
+/// Line and function execution counts for a single source file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// Line number to execution count.
+    pub lines: BTreeMap<u32, u64>,
+    /// Function name to (first line, execution count).
+    pub functions: BTreeMap<String, (u32, u64)>,
+}
+
+/// Coverage of guest source files, keyed by source file path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+impl CoverageReport {
+    /// Attributes the execution counts in `pc_counts` to source lines using the debug info of the
+    /// ELF at `elf_path`, which must be the ELF that `exe` was transpiled from. Every instruction
+    /// of the program is included, so lines that were never executed are reported with zero
+    /// hits. Only source files under `source_root` are kept, if it is provided.
+    ///
+    /// A line's count is the largest count of the instructions it was lowered to. A function's
+    /// count is the count of its entry instruction, where function bounds are taken from
+    /// `exe.fn_bounds` when available and from the ELF symbol table otherwise.
+    pub fn from_execution(
+        exe: &VmExe<F>,
+        elf_path: impl AsRef<Path>,
+        pc_counts: &PcCounts,
+        source_root: Option<&Path>,
+    ) -> Result<Self> {
+        let elf_path = elf_path.as_ref();
+        let loader = Loader::new(elf_path).map_err(|e| {
+            eyre::eyre!("Failed to load debug info from {}: {e}", elf_path.display())
+        })?;
+        let program = &exe.program;
+
+        let mut report = Self::default();
+        let mut current_fn: Option<String> = None;
+        for idx in 0..program.len() {
+            if program.get_instruction_and_debug_info(idx).is_none() {
+                continue;
+            }
+            let pc = program.pc_base + idx as u32 * DEFAULT_PC_STEP;
+            let hits = pc_counts.get(&pc).copied().unwrap_or(0);
+
+            let fn_entry = if exe.fn_bounds.is_empty() {
+                loader.find_symbol(pc as u64).map(|name| name.to_string())
+            } else {
+                exe.fn_bounds
+                    .get(&pc)
+                    .and_then(|_| loader.find_symbol(pc as u64))
+                    .map(|name| name.to_string())
+            };
+            let is_fn_entry = fn_entry.is_some() && fn_entry != current_fn;
+            if is_fn_entry {
+                current_fn = fn_entry;
+            }
+
+            let Some(location) = loader
+                .find_location(pc as u64)
+                .map_err(|e| eyre::eyre!("Failed to read debug info for pc {pc:#x}: {e}"))?
+            else {
+                continue;
+            };
+            let (Some(file), Some(line)) = (location.file, location.line) else {
+                continue;
+            };
+            let file = PathBuf::from(file);
+            if source_root.is_some_and(|root| !file.starts_with(root)) {
+                continue;
+            }
+
+            let file_coverage = report.files.entry(file).or_default();
+            let line_hits = file_coverage.lines.entry(line).or_default();
+            *line_hits = (*line_hits).max(hits);
+            if is_fn_entry {
+                let name = rustc_demangle::demangle(current_fn.as_ref().unwrap()).to_string();
+                file_coverage.functions.entry(name).or_insert((line, hits));
+            }
+        }
+        Ok(report)
+    }
+
+    /// Adds the counts of `other` to the counts of `self`.
+    pub fn merge(&mut self, other: CoverageReport) {
+        for (file, other_file) in other.files {
+            let file_coverage = self.files.entry(file).or_default();
+            for (line, hits) in other_file.lines {
+                *file_coverage.lines.entry(line).or_default() += hits;
+            }
+            for (name, (line, hits)) in other_file.functions {
+                file_coverage.functions.entry(name).or_insert((line, 0)).1 += hits;
+            }
+        }
+    }
+
+    /// Writes the report to `path`. If a report already exists at `path`, the counts are merged
+    /// into it so that coverage accumulates across runs.
+    pub fn write_merged(mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = CoverageFormat::from_path(path);
+        if path.exists() {
+            let existing = read_to_string(path)?;
+            let existing = match format {
+                CoverageFormat::Lcov => Self::from_lcov(&existing)?,
+                CoverageFormat::Cobertura => Self::from_cobertura(&existing)?,
+            };
+            self.merge(existing);
+        }
+        let contents = match format {
+            CoverageFormat::Lcov => self.to_lcov(),
+            CoverageFormat::Cobertura => self.to_cobertura(),
+        };
+        write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn num_lines(&self) -> usize {
+        self.files.values().map(|f| f.lines.len()).sum()
+    }
+
+    pub fn num_lines_hit(&self) -> usize {
+        self.files
+            .values()
+            .map(|f| f.lines.values().filter(|hits| **hits > 0).count())
+            .sum()
+    }
+
+    pub fn to_lcov(&self) -> String {
+        let mut out = String::new();
+        for (file, coverage) in &self.files {
+            writeln!(out, "TN:").unwrap();
+            writeln!(out, "SF:{}", file.display()).unwrap();
+            for (name, (line, _)) in &coverage.functions {
+                writeln!(out, "FN:{line},{name}").unwrap();
+            }
+            for (name, (_, hits)) in &coverage.functions {
+                writeln!(out, "FNDA:{hits},{name}").unwrap();
+            }
+            let fns_hit = coverage.functions.values().filter(|f| f.1 > 0).count();
+            writeln!(out, "FNF:{}", coverage.functions.len()).unwrap();
+            writeln!(out, "FNH:{fns_hit}").unwrap();
+            for (line, hits) in &coverage.lines {
+                writeln!(out, "DA:{line},{hits}").unwrap();
+            }
+            let lines_hit = coverage.lines.values().filter(|hits| **hits > 0).count();
+            writeln!(out, "LF:{}", coverage.lines.len()).unwrap();
+            writeln!(out, "LH:{lines_hit}").unwrap();
+            writeln!(out, "end_of_record").unwrap();
+        }
+        out
+    }
+
+    pub fn from_lcov(contents: &str) -> Result<Self> {
+        let mut report = Self::default();
+        let mut fn_lines = BTreeMap::new();
+        let mut current: Option<&mut FileCoverage> = None;
+        for line in contents.lines() {
+            let (tag, value) = line.split_once(':').unwrap_or((line, ""));
+            match tag {
+                "SF" => {
+                    fn_lines.clear();
+                    current = Some(report.files.entry(PathBuf::from(value)).or_default());
+                }
+                "end_of_record" => current = None,
+                "DA" | "FN" | "FNDA" => {
+                    let file = current
+                        .as_mut()
+                        .ok_or_else(|| eyre::eyre!("lcov record `{line}` outside of a file"))?;
+                    let (first, rest) = value
+                        .split_once(',')
+                        .ok_or_else(|| eyre::eyre!("Malformed lcov record `{line}`"))?;
+                    match tag {
+                        "DA" => {
+                            // DA may carry an optional checksum after the hit count
+                            let hits = rest.split(',').next().unwrap_or(rest);
+                            *file.lines.entry(first.parse()?).or_default() +=
+                                hits.parse::<u64>()?;
+                        }
+                        "FN" => {
+                            fn_lines.insert(rest.to_string(), first.parse::<u32>()?);
+                        }
+                        _ => {
+                            let fn_line = fn_lines.get(rest).copied().unwrap_or_default();
+                            file.functions
+                                .entry(rest.to_string())
+                                .or_insert((fn_line, 0))
+                                .1 += first.parse::<u64>()?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(report)
+    }
+
+    /// Cobertura reports only carry line coverage; function coverage is omitted.
+    pub fn to_cobertura(&self) -> String {
+        let lines_valid = self.num_lines();
+        let lines_covered = self.num_lines_hit();
+        let mut out = String::new();
+        writeln!(out, r#"<?xml version="1.0" ?>"#).unwrap();
+        writeln!(
+            out,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r#"<coverage line-rate="{}" branch-rate="0" lines-covered="{lines_covered}" lines-valid="{lines_valid}" branches-covered="0" branches-valid="0" complexity="0" version="cargo-openvm">"#,
+            line_rate(lines_covered, lines_valid)
+        )
+        .unwrap();
+        writeln!(out, "  <packages>").unwrap();
+        writeln!(
+            out,
+            r#"    <package name="guest" line-rate="{}" branch-rate="0" complexity="0">"#,
+            line_rate(lines_covered, lines_valid)
+        )
+        .unwrap();
+        writeln!(out, "      <classes>").unwrap();
+        for (file, coverage) in &self.files {
+            let file = xml_escape(&file.display().to_string());
+            let hit = coverage.lines.values().filter(|hits| **hits > 0).count();
+            writeln!(
+                out,
+                r#"        <class name="{file}" filename="{file}" line-rate="{}" branch-rate="0" complexity="0">"#,
+                line_rate(hit, coverage.lines.len())
+            )
+            .unwrap();
+            writeln!(out, "          <methods/>").unwrap();
+            writeln!(out, "          <lines>").unwrap();
+            for (line, hits) in &coverage.lines {
+                writeln!(out, r#"            <line number="{line}" hits="{hits}"/>"#).unwrap();
+            }
+            writeln!(out, "          </lines>").unwrap();
+            writeln!(out, "        </class>").unwrap();
+        }
+        writeln!(out, "      </classes>").unwrap();
+        writeln!(out, "    </package>").unwrap();
+        writeln!(out, "  </packages>").unwrap();
+        writeln!(out, "</coverage>").unwrap();
+        out
+    }
+
+    /// Parses a Cobertura report as written by [`to_cobertura`](Self::to_cobertura).
+    pub fn from_cobertura(contents: &str) -> Result<Self> {
+        let mut report = Self::default();
+        let mut current: Option<&mut FileCoverage> = None;
+        for line in contents.lines().map(str::trim) {
+            if line.starts_with("<class ") {
+                let filename = xml_attr(line, "filename")
+                    .ok_or_else(|| eyre::eyre!("Cobertura class without filename: `{line}`"))?;
+                current = Some(
+                    report
+                        .files
+                        .entry(PathBuf::from(xml_unescape(filename)))
+                        .or_default(),
+                );
+            } else if line.starts_with("</class>") {
+                current = None;
+            } else if line.starts_with("<line ") {
+                let file = current
+                    .as_mut()
+                    .ok_or_else(|| eyre::eyre!("Cobertura line outside of a class: `{line}`"))?;
+                let (Some(number), Some(hits)) = (xml_attr(line, "number"), xml_attr(line, "hits"))
+                else {
+                    eyre::bail!("Malformed Cobertura line `{line}`");
+                };
+                *file.lines.entry(number.parse()?).or_default() += hits.parse::<u64>()?;
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn line_rate(hit: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        hit as f64 / total as f64
+    }
+}
+
+fn xml_attr<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let start = element.find(&format!(" {name}=\""))? + name.len() + 3;
+    let len = element[start..].find('"')?;
+    Some(&element[start..start + len])
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> CoverageReport {
+        let mut main = FileCoverage::default();
+        main.lines.extend([(6, 1), (10, 1024), (11, 1023), (16, 0)]);
+        main.functions.insert("guest::main".to_string(), (6, 1));
+        main.functions.insert("guest::unused".to_string(), (20, 0));
+        let mut lib = FileCoverage::default();
+        lib.lines.extend([(3, 7)]);
+        CoverageReport {
+            files: BTreeMap::from([
+                (PathBuf::from("src/main.rs"), main),
+                (PathBuf::from("src/<generated>&\"lib\".rs"), lib),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_lcov_roundtrip() {
+        let report = sample_report();
+        let lcov = report.to_lcov();
+        assert!(lcov.contains("DA:10,1024\n"));
+        assert!(lcov.contains("DA:16,0\n"));
+        assert!(lcov.contains("FNDA:1,guest::main\n"));
+        assert!(lcov.contains("LF:4\nLH:3\n"));
+        assert_eq!(CoverageReport::from_lcov(&lcov).unwrap(), report);
+    }
+
+    #[test]
+    fn test_lcov_checksums_and_errors() {
+        let report =
+            CoverageReport::from_lcov("SF:a.rs\nDA:1,5,abcdef\nDA:1,2\nend_of_record\n").unwrap();
+        assert_eq!(report.files[Path::new("a.rs")].lines[&1], 7);
+        assert!(CoverageReport::from_lcov("DA:1,5\n").is_err());
+        assert!(CoverageReport::from_lcov("SF:a.rs\nDA:1\n").is_err());
+    }
+
+    #[test]
+    fn test_cobertura_roundtrip() {
+        let report = sample_report();
+        let xml = report.to_cobertura();
+        assert!(xml.contains(r#"lines-covered="4" lines-valid="5""#));
+        assert!(xml.contains(r#"<line number="11" hits="1023"/>"#));
+        // Cobertura does not carry function coverage.
+        let mut expected = report;
+        for file in expected.files.values_mut() {
+            file.functions.clear();
+        }
+        assert_eq!(CoverageReport::from_cobertura(&xml).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut report = sample_report();
+        let mut other = CoverageReport::default();
+        let mut main = FileCoverage::default();
+        main.lines.extend([(16, 2), (30, 1)]);
+        main.functions.insert("guest::unused".to_string(), (20, 3));
+        other.files.insert(PathBuf::from("src/main.rs"), main);
+        report.merge(other);
+
+        let main = &report.files[Path::new("src/main.rs")];
+        assert_eq!(main.lines[&10], 1024);
+        assert_eq!(main.lines[&16], 2);
+        assert_eq!(main.lines[&30], 1);
+        assert_eq!(main.functions["guest::unused"], (20, 3));
+        assert_eq!(main.functions["guest::main"], (6, 1));
+    }
+
+    #[test]
+    fn test_write_merged_accumulates() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["lcov.info", "coverage.xml"] {
+            let path = dir.path().join(name);
+            sample_report().write_merged(&path).unwrap();
+            sample_report().write_merged(&path).unwrap();
+            let contents = read_to_string(&path).unwrap();
+            let merged = match CoverageFormat::from_path(&path) {
+                CoverageFormat::Lcov => CoverageReport::from_lcov(&contents).unwrap(),
+                CoverageFormat::Cobertura => CoverageReport::from_cobertura(&contents).unwrap(),
+            };
+            for (file, coverage) in &sample_report().files {
+                for (line, hits) in &coverage.lines {
+                    assert_eq!(merged.files[file].lines[line], 2 * hits);
+                }
+            }
+        }
+    }
+}
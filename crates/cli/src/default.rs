@@ -24,6 +24,18 @@ pub fn default_asm_path() -> String {
     env::var("HOME").unwrap() + "/.openvm/root.asm"
 }
 
+pub fn default_root_verifier_asm_cache_dir() -> String {
+    env::var("HOME").unwrap() + "/.openvm/verifier_asm/"
+}
+
+pub fn default_key_cache_dir() -> String {
+    env::var("HOME").unwrap() + "/.openvm/keys/"
+}
+
+pub fn default_cost_model_path() -> String {
+    env::var("HOME").unwrap() + "/.openvm/cost_model.json"
+}
+
 pub fn default_params_dir() -> String {
     env::var("HOME").unwrap() + "/.openvm/params/"
 }
@@ -49,5 +61,6 @@ pub fn default_app_config() -> AppConfig<SdkVmConfig> {
         )
         .into(),
         compiler_options: Default::default(),
+        memory_layout: None,
     }
 }
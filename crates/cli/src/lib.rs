@@ -2,6 +2,7 @@
 #![cfg_attr(feature = "tco", feature(explicit_tail_calls))]
 
 pub mod commands;
+pub mod coverage;
 pub mod default;
 pub mod input;
 pub mod util;
@@ -23,7 +23,9 @@ pub(crate) fn read_to_struct_toml<T: DeserializeOwned>(path: impl AsRef<Path>) -
 
 pub fn read_config_toml_or_default(config: impl AsRef<Path>) -> Result<AppConfig<SdkVmConfig>> {
     if config.as_ref().exists() {
-        read_to_struct_toml(config)
+        let mut app_config: AppConfig<SdkVmConfig> = read_to_struct_toml(config)?;
+        app_config.apply_memory_layout()?;
+        Ok(app_config)
     } else {
         println!(
             "{:?} not found, using default application configuration",
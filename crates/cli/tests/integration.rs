@@ -6,6 +6,7 @@ use std::{
     sync::OnceLock,
 };
 
+use cargo_openvm::coverage::CoverageReport;
 use eyre::Result;
 use itertools::Itertools;
 use tempfile::tempdir;
@@ -313,6 +314,72 @@ fn test_cli_run_meter() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_run_coverage() -> Result<()> {
+    let temp_dir = tempdir()?;
+    install_cli();
+    let exe_path = build_fibonacci_once()?;
+    let coverage_path = temp_dir.path().join("lcov.info");
+    let elf_path =
+        "tests/programs/fibonacci/target/riscv32im-risc0-zkvm-elf/release/openvm-cli-example-test";
+
+    // Running twice should merge into a single report
+    let mut reports = vec![];
+    for _ in 0..2 {
+        run_cmd(
+            "cargo",
+            &[
+                "openvm",
+                "run",
+                "--exe",
+                exe_path,
+                "--elf",
+                elf_path,
+                "--config",
+                "tests/programs/fibonacci/openvm.toml",
+                "--manifest-path",
+                "tests/programs/fibonacci/Cargo.toml",
+                "--coverage",
+                coverage_path.to_str().unwrap(),
+            ],
+        )?;
+        reports.push(CoverageReport::from_lcov(&read_to_string(&coverage_path)?)?);
+    }
+
+    let (main_path, main) = reports[0]
+        .files
+        .iter()
+        .find(|(file, _)| file.ends_with("fibonacci/src/main.rs"))
+        .expect("coverage report should contain the guest main.rs");
+    // `main` runs once, and the loop body runs `n - 1 = 1023` times.
+    assert!(main
+        .functions
+        .iter()
+        .any(|(name, (_, hits))| name.ends_with("::main") && *hits == 1));
+    let loop_hits = main.lines.range(10..=13).map(|(_, hits)| *hits).max();
+    assert!(matches!(loop_hits, Some(1023 | 1024)), "{loop_hits:?}");
+    assert_eq!(main.lines.get(&16).copied().unwrap_or(0), 0);
+
+    // The second run adds its counts to the first.
+    assert_eq!(reports[0].files.len(), reports[1].files.len());
+    for (file, coverage) in &reports[0].files {
+        for (line, hits) in &coverage.lines {
+            assert_eq!(reports[1].files[file].lines[line], 2 * hits);
+        }
+    }
+    let merged_main = &reports[1].files[main_path];
+    assert_eq!(
+        merged_main
+            .lines
+            .range(10..=13)
+            .map(|(_, hits)| *hits)
+            .max(),
+        loop_hits.map(|h| 2 * h)
+    );
+
+    Ok(())
+}
+
 fn run_cmd(program: &str, args: &[&str]) -> Result<()> {
     let package_dir = env::current_dir()?;
     let prefix = "[test cli e2e]";
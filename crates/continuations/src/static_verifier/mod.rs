@@ -21,6 +21,7 @@ use crate::{
         common::{
             assert_single_segment_vm_exit_successfully_with_connector_air_id, types::SpecialAirIds,
         },
+        multi_root::types::MultiRootVmVerifierPvs,
         root::types::RootVmVerifierPvs,
         utils::compress_babybear_var_to_bn254,
     },
@@ -68,6 +69,46 @@ impl StaticVerifierPvHandler for DefaultStaticVerifierPvHandler {
     }
 }
 
+/// Public values handler for the static verifier of a multi-program root verifier. For each
+/// program in order, the public values of the final EVM proof are the exe commit, the leaf
+/// verifier commit and the user public values, in the same format as
+/// [DefaultStaticVerifierPvHandler].
+pub struct MultiProgramStaticVerifierPvHandler {
+    /// Number of user public values of each program, in program order.
+    pub num_user_public_values: Vec<usize>,
+}
+
+impl StaticVerifierPvHandler for MultiProgramStaticVerifierPvHandler {
+    fn handle_public_values(
+        &self,
+        builder: &mut Builder<OuterConfig>,
+        input: &StarkProofVariable<OuterConfig>,
+        special_air_ids: &SpecialAirIds,
+    ) -> usize {
+        let pv_air = builder.get(&input.per_air, special_air_ids.public_values_air_id);
+        let public_values: Vec<_> = pv_air
+            .public_values
+            .vec()
+            .into_iter()
+            .map(|x| builder.cast_felt_to_var(x))
+            .collect();
+        let pvs = MultiRootVmVerifierPvs::from_flatten(public_values, &self.num_user_public_values);
+        let mut num_public_values = 0;
+        for pvs in pvs.programs {
+            let exe_commit = compress_babybear_var_to_bn254(builder, pvs.exe_commit);
+            let leaf_commit = compress_babybear_var_to_bn254(builder, pvs.leaf_verifier_commit);
+            builder.static_commit_public_value(num_public_values, exe_commit);
+            builder.static_commit_public_value(num_public_values + 1, leaf_commit);
+            num_public_values += 2;
+            for x in pvs.public_values {
+                builder.static_commit_public_value(num_public_values, x);
+                num_public_values += 1;
+            }
+        }
+        num_public_values
+    }
+}
+
 /// Config to generate static verifier DSL operations.
 pub struct StaticVerifierConfig {
     pub root_verifier_fri_params: FriParameters,
@@ -90,6 +90,31 @@ pub fn assert_or_assign_memory_pvs<C: Config>(
     builder.assign(&dst.final_root, proof_pvs.final_root);
 }
 
+/// Asserts that `fri_profile` is one of the first `num_app_fri_profiles` app FRI profiles accepted
+/// by the leaf verifier.
+pub fn assert_app_fri_profile_accepted<C: Config>(
+    builder: &mut Builder<C>,
+    fri_profile: Felt<C::F>,
+    num_app_fri_profiles: usize,
+) {
+    assert!(
+        num_app_fri_profiles > 0,
+        "At least 1 app FRI profile should be accepted"
+    );
+    let fri_profile = builder.cast_felt_to_var(fri_profile);
+    if num_app_fri_profiles == 1 {
+        builder.assert_var_eq(fri_profile, C::N::ZERO);
+        return;
+    }
+    let num_matches: Var<_> = builder.eval(C::N::ZERO);
+    for profile_idx in 0..num_app_fri_profiles {
+        builder
+            .if_eq(fri_profile, C::N::from_canonical_usize(profile_idx))
+            .then(|builder| builder.assign(&num_matches, num_matches + C::N::ONE));
+    }
+    builder.assert_var_eq(num_matches, C::N::ONE);
+}
+
 pub fn get_program_commit<C: Config>(
     builder: &mut Builder<C>,
     proof: &StarkProofVariable<C>,
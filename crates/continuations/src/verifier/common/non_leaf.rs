@@ -53,6 +53,7 @@ impl<C: Config> NonLeafVerifierVariables<C> {
             builder.if_eq(i, RVar::zero()).then_or_else(
                 |builder| {
                     builder.assign(&pvs.app_commit, proof_vm_pvs.vm_verifier_pvs.app_commit);
+                    builder.assign(&pvs.fri_profile, proof_vm_pvs.vm_verifier_pvs.fri_profile);
                     builder.assign(
                         &leaf_verifier_commit,
                         proof_vm_pvs.extra_pvs.leaf_verifier_commit,
@@ -67,6 +68,8 @@ impl<C: Config> NonLeafVerifierVariables<C> {
                         leaf_verifier_commit,
                         proof_vm_pvs.extra_pvs.leaf_verifier_commit,
                     );
+                    builder
+                        .assert_felt_eq(pvs.fri_profile, proof_vm_pvs.vm_verifier_pvs.fri_profile);
                 },
             );
             assert_or_assign_connector_pvs(
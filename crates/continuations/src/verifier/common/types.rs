@@ -26,6 +26,9 @@ pub struct VmVerifierPvs<T> {
     /// The merkle root of all public values. This is only meaningful when the last segment is
     /// aggregated by this circuit.
     pub public_values_commit: [T; DIGEST_SIZE],
+    /// Index of the FRI profile of the app proofs in the profiles accepted by the leaf verifier.
+    /// Profile `0` is the primary profile.
+    pub fri_profile: T,
 }
 
 impl<F: PrimeField32> VmVerifierPvs<Felt<F>> {
@@ -43,6 +46,7 @@ impl<F: PrimeField32> VmVerifierPvs<Felt<F>> {
                 final_root: array::from_fn(|_| builder.uninit()),
             },
             public_values_commit: array::from_fn(|_| builder.uninit()),
+            fri_profile: builder.uninit(),
         }
     }
 }
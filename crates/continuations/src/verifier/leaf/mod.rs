@@ -37,6 +37,17 @@ pub struct LeafVmVerifierConfig {
     pub app_fri_params: FriParameters,
     pub app_system_config: SystemConfig,
     pub compiler_options: CompilerOptions,
+    /// Additional FRI profiles of app proofs accepted by the leaf verifier. The primary profile
+    /// `0` is `app_fri_params`, and additional profiles are numbered from `1` in order. The
+    /// profile of the verified proofs is exposed in [VmVerifierPvs::fri_profile].
+    pub extra_app_profiles: Vec<AppFriProfile>,
+}
+
+/// FRI parameters of app proofs together with the app VM verifying key generated for them.
+#[derive(Clone)]
+pub struct AppFriProfile {
+    pub fri_params: FriParameters,
+    pub app_vm_vk: MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
 }
 
 impl LeafVmVerifierConfig {
@@ -44,16 +55,31 @@ impl LeafVmVerifierConfig {
         &self,
         app_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
     ) -> Program<F> {
-        let m_advice = new_from_inner_multi_vk(app_vm_vk);
         let mut builder = Builder::<C>::default();
 
         {
             builder.cycle_tracker_start("InitializePcsConst");
-            let pcs = TwoAdicFriPcsVariable {
-                config: const_fri_config(&mut builder, &self.app_fri_params),
-            };
+            let profiles = [(&self.app_fri_params, app_vm_vk)]
+                .into_iter()
+                .chain(
+                    self.extra_app_profiles
+                        .iter()
+                        .map(|profile| (&profile.fri_params, &profile.app_vm_vk)),
+                )
+                .map(|(fri_params, vk)| {
+                    let pcs = TwoAdicFriPcsVariable {
+                        config: const_fri_config(&mut builder, fri_params),
+                    };
+                    (pcs, new_from_inner_multi_vk(vk))
+                })
+                .collect::<Vec<_>>();
             builder.cycle_tracker_end("InitializePcsConst");
             builder.cycle_tracker_start("ReadProofsFromInput");
+            let mut pvs = VmVerifierPvs::<Felt<F>>::uninit(&mut builder);
+            // The FRI profile is selected by the public value flag, so the selected profile is
+            // committed in the leaf proof and checked against the accepted profiles by the root.
+            pvs.fri_profile = builder.hint_felt();
+            let fri_profile = builder.cast_felt_to_var(pvs.fri_profile);
             let proofs: Array<C, StarkProofVariable<_>> =
                 <Vec<Proof<BabyBearPoseidon2Config>> as Hintable<C>>::read(&mut builder);
             // At least 1 proof should be provided.
@@ -61,14 +87,32 @@ impl LeafVmVerifierConfig {
             builder.cycle_tracker_end("ReadProofsFromInput");
 
             builder.cycle_tracker_start("VerifyProofs");
-            let pvs = VmVerifierPvs::<Felt<F>>::uninit(&mut builder);
+            if profiles.len() == 1 {
+                builder.assert_var_eq(fri_profile, F::ZERO);
+            }
             builder.range(0, proofs.len()).for_each(|i_vec, builder| {
                 let i = i_vec[0];
                 let proof = builder.get(&proofs, i);
                 assert_required_air_for_app_vm_present(builder, &proof);
-                StarkVerifier::verify::<DuplexChallengerVariable<C>>(
-                    builder, &pcs, &m_advice, &proof,
-                );
+                if let [(pcs, m_advice)] = profiles.as_slice() {
+                    StarkVerifier::verify::<DuplexChallengerVariable<C>>(
+                        builder, pcs, m_advice, &proof,
+                    );
+                } else {
+                    // Exactly one of the branches must be taken.
+                    let num_verified: Var<_> = builder.eval(F::ZERO);
+                    for (profile_idx, (pcs, m_advice)) in profiles.iter().enumerate() {
+                        builder
+                            .if_eq(fri_profile, F::from_canonical_usize(profile_idx))
+                            .then(|builder| {
+                                StarkVerifier::verify::<DuplexChallengerVariable<C>>(
+                                    builder, pcs, m_advice, &proof,
+                                );
+                                builder.assign(&num_verified, num_verified + F::ONE);
+                            });
+                    }
+                    builder.assert_var_eq(num_verified, F::ONE);
+                }
                 {
                     let commit = get_program_commit(builder, &proof);
                     builder.if_eq(i, RVar::zero()).then_or_else(
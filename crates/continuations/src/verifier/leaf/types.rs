@@ -24,6 +24,10 @@ pub struct LeafVmVerifierInput<SC: StarkGenericConfig> {
     /// The public values root proof. Leaf VM verifier only needs this when verifying the last
     /// segment.
     pub public_values_root_proof: Option<UserPublicValuesRootProof<Val<SC>>>,
+    /// The FRI profile the proofs were generated with, as numbered in
+    /// [LeafVmVerifierConfig::extra_app_profiles](super::LeafVmVerifierConfig::extra_app_profiles).
+    #[serde(default)]
+    pub fri_profile: usize,
 }
 assert_impl_all!(LeafVmVerifierInput<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
 
@@ -50,6 +54,7 @@ impl<SC: StarkGenericConfig> LeafVmVerifierInput<SC> {
             .map(|proof| Self {
                 proofs: proof.to_vec(),
                 public_values_root_proof: None,
+                fri_profile: 0,
             })
             .collect();
         ret.last_mut().unwrap().public_values_root_proof =
@@ -31,7 +31,8 @@ impl<SC: StarkGenericConfig> LeafVmVerifierInput<SC> {
         Vec<Proof<SC>>: Hintable<C>,
         UserPublicValuesRootProof<Val<SC>>: Hintable<C>,
     {
-        let mut ret = Hintable::<C>::write(&self.proofs);
+        let mut ret = Hintable::<C>::write(&self.fri_profile);
+        ret.extend(Hintable::<C>::write(&self.proofs));
         if let Some(pvs_root_proof) = &self.public_values_root_proof {
             ret.extend(Hintable::<C>::write(pvs_root_proof));
         }
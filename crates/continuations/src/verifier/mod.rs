@@ -2,5 +2,6 @@
 pub mod common;
 pub mod internal;
 pub mod leaf;
+pub mod multi_root;
 pub mod root;
 pub mod utils;
@@ -0,0 +1,173 @@
+use std::array;
+
+use openvm_circuit::arch::instructions::program::Program;
+use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
+use openvm_native_recursion::{
+    fri::TwoAdicFriPcsVariable, hints::Hintable, types::new_from_inner_multi_vk,
+    utils::const_fri_config,
+};
+use openvm_stark_sdk::{
+    config::FriParameters,
+    openvm_stark_backend::{keygen::types::MultiStarkVerifyingKey, p3_field::FieldAlgebra},
+};
+
+use crate::{
+    verifier::{
+        common::{assert_app_fri_profile_accepted, non_leaf::NonLeafVerifierVariables},
+        multi_root::{
+            types::{MultiRootVmVerifierInput, MultiRootVmVerifierPvs},
+            vars::MultiRootVmVerifierInputVariable,
+        },
+        root::{compute_exe_commit, types::RootVmVerifierPvs},
+        utils::VariableP2Hasher,
+    },
+    C, F, SC,
+};
+
+pub mod types;
+mod vars;
+
+/// Config to generate the multi-program root VM verifier program.
+///
+/// The multi-program root verifier aggregates the executions of several, possibly different, App
+/// VM programs. Each program may use a different App VM config, as long as all App VMs share the
+/// leaf and internal verifier VMs. For every program, it checks the same conditions as the root
+/// verifier and exposes the program's exe commit, leaf verifier commit and user public values.
+pub struct MultiRootVmVerifierConfig {
+    pub leaf_fri_params: FriParameters,
+    pub internal_fri_params: FriParameters,
+    /// Number of user public values of each program, in program order. The number of aggregated
+    /// programs is fixed to the length of this list.
+    pub num_user_public_values: Vec<usize>,
+    pub internal_vm_verifier_commit: [F; DIGEST_SIZE],
+    pub compiler_options: CompilerOptions,
+    /// Number of app FRI profiles accepted by the leaf verifiers, see
+    /// [LeafVmVerifierConfig::extra_app_profiles](crate::verifier::leaf::LeafVmVerifierConfig::extra_app_profiles).
+    pub num_app_fri_profiles: usize,
+}
+
+impl MultiRootVmVerifierConfig {
+    pub fn build_program(
+        &self,
+        leaf_vm_vk: &MultiStarkVerifyingKey<SC>,
+        internal_vm_vk: &MultiStarkVerifyingKey<SC>,
+    ) -> Program<F> {
+        assert!(
+            !self.num_user_public_values.is_empty(),
+            "At least 1 program should be aggregated"
+        );
+        let mut builder = Builder::<C>::default();
+
+        builder.cycle_tracker_start("ReadProofsFromInput");
+        let input = MultiRootVmVerifierInput::<SC>::read(&mut builder);
+        builder.cycle_tracker_end("ReadProofsFromInput");
+        let pvs = self.verifier_impl(&mut builder, leaf_vm_vk, internal_vm_vk, input);
+        pvs.flatten()
+            .into_iter()
+            .for_each(|v| builder.commit_public_value(v));
+        builder.halt();
+        builder.compile_isa_with_options(self.compiler_options)
+    }
+
+    fn verifier_impl(
+        &self,
+        builder: &mut Builder<C>,
+        leaf_vm_vk: &MultiStarkVerifyingKey<SC>,
+        internal_vm_vk: &MultiStarkVerifyingKey<SC>,
+        input: MultiRootVmVerifierInputVariable<C>,
+    ) -> MultiRootVmVerifierPvs<Felt<F>> {
+        let num_programs = self.num_user_public_values.len();
+        let total_num_user_public_values = self.num_user_public_values.iter().sum::<usize>();
+        let MultiRootVmVerifierInputVariable {
+            proofs,
+            public_values,
+        } = input;
+        builder.assert_usize_eq(proofs.len(), RVar::from(num_programs));
+        builder.assert_usize_eq(
+            public_values.len(),
+            RVar::from(total_num_user_public_values),
+        );
+
+        builder.cycle_tracker_start("InitializePcsConst");
+        let leaf_pcs = TwoAdicFriPcsVariable {
+            config: const_fri_config(builder, &self.leaf_fri_params),
+        };
+        let internal_pcs = TwoAdicFriPcsVariable {
+            config: const_fri_config(builder, &self.internal_fri_params),
+        };
+        builder.cycle_tracker_end("InitializePcsConst");
+        let internal_program_commit =
+            array::from_fn(|i| builder.eval(self.internal_vm_verifier_commit[i]));
+        let non_leaf_verifier = NonLeafVerifierVariables {
+            internal_program_commit,
+            leaf_pcs,
+            leaf_advice: new_from_inner_multi_vk(leaf_vm_vk),
+            internal_pcs,
+            internal_advice: new_from_inner_multi_vk(internal_vm_vk),
+        };
+        let hasher = VariableP2Hasher::new(builder);
+
+        // The proofs are verified in a dynamic loop so the verifier is only emitted once. The
+        // commits of each program are stored to check the public values afterwards.
+        builder.cycle_tracker_start("VerifyProofs");
+        let exe_commits = builder.array::<[Felt<F>; DIGEST_SIZE]>(num_programs);
+        let leaf_verifier_commits = builder.array::<[Felt<F>; DIGEST_SIZE]>(num_programs);
+        let public_values_commits = builder.array::<[Felt<F>; DIGEST_SIZE]>(num_programs);
+        builder.range(0, proofs.len()).for_each(|i_vec, builder| {
+            let i = i_vec[0];
+            let proof = builder.get(&proofs, i);
+            let program_proofs = builder.array(1);
+            builder.set(&program_proofs, 0, proof);
+            let (merged_pvs, leaf_verifier_commit) =
+                non_leaf_verifier.verify_internal_or_leaf_verifier_proofs(builder, &program_proofs);
+
+            // App Program should terminate
+            builder.assert_felt_eq(merged_pvs.connector.is_terminate, F::ONE);
+            // App Program should exit successfully
+            builder.assert_felt_eq(merged_pvs.connector.exit_code, F::ZERO);
+            // App proofs should use one of the accepted app FRI profiles
+            assert_app_fri_profile_accepted(
+                builder,
+                merged_pvs.fri_profile,
+                self.num_app_fri_profiles,
+            );
+
+            let exe_commit = compute_exe_commit(
+                builder,
+                &hasher,
+                merged_pvs.app_commit,
+                merged_pvs.memory.initial_root,
+                merged_pvs.connector.initial_pc,
+            );
+            builder.set_value(&exe_commits, i, exe_commit);
+            builder.set_value(&leaf_verifier_commits, i, leaf_verifier_commit);
+            builder.set_value(&public_values_commits, i, merged_pvs.public_values_commit);
+        });
+        builder.cycle_tracker_end("VerifyProofs");
+
+        builder.cycle_tracker_start("ExtractPublicValues");
+        let mut offset = 0;
+        let programs = self
+            .num_user_public_values
+            .iter()
+            .enumerate()
+            .map(|(i, &num_user_public_values)| {
+                let program_public_values: Vec<Felt<F>> = (offset..offset + num_user_public_values)
+                    .map(|j| builder.get(&public_values, j))
+                    .collect();
+                offset += num_user_public_values;
+                let pv_commit = hasher.merkle_root(builder, &program_public_values);
+                let expected_pv_commit = builder.get(&public_values_commits, i);
+                builder.assert_eq::<[_; DIGEST_SIZE]>(expected_pv_commit, pv_commit);
+                RootVmVerifierPvs {
+                    exe_commit: builder.get(&exe_commits, i),
+                    leaf_verifier_commit: builder.get(&leaf_verifier_commits, i),
+                    public_values: program_public_values,
+                }
+            })
+            .collect();
+        builder.cycle_tracker_end("ExtractPublicValues");
+
+        MultiRootVmVerifierPvs { programs }
+    }
+}
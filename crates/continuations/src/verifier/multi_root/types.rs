@@ -0,0 +1,70 @@
+use derivative::Derivative;
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Config,
+    openvm_stark_backend::{
+        config::{Com, StarkGenericConfig, Val},
+        proof::Proof,
+    },
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+
+use crate::verifier::root::types::RootVmVerifierPvs;
+
+/// Public values of the multi-program root verifier: the [RootVmVerifierPvs] of every aggregated
+/// program, in program order.
+#[derive(Debug)]
+pub struct MultiRootVmVerifierPvs<T> {
+    pub programs: Vec<RootVmVerifierPvs<T>>,
+}
+
+/// Input for the multi-program root VM verifier.
+#[derive(Serialize, Deserialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = "Com<SC>: Clone"))]
+pub struct MultiRootVmVerifierInput<SC: StarkGenericConfig> {
+    /// One leaf verifier or internal verifier proof per program, in program order. Each proof
+    /// must aggregate the whole execution of its program.
+    pub proofs: Vec<Proof<SC>>,
+    /// Concatenation of the user public values of every program, in program order.
+    pub public_values: Vec<Val<SC>>,
+}
+assert_impl_all!(MultiRootVmVerifierInput<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
+
+impl<F: Copy> MultiRootVmVerifierPvs<F> {
+    pub fn flatten(self) -> Vec<F> {
+        self.programs
+            .into_iter()
+            .flat_map(RootVmVerifierPvs::flatten)
+            .collect()
+    }
+
+    /// `num_user_public_values` is the number of user public values of each program.
+    pub fn from_flatten(flatten: Vec<F>, num_user_public_values: &[usize]) -> Self {
+        assert_eq!(
+            flatten.len(),
+            Self::width(num_user_public_values),
+            "Unexpected number of public values"
+        );
+        let mut offset = 0;
+        let programs = num_user_public_values
+            .iter()
+            .map(|&num_user_public_values| {
+                let width = 2 * DIGEST_SIZE + num_user_public_values;
+                let pvs = RootVmVerifierPvs::from_flatten(flatten[offset..offset + width].to_vec());
+                offset += width;
+                pvs
+            })
+            .collect();
+        Self { programs }
+    }
+
+    /// Number of public values of the multi-program root verifier.
+    pub fn width(num_user_public_values: &[usize]) -> usize {
+        num_user_public_values
+            .iter()
+            .map(|num_user_public_values| 2 * DIGEST_SIZE + num_user_public_values)
+            .sum()
+    }
+}
@@ -0,0 +1,32 @@
+use openvm_native_compiler::prelude::*;
+use openvm_native_recursion::{hints::Hintable, vars::StarkProofVariable};
+use openvm_stark_sdk::openvm_stark_backend::{config::Val, proof::Proof};
+
+use crate::{verifier::multi_root::types::MultiRootVmVerifierInput, C, SC};
+
+#[derive(DslVariable, Clone)]
+pub struct MultiRootVmVerifierInputVariable<C: Config> {
+    /// One leaf verifier or internal verifier proof per program.
+    pub proofs: Array<C, StarkProofVariable<C>>,
+    /// Concatenated public values of all programs.
+    pub public_values: Array<C, Felt<C::F>>,
+}
+
+impl Hintable<C> for MultiRootVmVerifierInput<SC> {
+    type HintVariable = MultiRootVmVerifierInputVariable<C>;
+
+    fn read(builder: &mut Builder<C>) -> Self::HintVariable {
+        let proofs = Vec::<Proof<SC>>::read(builder);
+        let public_values = Vec::<Val<SC>>::read(builder);
+        Self::HintVariable {
+            proofs,
+            public_values,
+        }
+    }
+
+    fn write(&self) -> Vec<Vec<<C as Config>::N>> {
+        let mut stream = self.proofs.write();
+        stream.extend(self.public_values.write());
+        stream
+    }
+}
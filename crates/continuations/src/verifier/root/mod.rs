@@ -14,7 +14,7 @@ use openvm_stark_sdk::{
 
 use crate::{
     verifier::{
-        common::non_leaf::NonLeafVerifierVariables,
+        common::{assert_app_fri_profile_accepted, non_leaf::NonLeafVerifierVariables},
         root::{
             types::{RootVmVerifierInput, RootVmVerifierPvs},
             vars::RootVmVerifierInputVariable,
@@ -34,6 +34,9 @@ pub struct RootVmVerifierConfig {
     pub num_user_public_values: usize,
     pub internal_vm_verifier_commit: [F; DIGEST_SIZE],
     pub compiler_options: CompilerOptions,
+    /// Number of app FRI profiles accepted by the leaf verifiers, see
+    /// [LeafVmVerifierConfig::extra_app_profiles](crate::verifier::leaf::LeafVmVerifierConfig::extra_app_profiles).
+    pub num_app_fri_profiles: usize,
 }
 impl RootVmVerifierConfig {
     pub fn build_program(
@@ -176,6 +179,8 @@ impl RootVmVerifierConfig {
         builder.assert_felt_eq(merged_pvs.connector.is_terminate, F::ONE);
         // App Program should exit successfully
         builder.assert_felt_eq(merged_pvs.connector.exit_code, F::ZERO);
+        // App proofs should use one of the accepted app FRI profiles
+        assert_app_fri_profile_accepted(builder, merged_pvs.fri_profile, self.num_app_fri_profiles);
 
         builder.cycle_tracker_start("ExtractPublicValues");
         builder.assert_usize_eq(public_values.len(), RVar::from(self.num_user_public_values));
@@ -201,7 +206,7 @@ impl RootVmVerifierConfig {
     }
 }
 
-fn compute_exe_commit<C: Config>(
+pub(crate) fn compute_exe_commit<C: Config>(
     builder: &mut Builder<C>,
     hasher: &VariableP2Hasher<C>,
     app_commit: [Felt<C::F>; DIGEST_SIZE],
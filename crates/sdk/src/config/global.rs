@@ -1,9 +1,11 @@
 use bon::Builder;
 use openvm_algebra_circuit::{
-    AlgebraCpuProverExt, Fp2Extension, Fp2ExtensionExecutor, ModularExtension,
-    ModularExtensionExecutor,
+    AlgebraCpuProverExt, CustomFieldExprExtension, CustomFieldExprExtensionExecutor, Fp2Extension,
+    Fp2ExtensionExecutor, ModularExtension, ModularExtensionExecutor,
+};
+use openvm_algebra_transpiler::{
+    CustomFieldExprTranspilerExtension, Fp2TranspilerExtension, ModularTranspilerExtension,
 };
-use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
 use openvm_bigint_circuit::{Int256, Int256CpuProverExt, Int256Executor};
 use openvm_bigint_transpiler::Int256TranspilerExtension;
 use openvm_circuit::{
@@ -97,6 +99,7 @@ pub struct SdkVmConfig {
     pub fp2: Option<Fp2Extension>,
     pub pairing: Option<PairingExtension>,
     pub ecc: Option<WeierstrassExtension>,
+    pub custom_field_expr: Option<CustomFieldExprExtension>,
 }
 
 impl SdkVmConfig {
@@ -173,7 +176,16 @@ impl SdkVmConfig {
 
     /// `openvm_toml` should be the TOML string read from an openvm.toml file.
     pub fn from_toml(openvm_toml: &str) -> Result<AppConfig<Self>, toml::de::Error> {
-        toml::from_str(openvm_toml)
+        let mut app_config: AppConfig<Self> = toml::from_str(openvm_toml)?;
+        app_config
+            .apply_memory_layout()
+            .map_err(|e| serde::de::Error::custom(e.to_string()))?;
+        if let Some(custom_field_expr) = &app_config.app_vm_config.custom_field_expr {
+            custom_field_expr
+                .validate()
+                .map_err(|e| serde::de::Error::custom(e.to_string()))?;
+        }
+        Ok(app_config)
     }
 }
 
@@ -223,6 +235,9 @@ impl TranspilerConfig<F> for SdkVmConfig {
         if self.ecc.is_some() {
             transpiler = transpiler.with_extension(EccTranspilerExtension);
         }
+        if self.custom_field_expr.is_some() {
+            transpiler = transpiler.with_extension(CustomFieldExprTranspilerExtension);
+        }
         transpiler
     }
 }
@@ -278,6 +293,7 @@ impl SdkVmConfig {
         let fp2 = config.fp2.clone();
         let pairing = config.pairing.clone();
         let ecc = config.ecc.clone();
+        let custom_field_expr = config.custom_field_expr.clone();
 
         SdkVmConfigInner {
             system,
@@ -293,6 +309,7 @@ impl SdkVmConfig {
             fp2,
             pairing,
             ecc,
+            custom_field_expr,
         }
     }
 }
@@ -334,6 +351,8 @@ pub struct SdkVmConfigInner {
     pub pairing: Option<PairingExtension>,
     #[extension(executor = "WeierstrassExtensionExecutor")]
     pub ecc: Option<WeierstrassExtension>,
+    #[extension(executor = "CustomFieldExprExtensionExecutor")]
+    pub custom_field_expr: Option<CustomFieldExprExtension>,
 }
 
 // Generated by macro
@@ -419,6 +438,13 @@ where
         if let Some(ecc) = &config.ecc {
             VmProverExtension::<E, _, _>::extend_prover(&EccCpuProverExt, ecc, inventory)?;
         }
+        if let Some(custom_field_expr) = &config.custom_field_expr {
+            VmProverExtension::<E, _, _>::extend_prover(
+                &AlgebraCpuProverExt,
+                custom_field_expr,
+                inventory,
+            )?;
+        }
         Ok(chip_complex)
     }
 }
@@ -483,6 +509,13 @@ impl VmBuilder<GpuBabyBearPoseidon2Engine> for SdkVmGpuBuilder {
         if let Some(ecc) = &config.ecc {
             VmProverExtension::<E, _, _>::extend_prover(&EccProverExt, ecc, inventory)?;
         }
+        if let Some(custom_field_expr) = &config.custom_field_expr {
+            VmProverExtension::<E, _, _>::extend_prover(
+                &AlgebraProverExt,
+                custom_field_expr,
+                inventory,
+            )?;
+        }
         Ok(chip_complex)
     }
 }
@@ -496,7 +529,11 @@ impl InitFileGenerator for SdkVmConfig {
 }
 impl InitFileGenerator for SdkVmConfigInner {
     fn generate_init_file_contents(&self) -> Option<String> {
-        if self.modular.is_some() || self.fp2.is_some() || self.ecc.is_some() {
+        if self.modular.is_some()
+            || self.fp2.is_some()
+            || self.ecc.is_some()
+            || self.custom_field_expr.is_some()
+        {
             let mut contents = String::new();
             contents.push_str(
                 "// This file is automatically generated by cargo openvm. Do not rename or edit.\n",
@@ -522,6 +559,11 @@ impl InitFileGenerator for SdkVmConfigInner {
                 contents.push('\n');
             }
 
+            if let Some(custom_field_expr_config) = &self.custom_field_expr {
+                contents.push_str(&custom_field_expr_config.generate_field_expr_init());
+                contents.push('\n');
+            }
+
             Some(contents)
         } else {
             None
@@ -602,6 +644,7 @@ struct SdkVmConfigWithDefaultDeser {
     pub fp2: Option<Fp2Extension>,
     pub pairing: Option<PairingExtension>,
     pub ecc: Option<WeierstrassExtension>,
+    pub custom_field_expr: Option<CustomFieldExprExtension>,
 }
 
 impl From<SdkVmConfigWithDefaultDeser> for SdkVmConfig {
@@ -620,6 +663,7 @@ impl From<SdkVmConfigWithDefaultDeser> for SdkVmConfig {
             fp2: config.fp2,
             pairing: config.pairing,
             ecc: config.ecc,
+            custom_field_expr: config.custom_field_expr,
         };
         ret.optimize()
     }
@@ -628,6 +672,7 @@ impl From<SdkVmConfigWithDefaultDeser> for SdkVmConfig {
 #[cfg(test)]
 mod tests {
     use itertools::zip_eq;
+    use openvm_circuit::arch::{instructions::riscv::RV32_MEMORY_AS, MemoryConfig};
 
     use super::*;
 
@@ -653,4 +698,81 @@ mod tests {
             assert_eq!(line1, line2);
         }
     }
+
+    #[test]
+    fn test_memory_layout_from_toml() {
+        let toml_config = SdkVmConfig::from_toml(
+            r#"
+[app_vm_config.rv32i]
+[app_vm_config.rv32m]
+[app_vm_config.io]
+
+[memory_layout]
+stack_size = 0x800000
+mem_bits = 28
+"#,
+        )
+        .unwrap();
+        let layout = toml_config.memory_layout().unwrap();
+        assert_eq!(layout.stack_top, 0x800400);
+        assert_eq!(layout.text_start, 0x800800);
+        assert_eq!(layout.mem_size(), 1 << 28);
+        let memory_config = &toml_config.app_vm_config.system.config.memory_config;
+        assert_eq!(memory_config.pointer_max_bits, 28);
+        assert_eq!(
+            memory_config.addr_spaces[RV32_MEMORY_AS as usize].num_cells,
+            1 << 28
+        );
+
+        assert!(SdkVmConfig::from_toml(
+            r#"
+[app_vm_config.rv32i]
+
+[memory_layout]
+mem_bits = 30
+"#
+        )
+        .is_err());
+        assert!(SdkVmConfig::from_toml(
+            r#"
+[app_vm_config.rv32i]
+
+[memory_layout]
+stack_size = 0xFFFFFF00
+"#
+        )
+        .is_err());
+
+        // The native address space of the aggregation config does not fit in 2^28 addresses.
+        let mut memory_config = MemoryConfig::aggregation();
+        assert!(memory_config.set_rv32_memory_bits(28).is_err());
+        assert_eq!(
+            memory_config.pointer_max_bits,
+            MemoryConfig::aggregation().pointer_max_bits
+        );
+    }
+
+    #[test]
+    fn test_custom_field_expr_from_toml() {
+        let toml = |outputs: &str| {
+            format!(
+                r#"
+[app_vm_config.rv32i]
+[app_vm_config.io]
+
+[[app_vm_config.custom_field_expr.expressions]]
+name = "mul_add"
+modulus = "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+inputs = ["a", "b", "c"]
+outputs = [{outputs}]
+"#
+            )
+        };
+        let app_config = SdkVmConfig::from_toml(&toml(r#""a * b + c""#)).unwrap();
+        let custom_field_expr = app_config.app_vm_config.custom_field_expr.unwrap();
+        assert_eq!(custom_field_expr.expressions.len(), 1);
+
+        assert!(SdkVmConfig::from_toml(&toml(r#""a * d""#)).is_err());
+        assert!(SdkVmConfig::from_toml(&toml("")).is_err());
+    }
 }
@@ -0,0 +1,218 @@
+use std::{fmt, str::FromStr};
+
+use openvm_circuit::arch::execution_mode::metered::segment_ctx::{
+    SegmentationLimits, DEFAULT_MAX_TRACE_HEIGHT,
+};
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_backend::{
+    config::StarkGenericConfig, keygen::types::MultiStarkProvingKey,
+    p3_field::FieldExtensionAlgebra,
+};
+
+/// Fraction of the memory budget available to the traces of a segment. The rest is left for the
+/// executor, the guest memory image and allocator fragmentation.
+pub const PROVER_MEMORY_TRACE_FRACTION: f64 = 0.75;
+/// Number of commitment rounds (main, after challenge and quotient) with a merkle tree over the
+/// LDE of each trace.
+const NUM_COMMITMENT_ROUNDS: usize = 3;
+
+/// Estimates the peak memory used by the prover to prove one segment of the App VM, in order to
+/// derive [SegmentationLimits] from a memory budget.
+///
+/// For each AIR, the estimate per trace row accounts for
+/// - the main and after challenge traces and their low degree extensions by `2^log_blowup`,
+/// - the quotient chunks and their low degree extensions,
+/// - the merkle trees committing to the low degree extensions.
+#[derive(Clone, Debug)]
+pub struct ProverMemoryModel {
+    /// Main trace width of each AIR.
+    main_widths: Vec<usize>,
+    /// Estimated bytes per trace row of each AIR.
+    bytes_per_row: Vec<usize>,
+}
+
+impl ProverMemoryModel {
+    pub fn new<SC: StarkGenericConfig>(pk: &MultiStarkProvingKey<SC>, log_blowup: usize) -> Self {
+        let ext_degree = SC::Challenge::D;
+        let field_bytes = size_of::<u32>();
+        let lde_factor = 1 << log_blowup;
+        let (main_widths, bytes_per_row) = pk
+            .per_air
+            .iter()
+            .map(|pk| {
+                let width = &pk.vk.params.width;
+                let trace_width = width.total_width(ext_degree);
+                let quotient_width = ext_degree * pk.vk.quotient_degree as usize;
+                let trace_bytes = (trace_width + quotient_width) * (1 + lde_factor) * field_bytes;
+                // A merkle tree has about twice as many digests as leaves.
+                let merkle_bytes =
+                    NUM_COMMITMENT_ROUNDS * 2 * lde_factor * DIGEST_SIZE * field_bytes;
+                (width.main_width(), trace_bytes + merkle_bytes)
+            })
+            .unzip();
+        Self {
+            main_widths,
+            bytes_per_row,
+        }
+    }
+
+    /// Estimated peak memory in bytes to prove a segment with the given trace heights, ordered by
+    /// AIR ID.
+    pub fn segment_memory(&self, trace_heights: &[u32]) -> usize {
+        assert_eq!(trace_heights.len(), self.bytes_per_row.len());
+        trace_heights
+            .iter()
+            .zip(&self.bytes_per_row)
+            .map(|(&height, &bytes)| height.next_power_of_two() as usize * bytes)
+            .sum()
+    }
+
+    /// Number of main trace cells of a segment with the given trace heights, ordered by AIR ID.
+    pub fn main_cells(&self, trace_heights: &[u32]) -> usize {
+        assert_eq!(trace_heights.len(), self.main_widths.len());
+        trace_heights
+            .iter()
+            .zip(&self.main_widths)
+            .map(|(&height, &width)| height as usize * width)
+            .sum()
+    }
+
+    /// Derives segmentation limits such that the estimated memory of a segment stays within
+    /// [PROVER_MEMORY_TRACE_FRACTION] of `max_memory` bytes.
+    ///
+    /// `max_cells` assumes the main cells of a segment are distributed across AIRs proportionally
+    /// to their widths. `max_trace_height` is the largest power of two, at most
+    /// [DEFAULT_MAX_TRACE_HEIGHT], such that the most memory intensive AIR fits the budget on its
+    /// own.
+    pub fn segmentation_limits(&self, max_memory: usize) -> SegmentationLimits {
+        let trace_memory = (max_memory as f64 * PROVER_MEMORY_TRACE_FRACTION) as usize;
+        let total_main_width: usize = self.main_widths.iter().sum();
+        let total_bytes_per_row: usize = self.bytes_per_row.iter().sum();
+        let max_cells = if total_bytes_per_row == 0 {
+            usize::MAX
+        } else {
+            (trace_memory as u128 * total_main_width as u128 / total_bytes_per_row as u128) as usize
+        };
+
+        let max_bytes_per_row = self.bytes_per_row.iter().copied().max().unwrap_or(0);
+        let max_height = trace_memory
+            .checked_div(max_bytes_per_row)
+            .unwrap_or(usize::MAX);
+        let max_trace_height = if max_height == 0 {
+            1
+        } else {
+            // Largest power of two <= max_height
+            1 << max_height.ilog2().min(DEFAULT_MAX_TRACE_HEIGHT.ilog2())
+        };
+        SegmentationLimits::default()
+            .with_max_trace_height(max_trace_height)
+            .with_max_cells(max_cells)
+    }
+}
+
+/// A memory size in bytes, parsed from strings such as `64G`, `512MiB` or `1073741824`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemorySize(pub usize);
+
+impl FromStr for MemorySize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let digits_end = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (value, unit) = s.split_at(digits_end);
+        let value: f64 = value
+            .parse()
+            .map_err(|_| format!("invalid memory size: {s}"))?;
+        let shift = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 0,
+            "K" | "KB" | "KIB" => 10,
+            "M" | "MB" | "MIB" => 20,
+            "G" | "GB" | "GIB" => 30,
+            "T" | "TB" | "TIB" => 40,
+            _ => return Err(format!("invalid memory size unit: {unit}")),
+        };
+        Ok(Self((value * (1u64 << shift) as f64) as usize))
+    }
+}
+
+impl fmt::Display for MemorySize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_circuit::arch::execution_mode::metered::segment_ctx::SegmentationCtx;
+
+    use super::*;
+
+    fn test_model() -> ProverMemoryModel {
+        ProverMemoryModel {
+            main_widths: vec![10, 20],
+            bytes_per_row: vec![100, 400],
+        }
+    }
+
+    #[test]
+    fn test_segmentation_limits_monotonic() {
+        let model = test_model();
+        let mut prev = model.segmentation_limits(0);
+        for log_memory in 10..48 {
+            let limits = model.segmentation_limits(1 << log_memory);
+            assert!(limits.max_cells >= prev.max_cells);
+            assert!(limits.max_trace_height >= prev.max_trace_height);
+            assert!(limits.max_trace_height.is_power_of_two());
+            prev = limits;
+        }
+    }
+
+    #[test]
+    fn test_max_trace_height_clamped() {
+        let model = test_model();
+        assert_eq!(model.segmentation_limits(0).max_trace_height, 1);
+        assert_eq!(
+            model.segmentation_limits(1 << 50).max_trace_height,
+            DEFAULT_MAX_TRACE_HEIGHT
+        );
+        // 2^30 * 0.75 / 400 bytes per row of the largest AIR is between 2^20 and 2^21 rows.
+        assert_eq!(model.segmentation_limits(1 << 30).max_trace_height, 1 << 20);
+    }
+
+    #[test]
+    fn test_segmentation_uses_budget_limits() {
+        let model = test_model();
+        let limits = model.segmentation_limits(1 << 30);
+        let mut ctx = SegmentationCtx::new(
+            vec!["A".to_string(), "B".to_string()],
+            model.main_widths.clone(),
+            vec![0, 0],
+            limits,
+        );
+        let is_constant = [false, false];
+        let max_height = limits.max_trace_height;
+        assert!(!ctx.check_and_segment(1, &mut [max_height, max_height], &is_constant));
+        assert!(ctx.check_and_segment(2, &mut [max_height + 1, max_height], &is_constant));
+        assert_eq!(ctx.segments.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_memory_size() {
+        assert_eq!("64G".parse(), Ok(MemorySize(64 << 30)));
+        assert_eq!("512MiB".parse(), Ok(MemorySize(512 << 20)));
+        assert_eq!("1.5gb".parse(), Ok(MemorySize(3 << 29)));
+        assert_eq!("4096".parse(), Ok(MemorySize(4096)));
+        assert!("64X".parse::<MemorySize>().is_err());
+        assert!("G".parse::<MemorySize>().is_err());
+    }
+}
@@ -1,7 +1,12 @@
 use clap::Args;
-use openvm_circuit::arch::DEFAULT_MAX_NUM_PUBLIC_VALUES;
+use openvm::platform::memory::{MemoryLayout, MEM_BITS};
+use openvm_circuit::arch::{
+    instructions::riscv::RV32_MEMORY_AS, GuardRegion, MemoryGuardConfig, SystemConfig,
+    DEFAULT_MAX_NUM_PUBLIC_VALUES,
+};
 use openvm_continuations::verifier::{
     common::types::VmVerifierPvs, internal::types::InternalVmVerifierPvs,
+    multi_root::types::MultiRootVmVerifierPvs,
 };
 use openvm_native_circuit::NativeConfig;
 use openvm_native_compiler::{conversion::CompilerOptions, ir::DIGEST_SIZE};
@@ -10,7 +15,9 @@ use openvm_transpiler::transpiler::Transpiler;
 use serde::{Deserialize, Serialize};
 
 mod global;
+mod memory;
 pub use global::*;
+pub use memory::*;
 
 pub const DEFAULT_APP_LOG_BLOWUP: usize = 1;
 pub const DEFAULT_LEAF_LOG_BLOWUP: usize = 1;
@@ -41,9 +48,68 @@ pub struct AppConfig<VC> {
     /// Only for AggVM debugging. App VM users should not need this in regular flow.
     #[serde(default)]
     pub compiler_options: CompilerOptions,
+    /// Guest memory layout. The default layout of `openvm_platform::memory` is used if not set.
+    /// Use [AppConfig::with_memory_layout] to keep the VM config consistent with the layout.
+    #[serde(default)]
+    pub memory_layout: Option<MemoryLayoutConfig>,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+/// Guest memory layout as specified in `openvm.toml`. Unset fields keep their default values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryLayoutConfig {
+    /// Size of the stack in bytes. The stack grows down towards `GUEST_MIN_MEM`.
+    #[serde(default)]
+    pub stack_size: Option<u32>,
+    /// Address where the program is loaded. Defaults to right above the stack.
+    #[serde(default)]
+    pub text_start: Option<u32>,
+    /// Guest memory consists of `2^mem_bits` bytes. This also sets the
+    /// `memory_config.pointer_max_bits` of the VM.
+    #[serde(default)]
+    pub mem_bits: Option<usize>,
+}
+
+impl MemoryLayoutConfig {
+    pub fn to_layout(&self) -> eyre::Result<MemoryLayout> {
+        let mem_bits = self.mem_bits.unwrap_or(MEM_BITS);
+        let mut layout = match self.stack_size {
+            Some(stack_size) => MemoryLayout::with_stack_size(mem_bits, stack_size)
+                .map_err(|e| eyre::eyre!("invalid memory layout: {e}"))?,
+            None => MemoryLayout {
+                mem_bits,
+                ..MemoryLayout::DEFAULT
+            },
+        };
+        if let Some(text_start) = self.text_start {
+            layout.text_start = text_start;
+        }
+        layout
+            .validate()
+            .map_err(|e| eyre::eyre!("invalid memory layout {layout:?}: {e}"))?;
+        Ok(layout)
+    }
+}
+
+/// Guard regions derived from the memory layout of a program in a VM whose RV32 memory has
+/// `memory_size` bytes. The stack guard covers the memory below the bottom of the stack, at
+/// `stack_top - stack_size`. The heap guard covers the memory between the end of the heap and the
+/// end of the VM memory, if the VM memory is larger than the layout.
+pub fn memory_guards_for_layout(layout: &MemoryLayout, memory_size: usize) -> MemoryGuardConfig {
+    let stack_bottom = layout.stack_top - layout.stack_size();
+    let heap_end = layout.mem_size();
+    MemoryGuardConfig {
+        stack_guard: (stack_bottom > 0).then_some(GuardRegion {
+            start: 0,
+            end: stack_bottom,
+        }),
+        heap_guard: (heap_end < memory_size).then(|| GuardRegion {
+            start: heap_end as u32,
+            end: memory_size as u32,
+        }),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AggregationConfig {
     pub max_num_user_public_values: usize,
     pub leaf_fri_params: FriParameters,
@@ -55,6 +121,11 @@ pub struct AggregationConfig {
     pub compiler_options: CompilerOptions,
     /// Max constraint degree for FRI logup chunking
     pub root_max_constraint_degree: usize,
+    /// FRI parameters of app proofs accepted in addition to the app FRI parameters of the
+    /// [AppConfig]. App proofs generated with `extra_app_fri_params[i]` use app FRI profile
+    /// `i + 1`, and the leaf and root verifiers accept all of these profiles.
+    #[serde(default)]
+    pub extra_app_fri_params: Vec<FriParameters>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -106,6 +177,7 @@ impl<VC> AppConfig<VC> {
             app_vm_config,
             leaf_fri_params: Default::default(),
             compiler_options: Default::default(),
+            memory_layout: None,
         }
     }
 
@@ -119,7 +191,54 @@ impl<VC> AppConfig<VC> {
             app_vm_config,
             leaf_fri_params: LeafFriParams::from(leaf_fri_params),
             compiler_options: Default::default(),
+            memory_layout: None,
+        }
+    }
+
+    /// The guest memory layout of the app.
+    pub fn memory_layout(&self) -> eyre::Result<MemoryLayout> {
+        self.memory_layout
+            .map_or(Ok(MemoryLayout::DEFAULT), |config| config.to_layout())
+    }
+}
+
+impl<VC: AsRef<SystemConfig>> AppConfig<VC> {
+    /// Guard regions of guest memory checked by `GenericSdk::execute`: the `memory_guards` of the
+    /// app VM config if set, and otherwise the regions given by [memory_guards_for_layout] for
+    /// [AppConfig::memory_layout].
+    pub fn memory_guards(&self) -> eyre::Result<MemoryGuardConfig> {
+        let system_config = self.app_vm_config.as_ref();
+        if let Some(memory_guards) = system_config.memory_guards {
+            return Ok(memory_guards);
+        }
+        let memory_size =
+            system_config.memory_config.addr_spaces[RV32_MEMORY_AS as usize].num_cells;
+        Ok(memory_guards_for_layout(
+            &self.memory_layout()?,
+            memory_size,
+        ))
+    }
+}
+
+impl<VC: AsMut<SystemConfig>> AppConfig<VC> {
+    /// Sets the guest memory layout and sizes the memory of the app VM to match it.
+    pub fn with_memory_layout(mut self, memory_layout: MemoryLayoutConfig) -> eyre::Result<Self> {
+        self.memory_layout = Some(memory_layout);
+        self.apply_memory_layout()?;
+        Ok(self)
+    }
+
+    /// Sizes the memory of the app VM to match [AppConfig::memory_layout]. This must be called
+    /// after deserializing an `openvm.toml` that sets `memory_layout`.
+    pub fn apply_memory_layout(&mut self) -> eyre::Result<()> {
+        if self.memory_layout.is_some() {
+            let layout = self.memory_layout()?;
+            self.app_vm_config
+                .as_mut()
+                .memory_config
+                .set_rv32_memory_bits(layout.mem_bits)?;
         }
+        Ok(())
     }
 }
 
@@ -139,6 +258,7 @@ impl Default for AggregationConfig {
             profiling: false,
             compiler_options: Default::default(),
             root_max_constraint_degree: (1 << DEFAULT_ROOT_LOG_BLOWUP) + 1,
+            extra_app_fri_params: vec![],
         }
     }
 }
@@ -198,6 +318,11 @@ impl From<FriParameters> for LeafFriParams {
 pub const SBOX_SIZE: usize = 7;
 
 impl AggregationConfig {
+    /// Number of app FRI profiles accepted by the leaf and root verifiers, including the primary
+    /// profile of the [AppConfig].
+    pub fn num_app_fri_profiles(&self) -> usize {
+        1 + self.extra_app_fri_params.len()
+    }
     pub fn leaf_vm_config(&self) -> NativeConfig {
         let mut config = NativeConfig::aggregation(
             VmVerifierPvs::<u8>::width(),
@@ -223,6 +348,16 @@ impl AggregationConfig {
         config.system.profiling = self.profiling;
         config
     }
+    /// VM config of the multi-program root verifier aggregating programs with the given numbers of
+    /// user public values.
+    pub fn multi_root_verifier_vm_config(&self, num_user_public_values: &[usize]) -> NativeConfig {
+        let mut config = NativeConfig::aggregation(
+            MultiRootVmVerifierPvs::<u8>::width(num_user_public_values),
+            SBOX_SIZE.min(self.root_fri_params.max_constraint_degree()),
+        );
+        config.system.profiling = self.profiling;
+        config
+    }
 }
 
 impl Default for AggregationTreeConfig {
@@ -17,7 +17,15 @@ pub enum SdkError {
     #[error("Transpiler error: {0}")]
     Transpiler(#[from] TranspilerError),
     #[error("VM error: {0}")]
-    Vm(#[from] VirtualMachineError),
+    Vm(VirtualMachineError),
+    #[error("Proving cancelled")]
+    Cancelled,
+    #[error("The {key} proving key was generated for a different config (expected config hash {expected}, found {actual}), regenerate it")]
+    ConfigHashMismatch {
+        key: &'static str,
+        expected: String,
+        actual: String,
+    },
     #[error("Invalid app exe commit: expected {expected}, actual {actual}")]
     InvalidAppExeCommit {
         expected: CommitBytes,
@@ -28,10 +36,29 @@ pub enum SdkError {
         expected: CommitBytes,
         actual: CommitBytes,
     },
+    #[error("Expected one proof per program ({expected}), got {actual} proofs")]
+    InvalidNumProofs { expected: usize, actual: usize },
+    #[error("Invalid number of user public values for program {program}: expected {expected}, actual {actual}")]
+    InvalidNumUserPublicValues {
+        program: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Invalid app FRI profile {actual}: only {num_profiles} profiles are accepted")]
+    InvalidAppFriProfile { num_profiles: u32, actual: u32 },
     #[error("Other error: {0}")]
     Other(eyre::Error),
 }
 
+impl From<VirtualMachineError> for SdkError {
+    fn from(error: VirtualMachineError) -> Self {
+        match error {
+            VirtualMachineError::Cancelled => SdkError::Cancelled,
+            error => SdkError::Vm(error),
+        }
+    }
+}
+
 impl From<VmVerificationError> for SdkError {
     fn from(error: VmVerificationError) -> Self {
         SdkError::Vm(error.into())
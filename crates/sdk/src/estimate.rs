@@ -0,0 +1,190 @@
+//! Estimates of the cost of proving a program, derived from metered execution and a per-machine
+//! [CostModel].
+
+use std::{fmt, time::Instant};
+
+use openvm_circuit::arch::execution_mode::Segment;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    AggregationTreeConfig, MemorySize, ProverMemoryModel, PROVER_MEMORY_TRACE_FRACTION,
+};
+
+/// Proving throughput of a machine. The defaults are rough figures for a 64 core CPU machine; use
+/// [`GenericSdk::calibrate_cost_model`](crate::GenericSdk::calibrate_cost_model) to measure them on
+/// the machine that will generate the proofs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostModel {
+    /// Main trace cells of the App VM proven per second.
+    pub app_cells_per_sec: f64,
+    /// Fixed time to prove a segment, independent of its number of cells.
+    pub app_segment_overhead_secs: f64,
+    pub leaf_proof_secs: f64,
+    pub internal_proof_secs: f64,
+    /// Time to prove the root verifier, including internal wrapper layers.
+    pub root_proof_secs: f64,
+    /// Time to prove the static verifier and wrapper circuits for an EVM proof.
+    pub halo2_proof_secs: f64,
+    /// Peak memory in bytes to generate leaf and internal proofs.
+    pub agg_peak_memory: usize,
+    /// Peak memory in bytes to generate the halo2 proofs for an EVM proof.
+    pub halo2_peak_memory: usize,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            app_cells_per_sec: 7e7,
+            app_segment_overhead_secs: 1.0,
+            leaf_proof_secs: 4.0,
+            internal_proof_secs: 5.0,
+            root_proof_secs: 15.0,
+            halo2_proof_secs: 120.0,
+            agg_peak_memory: 16 << 30,
+            halo2_peak_memory: 64 << 30,
+        }
+    }
+}
+
+impl CostModel {
+    /// Estimates the cost of proving an execution split into `segments`.
+    pub fn estimate(
+        &self,
+        segments: &[Segment],
+        memory_model: &ProverMemoryModel,
+        tree_config: &AggregationTreeConfig,
+    ) -> ProofEstimate {
+        let num_segments = segments.len();
+        let segment_cells: Vec<usize> = segments
+            .iter()
+            .map(|segment| memory_model.main_cells(&segment.trace_heights))
+            .collect();
+        let total_cells = segment_cells.iter().sum();
+        let app_secs = segment_cells
+            .iter()
+            .map(|&cells| cells as f64 / self.app_cells_per_sec + self.app_segment_overhead_secs)
+            .sum::<f64>();
+        let peak_app_memory = segments
+            .iter()
+            .map(|segment| memory_model.segment_memory(&segment.trace_heights))
+            .max()
+            .map_or(0, |memory| {
+                (memory as f64 / PROVER_MEMORY_TRACE_FRACTION) as usize
+            });
+
+        let num_leaf_proofs = num_segments.div_ceil(tree_config.num_children_leaf);
+        let (num_internal_layers, num_internal_proofs) =
+            internal_proof_counts(num_leaf_proofs, tree_config.num_children_internal);
+        let stark_secs = app_secs
+            + num_leaf_proofs as f64 * self.leaf_proof_secs
+            + num_internal_proofs as f64 * self.internal_proof_secs;
+        let evm_secs = stark_secs + self.root_proof_secs + self.halo2_proof_secs;
+
+        ProofEstimate {
+            num_segments,
+            total_cells,
+            num_leaf_proofs,
+            num_internal_layers,
+            num_internal_proofs,
+            peak_app_memory,
+            peak_stark_memory: peak_app_memory.max(self.agg_peak_memory),
+            peak_evm_memory: peak_app_memory
+                .max(self.agg_peak_memory)
+                .max(self.halo2_peak_memory),
+            app_secs,
+            stark_secs,
+            evm_secs,
+        }
+    }
+}
+
+/// Estimated cost of proving a program, see [CostModel::estimate].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProofEstimate {
+    pub num_segments: usize,
+    /// Main trace cells across all segments.
+    pub total_cells: usize,
+    pub num_leaf_proofs: usize,
+    pub num_internal_layers: usize,
+    pub num_internal_proofs: usize,
+    /// Peak memory in bytes to generate the app proof.
+    pub peak_app_memory: usize,
+    pub peak_stark_memory: usize,
+    pub peak_evm_memory: usize,
+    /// Wall-clock time in seconds to generate the app proof.
+    pub app_secs: f64,
+    /// Wall-clock time in seconds to generate the STARK proof, including the app proof.
+    pub stark_secs: f64,
+    /// Wall-clock time in seconds to generate the EVM proof, including the STARK proof.
+    pub evm_secs: f64,
+}
+
+impl fmt::Display for ProofEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Segments: {}", self.num_segments)?;
+        writeln!(f, "Total main trace cells: {}", self.total_cells)?;
+        writeln!(f, "Leaf proofs: {}", self.num_leaf_proofs)?;
+        writeln!(
+            f,
+            "Internal proofs: {} in {} layers",
+            self.num_internal_proofs, self.num_internal_layers
+        )?;
+        writeln!(
+            f,
+            "App proof:   {:>10.1}s, peak memory {}",
+            self.app_secs,
+            MemorySize(self.peak_app_memory)
+        )?;
+        writeln!(
+            f,
+            "STARK proof: {:>10.1}s, peak memory {}",
+            self.stark_secs,
+            MemorySize(self.peak_stark_memory)
+        )?;
+        write!(
+            f,
+            "EVM proof:   {:>10.1}s, peak memory {}",
+            self.evm_secs,
+            MemorySize(self.peak_evm_memory)
+        )
+    }
+}
+
+/// Returns the number of internal layers and internal proofs to aggregate `num_leaf_proofs` leaf
+/// proofs, matching [AggStarkProver](crate::prover::AggStarkProver). At least one internal proof
+/// is generated. Internal wrapper layers are not counted.
+pub fn internal_proof_counts(
+    num_leaf_proofs: usize,
+    num_children_internal: usize,
+) -> (usize, usize) {
+    let mut num_proofs = num_leaf_proofs;
+    let mut num_layers = 0;
+    let mut num_internal_proofs = 0;
+    while num_proofs > 1 || num_layers == 0 {
+        num_proofs = num_proofs.div_ceil(num_children_internal);
+        num_internal_proofs += num_proofs;
+        num_layers += 1;
+    }
+    (num_layers, num_internal_proofs)
+}
+
+/// Measures the wall-clock time of `f` in seconds.
+pub(crate) fn timed<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let start = Instant::now();
+    let ret = f();
+    (ret, start.elapsed().as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::internal_proof_counts;
+
+    #[test]
+    fn test_internal_proof_counts() {
+        assert_eq!(internal_proof_counts(1, 3), (1, 1));
+        assert_eq!(internal_proof_counts(3, 3), (1, 1));
+        assert_eq!(internal_proof_counts(4, 3), (2, 3));
+        assert_eq!(internal_proof_counts(10, 3), (3, 7));
+    }
+}
@@ -1,14 +1,18 @@
 use std::{
-    fs::{create_dir_all, read, write, File},
-    path::Path,
+    fs::{create_dir_all, read, rename, write, File},
+    path::{Path, PathBuf},
 };
 
 use eyre::{Report, Result};
+use openvm_build::ROOT_VERIFIER_ASM_FILENAME;
 #[cfg(feature = "evm-prove")]
 use openvm_native_recursion::halo2::wrapper::EvmVerifierByteCode;
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::codec::{Decode, Encode};
+use crate::{
+    codec::{Decode, Encode},
+    keygen::AggVerifyingKey,
+};
 #[cfg(feature = "evm-prove")]
 use crate::{types::EvmHalo2Verifier, OPENVM_VERSION};
 
@@ -94,6 +98,32 @@ pub fn write_evm_halo2_verifier_to_folder<P: AsRef<Path>>(
     Ok(())
 }
 
+/// Writes the root verifier ASM generated from `agg_vk` to
+/// `cache_dir/<hash>/root_verifier.asm`, where `<hash>` is
+/// [`AggVerifyingKey::root_verifier_hash`]. The ASM is only generated if it is not cached yet.
+///
+/// Returns the folder containing the ASM and the hash.
+pub fn cache_root_verifier_asm<P: AsRef<Path>>(
+    agg_vk: &AggVerifyingKey,
+    cache_dir: P,
+) -> Result<(PathBuf, String)> {
+    let hash = agg_vk.root_verifier_hash();
+    let folder = cache_dir.as_ref().join(&hash);
+    let path = folder.join(ROOT_VERIFIER_ASM_FILENAME);
+    if !path.exists() {
+        create_dir_all(&folder).map_err(|e| write_error(&path, e.into()))?;
+        let asm = agg_vk.generate_root_verifier_asm();
+        // Write to a temporary file first so concurrent builds never read a partial ASM.
+        let tmp_path = folder.join(format!(
+            "{ROOT_VERIFIER_ASM_FILENAME}.{}",
+            std::process::id()
+        ));
+        write(&tmp_path, asm).map_err(|e| write_error(&tmp_path, e.into()))?;
+        rename(&tmp_path, &path).map_err(|e| write_error(&path, e.into()))?;
+    }
+    Ok((folder, hash))
+}
+
 pub fn read_object_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
     read_from_file_bitcode(path)
 }
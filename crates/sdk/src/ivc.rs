@@ -0,0 +1,181 @@
+//! Incremental verifiable computation (IVC): proving a state machine step by step, where the guest
+//! program of each step verifies the proof of the previous step and carries its state forward.
+//!
+//! The step program uses `openvm_verify_stark::ivc` to verify the previous step with a root
+//! verifier ASM generated by
+//! [`Sdk::generate_root_verifier_asm`](crate::GenericSdk::generate_root_verifier_asm).
+//! The user public values of every step start with an [IVC_HEADER_BYTES] bytes header containing
+//! the commitments of the step program and the number of steps proven so far, followed by the
+//! state. See `openvm_verify_stark::ivc` for the layout.
+
+use openvm_circuit::arch::{
+    Executor, MeteredExecutor, PreflightExecutor, VmBuilder, VmExecutionConfig,
+};
+use openvm_continuations::verifier::internal::types::VmStarkProof;
+use openvm_native_circuit::NativeConfig;
+use openvm_native_recursion::hints::Hintable;
+use openvm_stark_backend::{p3_field::PrimeField32, proof::Proof};
+use openvm_stark_sdk::engine::StarkFriEngine;
+use serde::{Deserialize, Serialize};
+
+use crate::{commit::AppExecutionCommit, prover::StarkProver, stdin::StdIn, SdkError, F, SC};
+
+/// Number of bytes of the IVC header in the user public values.
+pub const IVC_HEADER_BYTES: usize = 68;
+
+/// Compute the hint key for `verify_openvm_stark` function, which reads a stark proof from stream
+/// `kv_store`.
+pub fn compute_hint_key_for_verify_openvm_stark(
+    asm_filename: &str,
+    exe_commit_u32: &[u32; 8],
+    vm_commit_u32: &[u32; 8],
+    pvs: &[u8],
+) -> Vec<u8> {
+    asm_filename
+        .as_bytes()
+        .iter()
+        .cloned()
+        .chain(exe_commit_u32.iter().flat_map(|x| x.to_le_bytes()))
+        .chain(vm_commit_u32.iter().flat_map(|x| x.to_le_bytes()))
+        .chain(pvs.iter().cloned())
+        .collect()
+}
+
+/// Encode a proof into a KV store value so `verify_openvm_stark` can hint it.
+pub fn encode_proof_to_kv_store_value(proof: &Proof<SC>) -> Vec<u8> {
+    let to_encode: Vec<Vec<F>> = proof.write();
+    openvm::hint_load_by_key_encode(&to_encode)
+}
+
+/// Public values of an IVC proof.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IvcPublicValues {
+    pub app_exe_commit: [u32; 8],
+    pub app_vm_commit: [u32; 8],
+    /// Number of steps attested by the proof.
+    pub num_steps: u32,
+    /// State revealed by the last step, zero padded up to the number of user public values.
+    pub state: Vec<u8>,
+}
+
+impl IvcPublicValues {
+    /// Parses the user public values of an IVC step proof. Returns `None` if there are too few
+    /// public values or they are not bytes.
+    pub fn from_user_public_values(user_public_values: &[F]) -> Option<Self> {
+        let bytes = user_public_values
+            .iter()
+            .map(|x| u8::try_from(x.as_canonical_u32()).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        if bytes.len() < IVC_HEADER_BYTES {
+            return None;
+        }
+        let word = |i: usize| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap());
+        Some(Self {
+            app_exe_commit: std::array::from_fn(word),
+            app_vm_commit: std::array::from_fn(|i| word(8 + i)),
+            num_steps: word(16),
+            state: bytes[IVC_HEADER_BYTES..].to_vec(),
+        })
+    }
+
+    /// Returns whether the header commitments are the commitments of the given program.
+    pub fn is_committed_to(&self, app_commit: &AppExecutionCommit) -> bool {
+        self.app_exe_commit == app_commit.app_exe_commit.to_u32_digest()
+            && self.app_vm_commit == app_commit.app_vm_commit.to_u32_digest()
+    }
+}
+
+/// Prover for an IVC step program. Each call to [`prove_step`](Self::prove_step) proves one step
+/// which verifies the proof of the previous step.
+pub struct IvcProver<E, VB, NativeBuilder>
+where
+    E: StarkFriEngine<SC = SC>,
+    VB: VmBuilder<E>,
+    NativeBuilder: VmBuilder<E, VmConfig = NativeConfig>,
+{
+    stark_prover: StarkProver<E, VB, NativeBuilder>,
+    /// Filename of the root verifier ASM passed to `define_verify_openvm_stark!` in the step
+    /// program. It is part of the hint key of the previous proof.
+    verifier_asm_filename: String,
+    app_commit: AppExecutionCommit,
+    last_proof: Option<VmStarkProof<SC>>,
+}
+
+impl<E, VB, NativeBuilder> IvcProver<E, VB, NativeBuilder>
+where
+    E: StarkFriEngine<SC = SC>,
+    VB: VmBuilder<E>,
+    <VB::VmConfig as VmExecutionConfig<F>>::Executor:
+        Executor<F> + MeteredExecutor<F> + PreflightExecutor<F, VB::RecordArena>,
+    NativeBuilder: VmBuilder<E, VmConfig = NativeConfig> + Clone,
+    <NativeConfig as VmExecutionConfig<F>>::Executor:
+        PreflightExecutor<F, <NativeBuilder as VmBuilder<E>>::RecordArena>,
+{
+    /// `stark_prover` must be a prover of the step program.
+    pub fn new(
+        stark_prover: StarkProver<E, VB, NativeBuilder>,
+        verifier_asm_filename: impl Into<String>,
+    ) -> Self {
+        let app_commit = stark_prover.app_commit();
+        Self {
+            stark_prover,
+            verifier_asm_filename: verifier_asm_filename.into(),
+            app_commit,
+            last_proof: None,
+        }
+    }
+
+    /// Resumes the chain from the proof of a previous step.
+    pub fn with_previous_proof(mut self, proof: VmStarkProof<SC>) -> Self {
+        self.last_proof = Some(proof);
+        self
+    }
+
+    pub fn app_commit(&self) -> AppExecutionCommit {
+        self.app_commit
+    }
+
+    /// The proof of the last proven step, which attests to all steps so far.
+    pub fn last_proof(&self) -> Option<&VmStarkProof<SC>> {
+        self.last_proof.as_ref()
+    }
+
+    pub fn into_last_proof(self) -> Option<VmStarkProof<SC>> {
+        self.last_proof
+    }
+
+    /// Proves the next step. `input` contains the inputs of the user, which the step program reads
+    /// after `openvm_verify_stark::ivc::begin_step`.
+    pub fn prove_step(&mut self, input: StdIn) -> Result<&VmStarkProof<SC>, SdkError> {
+        let exe_commit = self.app_commit.app_exe_commit.to_u32_digest();
+        let vm_commit = self.app_commit.app_vm_commit.to_u32_digest();
+
+        let mut stdin = StdIn::default();
+        stdin.write(&exe_commit);
+        stdin.write(&vm_commit);
+        match &self.last_proof {
+            Some(proof) => {
+                let pvs = proof
+                    .user_public_values
+                    .iter()
+                    .map(|x| u8::try_from(x.as_canonical_u32()))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .map_err(|_| SdkError::Other(eyre::eyre!("IVC public values must be bytes")))?;
+                let key = compute_hint_key_for_verify_openvm_stark(
+                    &self.verifier_asm_filename,
+                    &exe_commit,
+                    &vm_commit,
+                    &pvs,
+                );
+                stdin.add_key_value(key, encode_proof_to_kv_store_value(&proof.inner));
+                stdin.write(&Some(pvs));
+            }
+            None => stdin.write(&None::<Vec<u8>>),
+        }
+        stdin.buffer.extend(input.buffer);
+        stdin.kv_store.extend(input.kv_store);
+
+        let proof = self.stark_prover.prove(stdin)?;
+        Ok(self.last_proof.insert(proof))
+    }
+}
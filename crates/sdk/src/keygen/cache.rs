@@ -0,0 +1,179 @@
+//! Content-addressed cache of proving keys, keyed by a stable hash of the configuration they were
+//! generated from.
+
+use std::{
+    fs::{create_dir_all, rename},
+    path::{Path, PathBuf},
+};
+
+use openvm_stark_backend::proof::Proof;
+use openvm_stark_sdk::config::FriParameters;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "evm-prove")]
+use crate::{
+    config::Halo2Config,
+    keygen::{Halo2ProvingKey, RootVerifierProvingKey},
+};
+use crate::{
+    config::{AggregationConfig, AppConfig},
+    fs::{read_object_from_file, write_object_to_file},
+    keygen::{AggProvingKey, AppProvingKey},
+    SdkError, OPENVM_VERSION, SC,
+};
+
+const APP_PK_FILENAME: &str = "app.pk";
+const AGG_PK_FILENAME: &str = "agg.pk";
+const AGG_DUMMY_PROOF_FILENAME: &str = "agg_dummy_internal.proof";
+#[cfg(feature = "evm-prove")]
+const HALO2_PK_FILENAME: &str = "halo2.pk";
+
+/// Hex encoded SHA-256 hash of the serialized `config`, domain separated by `kind` and the OpenVM
+/// version. Segmentation limits are not serialized, so they do not affect the hash.
+fn config_hash(kind: &str, config: &impl Serialize, extra: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(kind.as_bytes());
+    hasher.update(OPENVM_VERSION.as_bytes());
+    hasher.update(bitcode::serialize(config).expect("failed to serialize config"));
+    hasher.update(extra);
+    hex::encode(hasher.finalize())
+}
+
+/// Hash of the App VM config and the extra app FRI parameters accepted by the leaf verifier, stored
+/// in the [AppProvingKey] generated from them.
+pub fn app_config_hash<VC: Serialize>(
+    config: &AppConfig<VC>,
+    extra_app_fri_params: &[FriParameters],
+) -> String {
+    let extra = bitcode::serialize(extra_app_fri_params).expect("failed to serialize FRI params");
+    config_hash("app", config, &extra)
+}
+
+/// Hash of the aggregation config, stored in the [AggProvingKey] generated from it.
+pub fn agg_config_hash(config: &AggregationConfig) -> String {
+    config_hash("agg", config, &[])
+}
+
+/// Hash of the halo2 config and the root verifier that the halo2 circuits verify, stored in the
+/// [Halo2ProvingKey] generated from them.
+#[cfg(feature = "evm-prove")]
+pub fn halo2_config_hash(
+    config: &Halo2Config,
+    root_verifier_pk: &RootVerifierProvingKey,
+) -> String {
+    let root_commit = root_verifier_pk.root_committed_exe.get_program_commit();
+    let root_commit =
+        bitcode::serialize(&root_commit).expect("failed to serialize root verifier commit");
+    config_hash("halo2", config, &root_commit)
+}
+
+/// Returns [SdkError::ConfigHashMismatch] if the `key` proving key was generated for a config
+/// with a different hash than `expected`.
+pub(crate) fn check_config_hash(
+    key: &'static str,
+    expected: String,
+    actual: &str,
+) -> Result<(), SdkError> {
+    if expected != actual {
+        return Err(SdkError::ConfigHashMismatch {
+            key,
+            expected,
+            actual: actual.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Directory of proving keys, where the keys generated from a config are stored in the
+/// subdirectory named after the config hash. Keys are written to a temporary file and renamed, so
+/// concurrent processes never observe a partially written key.
+#[derive(Clone, Debug)]
+pub struct KeyCache {
+    dir: PathBuf,
+}
+
+impl KeyCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Directory of the keys generated from the config with hash `config_hash`.
+    pub fn key_dir(&self, config_hash: &str) -> PathBuf {
+        self.dir.join(config_hash)
+    }
+
+    pub fn load_app_pk<VC: DeserializeOwned>(
+        &self,
+        config_hash: &str,
+    ) -> Option<AppProvingKey<VC>> {
+        let pk: AppProvingKey<VC> = self.load(config_hash, APP_PK_FILENAME)?;
+        (pk.config_hash == config_hash).then_some(pk)
+    }
+
+    pub fn store_app_pk<VC: Serialize>(&self, pk: &AppProvingKey<VC>) -> Result<(), SdkError> {
+        self.store(&pk.config_hash, APP_PK_FILENAME, pk)
+    }
+
+    /// Loads the aggregation proving key and the dummy internal proof generated with it.
+    pub fn load_agg_pk(&self, config_hash: &str) -> Option<(AggProvingKey, Proof<SC>)> {
+        let pk: AggProvingKey = self.load(config_hash, AGG_PK_FILENAME)?;
+        let dummy_proof = self.load(config_hash, AGG_DUMMY_PROOF_FILENAME)?;
+        (pk.config_hash == config_hash).then_some((pk, dummy_proof))
+    }
+
+    pub fn store_agg_pk(
+        &self,
+        pk: &AggProvingKey,
+        dummy_proof: &Proof<SC>,
+    ) -> Result<(), SdkError> {
+        self.store(&pk.config_hash, AGG_DUMMY_PROOF_FILENAME, dummy_proof)?;
+        self.store(&pk.config_hash, AGG_PK_FILENAME, pk)
+    }
+
+    #[cfg(feature = "evm-prove")]
+    pub fn load_halo2_pk(&self, config_hash: &str) -> Option<Halo2ProvingKey> {
+        let pk: Halo2ProvingKey = self.load(config_hash, HALO2_PK_FILENAME)?;
+        (pk.config_hash == config_hash).then_some(pk)
+    }
+
+    #[cfg(feature = "evm-prove")]
+    pub fn store_halo2_pk(&self, pk: &Halo2ProvingKey) -> Result<(), SdkError> {
+        self.store(&pk.config_hash, HALO2_PK_FILENAME, pk)
+    }
+
+    fn load<T: DeserializeOwned>(&self, config_hash: &str, filename: &str) -> Option<T> {
+        let path = self.key_dir(config_hash).join(filename);
+        if !path.exists() {
+            return None;
+        }
+        match read_object_from_file(&path) {
+            Ok(key) => {
+                tracing::info!("loaded {} from key cache", path.display());
+                Some(key)
+            }
+            Err(e) => {
+                tracing::warn!("ignoring unreadable cached key: {e}");
+                None
+            }
+        }
+    }
+
+    fn store<T: Serialize>(
+        &self,
+        config_hash: &str,
+        filename: &str,
+        key: &T,
+    ) -> Result<(), SdkError> {
+        let dir = self.key_dir(config_hash);
+        create_dir_all(&dir)?;
+        let tmp_path = dir.join(format!("{filename}.{}.tmp", std::process::id()));
+        write_object_to_file(&tmp_path, key).map_err(SdkError::Other)?;
+        rename(&tmp_path, dir.join(filename))?;
+        Ok(())
+    }
+}
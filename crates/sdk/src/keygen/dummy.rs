@@ -16,10 +16,8 @@ use openvm_circuit::{
 use openvm_continuations::verifier::{
     internal::types::InternalVmVerifierInput,
     leaf::{types::LeafVmVerifierInput, LeafVmVerifierConfig},
-    root::types::RootVmVerifierInput,
 };
 use openvm_native_circuit::{NativeConfig, NativeCpuBuilder, NATIVE_MAX_TRACE_HEIGHTS};
-use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::hints::Hintable;
 use openvm_rv32im_circuit::{Rv32ImConfig, Rv32ImCpuBuilder};
 use openvm_stark_backend::{
@@ -45,8 +43,9 @@ use crate::{
     F, SC,
 };
 
-/// Given a dummy internal proof, which is the input to the root verifier circuit, we will run
-/// tracegen on the root verifier circuit to determine the trace heights. These trace heights will
+/// Given the input stream of a dummy input to the root verifier circuit, e.g. a
+/// `RootVmVerifierInput` containing a dummy internal proof, we will run tracegen on the root
+/// verifier circuit to determine the trace heights. These trace heights will
 /// become the fixed trace heights that we **force** the root verifier circuit's trace matrices to
 /// have.
 ///
@@ -57,19 +56,14 @@ use crate::{
 pub(super) fn compute_root_proof_heights(
     root_vm: &mut VirtualMachine<BabyBearPoseidon2RootEngine, NativeCpuBuilder>,
     root_committed_exe: &VmCommittedExe<BabyBearPoseidon2RootConfig>,
-    dummy_internal_proof: &Proof<SC>,
+    root_input: Vec<Vec<F>>,
 ) -> Result<Vec<u32>, VirtualMachineError> {
     let num_public_values = root_vm.config().as_ref().num_public_values;
-    let num_user_public_values = num_public_values - 2 * DIGEST_SIZE;
-    let root_input = RootVmVerifierInput {
-        proofs: vec![dummy_internal_proof.clone()],
-        public_values: vec![F::ZERO; num_user_public_values],
-    };
     // The following is the same as impl SingleSegmentVmProver for VmLocalProver except we stop
     // after tracegen:
     let mut trace_heights = NATIVE_MAX_TRACE_HEIGHTS.to_vec();
     trace_heights[PUBLIC_VALUES_AIR_ID] = num_public_values as u32;
-    let state = root_vm.create_initial_state(&root_committed_exe.exe, root_input.write());
+    let state = root_vm.create_initial_state(&root_committed_exe.exe, root_input);
     let cached_program_trace = root_vm.transport_committed_exe_to_device(root_committed_exe);
     root_vm.load_program(cached_program_trace);
     root_vm.transport_init_memory_to_device(&state.memory);
@@ -147,6 +141,7 @@ where
         app_fri_params: app_vm_pk.fri_params,
         app_system_config: app_vm_pk.vm_config.as_ref().clone(),
         compiler_options: Default::default(),
+        extra_app_profiles: vec![],
     }
     .build_program(&app_vm_pk.vm_pk.get_vk());
     assert_eq!(
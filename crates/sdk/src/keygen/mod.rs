@@ -1,20 +1,30 @@
-use std::sync::Arc;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::Arc,
+};
 
 use derivative::Derivative;
 // use dummy::{compute_root_proof_heights, dummy_internal_proof_riscv_app_vm};
 use openvm_circuit::{
-    arch::{AirInventoryError, SystemConfig, VirtualMachine, VirtualMachineError, VmCircuitConfig},
+    arch::{
+        execution_mode::metered::segment_ctx::SegmentationLimits, instructions::program::Program,
+        AirInventoryError, SystemConfig, VirtualMachine, VirtualMachineError, VmCircuitConfig,
+    },
     system::memory::dimensions::MemoryDimensions,
 };
 use openvm_continuations::verifier::{
-    internal::InternalVmVerifierConfig, leaf::LeafVmVerifierConfig, root::RootVmVerifierConfig,
+    internal::InternalVmVerifierConfig,
+    leaf::{AppFriProfile, LeafVmVerifierConfig},
+    multi_root::{types::MultiRootVmVerifierInput, MultiRootVmVerifierConfig},
+    root::{types::RootVmVerifierInput, RootVmVerifierConfig},
 };
 use openvm_native_circuit::{NativeConfig, NativeCpuBuilder};
 use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_native_recursion::hints::Hintable;
 use openvm_stark_backend::{
     config::Val,
     engine::StarkEngine,
-    p3_field::{FieldExtensionAlgebra, PrimeField32, TwoAdicField},
+    p3_field::{FieldAlgebra, FieldExtensionAlgebra, PrimeField32, TwoAdicField},
 };
 use openvm_stark_sdk::{
     config::{
@@ -29,10 +39,13 @@ use openvm_stark_sdk::{
     },
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tracing::{info_span, instrument};
 #[cfg(feature = "evm-prove")]
 use {
-    openvm_continuations::static_verifier::StaticVerifierPvHandler,
+    openvm_continuations::static_verifier::{
+        MultiProgramStaticVerifierPvHandler, StaticVerifierPvHandler,
+    },
     openvm_native_recursion::halo2::{
         utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey,
         wrapper::Halo2WrapperProvingKey,
@@ -43,17 +56,19 @@ use {
 use crate::config::Halo2Config;
 use crate::{
     commit::VmCommittedExe,
-    config::{AggregationConfig, AppConfig},
+    config::{AggregationConfig, AppConfig, MemorySize, ProverMemoryModel},
     keygen::{
+        asm::program_to_asm,
         dummy::{compute_root_proof_heights, dummy_internal_proof_riscv_app_vm},
         perm::AirIdPermutation,
     },
     prover::vm::types::VmProvingKey,
     util::check_max_constraint_degrees,
-    RootSC, SC,
+    RootSC, F, SC,
 };
 
 pub mod asm;
+pub mod cache;
 pub(crate) mod dummy;
 pub mod perm;
 #[cfg(feature = "evm-prove")]
@@ -67,6 +82,13 @@ pub struct AppProvingKey<VC> {
     pub leaf_committed_exe: Arc<VmCommittedExe<SC>>,
     pub leaf_fri_params: FriParameters,
     pub app_vm_pk: Arc<VmProvingKey<SC, VC>>,
+    /// App VM proving keys of the extra app FRI profiles accepted by the leaf verifier, see
+    /// [AggregationConfig::extra_app_fri_params]. The proving key of profile `i + 1` is
+    /// `extra_app_vm_pks[i]`.
+    #[serde(default)]
+    pub extra_app_vm_pks: Vec<Arc<VmProvingKey<SC, VC>>>,
+    /// Hash of the [AppConfig] this key was generated from, see [cache::app_config_hash].
+    pub config_hash: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -88,6 +110,12 @@ pub struct AggProvingKey {
     pub internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     pub internal_committed_exe: Arc<VmCommittedExe<SC>>,
     pub root_verifier_pk: RootVerifierProvingKey,
+    /// Number of app FRI profiles accepted by the root verifier, see
+    /// [AggregationConfig::num_app_fri_profiles].
+    pub num_app_fri_profiles: usize,
+    /// Hash of the [AggregationConfig] this key was generated from, see
+    /// [cache::agg_config_hash].
+    pub config_hash: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -98,6 +126,10 @@ pub struct AggVerifyingKey {
     pub(super) internal_fri_params: FriParameters,
     pub(super) internal_vk: MultiStarkVerifyingKey<SC>,
     pub(super) internal_verifier_program_commit: Com<SC>,
+    /// Number of user public values of the root verifier of the aggregation key.
+    pub(super) num_user_public_values: usize,
+    /// Number of app FRI profiles accepted by the root verifier of the aggregation key.
+    pub(super) num_app_fri_profiles: usize,
 }
 
 /// Attention: the serialized size of this struct is VERY large, usually >10GB.
@@ -113,41 +145,49 @@ pub struct Halo2ProvingKey {
     pub wrapper: Arc<Halo2WrapperProvingKey>,
     /// Whether to collect detailed profiling metrics
     pub profiling: bool,
+    /// Hash of the [Halo2Config] and root verifier this key was generated from, see
+    /// [cache::halo2_config_hash].
+    pub config_hash: String,
 }
 
 impl<VC> AppProvingKey<VC>
 where
     VC: Clone + VmCircuitConfig<SC> + AsRef<SystemConfig>,
 {
-    pub fn keygen(config: AppConfig<VC>) -> Result<Self, AirInventoryError> {
-        let app_engine = BabyBearPoseidon2Engine::new(config.app_fri_params.fri_params);
-        let app_vm_pk = {
-            let vm_pk = config.app_vm_config.create_airs()?.keygen(&app_engine);
-            assert!(
-                vm_pk.max_constraint_degree
-                    <= config.app_fri_params.fri_params.max_constraint_degree()
-            );
-            check_max_constraint_degrees(
-                config.app_vm_config.as_ref(),
-                &config.app_fri_params.fri_params,
-            );
-            VmProvingKey {
-                fri_params: config.app_fri_params.fri_params,
-                vm_config: config.app_vm_config.clone(),
-                vm_pk,
-            }
-        };
-        check_recursive_verifier_size(
-            &app_vm_pk.vm_pk.get_vk(),
-            config.app_fri_params.fri_params,
-            config.leaf_fri_params.fri_params.log_blowup,
-        );
+    pub fn keygen(config: AppConfig<VC>) -> Result<Self, AirInventoryError>
+    where
+        VC: Serialize,
+    {
+        Self::keygen_with_extra_app_fri_params(config, &[])
+    }
+
+    /// Same as [keygen](Self::keygen), but the leaf verifier also accepts app proofs generated
+    /// with any of `extra_app_fri_params`, see [AggregationConfig::extra_app_fri_params].
+    pub fn keygen_with_extra_app_fri_params(
+        config: AppConfig<VC>,
+        extra_app_fri_params: &[FriParameters],
+    ) -> Result<Self, AirInventoryError>
+    where
+        VC: Serialize,
+    {
+        let app_vm_pk = app_vm_keygen(&config, config.app_fri_params.fri_params)?;
+        let extra_app_vm_pks = extra_app_fri_params
+            .iter()
+            .map(|&fri_params| app_vm_keygen(&config, fri_params).map(Arc::new))
+            .collect::<Result<Vec<_>, _>>()?;
         let leaf_committed_exe = {
             let leaf_engine = BabyBearPoseidon2Engine::new(config.leaf_fri_params.fri_params);
             let leaf_program = LeafVmVerifierConfig {
                 app_fri_params: config.app_fri_params.fri_params,
                 app_system_config: config.app_vm_config.as_ref().clone(),
                 compiler_options: config.compiler_options,
+                extra_app_profiles: extra_app_vm_pks
+                    .iter()
+                    .map(|pk| AppFriProfile {
+                        fri_params: pk.fri_params,
+                        app_vm_vk: pk.vm_pk.get_vk(),
+                    })
+                    .collect(),
             }
             .build_program(&app_vm_pk.vm_pk.get_vk());
             Arc::new(VmCommittedExe::commit(
@@ -159,9 +199,24 @@ where
             leaf_committed_exe,
             leaf_fri_params: config.leaf_fri_params.fri_params,
             app_vm_pk: Arc::new(app_vm_pk),
+            extra_app_vm_pks,
+            config_hash: cache::app_config_hash(&config, extra_app_fri_params),
         })
     }
 
+    /// Number of app FRI profiles accepted by the leaf verifier of this proving key.
+    pub fn num_app_fri_profiles(&self) -> usize {
+        1 + self.extra_app_vm_pks.len()
+    }
+
+    /// App VM proving key of the app FRI profile `fri_profile`, if the leaf verifier accepts it.
+    pub fn app_vm_pk_for_profile(&self, fri_profile: usize) -> Option<&Arc<VmProvingKey<SC, VC>>> {
+        match fri_profile {
+            0 => Some(&self.app_vm_pk),
+            _ => self.extra_app_vm_pks.get(fri_profile - 1),
+        }
+    }
+
     pub fn num_public_values(&self) -> usize {
         self.app_vm_pk.vm_config.as_ref().num_public_values
     }
@@ -197,8 +252,60 @@ where
             app_vm_config: self.vm_config().clone(),
             leaf_fri_params: self.leaf_fri_params.into(),
             compiler_options: Default::default(),
+            memory_layout: None,
         }
     }
+
+    /// Memory model of the App VM prover with this proving key.
+    pub fn prover_memory_model(&self) -> ProverMemoryModel {
+        ProverMemoryModel::new(&self.app_vm_pk.vm_pk, self.app_fri_params().log_blowup)
+    }
+}
+
+impl<VC> AppProvingKey<VC>
+where
+    VC: Clone + VmCircuitConfig<SC> + AsRef<SystemConfig> + AsMut<SystemConfig>,
+{
+    /// Derives the segmentation limits of the App VM from the [ProverMemoryModel] so that proving
+    /// a segment fits in `max_memory` bytes, and sets them in the App VM config.
+    pub fn set_max_prover_memory(&mut self, max_memory: usize) -> SegmentationLimits {
+        let limits = self.prover_memory_model().segmentation_limits(max_memory);
+        tracing::info!(
+            "segmentation limits for a prover memory budget of {}: max_trace_height={}, max_cells={}",
+            MemorySize(max_memory),
+            limits.max_trace_height,
+            limits.max_cells
+        );
+        Arc::make_mut(&mut self.app_vm_pk)
+            .vm_config
+            .as_mut()
+            .set_segmentation_limits(limits);
+        limits
+    }
+}
+
+/// Keygen of the App VM of `config` with the FRI parameters `fri_params`.
+fn app_vm_keygen<VC>(
+    config: &AppConfig<VC>,
+    fri_params: FriParameters,
+) -> Result<VmProvingKey<SC, VC>, AirInventoryError>
+where
+    VC: Clone + VmCircuitConfig<SC> + AsRef<SystemConfig>,
+{
+    let app_engine = BabyBearPoseidon2Engine::new(fri_params);
+    let vm_pk = config.app_vm_config.create_airs()?.keygen(&app_engine);
+    assert!(vm_pk.max_constraint_degree <= fri_params.max_constraint_degree());
+    check_max_constraint_degrees(config.app_vm_config.as_ref(), &fri_params);
+    check_recursive_verifier_size(
+        &vm_pk.get_vk(),
+        fri_params,
+        config.leaf_fri_params.fri_params.log_blowup,
+    );
+    Ok(VmProvingKey {
+        fri_params,
+        vm_config: config.app_vm_config.clone(),
+        vm_pk,
+    })
 }
 
 /// Try to determine statically if there will be an issue with the recursive verifier size and log
@@ -363,56 +470,29 @@ impl AggProvingKey {
             config.max_num_user_public_values,
         )?;
 
-        let root_verifier_pk = {
-            let mut root_engine = BabyBearPoseidon2RootEngine::new(config.root_fri_params);
-            root_engine.max_constraint_degree = config.root_max_constraint_degree;
-            let root_program = RootVmVerifierConfig {
-                leaf_fri_params: config.leaf_fri_params,
-                internal_fri_params: config.internal_fri_params,
-                num_user_public_values: config.max_num_user_public_values,
-                internal_vm_verifier_commit: internal_committed_exe.get_program_commit().into(),
-                compiler_options: config.compiler_options,
-            }
-            .build_program(&leaf_vm_vk, &internal_vm_vk);
-            let (mut vm, mut vm_pk) = VirtualMachine::new_with_keygen(
-                root_engine,
-                NativeCpuBuilder,
-                root_vm_config.clone(),
-            )?;
-            let root_committed_exe = Arc::new(VmCommittedExe::commit(
-                root_program.into(),
-                vm.engine.config().pcs(),
-            ));
-
-            assert!(vm_pk.max_constraint_degree <= config.root_fri_params.max_constraint_degree());
-
-            let air_heights =
-                compute_root_proof_heights(&mut vm, &root_committed_exe, &internal_proof)?;
-            let root_air_perm = AirIdPermutation::compute(&air_heights);
-            // ATTENTION: make sure to permute everything in vm_pk that references the original AIR
-            // ID ordering:
-            root_air_perm.permute(&mut vm_pk.per_air);
-            #[cfg(not(feature = "legacy-v1-3-evm-verifier"))]
-            for thc in &mut vm_pk.trace_height_constraints {
-                root_air_perm.permute(&mut thc.coefficients);
-            }
-
-            RootVerifierProvingKey {
-                vm_pk: Arc::new(VmProvingKey {
-                    fri_params: config.root_fri_params,
-                    vm_config: root_vm_config,
-                    vm_pk,
-                }),
-                root_committed_exe,
-                air_heights,
-            }
+        let root_program = RootVmVerifierConfig {
+            leaf_fri_params: config.leaf_fri_params,
+            internal_fri_params: config.internal_fri_params,
+            num_user_public_values: config.max_num_user_public_values,
+            internal_vm_verifier_commit: internal_committed_exe.get_program_commit().into(),
+            compiler_options: config.compiler_options,
+            num_app_fri_profiles: config.num_app_fri_profiles(),
+        }
+        .build_program(&leaf_vm_vk, &internal_vm_vk);
+        let root_input = RootVmVerifierInput {
+            proofs: vec![internal_proof.clone()],
+            public_values: vec![F::ZERO; config.max_num_user_public_values],
         };
+        let root_verifier_pk =
+            root_verifier_keygen(&config, root_vm_config, root_program, root_input.write())?;
         Ok((
             Self {
                 leaf_vm_pk,
                 internal_vm_pk,
                 internal_committed_exe,
                 root_verifier_pk,
+                num_app_fri_profiles: config.num_app_fri_profiles(),
+                config_hash: cache::agg_config_hash(&config),
             },
             internal_proof,
         ))
@@ -430,6 +510,8 @@ impl AggProvingKey {
             internal_fri_params,
             internal_vk,
             internal_verifier_program_commit,
+            num_user_public_values: self.num_user_public_values(),
+            num_app_fri_profiles: self.num_app_fri_profiles,
         }
     }
 
@@ -441,6 +523,140 @@ impl AggProvingKey {
             .num_public_values
             - (2 * DIGEST_SIZE)
     }
+
+    /// Generates the proving key of a multi-program root verifier, which aggregates one proof per
+    /// program into a single root proof. Program `i` must have `num_user_public_values[i]` user
+    /// public values. The App VM of each program may use any App VM config whose leaf verifier
+    /// runs on the leaf VM of this proving key.
+    ///
+    /// `config` must be the config this proving key was generated from. Also returns a dummy input
+    /// of the multi-program root verifier, which is needed for halo2 keygen.
+    #[tracing::instrument(level = "info", fields(group = "multi_root_keygen"), skip_all)]
+    pub fn multi_root_keygen(
+        &self,
+        config: &AggregationConfig,
+        num_user_public_values: Vec<usize>,
+    ) -> Result<(MultiRootVerifierProvingKey, MultiRootVmVerifierInput<SC>), VirtualMachineError>
+    {
+        let leaf_vm_vk = self.leaf_vm_pk.vm_pk.get_vk();
+        let internal_vm_vk = self.internal_vm_pk.vm_pk.get_vk();
+        let root_program = MultiRootVmVerifierConfig {
+            leaf_fri_params: config.leaf_fri_params,
+            internal_fri_params: config.internal_fri_params,
+            num_user_public_values: num_user_public_values.clone(),
+            internal_vm_verifier_commit: self.internal_committed_exe.get_program_commit().into(),
+            compiler_options: config.compiler_options,
+            num_app_fri_profiles: self.num_app_fri_profiles,
+        }
+        .build_program(&leaf_vm_vk, &internal_vm_vk);
+
+        // Dummy internal proofs only depend on the number of user public values.
+        let mut dummy_internal_proofs = BTreeMap::new();
+        let mut proofs = Vec::with_capacity(num_user_public_values.len());
+        for &num_public_values in &num_user_public_values {
+            let proof = match dummy_internal_proofs.entry(num_public_values) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(dummy_internal_proof_riscv_app_vm(
+                    self.leaf_vm_pk.clone(),
+                    self.internal_vm_pk.clone(),
+                    self.internal_committed_exe.clone(),
+                    num_public_values,
+                )?),
+            };
+            proofs.push(proof.clone());
+        }
+        let dummy_input = MultiRootVmVerifierInput {
+            proofs,
+            public_values: vec![F::ZERO; num_user_public_values.iter().sum()],
+        };
+        let root_verifier_pk = root_verifier_keygen(
+            config,
+            config.multi_root_verifier_vm_config(&num_user_public_values),
+            root_program,
+            dummy_input.write(),
+        )?;
+        Ok((
+            MultiRootVerifierProvingKey {
+                root_verifier_pk,
+                num_user_public_values,
+            },
+            dummy_input,
+        ))
+    }
+}
+
+impl AggVerifyingKey {
+    pub fn num_user_public_values(&self) -> usize {
+        self.num_user_public_values
+    }
+
+    pub fn num_app_fri_profiles(&self) -> usize {
+        self.num_app_fri_profiles
+    }
+
+    /// Generates the ASM of the root verifier for
+    /// `openvm_verify_stark::define_verify_openvm_stark!`, which verifies aggregate STARK
+    /// proofs of this aggregation key in a guest program.
+    pub fn generate_root_verifier_asm(&self) -> String {
+        let kernel_asm = RootVmVerifierConfig {
+            leaf_fri_params: self.leaf_fri_params,
+            internal_fri_params: self.internal_fri_params,
+            num_user_public_values: self.num_user_public_values,
+            internal_vm_verifier_commit: self.internal_verifier_program_commit.into(),
+            compiler_options: Default::default(),
+            num_app_fri_profiles: self.num_app_fri_profiles,
+        }
+        .build_kernel_asm(&self.leaf_vk, &self.internal_vk);
+        program_to_asm(kernel_asm)
+    }
+
+    /// Hex encoded SHA-256 hash identifying the root verifier generated by
+    /// [generate_root_verifier_asm](Self::generate_root_verifier_asm).
+    pub fn root_verifier_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(bitcode::serialize(self).expect("failed to serialize AggVerifyingKey"));
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Keygen for a root verifier program. The fixed trace heights of the root verifier are determined
+/// by executing `root_program` on the `dummy_input` stream.
+fn root_verifier_keygen(
+    config: &AggregationConfig,
+    root_vm_config: NativeConfig,
+    root_program: Program<F>,
+    dummy_input: Vec<Vec<F>>,
+) -> Result<RootVerifierProvingKey, VirtualMachineError> {
+    let mut root_engine = BabyBearPoseidon2RootEngine::new(config.root_fri_params);
+    root_engine.max_constraint_degree = config.root_max_constraint_degree;
+    let (mut vm, mut vm_pk) =
+        VirtualMachine::new_with_keygen(root_engine, NativeCpuBuilder, root_vm_config.clone())?;
+    let root_committed_exe = Arc::new(VmCommittedExe::commit(
+        root_program.into(),
+        vm.engine.config().pcs(),
+    ));
+
+    assert!(vm_pk.max_constraint_degree <= config.root_fri_params.max_constraint_degree());
+
+    let air_heights = compute_root_proof_heights(&mut vm, &root_committed_exe, dummy_input)?;
+    let root_air_perm = AirIdPermutation::compute(&air_heights);
+    // ATTENTION: make sure to permute everything in vm_pk that references the original AIR
+    // ID ordering:
+    root_air_perm.permute(&mut vm_pk.per_air);
+    #[cfg(not(feature = "legacy-v1-3-evm-verifier"))]
+    for thc in &mut vm_pk.trace_height_constraints {
+        root_air_perm.permute(&mut thc.coefficients);
+    }
+
+    Ok(RootVerifierProvingKey {
+        vm_pk: Arc::new(VmProvingKey {
+            fri_params: config.root_fri_params,
+            vm_config: root_vm_config,
+            vm_pk,
+        }),
+        root_committed_exe,
+        air_heights,
+    })
 }
 
 /// Proving key for the root verifier.
@@ -461,13 +677,62 @@ pub struct RootVerifierProvingKey {
     pub air_heights: Vec<u32>,
 }
 
-#[cfg(feature = "evm-prove")]
 impl RootVerifierProvingKey {
     pub(crate) fn air_id_permutation(&self) -> AirIdPermutation {
         AirIdPermutation::compute(&self.air_heights)
     }
 }
 
+/// Proving key for the multi-program root verifier, which aggregates the proofs of several
+/// programs into a single root proof. See [AggProvingKey::multi_root_keygen].
+///
+/// This is lightweight to clone as it contains smart pointers to the proving keys.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MultiRootVerifierProvingKey {
+    /// Root verifier proving key for the multi-program root verifier program.
+    pub root_verifier_pk: RootVerifierProvingKey,
+    /// Number of user public values of each program, in program order.
+    pub num_user_public_values: Vec<usize>,
+}
+
+/// Verifying key for proofs of the multi-program root verifier.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MultiRootVerifyingKey {
+    pub(super) fri_params: FriParameters,
+    /// AIRs are ordered by the fixed trace heights of the root verifier.
+    pub(super) vk: MultiStarkVerifyingKey<RootSC>,
+    pub(super) program_commit: Com<RootSC>,
+    pub(super) program_air_id: usize,
+    pub(super) connector_air_id: usize,
+    pub(super) public_values_air_id: usize,
+    pub(super) num_user_public_values: Vec<usize>,
+}
+
+impl MultiRootVerifierProvingKey {
+    pub fn num_programs(&self) -> usize {
+        self.num_user_public_values.len()
+    }
+
+    pub fn get_vk(&self) -> MultiRootVerifyingKey {
+        let special_air_ids = self
+            .root_verifier_pk
+            .air_id_permutation()
+            .get_special_air_ids();
+        MultiRootVerifyingKey {
+            fri_params: self.root_verifier_pk.vm_pk.fri_params,
+            vk: self.root_verifier_pk.vm_pk.vm_pk.get_vk(),
+            program_commit: self
+                .root_verifier_pk
+                .root_committed_exe
+                .get_program_commit(),
+            program_air_id: special_air_ids.program_air_id,
+            connector_air_id: special_air_ids.connector_air_id,
+            public_values_air_id: special_air_ids.public_values_air_id,
+            num_user_public_values: self.num_user_public_values.clone(),
+        }
+    }
+}
+
 #[cfg(feature = "evm-prove")]
 impl Halo2ProvingKey {
     /// Attention:
@@ -484,7 +749,50 @@ impl Halo2ProvingKey {
         let dummy_root_proof = agg_pk
             .root_verifier_pk
             .generate_dummy_root_proof(dummy_internal_proof)?;
-        let verifier = agg_pk.root_verifier_pk.keygen_static_verifier(
+        Ok(Self::keygen_for_root_verifier(
+            halo2_config,
+            reader,
+            pv_handler,
+            &agg_pk.root_verifier_pk,
+            dummy_root_proof,
+        ))
+    }
+
+    /// Keygen the halo2 circuits for a multi-program root verifier. The public values of the EVM
+    /// proof are defined by [MultiProgramStaticVerifierPvHandler]. `dummy_input` is the dummy input
+    /// returned by [AggProvingKey::multi_root_keygen].
+    ///
+    /// Attention: this function is as expensive as [Halo2ProvingKey::keygen].
+    #[tracing::instrument(level = "info", fields(group = "halo2_keygen"), skip_all)]
+    pub fn keygen_multi_root(
+        halo2_config: Halo2Config,
+        reader: &impl Halo2ParamsReader,
+        multi_root_pk: &MultiRootVerifierProvingKey,
+        dummy_input: MultiRootVmVerifierInput<SC>,
+    ) -> Result<Self, VirtualMachineError> {
+        let dummy_root_proof = multi_root_pk
+            .root_verifier_pk
+            .generate_root_proof_for_input(&dummy_input)?;
+        let pv_handler = MultiProgramStaticVerifierPvHandler {
+            num_user_public_values: multi_root_pk.num_user_public_values.clone(),
+        };
+        Ok(Self::keygen_for_root_verifier(
+            halo2_config,
+            reader,
+            &pv_handler,
+            &multi_root_pk.root_verifier_pk,
+            dummy_root_proof,
+        ))
+    }
+
+    fn keygen_for_root_verifier(
+        halo2_config: Halo2Config,
+        reader: &impl Halo2ParamsReader,
+        pv_handler: &impl StaticVerifierPvHandler,
+        root_verifier_pk: &RootVerifierProvingKey,
+        dummy_root_proof: Proof<RootSC>,
+    ) -> Self {
+        let verifier = root_verifier_pk.keygen_static_verifier(
             &reader.read_params(halo2_config.verifier_k),
             dummy_root_proof,
             pv_handler,
@@ -495,11 +803,12 @@ impl Halo2ProvingKey {
         } else {
             Halo2WrapperProvingKey::keygen_auto_tune(reader, dummy_snark)
         };
-        Ok(Halo2ProvingKey {
+        Halo2ProvingKey {
             verifier: Arc::new(verifier),
             wrapper: Arc::new(wrapper),
             profiling: halo2_config.profiling,
-        })
+            config_hash: cache::halo2_config_hash(&halo2_config, root_verifier_pk),
+        }
     }
 }
 
@@ -1,6 +1,5 @@
 use std::cmp::Reverse;
 
-#[cfg(feature = "evm-prove")]
 use openvm_continuations::verifier::common::types::SpecialAirIds;
 
 /// Permutation of the AIR IDs to order them by forced trace heights.
@@ -19,7 +18,6 @@ impl AirIdPermutation {
                 .collect(),
         }
     }
-    #[cfg(feature = "evm-prove")]
     pub fn get_special_air_ids(&self) -> SpecialAirIds {
         use openvm_circuit::arch::{CONNECTOR_AIR_ID, PROGRAM_AIR_ID, PUBLIC_VALUES_AIR_ID};
 
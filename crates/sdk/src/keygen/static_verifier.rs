@@ -4,15 +4,18 @@ use openvm_continuations::{
     verifier::root::types::RootVmVerifierInput,
 };
 use openvm_native_circuit::NATIVE_MAX_TRACE_HEIGHTS;
-use openvm_native_compiler::prelude::*;
+use openvm_native_compiler::{constraints::halo2::profile::ConstraintProfile, prelude::*};
+#[cfg(feature = "groth16")]
+use openvm_native_recursion::groth16::Groth16ProvingKey;
 use openvm_native_recursion::{
-    halo2::{verifier::Halo2VerifierProvingKey, Halo2Params, Halo2Prover},
+    config::outer::OuterConfig,
+    halo2::{verifier::Halo2VerifierProvingKey, DslOperations, Halo2Params, Halo2Prover},
     hints::Hintable,
     witness::Witnessable,
 };
 use openvm_stark_sdk::openvm_stark_backend::{p3_field::FieldAlgebra, proof::Proof};
 
-use crate::{keygen::RootVerifierProvingKey, prover::RootVerifierLocalProver, RootSC, F, SC};
+use crate::{keygen::RootVerifierProvingKey, prover::RootVerifierLocalProver, RootSC, C, F, SC};
 
 impl RootVerifierProvingKey {
     /// Keygen the static verifier for this root verifier.
@@ -24,38 +27,78 @@ impl RootVerifierProvingKey {
     ) -> Halo2VerifierProvingKey {
         let mut witness = Witness::default();
         root_proof.write(&mut witness);
+        let dsl_operations = self.static_verifier_operations(&root_proof, pv_handler);
+        Halo2VerifierProvingKey {
+            pinning: Halo2Prover::keygen(params, dsl_operations.clone(), witness),
+            dsl_ops: dsl_operations,
+        }
+    }
+
+    /// Builds the static verifier for this root verifier in a circuit of degree `2^k` and reports
+    /// the halo2 advice cells, lookups and copy constraints generated by each DSL instruction.
+    /// Run with `RUST_BACKTRACE=1` to also attribute them to the Rust call sites which built the
+    /// verifier.
+    pub fn profile_static_verifier(
+        &self,
+        k: usize,
+        root_proof: Proof<RootSC>,
+        pv_handler: &impl StaticVerifierPvHandler,
+    ) -> ConstraintProfile {
+        let mut witness = Witness::default();
+        root_proof.write(&mut witness);
+        let dsl_operations = self.static_verifier_operations(&root_proof, pv_handler);
+        Halo2Prover::profile(k, dsl_operations, witness)
+    }
+
+    /// Keygen a Groth16 wrapper verifying the root proof, as an alternative to the static verifier
+    /// and the halo2 wrapper. The setup randomness is derived from `seed`, so the key must only
+    /// be used for testing. See [Groth16ProvingKey::keygen_with_test_setup].
+    #[cfg(feature = "groth16")]
+    pub fn keygen_groth16_verifier(
+        &self,
+        root_proof: Proof<RootSC>,
+        pv_handler: &impl StaticVerifierPvHandler,
+        seed: u64,
+    ) -> Groth16ProvingKey {
+        let mut witness = Witness::default();
+        root_proof.write(&mut witness);
+        let dsl_operations = self.static_verifier_operations(&root_proof, pv_handler);
+        Groth16ProvingKey::keygen_with_test_setup(dsl_operations, witness, seed)
+    }
+
+    fn static_verifier_operations(
+        &self,
+        root_proof: &Proof<RootSC>,
+        pv_handler: &impl StaticVerifierPvHandler,
+    ) -> DslOperations<OuterConfig> {
         let special_air_ids = self.air_id_permutation().get_special_air_ids();
         let config = StaticVerifierConfig {
             root_verifier_fri_params: self.vm_pk.fri_params,
             special_air_ids,
             root_verifier_program_commit: self.root_committed_exe.get_program_commit().into(),
         };
-        let dsl_operations = config.build_static_verifier_operations(
-            &self.vm_pk.vm_pk.get_vk(),
-            &root_proof,
-            pv_handler,
-        );
-        Halo2VerifierProvingKey {
-            pinning: Halo2Prover::keygen(params, dsl_operations.clone(), witness),
-            dsl_ops: dsl_operations,
-        }
+        config.build_static_verifier_operations(&self.vm_pk.vm_pk.get_vk(), root_proof, pv_handler)
     }
 
     pub fn generate_dummy_root_proof(
         &self,
         dummy_internal_proof: Proof<SC>,
     ) -> Result<Proof<RootSC>, VirtualMachineError> {
-        let mut prover = RootVerifierLocalProver::new(self)?;
         // 2 * DIGEST_SIZE for exe_commit and leaf_commit
-        let num_public_values = prover.vm_config().as_ref().num_public_values - 2 * DIGEST_SIZE;
-        SingleSegmentVmProver::prove(
-            &mut prover,
-            RootVmVerifierInput {
-                proofs: vec![dummy_internal_proof],
-                public_values: vec![F::ZERO; num_public_values],
-            }
-            .write(),
-            NATIVE_MAX_TRACE_HEIGHTS,
-        )
+        let num_public_values = self.vm_pk.vm_config.as_ref().num_public_values - 2 * DIGEST_SIZE;
+        self.generate_root_proof_for_input(&RootVmVerifierInput {
+            proofs: vec![dummy_internal_proof],
+            public_values: vec![F::ZERO; num_public_values],
+        })
+    }
+
+    /// Proves the root verifier program on the given input. The input must be of the type read by
+    /// the root verifier program, e.g. [RootVmVerifierInput].
+    pub fn generate_root_proof_for_input(
+        &self,
+        input: &impl Hintable<C>,
+    ) -> Result<Proof<RootSC>, VirtualMachineError> {
+        let mut prover = RootVerifierLocalProver::new(self)?;
+        SingleSegmentVmProver::prove(&mut prover, input.write(), NATIVE_MAX_TRACE_HEIGHTS)
     }
 }
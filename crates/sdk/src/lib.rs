@@ -13,20 +13,21 @@ use alloy_sol_types::sol;
 use commit::AppExecutionCommit;
 use config::{AggregationTreeConfig, AppConfig};
 use getset::{Getters, MutGetters, WithSetters};
-use keygen::{AppProvingKey, AppVerifyingKey};
+use keygen::{AppProvingKey, AppVerifyingKey, MultiRootVerifierProvingKey, MultiRootVerifyingKey};
 use openvm_build::{
     build_guest_package, find_unique_executable, get_package, GuestOptions, TargetFilter,
 };
 use openvm_circuit::{
     arch::{
-        execution_mode::Segment,
+        execution_mode::{metered::segment_ctx::SegmentationLimits, PcCounts, Segment},
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
         instructions::exe::VmExe,
-        Executor, InitFileGenerator, MeteredExecutor, PreflightExecutor, VirtualMachineError,
-        VmBuilder, VmExecutionConfig, VmExecutor, VmVerificationError, CONNECTOR_AIR_ID,
-        PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
+        Executor, InitFileGenerator, MeteredExecutor, PreflightExecutor, SystemConfig,
+        VirtualMachineError, VmBuilder, VmExecutionConfig, VmExecutor, VmVerificationError,
+        CONNECTOR_AIR_ID, PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
     },
     system::{
+        connector::VmConnectorPvs,
         memory::{
             merkle::public_values::{extract_public_values, UserPublicValuesProofError},
             CHUNK,
@@ -39,7 +40,7 @@ pub use openvm_continuations::static_verifier::DefaultStaticVerifierPvHandler;
 use openvm_continuations::verifier::{
     common::types::VmVerifierPvs,
     internal::types::{InternalVmVerifierPvs, VmStarkProof},
-    root::RootVmVerifierConfig,
+    multi_root::types::{MultiRootVmVerifierInput, MultiRootVmVerifierPvs},
 };
 // Re-exports:
 pub use openvm_continuations::{RootSC, C, F, SC};
@@ -47,26 +48,38 @@ use openvm_native_circuit::{NativeConfig, NativeCpuBuilder};
 use openvm_native_compiler::conversion::CompilerOptions;
 #[cfg(feature = "evm-prove")]
 use openvm_native_recursion::halo2::utils::{CacheHalo2ParamsReader, Halo2ParamsReader};
-use openvm_stark_backend::proof::Proof;
+use openvm_stark_backend::{
+    p3_field::{FieldAlgebra, PrimeField32},
+    proof::Proof,
+};
 use openvm_stark_sdk::{
-    config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    config::{
+        baby_bear_poseidon2::BabyBearPoseidon2Engine,
+        baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine,
+    },
     engine::{StarkEngine, StarkFriEngine},
 };
-use openvm_transpiler::{
-    elf::Elf, openvm_platform::memory::MEM_SIZE, transpiler::Transpiler, FromElf,
-};
+use openvm_transpiler::{elf::Elf, transpiler::Transpiler, FromElf};
 #[cfg(feature = "evm-verify")]
 use snark_verifier_sdk::{evm::gen_evm_verifier_sol_code, halo2::aggregation::AggregationCircuit};
 
 #[cfg(feature = "evm-prove")]
 use crate::{
-    config::Halo2Config, keygen::Halo2ProvingKey, prover::EvmHalo2Prover, types::EvmProof,
+    config::Halo2Config,
+    keygen::Halo2ProvingKey,
+    prover::{EvmHalo2Prover, MultiProgramAggProver},
+    types::EvmProof,
 };
 use crate::{
     config::{AggregationConfig, SdkVmConfig, SdkVmCpuBuilder, TranspilerConfig},
-    keygen::{asm::program_to_asm, AggProvingKey, AggVerifyingKey},
-    prover::{AppProver, StarkProver},
-    types::ExecutableFormat,
+    estimate::{internal_proof_counts, timed, CostModel, ProofEstimate},
+    ivc::{IvcProver, IvcPublicValues},
+    keygen::{
+        cache::{self, KeyCache},
+        AggProvingKey, AggVerifyingKey,
+    },
+    prover::{AppProver, ProgressReporter, StarkProver},
+    types::{AggregatedProgramPublicValues, ExecutableFormat, GuestMemoryUsage},
 };
 
 cfg_if::cfg_if! {
@@ -85,7 +98,9 @@ cfg_if::cfg_if! {
 pub mod codec;
 pub mod commit;
 pub mod config;
+pub mod estimate;
 pub mod fs;
+pub mod ivc;
 pub mod keygen;
 pub mod prover;
 pub mod types;
@@ -136,6 +151,13 @@ where
     agg_config: AggregationConfig,
     #[getset(get = "pub", get_mut = "pub", set_with = "pub")]
     agg_tree_config: AggregationTreeConfig,
+    /// If set, proving keys are loaded from and stored in this cache instead of being generated
+    /// every time.
+    #[getset(get = "pub")]
+    key_cache: Option<KeyCache>,
+    /// Progress reporter and cancellation token passed to every prover constructed by the SDK.
+    #[getset(get = "pub", get_mut = "pub", set_with = "pub")]
+    progress: ProgressReporter,
     #[cfg(feature = "evm-prove")]
     #[getset(get = "pub", get_mut = "pub", set_with = "pub")]
     halo2_config: Halo2Config,
@@ -254,6 +276,8 @@ where
             #[cfg(feature = "evm-prove")]
             halo2_config,
             agg_tree_config: Default::default(),
+            key_cache: None,
+            progress: Default::default(),
             app_vm_builder: Default::default(),
             native_builder: Default::default(),
             transpiler: None,
@@ -281,6 +305,8 @@ where
         self.app_config
             .app_vm_config
             .write_to_init_file(pkg_dir.as_ref(), init_file_name)?;
+        let memory_layout = self.app_config.memory_layout().map_err(SdkError::Other)?;
+        let guest_opts = guest_opts.with_memory_layout(memory_layout);
         let pkg = get_package(pkg_dir.as_ref());
         let target_dir = match build_guest_package(&pkg, &guest_opts, None, target_filter) {
             Ok(target_dir) => target_dir,
@@ -295,7 +321,7 @@ where
         let elf_path =
             find_unique_executable(pkg_dir, target_dir, target_filter).map_err(SdkError::Other)?;
         let data = read(&elf_path)?;
-        Elf::decode(&data, MEM_SIZE as u32).map_err(SdkError::Other)
+        Elf::decode(&data, memory_layout.mem_size() as u32).map_err(SdkError::Other)
     }
 
     /// Transpiler for transpiling RISC-V ELF to OpenVM executable.
@@ -322,6 +348,13 @@ where
                 let transpiler = self.transpiler()?.clone();
                 Arc::new(VmExe::from_elf(elf, transpiler)?)
             }
+            ExecutableFormat::ElfBytes(bytes) => {
+                let memory_layout = self.app_config.memory_layout().map_err(SdkError::Other)?;
+                let elf = Elf::decode(&bytes, memory_layout.mem_size() as u32)
+                    .map_err(SdkError::Other)?;
+                let transpiler = self.transpiler()?.clone();
+                Arc::new(VmExe::from_elf(elf, transpiler)?)
+            }
             ExecutableFormat::VmExe(exe) => Arc::new(exe),
             ExecutableFormat::SharedVmExe(exe) => exe,
         };
@@ -342,18 +375,24 @@ where
         PreflightExecutor<F, <NativeBuilder as VmBuilder<E>>::RecordArena>,
 {
     /// Returns the user public values as field elements.
+    ///
+    /// Guest memory accesses are checked against the guard regions of
+    /// [AppConfig::memory_guards], so that stack overflow and heap exhaustion fail with a
+    /// dedicated error including the guest backtrace. The other execution methods do not check
+    /// guard regions.
     pub fn execute(
         &self,
         app_exe: impl Into<ExecutableFormat>,
         inputs: StdIn,
     ) -> Result<Vec<u8>, SdkError> {
         let exe = self.convert_to_exe(app_exe)?;
+        let guards = self.app_config.memory_guards().map_err(SdkError::Other)?;
         let instance = self
             .executor
-            .instance(&exe)
+            .guarded_instance(&exe)
             .map_err(VirtualMachineError::from)?;
         let final_memory = instance
-            .execute(inputs, None)
+            .execute_guarded(inputs, None, guards, &exe.fn_bounds)
             .map_err(VirtualMachineError::from)?
             .memory;
         let public_values = extract_public_values(
@@ -363,53 +402,89 @@ where
         Ok(public_values)
     }
 
+    /// Executes the program like [`execute`](Self::execute) while counting how many times each
+    /// instruction is executed. Returns the user public values together with the execution count
+    /// of every executed pc, which can be attributed to guest source lines for code coverage.
+    pub fn execute_with_coverage(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+    ) -> Result<(Vec<u8>, PcCounts), SdkError> {
+        let exe = self.convert_to_exe(app_exe)?;
+        let instance = self
+            .executor
+            .coverage_instance(&exe)
+            .map_err(VirtualMachineError::from)?;
+        let (pc_counts, final_state) = instance
+            .execute_with_coverage(inputs)
+            .map_err(VirtualMachineError::from)?;
+        let public_values = extract_public_values(
+            self.executor.config.as_ref().num_public_values,
+            &final_state.memory.memory,
+        );
+        Ok((public_values, pc_counts))
+    }
+
     /// Executes with segmentation for proof generation.
-    /// Returns both user public values and segments with instruction counts and trace heights.
+    /// Returns the user public values, segments with instruction counts and trace heights, and the
+    /// guest memory usage of the execution.
     pub fn execute_metered(
         &self,
         app_exe: impl Into<ExecutableFormat>,
         inputs: StdIn,
-    ) -> Result<(Vec<u8>, Vec<Segment>), SdkError> {
+    ) -> Result<(Vec<u8>, Vec<Segment>, GuestMemoryUsage), SdkError> {
         let app_prover = self.app_prover(app_exe)?;
 
         let vm = app_prover.vm();
         let exe = app_prover.exe();
 
-        let ctx = vm.build_metered_ctx(&exe);
+        let ctx = vm
+            .build_metered_ctx(&exe)
+            .with_memory_profile(self.executor.config.as_ref());
         let interpreter = vm
             .metered_interpreter(&exe)
             .map_err(VirtualMachineError::from)?;
 
-        let (segments, final_state) = interpreter
-            .execute_metered(inputs, ctx)
+        let (segments, memory_profile, final_state) = interpreter
+            .execute_metered_with_memory_profile(inputs, ctx)
             .map_err(VirtualMachineError::from)?;
         let public_values = extract_public_values(
             self.executor.config.as_ref().num_public_values,
             &final_state.memory.memory,
         );
+        let memory_layout = self.app_config.memory_layout().map_err(SdkError::Other)?;
+        let memory_usage = GuestMemoryUsage::new(
+            &exe,
+            memory_profile.unwrap_or_default(),
+            &memory_layout,
+            &final_state.memory.memory,
+        );
 
-        Ok((public_values, segments))
+        Ok((public_values, segments, memory_usage))
     }
 
     /// Executes with cost metering to measure computational cost in trace cells.
-    /// Returns both user public values, and cost along with instruction count.
+    /// Returns the user public values, the cost along with instruction count, and the guest memory
+    /// usage of the execution.
     pub fn execute_metered_cost(
         &self,
         app_exe: impl Into<ExecutableFormat>,
         inputs: StdIn,
-    ) -> Result<(Vec<u8>, (u64, u64)), SdkError> {
+    ) -> Result<(Vec<u8>, (u64, u64), GuestMemoryUsage), SdkError> {
         let app_prover = self.app_prover(app_exe)?;
 
         let vm = app_prover.vm();
         let exe = app_prover.exe();
 
-        let ctx = vm.build_metered_cost_ctx();
+        let ctx = vm
+            .build_metered_cost_ctx()
+            .with_memory_profile(self.executor.config.as_ref());
         let interpreter = vm
             .metered_cost_interpreter(&exe)
             .map_err(VirtualMachineError::from)?;
 
-        let (cost, final_state) = interpreter
-            .execute_metered_cost(inputs, ctx)
+        let (cost, memory_profile, final_state) = interpreter
+            .execute_metered_cost_with_memory_profile(inputs, ctx)
             .map_err(VirtualMachineError::from)?;
         let instret = final_state.instret();
 
@@ -417,8 +492,15 @@ where
             self.executor.config.as_ref().num_public_values,
             &final_state.memory.memory,
         );
+        let memory_layout = self.app_config.memory_layout().map_err(SdkError::Other)?;
+        let memory_usage = GuestMemoryUsage::new(
+            &exe,
+            memory_profile.unwrap_or_default(),
+            &memory_layout,
+            &final_state.memory.memory,
+        );
 
-        Ok((public_values, (cost, instret)))
+        Ok((public_values, (cost, instret), memory_usage))
     }
 
     // ======================== Proving Methods ============================
@@ -450,6 +532,21 @@ where
         Ok((proof, app_commit))
     }
 
+    /// Same as [`prove`](Self::prove), but checkpoints the app, leaf and internal proofs in
+    /// `checkpoint_dir`, so that a proof interrupted by a crash resumes where it stopped when
+    /// called again with the same program, input and directory.
+    pub fn prove_with_checkpoint(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+        checkpoint_dir: impl AsRef<Path>,
+    ) -> Result<(VmStarkProof<SC>, AppExecutionCommit), SdkError> {
+        let mut prover = self.prover(app_exe)?;
+        let app_commit = prover.app_prover.app_commit();
+        let proof = prover.prove_with_checkpoint(inputs, checkpoint_dir)?;
+        Ok((proof, app_commit))
+    }
+
     #[cfg(feature = "evm-prove")]
     pub fn prove_evm(
         &self,
@@ -462,6 +559,71 @@ where
         Ok(proof)
     }
 
+    // ======================== Estimation Methods ==========================
+
+    /// Estimates the number of segments, leaf and internal proofs, peak memory and wall-clock time
+    /// to prove the given `app_exe` with program inputs `inputs`, without generating any proof.
+    ///
+    /// This runs metered execution and generates the [AppProvingKey] if it does not already exist.
+    pub fn estimate(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+        cost_model: &CostModel,
+    ) -> Result<ProofEstimate, SdkError> {
+        let (_, segments, _) = self.execute_metered(app_exe, inputs)?;
+        let memory_model = self.app_pk().prover_memory_model();
+        Ok(cost_model.estimate(&segments, &memory_model, &self.agg_tree_config))
+    }
+
+    /// Calibrates a [CostModel] for this machine by generating an aggregate STARK proof of the
+    /// given `app_exe` with program inputs `inputs` and timing each stage. The root and halo2
+    /// proof times and the memory figures are left at their defaults.
+    ///
+    /// The program should run for several segments so that the per segment overhead is
+    /// amortized.
+    pub fn calibrate_cost_model(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        inputs: StdIn,
+    ) -> Result<CostModel, SdkError> {
+        let app_exe = self.convert_to_exe(app_exe)?;
+        let (_, segments, _) = self.execute_metered(app_exe.clone(), inputs.clone())?;
+        let memory_model = self.app_pk().prover_memory_model();
+        let total_cells: usize = segments
+            .iter()
+            .map(|segment| memory_model.main_cells(&segment.trace_heights))
+            .sum();
+
+        let mut prover = self.prover(app_exe)?;
+        let (app_proof, app_secs) = timed(|| prover.app_prover.prove(inputs));
+        let app_proof = app_proof?;
+        let (leaf_proofs, leaf_secs) = timed(|| prover.agg_prover.generate_leaf_proofs(&app_proof));
+        let leaf_proofs = leaf_proofs?;
+        let num_leaf_proofs = leaf_proofs.len();
+        let (_, num_internal_proofs) =
+            internal_proof_counts(num_leaf_proofs, prover.agg_prover.num_children_internal);
+        let public_values = app_proof.user_public_values.public_values;
+        let (proof, internal_secs) = timed(|| {
+            prover
+                .agg_prover
+                .aggregate_leaf_proofs(leaf_proofs, public_values)
+        });
+        proof?;
+
+        let default = CostModel::default();
+        let overhead_secs = default.app_segment_overhead_secs * segments.len() as f64;
+        // Attribute at least half of the app proof time to the cells
+        let cell_secs = (app_secs - overhead_secs).max(app_secs / 2.0);
+        Ok(CostModel {
+            app_cells_per_sec: total_cells as f64 / cell_secs,
+            app_segment_overhead_secs: (app_secs - cell_secs) / segments.len() as f64,
+            leaf_proof_secs: leaf_secs / num_leaf_proofs as f64,
+            internal_proof_secs: internal_secs / num_internal_proofs as f64,
+            ..default
+        })
+    }
+
     // ========================= Prover Constructors =========================
 
     /// Constructs a new [StarkProver] instance for the given executable.
@@ -470,18 +632,36 @@ where
     pub fn prover(
         &self,
         app_exe: impl Into<ExecutableFormat>,
+    ) -> Result<StarkProver<E, VB, NativeBuilder>, SdkError> {
+        self.prover_with_app_fri_profile(app_exe, 0)
+    }
+
+    /// Same as [`prover`](Self::prover), but the App VM proofs are generated with the app FRI
+    /// profile `fri_profile`, see [AggregationConfig::extra_app_fri_params].
+    pub fn prover_with_app_fri_profile(
+        &self,
+        app_exe: impl Into<ExecutableFormat>,
+        fri_profile: usize,
     ) -> Result<StarkProver<E, VB, NativeBuilder>, SdkError> {
         let app_exe = self.convert_to_exe(app_exe)?;
-        let app_pk = self.app_pk();
-        let agg_pk = self.agg_pk();
-        let stark_prover = StarkProver::<E, _, _>::new(
+        let app_pk = self.checked_app_pk()?;
+        let agg_pk = self.checked_agg_pk()?;
+        if fri_profile >= app_pk.num_app_fri_profiles() {
+            return Err(SdkError::InvalidAppFriProfile {
+                num_profiles: app_pk.num_app_fri_profiles() as u32,
+                actual: fri_profile as u32,
+            });
+        }
+        let stark_prover = StarkProver::<E, _, _>::new_with_app_fri_profile(
             self.app_vm_builder.clone(),
             self.native_builder.clone(),
             app_pk,
             app_exe,
             agg_pk,
             self.agg_tree_config,
-        )?;
+            fri_profile,
+        )?
+        .with_progress(self.progress.clone());
         Ok(stark_prover)
     }
 
@@ -495,15 +675,48 @@ where
             self.halo2_params_reader(),
             self.app_vm_builder.clone(),
             self.native_builder.clone(),
-            self.app_pk(),
+            self.checked_app_pk()?,
             app_exe,
-            self.agg_pk(),
-            self.halo2_pk().clone(),
+            self.checked_agg_pk()?,
+            self.checked_halo2_pk()?.clone(),
             self.agg_tree_config,
-        )?;
+        )?
+        .with_progress(self.progress.clone());
         Ok(evm_prover)
     }
 
+    /// Constructs a [MultiProgramAggProver] that aggregates the aggregate STARK proofs of several
+    /// programs, generated with [`prove`](Self::prove), into a single root proof.
+    ///
+    /// The proofs may be generated by SDKs with different App VM configs, as long as they share
+    /// the aggregation proving keys of this SDK.
+    #[cfg(feature = "evm-prove")]
+    pub fn multi_program_prover(
+        &self,
+        multi_root_pk: &MultiRootVerifierProvingKey,
+    ) -> Result<MultiProgramAggProver<E, NativeBuilder>, SdkError> {
+        let prover = MultiProgramAggProver::<E, _>::new(
+            self.native_builder.clone(),
+            self.checked_agg_pk()?,
+            multi_root_pk,
+            self.agg_tree_config,
+        )?;
+        Ok(prover)
+    }
+
+    /// Constructs an [IvcProver] for the given IVC step program. `verifier_asm_filename` is the
+    /// filename of the root verifier ASM, generated by
+    /// [`generate_root_verifier_asm`](Self::generate_root_verifier_asm), which the step program
+    /// passes to `define_verify_openvm_stark!`.
+    pub fn ivc_prover(
+        &self,
+        step_exe: impl Into<ExecutableFormat>,
+        verifier_asm_filename: impl Into<String>,
+    ) -> Result<IvcProver<E, VB, NativeBuilder>, SdkError> {
+        let stark_prover = self.prover(step_exe)?;
+        Ok(IvcProver::new(stark_prover, verifier_asm_filename))
+    }
+
     /// This constructor is for generating app proofs that do not require a single aggregate STARK
     /// proof of the full program execution. For a single STARK proof, use the
     /// [`prove`](Self::prove) method instead.
@@ -516,13 +729,14 @@ where
         exe: impl Into<ExecutableFormat>,
     ) -> Result<AppProver<E, VB>, SdkError> {
         let exe = self.convert_to_exe(exe)?;
-        let app_pk = self.app_pk();
+        let app_pk = self.checked_app_pk()?;
         let prover = AppProver::<E, VB>::new(
             self.app_vm_builder.clone(),
             &app_pk.app_vm_pk,
             exe,
             app_pk.leaf_verifier_program_commit(),
-        )?;
+        )?
+        .with_progress(self.progress.clone());
         Ok(prover)
     }
 
@@ -545,7 +759,25 @@ where
     pub fn app_pk(&self) -> &AppProvingKey<VB::VmConfig> {
         // TODO[jpw]: use `get_or_try_init` once it is stable
         self.app_pk.get_or_init(|| {
-            AppProvingKey::keygen(self.app_config.clone()).expect("app_keygen failed")
+            let cached = self.key_cache.as_ref().and_then(|key_cache| {
+                key_cache.load_app_pk(&cache::app_config_hash(
+                    &self.app_config,
+                    &self.agg_config.extra_app_fri_params,
+                ))
+            });
+            cached.unwrap_or_else(|| {
+                let app_pk = AppProvingKey::keygen_with_extra_app_fri_params(
+                    self.app_config.clone(),
+                    &self.agg_config.extra_app_fri_params,
+                )
+                .expect("app_keygen failed");
+                if let Some(key_cache) = &self.key_cache {
+                    if let Err(e) = key_cache.store_app_pk(&app_pk) {
+                        tracing::warn!("failed to cache app proving key: {e}");
+                    }
+                }
+                app_pk
+            })
         })
     }
     /// Sets the app proving key. Returns `Ok(())` if app keygen has not been called and
@@ -565,6 +797,26 @@ where
         self
     }
 
+    /// Derives the segmentation limits of the App VM from a prover memory budget of `max_memory`
+    /// bytes, see [ProverMemoryModel](config::ProverMemoryModel). This generates the app proving
+    /// key if it does not already exist.
+    pub fn set_max_prover_memory(&mut self, max_memory: usize) -> SegmentationLimits
+    where
+        VB::VmConfig: AsMut<SystemConfig>,
+    {
+        self.app_pk();
+        let limits = self
+            .app_pk
+            .get_mut()
+            .unwrap()
+            .set_max_prover_memory(max_memory);
+        self.app_config
+            .app_vm_config
+            .as_mut()
+            .set_segmentation_limits(limits);
+        limits
+    }
+
     /// Generates the proving keys necessary for STARK aggregation. Generates the proving keys once
     /// and caches them. Future calls will return the cached key. This function does not include
     /// [`app_keygen`](Self::app_keygen), which is specific to the App VM config. The proving keys
@@ -581,8 +833,7 @@ where
     pub fn agg_pk(&self) -> &AggProvingKey {
         // TODO[jpw]: use `get_or_try_init` once it is stable
         self.agg_pk.get_or_init(|| {
-            let (agg_pk, dummy_proof) =
-                AggProvingKey::dummy_proof_and_keygen(self.agg_config).expect("agg_keygen failed");
+            let (agg_pk, dummy_proof) = self.load_or_generate_agg_pk();
             let prev = self.dummy_internal_proof.set(dummy_proof);
             if prev.is_err() {
                 tracing::debug!("dummy proof already exists, did not overwrite");
@@ -606,8 +857,7 @@ where
     // We have this function in case agg_pk is set externally without setting dummy proof.
     fn dummy_internal_proof(&self) -> &Proof<SC> {
         self.dummy_internal_proof.get_or_init(|| {
-            let (agg_pk, dummy_proof) =
-                AggProvingKey::dummy_proof_and_keygen(self.agg_config).expect("agg_keygen failed");
+            let (agg_pk, dummy_proof) = self.load_or_generate_agg_pk();
             let prev = self.agg_pk.set(agg_pk);
             if prev.is_err() {
                 tracing::debug!("agg_pk already exists, did not overwrite");
@@ -618,21 +868,74 @@ where
     pub fn agg_pk_and_dummy_internal_proof(&self) -> (&AggProvingKey, &Proof<SC>) {
         (self.agg_pk(), self.dummy_internal_proof())
     }
+    fn load_or_generate_agg_pk(&self) -> (AggProvingKey, Proof<SC>) {
+        let cached = self
+            .key_cache
+            .as_ref()
+            .and_then(|key_cache| key_cache.load_agg_pk(&cache::agg_config_hash(&self.agg_config)));
+        cached.unwrap_or_else(|| {
+            let (agg_pk, dummy_proof) =
+                AggProvingKey::dummy_proof_and_keygen(self.agg_config.clone())
+                    .expect("agg_keygen failed");
+            if let Some(key_cache) = &self.key_cache {
+                if let Err(e) = key_cache.store_agg_pk(&agg_pk, &dummy_proof) {
+                    tracing::warn!("failed to cache aggregation proving key: {e}");
+                }
+            }
+            (agg_pk, dummy_proof)
+        })
+    }
 
-    pub fn generate_root_verifier_asm(&self) -> String {
+    /// Loads proving keys from `key_cache` if it contains keys for the configs of this SDK, and
+    /// stores newly generated keys in it. This should only be used in a constructor, before any
+    /// keys are generated.
+    pub fn with_key_cache(mut self, key_cache: KeyCache) -> Self {
+        self.key_cache = Some(key_cache);
+        self
+    }
+
+    /// Returns the app proving key if it was generated for the App VM config of this SDK, and
+    /// [SdkError::ConfigHashMismatch] otherwise, e.g. when a stale key was set with
+    /// [`set_app_pk`](Self::set_app_pk).
+    pub fn checked_app_pk(&self) -> Result<&AppProvingKey<VB::VmConfig>, SdkError> {
+        let app_pk = self.app_pk();
+        cache::check_config_hash(
+            "app",
+            cache::app_config_hash(&self.app_config, &self.agg_config.extra_app_fri_params),
+            &app_pk.config_hash,
+        )?;
+        Ok(app_pk)
+    }
+
+    /// Same as [`checked_app_pk`](Self::checked_app_pk) for the aggregation proving key.
+    pub fn checked_agg_pk(&self) -> Result<&AggProvingKey, SdkError> {
         let agg_pk = self.agg_pk();
-        let kernel_asm = RootVmVerifierConfig {
-            leaf_fri_params: agg_pk.leaf_vm_pk.fri_params,
-            internal_fri_params: agg_pk.internal_vm_pk.fri_params,
-            num_user_public_values: agg_pk.num_user_public_values(),
-            internal_vm_verifier_commit: agg_pk.internal_committed_exe.get_program_commit().into(),
-            compiler_options: Default::default(),
-        }
-        .build_kernel_asm(
-            &agg_pk.leaf_vm_pk.vm_pk.get_vk(),
-            &agg_pk.internal_vm_pk.vm_pk.get_vk(),
-        );
-        program_to_asm(kernel_asm)
+        cache::check_config_hash(
+            "aggregation",
+            cache::agg_config_hash(&self.agg_config),
+            &agg_pk.config_hash,
+        )?;
+        Ok(agg_pk)
+    }
+
+    /// Generates the proving key of a multi-program root verifier aggregating one proof per
+    /// program, where program `i` has `num_user_public_values[i]` user public values. Also returns
+    /// a dummy input of the multi-program root verifier, which is needed for
+    /// [`Halo2ProvingKey::keygen_multi_root`](keygen::Halo2ProvingKey::keygen_multi_root).
+    ///
+    /// This function will generate the [AggProvingKey] if it does not already exist.
+    pub fn multi_root_keygen(
+        &self,
+        num_user_public_values: Vec<usize>,
+    ) -> Result<(MultiRootVerifierProvingKey, MultiRootVmVerifierInput<SC>), SdkError> {
+        let (multi_root_pk, dummy_input) = self
+            .agg_pk()
+            .multi_root_keygen(&self.agg_config, num_user_public_values)?;
+        Ok((multi_root_pk, dummy_input))
+    }
+
+    pub fn generate_root_verifier_asm(&self) -> String {
+        self.agg_pk().get_agg_vk().generate_root_verifier_asm()
     }
 
     #[cfg(feature = "evm-prove")]
@@ -642,19 +945,45 @@ where
 
     #[cfg(feature = "evm-prove")]
     pub fn halo2_pk(&self) -> &Halo2ProvingKey {
-        let (agg_pk, dummy_internal_proof) = self.agg_pk_and_dummy_internal_proof();
         // TODO[jpw]: use `get_or_try_init` once it is stable
         self.halo2_pk.get_or_init(|| {
-            Halo2ProvingKey::keygen(
-                self.halo2_config,
-                self.halo2_params_reader(),
-                &DefaultStaticVerifierPvHandler,
-                agg_pk,
-                dummy_internal_proof.clone(),
-            )
-            .expect("halo2_keygen failed")
+            let agg_pk = self.agg_pk();
+            let cached = self.key_cache.as_ref().and_then(|key_cache| {
+                key_cache.load_halo2_pk(&cache::halo2_config_hash(
+                    &self.halo2_config,
+                    &agg_pk.root_verifier_pk,
+                ))
+            });
+            cached.unwrap_or_else(|| {
+                let halo2_pk = Halo2ProvingKey::keygen(
+                    self.halo2_config,
+                    self.halo2_params_reader(),
+                    &DefaultStaticVerifierPvHandler,
+                    agg_pk,
+                    self.dummy_internal_proof().clone(),
+                )
+                .expect("halo2_keygen failed");
+                if let Some(key_cache) = &self.key_cache {
+                    if let Err(e) = key_cache.store_halo2_pk(&halo2_pk) {
+                        tracing::warn!("failed to cache halo2 proving key: {e}");
+                    }
+                }
+                halo2_pk
+            })
         })
     }
+    /// Same as [`checked_app_pk`](Self::checked_app_pk) for the halo2 proving key, which must
+    /// also have been generated for the root verifier of the aggregation proving key.
+    #[cfg(feature = "evm-prove")]
+    pub fn checked_halo2_pk(&self) -> Result<&Halo2ProvingKey, SdkError> {
+        let halo2_pk = self.halo2_pk();
+        cache::check_config_hash(
+            "halo2",
+            cache::halo2_config_hash(&self.halo2_config, &self.agg_pk().root_verifier_pk),
+            &halo2_pk.config_hash,
+        )?;
+        Ok(halo2_pk)
+    }
     /// Sets the halo2 proving keys. Returns `Ok(())` if halo2 keygen has not been called and
     /// `Err(halo2_pk)` if keygen has already been called.
     #[cfg(feature = "evm-prove")]
@@ -775,6 +1104,14 @@ where
             .into());
         }
 
+        let fri_profile = pvs.fri_profile.as_canonical_u32();
+        if fri_profile as usize >= agg_vk.num_app_fri_profiles {
+            return Err(SdkError::InvalidAppFriProfile {
+                num_profiles: agg_vk.num_app_fri_profiles as u32,
+                actual: fri_profile,
+            });
+        }
+
         let claimed_app_exe_commit = compute_exe_commit(
             &hasher,
             &pvs.app_commit,
@@ -800,6 +1137,102 @@ where
         Ok(())
     }
 
+    /// Verifies the proof of the last step of an IVC chain and returns its public values. Besides
+    /// the checks of [`verify_proof`](Self::verify_proof), this checks that the IVC header commits
+    /// to the step program, so the proof attests to `num_steps` steps of `expected_app_commit`.
+    pub fn verify_ivc_proof(
+        agg_vk: &AggVerifyingKey,
+        expected_app_commit: AppExecutionCommit,
+        proof: &VmStarkProof<SC>,
+    ) -> Result<IvcPublicValues, SdkError> {
+        Self::verify_proof(agg_vk, expected_app_commit, proof)?;
+        let pvs = IvcPublicValues::from_user_public_values(&proof.user_public_values)
+            .ok_or_else(|| SdkError::Other(eyre::eyre!("Invalid IVC public values")))?;
+        if !pvs.is_committed_to(&expected_app_commit) {
+            return Err(SdkError::Other(eyre::eyre!(
+                "IVC header does not commit to the app commit"
+            )));
+        }
+        Ok(pvs)
+    }
+
+    /// Verifies a root proof of the multi-program root verifier and returns the app commit and
+    /// user public values of each aggregated program, in program order. The caller must check the
+    /// app commits against the expected programs.
+    ///
+    /// **Note**: This function does not have any reliance on `self`.
+    pub fn verify_multi_program_proof(
+        vk: &MultiRootVerifyingKey,
+        proof: &Proof<RootSC>,
+    ) -> Result<Vec<AggregatedProgramPublicValues>, SdkError> {
+        let air_proof_data = |air_id: usize| {
+            proof
+                .per_air
+                .iter()
+                .find(|air_proof_data| air_proof_data.air_id == air_id)
+                .ok_or(VmVerificationError::SystemAirMissing { air_id })
+        };
+        let connector_air_proof_data = air_proof_data(vk.connector_air_id)?;
+        let public_values_air_proof_data = air_proof_data(vk.public_values_air_id)?;
+        air_proof_data(vk.program_air_id)?;
+
+        let program_commit = proof.commitments.main_trace[PROGRAM_CACHED_TRACE_INDEX];
+        if program_commit != vk.program_commit {
+            return Err(VmVerificationError::ProgramCommitMismatch { index: 0 }.into());
+        }
+        let e = BabyBearPoseidon2RootEngine::new(vk.fri_params);
+        e.verify(&vk.vk, proof).map_err(VmVerificationError::from)?;
+
+        let connector_pvs: &VmConnectorPvs<_> =
+            connector_air_proof_data.public_values.as_slice().borrow();
+        if connector_pvs.initial_pc != F::ZERO {
+            return Err(VmVerificationError::InitialPcMismatch {
+                initial: connector_pvs.initial_pc.as_canonical_u32(),
+                prev_final: 0,
+            }
+            .into());
+        }
+        if let Some(exit_code) = connector_pvs.exit_code() {
+            if exit_code != 0 {
+                return Err(VmVerificationError::ExitCodeMismatch {
+                    expected: 0,
+                    actual: exit_code,
+                }
+                .into());
+            }
+        } else {
+            return Err(VmVerificationError::IsTerminateMismatch {
+                expected: true,
+                actual: false,
+            }
+            .into());
+        }
+
+        let public_values = &public_values_air_proof_data.public_values;
+        let expected_num_public_values =
+            MultiRootVmVerifierPvs::<u8>::width(&vk.num_user_public_values);
+        if public_values.len() != expected_num_public_values {
+            return Err(VmVerificationError::UnexpectedPvs {
+                expected: expected_num_public_values,
+                actual: public_values.len(),
+            }
+            .into());
+        }
+        let pvs =
+            MultiRootVmVerifierPvs::from_flatten(public_values.clone(), &vk.num_user_public_values);
+        Ok(pvs
+            .programs
+            .into_iter()
+            .map(|pvs| AggregatedProgramPublicValues {
+                app_commit: AppExecutionCommit::from_field_commit(
+                    pvs.exe_commit,
+                    pvs.leaf_verifier_commit,
+                ),
+                user_public_values: pvs.public_values,
+            })
+            .collect())
+    }
+
     #[cfg(feature = "evm-verify")]
     pub fn generate_halo2_verifier_solidity(&self) -> Result<types::EvmHalo2Verifier, SdkError> {
         use std::{
@@ -16,8 +16,11 @@ use openvm_stark_sdk::{engine::StarkFriEngine, openvm_stark_backend::proof::Proo
 use tracing::{info_span, instrument};
 
 use crate::{
-    config::AggregationTreeConfig, keygen::AggProvingKey, prover::vm::new_local_prover,
-    util::check_max_constraint_degrees, F, SC,
+    config::AggregationTreeConfig,
+    keygen::AggProvingKey,
+    prover::{vm::new_local_prover, ProgressReporter, ProofCheckpoint, ProvingEvent},
+    util::check_max_constraint_degrees,
+    F, SC,
 };
 #[cfg(feature = "evm-prove")]
 use crate::{prover::RootVerifierLocalProver, RootSC};
@@ -35,11 +38,21 @@ where
     root_prover: RootVerifierLocalProver,
     pub num_children_internal: usize,
     pub max_internal_wrapper_layers: usize,
+    /// If set, leaf and internal proofs are loaded from and persisted to this checkpoint.
+    pub checkpoint: Option<ProofCheckpoint>,
+    /// Hash of the verifying key of the [AggProvingKey] the prover was constructed from, which
+    /// identifies the aggregation key of checkpoints. Only set by [AggStarkProver::new].
+    pub agg_vk_hash: Option<String>,
+    /// Receives leaf, internal layer and root proof events.
+    pub progress: ProgressReporter,
 }
 
 pub struct LeafProvingController {
     /// Each leaf proof aggregations `<= num_children` App VM proofs
     pub num_children: usize,
+    /// App FRI profile of the App VM proofs, see
+    /// [AggregationConfig::extra_app_fri_params](crate::config::AggregationConfig::extra_app_fri_params).
+    pub fri_profile: usize,
 }
 
 impl<E, NativeBuilder> AggStarkProver<E, NativeBuilder>
@@ -67,13 +80,15 @@ where
         )?;
         #[cfg(feature = "evm-prove")]
         let root_prover = RootVerifierLocalProver::new(&agg_pk.root_verifier_pk)?;
-        Ok(Self::new_from_instances(
+        let mut prover = Self::new_from_instances(
             leaf_prover,
             internal_prover,
             #[cfg(feature = "evm-prove")]
             root_prover,
             tree_config,
-        ))
+        );
+        prover.agg_vk_hash = Some(agg_pk.get_agg_vk().root_verifier_hash());
+        Ok(prover)
     }
 
     pub fn new_from_instances(
@@ -84,6 +99,7 @@ where
     ) -> Self {
         let leaf_controller = LeafProvingController {
             num_children: tree_config.num_children_leaf,
+            fri_profile: 0,
         };
         Self {
             leaf_prover: leaf_instance,
@@ -93,6 +109,9 @@ where
             root_prover: root_instance,
             num_children_internal: tree_config.num_children_internal,
             max_internal_wrapper_layers: tree_config.max_internal_wrapper_layers,
+            checkpoint: None,
+            agg_vk_hash: None,
+            progress: ProgressReporter::default(),
         }
     }
 
@@ -101,6 +120,13 @@ where
         self
     }
 
+    /// Sets the app FRI profile of the App VM proofs to aggregate. Defaults to the primary
+    /// profile `0`.
+    pub fn with_app_fri_profile(mut self, fri_profile: usize) -> Self {
+        self.leaf_controller.fri_profile = fri_profile;
+        self
+    }
+
     pub fn with_num_children_internal(mut self, num_children_internal: usize) -> Self {
         self.num_children_internal = num_children_internal;
         self
@@ -111,6 +137,20 @@ where
         self
     }
 
+    pub fn with_checkpoint(mut self, checkpoint: ProofCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    pub fn num_children_leaf(&self) -> usize {
+        self.leaf_controller.num_children
+    }
+
     /// Generate the root proof for outer recursion.
     #[cfg(feature = "evm-prove")]
     pub fn generate_root_proof(
@@ -118,7 +158,9 @@ where
         app_proofs: ContinuationVmProof<SC>,
     ) -> Result<Proof<RootSC>, VirtualMachineError> {
         let root_verifier_input = self.generate_root_verifier_input(app_proofs)?;
-        self.generate_root_proof_impl(root_verifier_input)
+        let root_proof = self.generate_root_proof_impl(root_verifier_input)?;
+        self.progress.report(ProvingEvent::RootProved)?;
+        Ok(root_proof)
     }
 
     pub fn generate_leaf_proofs(
@@ -129,8 +171,12 @@ where
             self.leaf_prover.vm.config().as_ref(),
             &self.leaf_prover.vm.engine.fri_params(),
         );
-        self.leaf_controller
-            .generate_proof(&mut self.leaf_prover, app_proofs)
+        self.leaf_controller.generate_proof(
+            &mut self.leaf_prover,
+            app_proofs,
+            self.checkpoint.as_ref(),
+            &self.progress,
+        )
     }
 
     /// This is typically only used for the halo2 verifier.
@@ -178,19 +224,37 @@ where
                         .absolute(self.internal_prover.vm.engine.fri_params().log_blowup as u64);
                     metrics::counter!("num_children").absolute(self.num_children_internal as u64);
                 }
+                let checkpoint = self.checkpoint.as_ref();
+                let progress = &self.progress;
                 internal_inputs
                     .into_iter()
-                    .map(|input| {
+                    .enumerate()
+                    .map(|(layer_idx, input)| {
                         internal_node_idx += 1;
-                        info_span!("single_internal_agg", idx = internal_node_idx,).in_scope(|| {
-                            SingleSegmentVmProver::prove(
-                                &mut self.internal_prover,
-                                input.write(),
-                                NATIVE_MAX_TRACE_HEIGHTS,
-                            )
-                        })
+                        if let Some(proof) = checkpoint
+                            .and_then(|c| c.load_internal_proof(internal_node_height, layer_idx))
+                        {
+                            return Ok(proof);
+                        }
+                        progress.check_cancelled()?;
+                        let proof = info_span!("single_internal_agg", idx = internal_node_idx,)
+                            .in_scope(|| {
+                                SingleSegmentVmProver::prove(
+                                    &mut self.internal_prover,
+                                    input.write(),
+                                    NATIVE_MAX_TRACE_HEIGHTS,
+                                )
+                            })?;
+                        if let Some(checkpoint) = checkpoint {
+                            checkpoint.save_internal_proof(internal_node_height, layer_idx, &proof);
+                        }
+                        Ok(proof)
                     })
-                    .collect::<Result<Vec<_>, _>>()
+                    .collect::<Result<Vec<_>, VirtualMachineError>>()
+            })?;
+            self.progress.report(ProvingEvent::InternalLayerProved {
+                height: internal_node_height,
+                num_proofs: proofs.len(),
             })?;
             internal_node_height += 1;
         }
@@ -291,6 +355,8 @@ impl LeafProvingController {
         &self,
         prover: &mut VmInstance<E, NativeBuilder>,
         app_proofs: &ContinuationVmProof<SC>,
+        checkpoint: Option<&ProofCheckpoint>,
+        progress: &ProgressReporter,
     ) -> Result<Vec<Proof<SC>>, VirtualMachineError>
     where
         E: StarkFriEngine<SC = SC>,
@@ -304,20 +370,40 @@ impl LeafProvingController {
                 .absolute(prover.vm.engine.fri_params().log_blowup as u64);
             metrics::counter!("num_children").absolute(self.num_children as u64);
         }
-        let leaf_inputs =
+        let mut leaf_inputs =
             LeafVmVerifierInput::chunk_continuation_vm_proof(app_proofs, self.num_children);
-        tracing::info!("num_leaf_proofs={}", leaf_inputs.len());
+        for input in &mut leaf_inputs {
+            input.fri_profile = self.fri_profile;
+        }
+        let num_leaf_proofs = leaf_inputs.len();
+        tracing::info!("num_leaf_proofs={}", num_leaf_proofs);
         leaf_inputs
             .into_iter()
             .enumerate()
             .map(|(leaf_node_idx, input)| {
-                info_span!("single_leaf_agg", idx = leaf_node_idx).in_scope(|| {
-                    SingleSegmentVmProver::prove(
-                        prover,
-                        input.write_to_stream(),
-                        NATIVE_MAX_TRACE_HEIGHTS,
-                    )
-                })
+                let proof = match checkpoint.and_then(|c| c.load_leaf_proof(leaf_node_idx)) {
+                    Some(proof) => proof,
+                    None => {
+                        progress.check_cancelled()?;
+                        let proof =
+                            info_span!("single_leaf_agg", idx = leaf_node_idx).in_scope(|| {
+                                SingleSegmentVmProver::prove(
+                                    prover,
+                                    input.write_to_stream(),
+                                    NATIVE_MAX_TRACE_HEIGHTS,
+                                )
+                            })?;
+                        if let Some(checkpoint) = checkpoint {
+                            checkpoint.save_leaf_proof(leaf_node_idx, &proof);
+                        }
+                        proof
+                    }
+                };
+                progress.report(ProvingEvent::LeafProved {
+                    leaf_idx: leaf_node_idx,
+                    num_leaf_proofs,
+                })?;
+                Ok(proof)
             })
             .collect()
     }
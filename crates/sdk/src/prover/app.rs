@@ -8,9 +8,9 @@ use openvm_circuit::{
     arch::{
         hasher::poseidon2::{vm_poseidon2_hasher, Poseidon2Hasher},
         instructions::exe::VmExe,
-        verify_segments, ContinuationVmProof, ContinuationVmProver, Executor, MeteredExecutor,
-        PreflightExecutor, VerifiedExecutionPayload, VirtualMachine, VirtualMachineError,
-        VmBuilder, VmExecutionConfig, VmInstance, VmVerificationError,
+        verify_segments, ContinuationVmProof, Executor, MeteredExecutor, PreflightExecutor,
+        VerifiedExecutionPayload, VirtualMachine, VirtualMachineError, VmBuilder,
+        VmExecutionConfig, VmInstance, VmVerificationError,
     },
     system::memory::CHUNK,
 };
@@ -28,7 +28,10 @@ use tracing::instrument;
 use crate::{
     commit::{AppExecutionCommit, CommitBytes},
     keygen::AppVerifyingKey,
-    prover::vm::{new_local_prover, types::VmProvingKey},
+    prover::{
+        vm::{new_local_prover, types::VmProvingKey},
+        ProgressReporter, ProvingEvent,
+    },
     util::check_max_constraint_degrees,
     StdIn, F, SC,
 };
@@ -46,6 +49,8 @@ where
     app_vm_vk: MultiStarkVerifyingKey<E::SC>,
     #[getset(get = "pub")]
     leaf_verifier_program_commit: Com<E::SC>,
+    /// Receives [ProvingEvent::SegmentsExecuted] and [ProvingEvent::SegmentProved] events.
+    pub progress: ProgressReporter,
 
     app_execution_commit: OnceLock<AppExecutionCommit>,
 }
@@ -89,6 +94,7 @@ where
             instance,
             app_vm_vk,
             leaf_verifier_program_commit,
+            progress: ProgressReporter::default(),
             app_execution_commit: OnceLock::new(),
         }
     }
@@ -102,6 +108,11 @@ where
         self
     }
 
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = progress;
+        self
+    }
+
     /// Returns [AppExecutionCommit], which is a commitment to **both** the App VM and the App
     /// VmExe.
     pub fn app_commit(&self) -> AppExecutionCommit {
@@ -142,7 +153,21 @@ where
         #[cfg(feature = "metrics")]
         metrics::counter!("fri.log_blowup")
             .absolute(self.instance.vm.engine.fri_params().log_blowup as u64);
-        ContinuationVmProver::prove(&mut self.instance, input)
+        let progress = &self.progress;
+        self.instance.prove_continuations_with_progress(
+            input,
+            |_, _| {},
+            |num_proven, num_segments| {
+                progress.report(if num_proven == 0 {
+                    ProvingEvent::SegmentsExecuted { num_segments }
+                } else {
+                    ProvingEvent::SegmentProved {
+                        segment_idx: num_proven - 1,
+                        num_segments,
+                    }
+                })
+            },
+        )
     }
 
     /// Generates proof for every continuation segment
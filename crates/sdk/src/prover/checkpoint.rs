@@ -0,0 +1,184 @@
+use std::{
+    fs::{create_dir_all, read_to_string, rename, write},
+    path::{Path, PathBuf},
+};
+
+use eyre::eyre;
+use openvm_circuit::arch::ContinuationVmProof;
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_backend::{p3_field::PrimeField32, proof::Proof};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    commit::AppExecutionCommit,
+    fs::{read_object_from_file, write_object_to_file},
+    SdkError, StdIn, F, SC,
+};
+
+const METADATA_FILENAME: &str = "checkpoint.json";
+const APP_PROOF_FILENAME: &str = "app.proof";
+
+/// Identifies the proof a checkpoint directory belongs to. Resuming from a checkpoint of a
+/// different program, input, aggregation key or aggregation tree shape is an error.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointMetadata {
+    pub app_exe_commit: [u32; 8],
+    pub app_vm_commit: [u32; 8],
+    /// Hex encoded SHA-256 digest of the input, see [input_digest].
+    pub input_digest: String,
+    /// Program commit of the internal verifier.
+    pub internal_program_commit: [u32; 8],
+    /// Hash of the aggregation verifying key, see
+    /// [AggVerifyingKey::root_verifier_hash](crate::keygen::AggVerifyingKey::root_verifier_hash).
+    pub agg_vk_hash: String,
+    pub num_children_leaf: usize,
+    pub num_children_internal: usize,
+}
+
+/// On-disk checkpoint of an aggregation. The app proof and every leaf and internal proof are
+/// persisted as soon as they are produced, so that an interrupted proof can be resumed from the
+/// same directory without regenerating them.
+///
+/// Proofs are written to a temporary file and renamed, so a crash never leaves a partially written
+/// proof behind. Failing to persist a proof only logs a warning since it does not affect the
+/// proof being generated.
+#[derive(Clone, Debug)]
+pub struct ProofCheckpoint {
+    dir: PathBuf,
+}
+
+impl ProofCheckpoint {
+    /// Opens the checkpoint in `dir`, creating it if it does not exist. Returns an error if `dir`
+    /// contains a checkpoint with different metadata.
+    pub fn open(dir: impl AsRef<Path>, metadata: &CheckpointMetadata) -> Result<Self, SdkError> {
+        let dir = dir.as_ref().to_path_buf();
+        let metadata_path = dir.join(METADATA_FILENAME);
+        if metadata_path.exists() {
+            let existing: CheckpointMetadata =
+                serde_json::from_str(&read_to_string(&metadata_path)?)
+                    .map_err(|e| SdkError::Other(eyre!("invalid checkpoint metadata: {e}")))?;
+            if &existing != metadata {
+                return Err(SdkError::Other(eyre!(
+                    "checkpoint in {} belongs to a different program, input, aggregation key or aggregation tree config",
+                    dir.display()
+                )));
+            }
+        } else {
+            create_dir_all(&dir)?;
+            let json =
+                serde_json::to_string_pretty(metadata).map_err(|e| SdkError::Other(e.into()))?;
+            write(&metadata_path, json)?;
+        }
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn load_app_proof(&self) -> Option<ContinuationVmProof<SC>> {
+        self.load(APP_PROOF_FILENAME)
+    }
+
+    pub fn save_app_proof(&self, proof: &ContinuationVmProof<SC>) {
+        self.save(APP_PROOF_FILENAME, proof)
+    }
+
+    pub fn load_leaf_proof(&self, idx: usize) -> Option<Proof<SC>> {
+        self.load(&leaf_filename(idx))
+    }
+
+    pub fn save_leaf_proof(&self, idx: usize, proof: &Proof<SC>) {
+        self.save(&leaf_filename(idx), proof)
+    }
+
+    /// Internal proofs are indexed by the height of their layer, starting from 0 for the layer
+    /// verifying leaf proofs, and their index within the layer.
+    pub fn load_internal_proof(&self, height: usize, idx: usize) -> Option<Proof<SC>> {
+        self.load(&internal_filename(height, idx))
+    }
+
+    pub fn save_internal_proof(&self, height: usize, idx: usize, proof: &Proof<SC>) {
+        self.save(&internal_filename(height, idx), proof)
+    }
+
+    fn load<T: DeserializeOwned>(&self, filename: &str) -> Option<T> {
+        let path = self.dir.join(filename);
+        if !path.exists() {
+            return None;
+        }
+        match read_object_from_file(&path) {
+            Ok(proof) => {
+                tracing::info!("loaded {} from checkpoint", path.display());
+                Some(proof)
+            }
+            Err(e) => {
+                tracing::warn!("ignoring unreadable checkpoint file: {e}");
+                None
+            }
+        }
+    }
+
+    fn save<T: Serialize>(&self, filename: &str, proof: &T) {
+        let path = self.dir.join(filename);
+        let tmp_path = self.dir.join(format!("{filename}.tmp"));
+        if let Err(e) = write_object_to_file(&tmp_path, proof)
+            .and_then(|_| rename(&tmp_path, &path).map_err(Into::into))
+        {
+            tracing::warn!("failed to checkpoint {}: {e}", path.display());
+        }
+    }
+}
+
+impl CheckpointMetadata {
+    pub fn new(
+        app_commit: &AppExecutionCommit,
+        input: &StdIn,
+        internal_program_commit: [F; DIGEST_SIZE],
+        agg_vk_hash: String,
+        num_children_leaf: usize,
+        num_children_internal: usize,
+    ) -> Self {
+        Self {
+            app_exe_commit: app_commit.app_exe_commit.to_u32_digest(),
+            app_vm_commit: app_commit.app_vm_commit.to_u32_digest(),
+            input_digest: input_digest(input),
+            internal_program_commit: internal_program_commit.map(|x| x.as_canonical_u32()),
+            agg_vk_hash,
+            num_children_leaf,
+            num_children_internal,
+        }
+    }
+}
+
+/// Hex encoded SHA-256 digest of the input streams and the key-value store. It does not depend on
+/// the iteration order of the key-value store.
+pub fn input_digest(input: &StdIn) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update((input.buffer.len() as u64).to_le_bytes());
+    for data in &input.buffer {
+        hasher.update((data.len() as u64).to_le_bytes());
+        for x in data {
+            hasher.update(x.as_canonical_u32().to_le_bytes());
+        }
+    }
+    let mut kv: Vec<_> = input.kv_store.iter().collect();
+    kv.sort();
+    hasher.update((kv.len() as u64).to_le_bytes());
+    for (key, value) in kv {
+        hasher.update((key.len() as u64).to_le_bytes());
+        hasher.update(key);
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(value);
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn leaf_filename(idx: usize) -> String {
+    format!("leaf_{idx}.proof")
+}
+
+fn internal_filename(height: usize, idx: usize) -> String {
+    format!("internal_{height}_{idx}.proof")
+}
@@ -1,14 +1,20 @@
 use std::sync::Arc;
 
+use openvm_circuit::arch::VirtualMachineError;
 use openvm_native_compiler::prelude::Witness;
 use openvm_native_recursion::{
-    halo2::{utils::Halo2ParamsReader, Halo2Params},
+    halo2::{utils::Halo2ParamsReader, Halo2Params, RawEvmProof},
     witness::Witnessable,
 };
 use openvm_stark_sdk::openvm_stark_backend::proof::Proof;
 use tracing::info_span;
 
-use crate::{keygen::Halo2ProvingKey, types::EvmProof, RootSC};
+use crate::{
+    keygen::Halo2ProvingKey,
+    prover::{Halo2Stage, ProgressReporter, ProvingEvent},
+    types::EvmProof,
+    RootSC,
+};
 
 pub struct Halo2Prover {
     halo2_pk: Halo2ProvingKey,
@@ -29,6 +35,24 @@ impl Halo2Prover {
         }
     }
     pub fn prove_for_evm(&self, root_proof: &Proof<RootSC>) -> EvmProof {
+        self.prove_raw_for_evm(root_proof).try_into().unwrap()
+    }
+
+    /// Generates the EVM proof without parsing its public values. This should be used when the
+    /// halo2 proving key was not generated with the default static verifier public values handler,
+    /// e.g. for the multi-program root verifier.
+    pub fn prove_raw_for_evm(&self, root_proof: &Proof<RootSC>) -> RawEvmProof {
+        self.prove_raw_for_evm_with_progress(root_proof, &ProgressReporter::default())
+            .expect("the default progress reporter never cancels")
+    }
+
+    /// Same as [`prove_raw_for_evm`](Self::prove_raw_for_evm), but reports each
+    /// [Halo2Stage] to `progress` and stops between stages if the proof is cancelled.
+    pub fn prove_raw_for_evm_with_progress(
+        &self,
+        root_proof: &Proof<RootSC>,
+        progress: &ProgressReporter,
+    ) -> Result<RawEvmProof, VirtualMachineError> {
         let mut witness = Witness::default();
         root_proof.write(&mut witness);
         let snark = info_span!("prove", group = "halo2_outer").in_scope(|| {
@@ -36,12 +60,13 @@ impl Halo2Prover {
                 .verifier
                 .prove(&self.verifier_srs, witness, self.halo2_pk.profiling)
         });
-        info_span!("prove_for_evm", group = "halo2_wrapper").in_scope(|| {
+        progress.report(ProvingEvent::Halo2StageProved(Halo2Stage::StaticVerifier))?;
+        let evm_proof = info_span!("prove_for_evm", group = "halo2_wrapper").in_scope(|| {
             self.halo2_pk
                 .wrapper
                 .prove_for_evm(&self.wrapper_srs, snark)
-                .try_into()
-                .unwrap()
-        })
+        });
+        progress.report(ProvingEvent::Halo2StageProved(Halo2Stage::Wrapper))?;
+        Ok(evm_proof)
     }
 }
@@ -1,19 +1,27 @@
 mod agg;
 mod app;
+mod checkpoint;
 #[cfg(feature = "evm-prove")]
 mod halo2;
 #[cfg(feature = "evm-prove")]
+mod multi;
+mod progress;
+#[cfg(feature = "evm-prove")]
 mod root;
 mod stark;
 pub mod vm;
 
 pub use agg::*;
 pub use app::*;
+pub use checkpoint::*;
 #[cfg(feature = "evm-prove")]
 pub use evm::*;
 #[cfg(feature = "evm-prove")]
 pub use halo2::*;
 #[cfg(feature = "evm-prove")]
+pub use multi::*;
+pub use progress::*;
+#[cfg(feature = "evm-prove")]
 pub use root::*;
 pub use stark::*;
 
@@ -29,7 +37,7 @@ mod evm {
     use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
     use openvm_stark_sdk::engine::StarkFriEngine;
 
-    use super::{Halo2Prover, StarkProver};
+    use super::{Halo2Prover, ProgressReporter, StarkProver};
     use crate::{
         config::AggregationTreeConfig,
         keygen::{AggProvingKey, AppProvingKey, Halo2ProvingKey},
@@ -46,6 +54,7 @@ mod evm {
     {
         pub stark_prover: StarkProver<E, VB, NativeBuilder>,
         pub halo2_prover: Halo2Prover,
+        progress: ProgressReporter,
     }
 
     impl<E, VB, NativeBuilder> EvmHalo2Prover<E, VB, NativeBuilder>
@@ -81,6 +90,7 @@ mod evm {
             Ok(Self {
                 stark_prover,
                 halo2_prover: Halo2Prover::new(reader, halo2_pk),
+                progress: ProgressReporter::default(),
             })
         }
 
@@ -93,12 +103,21 @@ mod evm {
             self
         }
 
+        /// Reports the progress of the STARK and halo2 proofs to `progress`.
+        pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+            self.stark_prover.set_progress(progress.clone());
+            self.progress = progress;
+            self
+        }
+
         pub fn prove_evm(&mut self, input: StdIn) -> Result<EvmProof, VirtualMachineError> {
             let root_proof = self
                 .stark_prover
                 .generate_proof_for_outer_recursion(input)?;
-            let evm_proof = self.halo2_prover.prove_for_evm(&root_proof);
-            Ok(evm_proof)
+            let evm_proof = self
+                .halo2_prover
+                .prove_raw_for_evm_with_progress(&root_proof, &self.progress)?;
+            Ok(evm_proof.try_into().unwrap())
         }
     }
 }
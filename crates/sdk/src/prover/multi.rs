@@ -0,0 +1,169 @@
+use openvm_circuit::arch::{
+    PreflightExecutor, SingleSegmentVmProver, VirtualMachineError, VmBuilder, VmExecutionConfig,
+    VmInstance,
+};
+use openvm_continuations::verifier::{
+    internal::types::{InternalVmVerifierInput, VmStarkProof},
+    multi_root::types::MultiRootVmVerifierInput,
+};
+use openvm_native_circuit::{NativeConfig, NATIVE_MAX_TRACE_HEIGHTS};
+use openvm_native_recursion::hints::Hintable;
+use openvm_stark_sdk::{engine::StarkFriEngine, openvm_stark_backend::proof::Proof};
+use tracing::{info_span, instrument};
+
+use crate::{
+    config::AggregationTreeConfig,
+    keygen::{AggProvingKey, MultiRootVerifierProvingKey},
+    prover::{vm::new_local_prover, RootVerifierLocalProver},
+    util::check_max_constraint_degrees,
+    RootSC, SdkError, F, SC,
+};
+
+/// Prover that aggregates the aggregate STARK proofs of several programs, as produced by
+/// [StarkProver::prove](crate::prover::StarkProver::prove), into a single root proof of the
+/// multi-program root verifier.
+pub struct MultiProgramAggProver<E, NativeBuilder>
+where
+    E: StarkFriEngine<SC = SC>,
+    NativeBuilder: VmBuilder<E, VmConfig = NativeConfig>,
+{
+    pub internal_prover: VmInstance<E, NativeBuilder>,
+    root_prover: RootVerifierLocalProver,
+    num_user_public_values: Vec<usize>,
+    pub max_internal_wrapper_layers: usize,
+}
+
+impl<E, NativeBuilder> MultiProgramAggProver<E, NativeBuilder>
+where
+    E: StarkFriEngine<SC = SC>,
+    NativeBuilder: VmBuilder<E, VmConfig = NativeConfig>,
+    <NativeConfig as VmExecutionConfig<F>>::Executor:
+        PreflightExecutor<F, <NativeBuilder as VmBuilder<E>>::RecordArena>,
+{
+    pub fn new(
+        native_builder: NativeBuilder,
+        agg_pk: &AggProvingKey,
+        multi_root_pk: &MultiRootVerifierProvingKey,
+        tree_config: AggregationTreeConfig,
+    ) -> Result<Self, VirtualMachineError> {
+        let internal_prover = new_local_prover(
+            native_builder,
+            &agg_pk.internal_vm_pk,
+            agg_pk.internal_committed_exe.exe.clone(),
+        )?;
+        let root_prover = RootVerifierLocalProver::new(&multi_root_pk.root_verifier_pk)?;
+        Ok(Self {
+            internal_prover,
+            root_prover,
+            num_user_public_values: multi_root_pk.num_user_public_values.clone(),
+            max_internal_wrapper_layers: tree_config.max_internal_wrapper_layers,
+        })
+    }
+
+    /// Generates the root proof aggregating `proofs`, which must contain one aggregate STARK
+    /// proof per program, in program order.
+    pub fn generate_root_proof(
+        &mut self,
+        proofs: Vec<VmStarkProof<SC>>,
+    ) -> Result<Proof<RootSC>, SdkError> {
+        let root_input = self.generate_root_verifier_input(proofs)?;
+        Ok(self.generate_root_proof_impl(root_input)?)
+    }
+
+    /// Wraps the proofs of all programs until the heights of the multi-program root verifier meet
+    /// its fixed heights, and returns the input of the multi-program root verifier.
+    pub fn generate_root_verifier_input(
+        &mut self,
+        proofs: Vec<VmStarkProof<SC>>,
+    ) -> Result<MultiRootVmVerifierInput<SC>, SdkError> {
+        if proofs.len() != self.num_user_public_values.len() {
+            return Err(SdkError::InvalidNumProofs {
+                expected: self.num_user_public_values.len(),
+                actual: proofs.len(),
+            });
+        }
+        let mut inner_proofs = Vec::with_capacity(proofs.len());
+        let mut public_values = Vec::new();
+        for (i, (proof, &num_user_public_values)) in proofs
+            .into_iter()
+            .zip(&self.num_user_public_values)
+            .enumerate()
+        {
+            if proof.user_public_values.len() != num_user_public_values {
+                return Err(SdkError::InvalidNumUserPublicValues {
+                    program: i,
+                    expected: num_user_public_values,
+                    actual: proof.user_public_values.len(),
+                });
+            }
+            inner_proofs.push(proof.inner);
+            public_values.extend(proof.user_public_values);
+        }
+
+        let internal_commit = (*self.internal_prover.program_commitment()).into();
+        let mut wrapper_layers = 0;
+        loop {
+            let input = MultiRootVmVerifierInput {
+                proofs: inner_proofs.clone(),
+                public_values: public_values.clone(),
+            };
+            let actual_air_heights = self.root_prover.execute_for_air_heights(input)?;
+            // Root verifier can handle the internal proofs. We can stop here.
+            if heights_le(&actual_air_heights, self.root_prover.fixed_air_heights()) {
+                break;
+            }
+            if wrapper_layers >= self.max_internal_wrapper_layers {
+                panic!("The heights of the multi-program root verifier still exceed the required heights after {} wrapper layers", self.max_internal_wrapper_layers);
+            }
+            wrapper_layers += 1;
+            // Every proof is wrapped since the fixed heights are only known for all proofs
+            // together.
+            let internal_prover = &mut self.internal_prover;
+            inner_proofs = info_span!(
+                "wrapper_layer",
+                group = format!("internal_wrapper.{wrapper_layers}")
+            )
+            .in_scope(|| {
+                inner_proofs
+                    .into_iter()
+                    .map(|proof| {
+                        let input = InternalVmVerifierInput {
+                            self_program_commit: internal_commit,
+                            proofs: vec![proof],
+                        };
+                        SingleSegmentVmProver::prove(
+                            internal_prover,
+                            input.write(),
+                            NATIVE_MAX_TRACE_HEIGHTS,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })?;
+        }
+        Ok(MultiRootVmVerifierInput {
+            proofs: inner_proofs,
+            public_values,
+        })
+    }
+
+    #[instrument(name = "agg_layer", skip_all, fields(group = "multi_root", idx = 0))]
+    fn generate_root_proof_impl(
+        &mut self,
+        root_input: MultiRootVmVerifierInput<SC>,
+    ) -> Result<Proof<RootSC>, VirtualMachineError> {
+        check_max_constraint_degrees(
+            self.root_prover.vm_config().as_ref(),
+            self.root_prover.fri_params(),
+        );
+        let input = root_input.write();
+        #[cfg(feature = "metrics")]
+        metrics::counter!("fri.log_blowup")
+            .absolute(self.root_prover.fri_params().log_blowup as u64);
+        SingleSegmentVmProver::prove(&mut self.root_prover, input, NATIVE_MAX_TRACE_HEIGHTS)
+    }
+}
+
+fn heights_le(a: &[u32], b: &[u32]) -> bool {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b.iter()).all(|(a, b)| a <= b)
+}
@@ -0,0 +1,120 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::Sender,
+    Arc,
+};
+
+use openvm_circuit::arch::VirtualMachineError;
+
+/// Progress of a proof, reported to a [ProgressReporter] as proving advances.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProvingEvent {
+    /// Metered execution finished and split the execution into `num_segments` segments.
+    SegmentsExecuted { num_segments: usize },
+    /// The app proof of segment `segment_idx` is done.
+    SegmentProved {
+        segment_idx: usize,
+        num_segments: usize,
+    },
+    /// Leaf proof `leaf_idx` is done.
+    LeafProved {
+        leaf_idx: usize,
+        num_leaf_proofs: usize,
+    },
+    /// All `num_proofs` internal proofs of the layer at `height` are done, where height 0 is the
+    /// layer verifying leaf proofs.
+    InternalLayerProved { height: usize, num_proofs: usize },
+    /// The root verifier proof for outer recursion is done.
+    RootProved,
+    /// A stage of the halo2 proof for EVM verification is done.
+    Halo2StageProved(Halo2Stage),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Halo2Stage {
+    /// The static verifier circuit verifying the root proof.
+    StaticVerifier,
+    /// The wrapper circuit producing the final EVM proof.
+    Wrapper,
+}
+
+/// Token to cancel a proof from another thread. Provers check it between proving stages and stop
+/// with [VirtualMachineError::Cancelled], which the SDK surfaces as
+/// [SdkError::Cancelled](crate::SdkError::Cancelled).
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(ProvingEvent) + Send + Sync>;
+
+/// Receives the [ProvingEvent]s of a proof through a callback or a channel, and cancels it through
+/// a [CancellationToken]. The default reporter ignores events and never cancels.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    callback: Option<ProgressCallback>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl ProgressReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `callback` on the proving thread for every event. It should return quickly.
+    pub fn with_callback(
+        mut self,
+        callback: impl Fn(ProvingEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sends every event to `sender`. Events are dropped once the receiver is disconnected.
+    pub fn with_channel(self, sender: Sender<ProvingEvent>) -> Self {
+        self.with_callback(move |event| {
+            let _ = sender.send(event);
+        })
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Reports `event` and returns [VirtualMachineError::Cancelled] if the proof was cancelled.
+    pub fn report(&self, event: ProvingEvent) -> Result<(), VirtualMachineError> {
+        if let Some(callback) = &self.callback {
+            callback(event);
+        }
+        self.check_cancelled()
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), VirtualMachineError> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(VirtualMachineError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("callback", &self.callback.is_some())
+            .field("cancellation", &self.cancellation)
+            .finish()
+    }
+}
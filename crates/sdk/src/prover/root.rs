@@ -4,7 +4,6 @@ use openvm_circuit::arch::{
     GenerationError, PreflightExecutionOutput, SingleSegmentVmProver, Streams, VirtualMachine,
     VirtualMachineError, VmInstance,
 };
-use openvm_continuations::verifier::root::types::RootVmVerifierInput;
 use openvm_native_circuit::{NativeConfig, NativeCpuBuilder, NATIVE_MAX_TRACE_HEIGHTS};
 use openvm_native_recursion::hints::Hintable;
 use openvm_stark_sdk::{
@@ -16,7 +15,7 @@ use openvm_stark_sdk::{
 use crate::{
     keygen::{perm::AirIdPermutation, RootVerifierProvingKey},
     prover::vm::new_local_prover,
-    RootSC, F, SC,
+    RootSC, C, F,
 };
 
 /// Local prover for a root verifier.
@@ -65,9 +64,12 @@ impl RootVerifierLocalProver {
         &self.inner.vm.engine.fri_params
     }
 
+    /// Executes the root verifier program on `input` and returns the resulting trace heights,
+    /// ordered by AIR ID. The input must be of the type read by the root verifier program, e.g.
+    /// [RootVmVerifierInput](openvm_continuations::verifier::root::types::RootVmVerifierInput).
     pub fn execute_for_air_heights(
         &mut self,
-        input: RootVmVerifierInput<SC>,
+        input: impl Hintable<C>,
     ) -> Result<Vec<u32>, VirtualMachineError> {
         let exe = self.inner.exe().clone();
         // See `SingleSegmentVmProver::prove` for explanation
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use openvm_circuit::arch::{
     instructions::exe::VmExe, Executor, MeteredExecutor, PreflightExecutor, VirtualMachineError,
@@ -16,8 +16,13 @@ use crate::{
     commit::AppExecutionCommit,
     config::AggregationTreeConfig,
     keygen::{AggProvingKey, AppProvingKey},
-    prover::{agg::AggStarkProver, app::AppProver},
-    StdIn, F, SC,
+    prover::{
+        agg::AggStarkProver,
+        app::AppProver,
+        checkpoint::{CheckpointMetadata, ProofCheckpoint},
+        progress::ProgressReporter,
+    },
+    SdkError, StdIn, F, SC,
 };
 
 /// This prover contains an [`app_prover`](StarkProver::app_prover) internally.
@@ -48,6 +53,38 @@ where
         agg_pk: &AggProvingKey,
         agg_tree_config: AggregationTreeConfig,
     ) -> Result<Self, VirtualMachineError> {
+        Self::new_with_app_fri_profile(
+            app_vm_builder,
+            native_builder,
+            app_pk,
+            app_exe,
+            agg_pk,
+            agg_tree_config,
+            0,
+        )
+    }
+
+    /// Same as [new](Self::new), but the App VM proofs are generated with the app FRI profile
+    /// `fri_profile`, see
+    /// [AggregationConfig::extra_app_fri_params](crate::config::AggregationConfig::extra_app_fri_params).
+    pub fn new_with_app_fri_profile(
+        app_vm_builder: VB,
+        native_builder: NativeBuilder,
+        app_pk: &AppProvingKey<VB::VmConfig>,
+        app_exe: Arc<VmExe<F>>,
+        agg_pk: &AggProvingKey,
+        agg_tree_config: AggregationTreeConfig,
+        fri_profile: usize,
+    ) -> Result<Self, VirtualMachineError> {
+        assert!(
+            app_pk.num_app_fri_profiles() <= agg_pk.num_app_fri_profiles,
+            "App VM is incompatible with Agg VM because of the accepted app FRI profiles"
+        );
+        let app_vm_pk = app_pk
+            .app_vm_pk_for_profile(fri_profile)
+            .unwrap_or_else(|| {
+                panic!("App FRI profile {fri_profile} is not accepted by the App VM")
+            });
         assert_eq!(
             app_pk.leaf_fri_params, agg_pk.leaf_vm_pk.fri_params,
             "App VM is incompatible with Agg VM because of leaf FRI parameters"
@@ -61,7 +98,7 @@ where
         Ok(Self {
             app_prover: AppProver::new(
                 app_vm_builder,
-                &app_pk.app_vm_pk,
+                app_vm_pk,
                 app_exe,
                 app_pk.leaf_committed_exe.get_program_commit(),
             )?,
@@ -70,7 +107,8 @@ where
                 agg_pk,
                 app_pk.leaf_committed_exe.exe.clone(),
                 agg_tree_config,
-            )?,
+            )?
+            .with_app_fri_profile(fri_profile),
         })
     }
 
@@ -93,6 +131,17 @@ where
         self
     }
 
+    /// Reports the progress of the app and aggregation proofs to `progress`.
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.set_progress(progress);
+        self
+    }
+    pub fn set_progress(&mut self, progress: ProgressReporter) -> &mut Self {
+        self.app_prover.progress = progress.clone();
+        self.agg_prover.progress = progress;
+        self
+    }
+
     pub fn app_commit(&self) -> AppExecutionCommit {
         self.app_prover.app_commit()
     }
@@ -104,6 +153,49 @@ where
             .aggregate_leaf_proofs(leaf_proofs, app_proof.user_public_values.public_values)
     }
 
+    /// Same as [`prove`](Self::prove), but persists the app proof and every leaf and internal proof
+    /// to `checkpoint_dir` as they are produced. If `checkpoint_dir` contains a checkpoint of an
+    /// interrupted proof of the same program and input, the proof resumes from it.
+    pub fn prove_with_checkpoint(
+        &mut self,
+        input: StdIn,
+        checkpoint_dir: impl AsRef<Path>,
+    ) -> Result<VmStarkProof<SC>, SdkError> {
+        let agg_vk_hash = self.agg_prover.agg_vk_hash.clone().ok_or_else(|| {
+            SdkError::Other(eyre::eyre!(
+                "checkpointing requires an aggregation prover constructed from an AggProvingKey"
+            ))
+        })?;
+        let metadata = CheckpointMetadata::new(
+            &self.app_commit(),
+            &input,
+            (*self.agg_prover.internal_prover.program_commitment()).into(),
+            agg_vk_hash,
+            self.agg_prover.num_children_leaf(),
+            self.agg_prover.num_children_internal,
+        );
+        let checkpoint = ProofCheckpoint::open(checkpoint_dir, &metadata)?;
+        let app_proof = match checkpoint.load_app_proof() {
+            Some(app_proof) => app_proof,
+            None => {
+                let app_proof = self.app_prover.prove(input)?;
+                checkpoint.save_app_proof(&app_proof);
+                app_proof
+            }
+        };
+
+        let prev_checkpoint = self.agg_prover.checkpoint.replace(checkpoint);
+        let proof = self
+            .agg_prover
+            .generate_leaf_proofs(&app_proof)
+            .and_then(|leaf_proofs| {
+                self.agg_prover
+                    .aggregate_leaf_proofs(leaf_proofs, app_proof.user_public_values.public_values)
+            });
+        self.agg_prover.checkpoint = prev_checkpoint;
+        Ok(proof?)
+    }
+
     #[cfg(feature = "evm-prove")]
     pub fn generate_proof_for_outer_recursion(
         &mut self,
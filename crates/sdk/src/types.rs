@@ -2,8 +2,14 @@ use std::{io::Cursor, sync::Arc};
 
 use derive_more::derive::From;
 use eyre::Result;
-use openvm::platform::memory::MEM_SIZE;
-use openvm_circuit::arch::instructions::exe::VmExe;
+use openvm::platform::memory::MemoryLayout;
+use openvm_circuit::{
+    arch::{
+        execution_mode::MemoryProfile,
+        instructions::{exe::VmExe, riscv::RV32_MEMORY_AS},
+    },
+    system::memory::{online::LinearMemory, MemoryImage},
+};
 use openvm_continuations::{verifier::internal::types::VmStarkProof, SC};
 use openvm_stark_backend::proof::Proof;
 use openvm_transpiler::elf::Elf;
@@ -34,22 +40,118 @@ const NUM_BN254_PROOF: usize = 43;
 #[derive(From)]
 pub enum ExecutableFormat {
     Elf(Elf),
+    /// Bytes of an ELF file, decoded with the memory size of the configured memory layout.
+    ElfBytes(Vec<u8>),
     VmExe(VmExe<crate::F>),
     SharedVmExe(Arc<VmExe<crate::F>>),
 }
 
 impl<'a> From<&'a [u8]> for ExecutableFormat {
     fn from(bytes: &'a [u8]) -> Self {
-        let elf = Elf::decode(bytes, MEM_SIZE.try_into().unwrap()).expect("Invalid ELF bytes");
-        ExecutableFormat::Elf(elf)
+        ExecutableFormat::ElfBytes(bytes.to_vec())
     }
 }
-impl From<Vec<u8>> for ExecutableFormat {
-    fn from(bytes: Vec<u8>) -> Self {
-        ExecutableFormat::from(&bytes[..])
+
+/// Guest memory usage of an execution, interpreting the [MemoryProfile] with the [MemoryLayout] of
+/// the program: the stack grows down from `stack_top` and the heap grows up from the end of the
+/// program's sections (the `_end` symbol).
+///
+/// The peak heap pointer is read from the bump allocator's heap pointer in the final memory, so it
+/// counts memory that was allocated but never accessed. It is only available for guests that use
+/// the bump allocator of `openvm-platform` (the default heap and the freelist heap, which takes its
+/// slabs from it). Otherwise only the highest accessed heap address is reported.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuestMemoryUsage {
+    /// Top of the stack in the memory layout of the program.
+    pub stack_top: u32,
+    /// End of the memory available to the heap in the memory layout of the program.
+    pub heap_end: u32,
+    /// First address after the program's text, data and bss sections, where the heap begins.
+    pub heap_start: u32,
+    /// Final value of the bump allocator's heap pointer, or `None` if the program does not use the
+    /// bump allocator or allocated nothing.
+    pub peak_heap_ptr: Option<u32>,
+    /// One past the highest address accessed at or above `heap_start`, or `None` if the heap was
+    /// not accessed.
+    pub max_heap_addr: Option<u32>,
+    /// Lowest address accessed below `stack_top`, or `None` if the stack was not accessed.
+    pub stack_low_water: Option<u32>,
+    pub profile: MemoryProfile,
+}
+
+impl GuestMemoryUsage {
+    /// `final_memory` is the guest memory at the end of the execution.
+    pub fn new(
+        exe: &VmExe<crate::F>,
+        profile: MemoryProfile,
+        layout: &MemoryLayout,
+        final_memory: &MemoryImage,
+    ) -> Self {
+        let stack_top = layout.stack_top;
+        // Without the `_end` symbol, fall back to the end of the initial memory image, which
+        // contains the zero-initialized .bss section.
+        let heap_start = exe.heap_symbols.heap_start.unwrap_or_else(|| {
+            exe.init_memory
+                .keys()
+                .filter(|(addr_space, _)| *addr_space == RV32_MEMORY_AS)
+                .map(|(_, ptr)| ptr + 1)
+                .max()
+                .unwrap_or(stack_top)
+                .max(stack_top)
+        });
+        let peak_heap_ptr = exe.heap_symbols.heap_pos.and_then(|heap_pos| {
+            let heap_pos = heap_pos as usize;
+            let bytes = final_memory.mem[RV32_MEMORY_AS as usize]
+                .as_slice()
+                .get(heap_pos..heap_pos + 4)?;
+            let ptr = u32::from_le_bytes(bytes.try_into().unwrap());
+            (ptr != 0).then_some(ptr)
+        });
+        let memory = profile.address_space(RV32_MEMORY_AS);
+        let max_heap_addr = memory
+            .map(|usage| usage.max_ptr)
+            .filter(|&max_ptr| max_ptr > heap_start);
+        let stack_low_water = memory
+            .map(|usage| usage.min_ptr)
+            .filter(|&min_ptr| min_ptr < stack_top);
+        Self {
+            stack_top,
+            heap_end: layout.mem_size() as u32,
+            heap_start,
+            peak_heap_ptr,
+            max_heap_addr,
+            stack_low_water,
+            profile,
+        }
+    }
+
+    /// Number of bytes from `heap_start` up to the peak heap pointer.
+    pub fn heap_bytes(&self) -> u32 {
+        self.peak_heap_ptr.map_or(0, |peak_heap_ptr| {
+            peak_heap_ptr.saturating_sub(self.heap_start)
+        })
+    }
+
+    /// Number of bytes from `heap_start` up to the highest accessed heap address.
+    pub fn accessed_heap_bytes(&self) -> u32 {
+        self.max_heap_addr
+            .map_or(0, |max_heap_addr| max_heap_addr - self.heap_start)
+    }
+
+    /// Maximum stack depth in bytes.
+    pub fn stack_bytes(&self) -> u32 {
+        self.stack_low_water
+            .map_or(0, |stack_low_water| self.stack_top - stack_low_water)
     }
 }
 
+/// Public values of one program aggregated into a proof of the multi-program root verifier.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AggregatedProgramPublicValues {
+    pub app_commit: crate::commit::AppExecutionCommit,
+    pub user_public_values: Vec<crate::F>,
+}
+
 #[cfg(feature = "evm-prove")]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EvmHalo2Verifier {
@@ -5,26 +5,42 @@ use std::{
 };
 
 use eyre::Result;
+use openvm::platform::memory::{MemoryLayout, GUEST_MIN_MEM};
 use openvm_build::GuestOptions;
 use openvm_circuit::{
     self,
     arch::{
-        instructions::exe::VmExe, ContinuationVmProof, ExecutionError, VirtualMachine,
-        VirtualMachineError, VmExecState,
+        instructions::exe::VmExe, ContinuationVmProof, ExecutionError, GuardRegion,
+        MemoryGuardConfig, VirtualMachine, VirtualMachineError, VmExecState,
     },
     utils::test_system_config,
 };
 use openvm_continuations::verifier::{
     common::types::VmVerifierPvs,
-    leaf::types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+    leaf::{
+        types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+        AppFriProfile, LeafVmVerifierConfig,
+    },
 };
 use openvm_native_circuit::{execute_program_with_config, NativeConfig, NativeCpuBuilder};
-use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
+use openvm_native_compiler::{
+    conversion::CompilerOptions,
+    prelude::*,
+    text::{assemble, disassemble},
+};
 use openvm_sdk::{
     codec::{Decode, Encode},
-    config::{AggregationConfig, AppConfig, SdkSystemConfig, SdkVmBuilder, SdkVmConfig},
-    prover::verify_app_proof,
-    DefaultStarkEngine, Sdk, StdIn,
+    config::{
+        memory_guards_for_layout, AggregationConfig, AppConfig, SdkSystemConfig, SdkVmBuilder,
+        SdkVmConfig,
+    },
+    keygen::{
+        cache::{app_config_hash, KeyCache},
+        AppProvingKey,
+    },
+    prover::{verify_app_proof, CancellationToken, ProgressReporter, ProvingEvent},
+    types::VersionedVmStarkProof,
+    DefaultStarkEngine, Sdk, SdkError, StdIn,
 };
 use openvm_stark_sdk::{
     config::{
@@ -121,6 +137,7 @@ fn agg_config_for_test() -> AggregationConfig {
             ..Default::default()
         },
         root_max_constraint_degree: (1 << ROOT_LOG_BLOWUP) + 1,
+        extra_app_fri_params: vec![],
     }
 }
 
@@ -145,6 +162,7 @@ fn small_test_app_config(app_log_blowup: usize) -> AppConfig<SdkVmConfig> {
             enable_cycle_tracker: true,
             ..Default::default()
         },
+        memory_layout: None,
     }
 }
 
@@ -178,6 +196,7 @@ fn test_public_values_and_leaf_verification() -> eyre::Result<()> {
             LeafVmVerifierInput {
                 proofs: app_proof.per_segment.clone(),
                 public_values_root_proof: None,
+                fri_profile: 0,
             },
         )
         .expect("failed to verify the first segment");
@@ -207,6 +226,7 @@ fn test_public_values_and_leaf_verification() -> eyre::Result<()> {
             LeafVmVerifierInput {
                 proofs: vec![app_last_proof.clone()],
                 public_values_root_proof: Some(pv_root_proof.clone()),
+                fri_profile: 0,
             },
         )
         .expect("failed to verify the second segment");
@@ -233,6 +253,7 @@ fn test_public_values_and_leaf_verification() -> eyre::Result<()> {
             LeafVmVerifierInput {
                 proofs: vec![app_last_proof.clone()],
                 public_values_root_proof: Some(wrong_pv_root_proof),
+                fri_profile: 0,
             },
         );
         assert!(
@@ -255,6 +276,7 @@ fn test_public_values_and_leaf_verification() -> eyre::Result<()> {
             LeafVmVerifierInput {
                 proofs: vec![app_last_proof.clone()],
                 public_values_root_proof: Some(wrong_pv_root_proof),
+                fri_profile: 0,
             },
         );
         assert!(
@@ -269,6 +291,56 @@ fn test_public_values_and_leaf_verification() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_leaf_verification_with_extra_fri_profile() -> eyre::Result<()> {
+    setup_tracing();
+    let exe = app_exe_for_test();
+    let leaf_vm_config = agg_config_for_test().leaf_vm_config();
+
+    // Profile 0 has log blowup 1 and profile 1 has log blowup 2.
+    let sdks = [
+        Sdk::new(small_test_app_config(1))?,
+        Sdk::new(small_test_app_config(2))?,
+    ];
+    let app_vks = sdks.each_ref().map(|sdk| sdk.app_pk().get_app_vk());
+    let leaf_program = LeafVmVerifierConfig {
+        app_fri_params: app_vks[0].fri_params,
+        app_system_config: app_vm_config_for_test().as_ref().clone(),
+        compiler_options: Default::default(),
+        extra_app_profiles: vec![AppFriProfile {
+            fri_params: app_vks[1].fri_params,
+            app_vm_vk: app_vks[1].vk.clone(),
+        }],
+    }
+    .build_program(&app_vks[0].vk);
+    let leaf_exe = VmExe::new(leaf_program);
+
+    for (profile, sdk) in sdks.iter().enumerate() {
+        let app_proof = sdk.app_prover(exe.clone())?.prove(StdIn::default())?;
+        let mut leaf_input = LeafVmVerifierInput::chunk_continuation_vm_proof(
+            &app_proof,
+            app_proof.per_segment.len(),
+        )
+        .pop()
+        .unwrap();
+        leaf_input.fri_profile = profile;
+        let runtime_pvs = run_leaf_verifier(&leaf_vm_config, &leaf_exe, leaf_input.clone())
+            .expect("failed to verify the app proof");
+        let leaf_vm_pvs: &VmVerifierPvs<F> = runtime_pvs.as_slice().borrow();
+        assert_eq!(leaf_vm_pvs.fri_profile, F::from_canonical_usize(profile));
+        assert_eq!(leaf_vm_pvs.connector.is_terminate, F::ONE);
+
+        // Failure: the proof is verified with the parameters of another profile.
+        leaf_input.fri_profile = 1 - profile;
+        assert!(run_leaf_verifier(&leaf_vm_config, &leaf_exe, leaf_input.clone()).is_err());
+
+        // Failure: the profile does not exist.
+        leaf_input.fri_profile = 2;
+        assert!(run_leaf_verifier(&leaf_vm_config, &leaf_exe, leaf_input).is_err());
+    }
+    Ok(())
+}
+
 #[test]
 fn test_metered_execution_suspension() -> eyre::Result<()> {
     setup_tracing();
@@ -432,6 +504,287 @@ fn test_e2e_proof_generation_and_verification_with_pvs() -> eyre::Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "evm-prove")]
+#[test]
+fn test_multi_program_aggregation() -> eyre::Result<()> {
+    setup_tracing();
+    let sdk1 = Sdk::new(small_test_app_config(1))?.with_agg_config(agg_config_for_test());
+    // A different App VM config has a different leaf verifier
+    let sdk2 = Sdk::new(small_test_app_config(2))?
+        .with_agg_config(agg_config_for_test())
+        .with_agg_pk(sdk1.agg_pk().clone());
+
+    let (proof1, app_commit1) = sdk1.prove(app_exe_for_test(), StdIn::default())?;
+    let (proof2, app_commit2) = sdk2.prove(app_exe_for_test(), StdIn::default())?;
+    assert_ne!(app_commit1.app_vm_commit, app_commit2.app_vm_commit);
+    let user_public_values = [
+        proof1.user_public_values.clone(),
+        proof2.user_public_values.clone(),
+    ];
+
+    let (multi_root_pk, _) = sdk1.multi_root_keygen(vec![NUM_PUB_VALUES, NUM_PUB_VALUES])?;
+    let mut prover = sdk1.multi_program_prover(&multi_root_pk)?;
+    assert!(matches!(
+        prover.generate_root_verifier_input(vec![proof1.clone()]),
+        Err(SdkError::InvalidNumProofs {
+            expected: 2,
+            actual: 1
+        })
+    ));
+    let mut truncated = proof2.clone();
+    truncated.user_public_values.pop();
+    assert!(matches!(
+        prover.generate_root_verifier_input(vec![proof1.clone(), truncated]),
+        Err(SdkError::InvalidNumUserPublicValues { program: 1, .. })
+    ));
+    let root_proof = prover.generate_root_proof(vec![proof1, proof2])?;
+
+    let programs = Sdk::verify_multi_program_proof(&multi_root_pk.get_vk(), &root_proof)?;
+    assert_eq!(programs.len(), 2);
+    for (program, (app_commit, user_public_values)) in programs.iter().zip(
+        [app_commit1, app_commit2]
+            .into_iter()
+            .zip(user_public_values),
+    ) {
+        assert_eq!(program.app_commit, app_commit);
+        assert_eq!(program.user_public_values, user_public_values);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_prove_with_checkpoint_resume() -> eyre::Result<()> {
+    let sdk = Sdk::new(small_test_app_config(1))?.with_agg_config(agg_config_for_test());
+    let checkpoint_dir = tempfile::tempdir()?;
+    let (proof, app_commit) =
+        sdk.prove_with_checkpoint(app_exe_for_test(), StdIn::default(), checkpoint_dir.path())?;
+
+    // Simulate a crash after the leaf proofs were generated
+    for entry in std::fs::read_dir(checkpoint_dir.path())? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("internal_"))
+        {
+            std::fs::remove_file(path)?;
+        }
+    }
+    let (resumed_proof, _) =
+        sdk.prove_with_checkpoint(app_exe_for_test(), StdIn::default(), checkpoint_dir.path())?;
+    Sdk::verify_proof(&sdk.agg_pk().get_agg_vk(), app_commit, &resumed_proof)?;
+    assert_eq!(proof.user_public_values, resumed_proof.user_public_values);
+
+    // The checkpoint belongs to a different input
+    let mut other_input = StdIn::default();
+    other_input.write(&1u32);
+    assert!(sdk
+        .prove_with_checkpoint(app_exe_for_test(), other_input, checkpoint_dir.path())
+        .is_err());
+
+    // The checkpoint was made with a different aggregation key
+    let metadata_path = checkpoint_dir.path().join("checkpoint.json");
+    let mut metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&metadata_path)?)?;
+    metadata["agg_vk_hash"] = serde_json::Value::String("00".repeat(32));
+    std::fs::write(&metadata_path, metadata.to_string())?;
+    assert!(sdk
+        .prove_with_checkpoint(app_exe_for_test(), StdIn::default(), checkpoint_dir.path())
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_prove_with_extra_app_fri_profile() -> eyre::Result<()> {
+    setup_tracing();
+    let mut agg_config = agg_config_for_test();
+    agg_config.extra_app_fri_params = vec![FriParameters::new_for_testing(2)];
+    let sdk = Sdk::new(small_test_app_config(1))?.with_agg_config(agg_config);
+    let agg_vk = sdk.agg_pk().get_agg_vk();
+    assert_eq!(agg_vk.num_app_fri_profiles(), 2);
+
+    for profile in 0..2 {
+        let mut prover = sdk.prover_with_app_fri_profile(app_exe_for_test(), profile)?;
+        let app_commit = prover.app_commit();
+        let proof = prover.prove(StdIn::default())?;
+        Sdk::verify_proof(&agg_vk, app_commit, &proof)?;
+    }
+
+    assert!(matches!(
+        sdk.prover_with_app_fri_profile(app_exe_for_test(), 2),
+        Err(SdkError::InvalidAppFriProfile {
+            num_profiles: 2,
+            actual: 2
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_standalone_verifier() -> eyre::Result<()> {
+    let sdk = Sdk::new(small_test_app_config(1))?.with_agg_config(agg_config_for_test());
+    let (proof, app_commit) = sdk.prove(app_exe_for_test(), StdIn::default())?;
+    let agg_vk = sdk.agg_pk().get_agg_vk();
+
+    let vk = openvm_verifier::AggVerifyingKey::from_bytes(&bitcode::serialize(&agg_vk)?)?;
+    let commit = openvm_verifier::AppExecutionCommit::new(
+        *app_commit.app_exe_commit.as_slice(),
+        *app_commit.app_vm_commit.as_slice(),
+    );
+    let versioned: openvm_verifier::VersionedVmStarkProof = serde_json::from_value(
+        serde_json::to_value(VersionedVmStarkProof::new(proof.clone())?)?,
+    )?;
+    let user_public_values =
+        openvm_verifier::verify_versioned_stark_proof(&vk, &commit, &versioned)?;
+    assert_eq!(user_public_values, proof.user_public_values);
+
+    let mut wrong_commit = commit;
+    wrong_commit.app_exe_commit[31] ^= 1;
+    assert!(matches!(
+        openvm_verifier::verify_versioned_stark_proof(&vk, &wrong_commit, &versioned),
+        Err(openvm_verifier::VerifyError::AppCommitMismatch { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_verifier_program_text_roundtrip() -> eyre::Result<()> {
+    let sdk = Sdk::new(small_test_app_config(1))?.with_agg_config(agg_config_for_test());
+    let (app_pk, _) = sdk.app_keygen();
+    let agg_pk = sdk.agg_pk();
+    for program in [
+        &app_pk.leaf_committed_exe.exe.program,
+        &agg_pk.internal_committed_exe.exe.program,
+    ] {
+        let text = disassemble(program);
+        let assembled = assemble::<F>(&text)?;
+        assert_eq!(assembled.pc_base, program.pc_base);
+        assert_eq!(assembled.len(), program.len());
+        assert_eq!(
+            assembled.defined_instructions(),
+            program.defined_instructions()
+        );
+        assert_eq!(disassemble(&assembled), text);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_prove_progress_and_cancellation() -> eyre::Result<()> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sdk = Sdk::new(small_test_app_config(1))?
+        .with_agg_config(agg_config_for_test())
+        .with_progress(ProgressReporter::new().with_channel(sender));
+    sdk.prove(app_exe_for_test(), StdIn::default())?;
+    let events: Vec<_> = receiver.try_iter().collect();
+    let ProvingEvent::SegmentsExecuted { num_segments } = events[0] else {
+        panic!(
+            "expected the first event to be SegmentsExecuted, got {:?}",
+            events[0]
+        );
+    };
+    assert!(events.contains(&ProvingEvent::SegmentProved {
+        segment_idx: num_segments - 1,
+        num_segments
+    }));
+    assert!(matches!(
+        events.last(),
+        Some(ProvingEvent::InternalLayerProved { num_proofs: 1, .. })
+    ));
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let sdk = sdk.with_progress(ProgressReporter::new().with_cancellation(token));
+    assert!(matches!(
+        sdk.prove(app_exe_for_test(), StdIn::default()),
+        Err(SdkError::Cancelled)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_segmentation_limits_from_memory_budget() -> eyre::Result<()> {
+    let mut app_pk = AppProvingKey::keygen(small_test_app_config(1))?;
+    let model = app_pk.prover_memory_model();
+    let small = model.segmentation_limits(1 << 30);
+    let large = model.segmentation_limits(64 << 30);
+    assert!(small.max_cells < large.max_cells);
+    assert!(small.max_trace_height <= large.max_trace_height);
+    assert!(large.max_trace_height.is_power_of_two());
+
+    let limits = app_pk.set_max_prover_memory(1 << 30);
+    assert_eq!(
+        app_pk
+            .vm_config()
+            .system
+            .config
+            .segmentation_limits
+            .max_cells,
+        limits.max_cells
+    );
+    Ok(())
+}
+
+#[test]
+fn test_memory_guards_from_memory_layout() -> eyre::Result<()> {
+    let layout = MemoryLayout::DEFAULT;
+    let guards = memory_guards_for_layout(&layout, layout.mem_size());
+    assert_eq!(
+        guards.stack_guard,
+        Some(GuardRegion {
+            start: 0,
+            end: GUEST_MIN_MEM as u32,
+        })
+    );
+    assert_eq!(guards.heap_guard, None);
+    let guards = memory_guards_for_layout(&layout, 2 * layout.mem_size());
+    assert_eq!(
+        guards.heap_guard,
+        Some(GuardRegion {
+            start: layout.mem_size() as u32,
+            end: 2 * layout.mem_size() as u32,
+        })
+    );
+
+    let mut config = small_test_app_config(1);
+    assert_eq!(config.memory_guards()?.stack_guard, guards.stack_guard);
+    let explicit = MemoryGuardConfig {
+        stack_guard: None,
+        heap_guard: Some(GuardRegion {
+            start: 0x1ff0_0000,
+            end: 0x2000_0000,
+        }),
+    };
+    config.app_vm_config.system.config.memory_guards = Some(explicit);
+    assert_eq!(config.memory_guards()?, explicit);
+    Ok(())
+}
+
+#[test]
+fn test_key_cache_reuse_and_stale_key() -> eyre::Result<()> {
+    let cache_dir = tempfile::tempdir()?;
+    let key_cache = KeyCache::new(cache_dir.path());
+    let config = small_test_app_config(1);
+    let (app_pk, _) = Sdk::new(config.clone())?
+        .with_key_cache(key_cache.clone())
+        .app_keygen();
+    assert_eq!(app_pk.config_hash, app_config_hash(&config, &[]));
+
+    let cached_pk: AppProvingKey<SdkVmConfig> = key_cache
+        .load_app_pk(&app_pk.config_hash)
+        .expect("app proving key should be cached");
+    assert_eq!(
+        cached_pk.leaf_committed_exe.get_program_commit(),
+        app_pk.leaf_committed_exe.get_program_commit()
+    );
+
+    let sdk = Sdk::new(small_test_app_config(2))?.with_app_pk(app_pk);
+    assert!(matches!(
+        sdk.app_prover(app_exe_for_test()),
+        Err(SdkError::ConfigHashMismatch { key: "app", .. })
+    ));
+    Ok(())
+}
+
 #[test]
 fn test_sdk_guest_build_and_transpile() -> eyre::Result<()> {
     let sdk = Sdk::new(small_test_app_config(1))?;
@@ -447,6 +800,38 @@ fn test_sdk_guest_build_and_transpile() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_invalid_elf_bytes_return_error() -> eyre::Result<()> {
+    let sdk = Sdk::new(small_test_app_config(1))?;
+    assert!(matches!(
+        sdk.convert_to_exe(vec![0u8; 64]),
+        Err(SdkError::Other(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_guest_memory_usage_from_heap_symbols() -> eyre::Result<()> {
+    let sdk = Sdk::new(small_test_app_config(1))?;
+    let mut pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    pkg_dir.push("guest/fib");
+    let elf = sdk.build(GuestOptions::default(), &pkg_dir, &None, None)?;
+    let exe = sdk.convert_to_exe(elf)?;
+    let heap_start = exe.heap_symbols.heap_start.expect("missing _end symbol");
+    // The heap pointer is a static, so it lies below the end of the program's sections. The symbol
+    // is absent if the linker discarded the allocator.
+    if let Some(heap_pos) = exe.heap_symbols.heap_pos {
+        assert!(heap_pos < heap_start);
+    }
+
+    let (_, _, memory_usage) = sdk.execute_metered_cost(exe, StdIn::default())?;
+    assert_eq!(memory_usage.heap_start, heap_start);
+    if let Some(peak_heap_ptr) = memory_usage.peak_heap_ptr {
+        assert!(heap_start <= peak_heap_ptr && peak_heap_ptr <= memory_usage.heap_end);
+    }
+    Ok(())
+}
+
 #[test]
 fn test_sdk_standard_with_p256() -> eyre::Result<()> {
     // WARNING: This test's keygen uses over the cargo test default stack
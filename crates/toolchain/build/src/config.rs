@@ -3,6 +3,7 @@
 use std::path::{Path, PathBuf};
 
 use cargo_metadata::Package;
+use openvm_platform::memory::MemoryLayout;
 use serde::{Deserialize, Serialize};
 
 /// Options defining how to embed a guest package.
@@ -18,6 +19,10 @@ pub struct GuestOptions {
     pub target_dir: Option<PathBuf>,
     /// Custom options to pass as args to `cargo build`.
     pub options: Vec<String>,
+    /// Memory layout to link the guest with.
+    pub memory_layout: MemoryLayout,
+    /// Environment variables to set for `cargo build`.
+    pub env: Vec<(String, String)>,
 }
 
 impl GuestOptions {
@@ -54,6 +59,18 @@ impl GuestOptions {
         self
     }
 
+    /// Set the memory layout to link the guest with.
+    pub fn with_memory_layout(mut self, memory_layout: MemoryLayout) -> Self {
+        self.memory_layout = memory_layout;
+        self
+    }
+
+    /// Set an environment variable for `cargo build`.
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
     #[allow(dead_code)]
     pub(crate) fn with_metadata(mut self, metadata: GuestMetadata) -> Self {
         self.rustc_flags = metadata.rustc_flags.unwrap_or_default();
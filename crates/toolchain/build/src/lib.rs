@@ -13,7 +13,7 @@ use std::{
 };
 
 use cargo_metadata::{Metadata, MetadataCommand, Package};
-use openvm_platform::memory;
+pub use openvm_platform::memory::MemoryLayout;
 
 pub use self::config::GuestOptions;
 
@@ -28,6 +28,14 @@ pub const DEFAULT_RUSTUP_TOOLCHAIN_NAME: &str = "nightly-2025-08-02";
 pub fn get_rustup_toolchain_name() -> String {
     env::var("OPENVM_RUST_TOOLCHAIN").unwrap_or_else(|_| DEFAULT_RUSTUP_TOOLCHAIN_NAME.to_string())
 }
+/// Environment variable with the directory of the root verifier ASM used by
+/// `openvm_verify_stark::define_verify_openvm_stark!` when no ASM folder is given.
+pub const ROOT_VERIFIER_ASM_DIR_ENV: &str = "OPENVM_ROOT_VERIFIER_ASM_DIR";
+/// Environment variable with the hash of the aggregation key the root verifier ASM in
+/// [ROOT_VERIFIER_ASM_DIR_ENV] was generated from.
+pub const ROOT_VERIFIER_HASH_ENV: &str = "OPENVM_ROOT_VERIFIER_HASH";
+/// Filename of the root verifier ASM in [ROOT_VERIFIER_ASM_DIR_ENV].
+pub const ROOT_VERIFIER_ASM_FILENAME: &str = "root_verifier.asm";
 const BUILD_LOCKED_ENV: &str = "OPENVM_BUILD_LOCKED";
 const SKIP_BUILD_ENV: &str = "OPENVM_SKIP_BUILD";
 const GUEST_LOGFILE_ENV: &str = "OPENVM_GUEST_LOGFILE";
@@ -245,6 +253,9 @@ fn sanitized_cmd(tool: &str) -> Command {
 
 /// Creates a std::process::Command to execute the given cargo
 /// command in an environment suitable for targeting the zkvm guest.
+///
+/// The guest is linked with the default [MemoryLayout] unless `rust_flags` include the flags
+/// returned by [memory_layout_rustc_flags].
 pub fn cargo_command(subcmd: &str, rust_flags: &[&str]) -> Command {
     let toolchain = format!("+{}", get_rustup_toolchain_name());
 
@@ -287,22 +298,51 @@ pub fn cargo_command(subcmd: &str, rust_flags: &[&str]) -> Command {
     cmd
 }
 
+/// Returns the rustc flags that link the guest with `memory_layout`: the program is loaded at
+/// `text_start`, and the `_openvm_stack_top` and `_openvm_heap_end` symbols, through which the
+/// guest runtime reads the initial stack pointer and the end of the memory available to the heap,
+/// are defined from the layout.
+pub fn memory_layout_rustc_flags(
+    memory_layout: &MemoryLayout,
+) -> Result<Vec<String>, &'static str> {
+    memory_layout.validate()?;
+    Ok(vec![
+        // Specify where to start loading the program in
+        // memory.  The clang linker understands the same
+        // command line arguments as the GNU linker does; see
+        // https://ftp.gnu.org/old-gnu/Manuals/ld-2.9.1/html_mono/ld.html#SEC3
+        // for details.
+        "-C".to_string(),
+        format!("link-arg=-Ttext=0x{:08X}", memory_layout.text_start),
+        "-C".to_string(),
+        format!(
+            "link-arg=--defsym=_openvm_stack_top=0x{:08X}",
+            memory_layout.stack_top
+        ),
+        "-C".to_string(),
+        format!(
+            "link-arg=--defsym=_openvm_heap_end=0x{:08X}",
+            memory_layout.mem_size()
+        ),
+    ])
+}
+
 /// Returns a string that can be set as the value of CARGO_ENCODED_RUSTFLAGS when compiling guests
 pub(crate) fn encode_rust_flags(rustc_flags: &[&str]) -> String {
+    let default_layout_flags = if rustc_flags.iter().any(|flag| flag.contains("-Ttext=")) {
+        vec![]
+    } else {
+        memory_layout_rustc_flags(&MemoryLayout::DEFAULT).unwrap()
+    };
+    let default_layout_flags: Vec<_> = default_layout_flags.iter().map(|s| s.as_str()).collect();
     [
         // Append other rust flags
         rustc_flags,
+        &default_layout_flags,
         &[
             // Replace atomic ops with nonatomic versions since the guest is single threaded.
             "-C",
             "passes=lower-atomic",
-            // Specify where to start loading the program in
-            // memory.  The clang linker understands the same
-            // command line arguments as the GNU linker does; see
-            // https://ftp.gnu.org/old-gnu/Manuals/ld-2.9.1/html_mono/ld.html#SEC3
-            // for details.
-            "-C",
-            &format!("link-arg=-Ttext=0x{:08X}", memory::TEXT_START),
             // Apparently not having an entry point is only a linker warning(!), so
             // error out in this case.
             "-C",
@@ -395,7 +435,19 @@ pub fn build_generic(guest_opts: &GuestOptions) -> Result<PathBuf, Option<i32>>
 
     let target_dir = guest_opts.target_dir.as_ref().unwrap();
     fs::create_dir_all(target_dir).unwrap();
-    let rust_flags: Vec<_> = guest_opts.rustc_flags.iter().map(|s| s.as_str()).collect();
+    let memory_layout_flags = match memory_layout_rustc_flags(&guest_opts.memory_layout) {
+        Ok(flags) => flags,
+        Err(e) => {
+            eprintln!("Invalid guest memory layout: {e}");
+            return Err(None);
+        }
+    };
+    let rust_flags: Vec<_> = guest_opts
+        .rustc_flags
+        .iter()
+        .chain(&memory_layout_flags)
+        .map(|s| s.as_str())
+        .collect();
 
     let mut cmd = cargo_command("build", &rust_flags);
 
@@ -412,6 +464,7 @@ pub fn build_generic(guest_opts: &GuestOptions) -> Result<PathBuf, Option<i32>>
     cmd.args(["--profile", profile]);
 
     cmd.args(&guest_opts.options);
+    cmd.envs(guest_opts.env.iter().map(|(k, v)| (k, v)));
 
     let command_string = format!(
         "{} {}",
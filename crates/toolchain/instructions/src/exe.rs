@@ -26,6 +26,8 @@ pub struct VmExe<F> {
     pub init_memory: SparseMemoryImage,
     /// Starting + ending bounds for each function.
     pub fn_bounds: FnBounds,
+    /// Addresses of the guest heap symbols, used to report heap usage.
+    pub heap_symbols: HeapSymbols,
 }
 
 impl<F> VmExe<F> {
@@ -35,6 +37,7 @@ impl<F> VmExe<F> {
             pc_start: 0,
             init_memory: BTreeMap::new(),
             fn_bounds: Default::default(),
+            heap_symbols: Default::default(),
         }
     }
     pub fn with_pc_start(mut self, pc_start: u32) -> Self {
@@ -53,6 +56,15 @@ impl<F: Field> From<Program<F>> for VmExe<F> {
     }
 }
 
+/// Addresses of the symbols of the guest heap, if present in the ELF symbol table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapSymbols {
+    /// Address of the `_end` linker symbol, where the heap starts after all ELF sections.
+    pub heap_start: Option<u32>,
+    /// Address of the word holding the bump allocator's next free heap address.
+    pub heap_pos: Option<u32>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FnBound {
     pub start: u32,
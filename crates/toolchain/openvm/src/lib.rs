@@ -115,15 +115,17 @@ unsafe extern "C" fn __start() -> ! {
     unreachable!()
 }
 
-#[cfg(target_os = "zkvm")]
-static STACK_TOP: u32 = openvm_platform::memory::STACK_TOP;
-
 // Entry point; sets up global pointer and stack pointer and passes
-// to zkvm_start.  TODO: when asm_const is stabilized, use that here
-// instead of defining a symbol and dereferencing it.
+// to zkvm_start. The top of the stack is the address of the `_openvm_stack_top`
+// symbol, which `openvm-build` defines from the program's memory layout. The weak
+// definitions below give the default layout to guests linked without those flags.
 #[cfg(target_os = "zkvm")]
 core::arch::global_asm!(
     r#"
+.weak _openvm_stack_top;
+.set _openvm_stack_top, {stack_top};
+.weak _openvm_heap_end;
+.set _openvm_heap_end, {heap_end};
 .section .text._start;
 .globl _start;
 _start:
@@ -131,11 +133,11 @@ _start:
     .option norelax;
     la gp, __global_pointer$;
     .option pop;
-    la sp, {0};
-    lw sp, 0(sp);
+    la sp, _openvm_stack_top;
     call __start;
 "#,
-    sym STACK_TOP
+    stack_top = const openvm_platform::memory::STACK_TOP,
+    heap_end = const openvm_platform::memory::GUEST_MAX_MEM,
 );
 
 /// Require that accesses to behind the given pointer before the memory
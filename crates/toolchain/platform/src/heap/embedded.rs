@@ -23,14 +23,15 @@ pub fn init() {
     }
     // SAFETY: _end is a linker symbol marking the end of the data segment
     let heap_pos: usize = unsafe { (&_end) as *const u8 as usize };
-    if heap_pos > crate::memory::GUEST_MAX_MEM {
+    let heap_end = crate::memory::heap_end();
+    if heap_pos > heap_end {
         crate::print::println("Not enough memory for heap.");
-        crate::rust_rt::terminate::<1>();
+        crate::rust_rt::terminate::<{ crate::memory::OUT_OF_MEMORY_EXIT_CODE }>();
     }
-    let heap_size: usize = crate::memory::GUEST_MAX_MEM - heap_pos;
+    let heap_size: usize = heap_end - heap_pos;
     // SAFETY:
     // - heap_pos points to valid memory after data segment (verified above)
-    // - heap_size is calculated to fit within GUEST_MAX_MEM bounds
+    // - heap_size is calculated to fit within the heap end of the memory layout
     // - HEAP is initialized once in single-threaded context
     unsafe { HEAP.init(heap_pos, heap_size) }
 }
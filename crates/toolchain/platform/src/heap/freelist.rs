@@ -0,0 +1,214 @@
+//! Size-class allocator with free lists, tuned for the zkVM.
+//!
+//! Allocations of up to `2^MAX_CLASS_BITS` bytes are rounded up to a power of two size class. Each
+//! size class keeps a singly linked list of freed blocks, so a freed block is reused by the next
+//! allocation of the same class in a constant number of instructions and without touching new
+//! memory pages. Blocks of small classes are carved from page-sized slabs so that they share
+//! pages, while blocks of larger classes are taken directly from the bump allocator.
+//!
+//! Larger allocations are rounded up to whole pages and kept in a first-fit list when freed. A
+//! reused block that is larger than requested is split and the remainder stays in the list.
+//! Adjacent free blocks are never coalesced.
+//!
+//! Fresh memory is obtained from [sys_alloc_aligned], which never reuses memory, so fresh blocks
+//! are known to be zero-initialized and `alloc_zeroed` only clears reused blocks.
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    cell::UnsafeCell,
+    ptr,
+};
+
+use crate::memory::sys_alloc_aligned;
+
+#[global_allocator]
+pub static HEAP: FreeListAlloc = FreeListAlloc::new();
+
+/// The smallest size class must fit a [FreeBlock] link.
+const MIN_CLASS_BITS: usize = 3;
+const MAX_CLASS_BITS: usize = 20;
+const NUM_CLASSES: usize = MAX_CLASS_BITS - MIN_CLASS_BITS + 1;
+/// Size classes up to this size are carved from slabs.
+const MAX_SLAB_CLASS_BITS: usize = 10;
+const NUM_SLAB_CLASSES: usize = MAX_SLAB_CLASS_BITS - MIN_CLASS_BITS + 1;
+const PAGE_SIZE: usize = 1 << 12;
+/// Slabs are page aligned, so every block in a slab is aligned to its size.
+const SLAB_SIZE: usize = PAGE_SIZE;
+
+/// Header written into a freed block of a size class.
+struct FreeBlock {
+    next: *mut FreeBlock,
+}
+
+/// Header written into a freed large block.
+struct LargeBlock {
+    next: *mut LargeBlock,
+    size: usize,
+}
+
+struct State {
+    free_lists: [*mut FreeBlock; NUM_CLASSES],
+    /// Next unused block of the current slab of each slab class.
+    slab_pos: [usize; NUM_SLAB_CLASSES],
+    /// End of the current slab of each slab class.
+    slab_end: [usize; NUM_SLAB_CLASSES],
+    large_free_list: *mut LargeBlock,
+}
+
+pub struct FreeListAlloc {
+    state: UnsafeCell<State>,
+}
+
+// SAFETY: The zkVM is single threaded and non-preemptive, so the state is never accessed
+// concurrently.
+unsafe impl Sync for FreeListAlloc {}
+
+/// Returns the index of the size class for an allocation of `layout`, or `None` if it is a large
+/// allocation.
+#[inline(always)]
+fn size_class(layout: &Layout) -> Option<usize> {
+    let size = layout.size().max(layout.align());
+    if size > 1 << MAX_CLASS_BITS || layout.align() > PAGE_SIZE {
+        return None;
+    }
+    let bits = (size.next_power_of_two().trailing_zeros() as usize).max(MIN_CLASS_BITS);
+    Some(bits - MIN_CLASS_BITS)
+}
+
+#[inline(always)]
+fn large_size(layout: &Layout) -> usize {
+    layout.size().max(1).next_multiple_of(PAGE_SIZE)
+}
+
+impl FreeListAlloc {
+    pub const fn new() -> Self {
+        Self {
+            state: UnsafeCell::new(State {
+                free_lists: [ptr::null_mut(); NUM_CLASSES],
+                slab_pos: [0; NUM_SLAB_CLASSES],
+                slab_end: [0; NUM_SLAB_CLASSES],
+                large_free_list: ptr::null_mut(),
+            }),
+        }
+    }
+
+    /// Returns the allocated block and whether it is fresh, i.e. never used before.
+    ///
+    /// # Safety
+    /// Must not be called concurrently with any other method.
+    unsafe fn alloc_block(&self, layout: Layout) -> (*mut u8, bool) {
+        let state = &mut *self.state.get();
+        match size_class(&layout) {
+            Some(class) => state.alloc_small(class),
+            None => state.alloc_large(&layout),
+        }
+    }
+}
+
+impl Default for FreeListAlloc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl State {
+    unsafe fn alloc_small(&mut self, class: usize) -> (*mut u8, bool) {
+        let head = self.free_lists[class];
+        if !head.is_null() {
+            self.free_lists[class] = (*head).next;
+            return (head as *mut u8, false);
+        }
+        let block_size = 1 << (class + MIN_CLASS_BITS);
+        if class < NUM_SLAB_CLASSES {
+            if self.slab_pos[class] == self.slab_end[class] {
+                let slab = sys_alloc_aligned(SLAB_SIZE, SLAB_SIZE) as usize;
+                self.slab_pos[class] = slab;
+                self.slab_end[class] = slab + SLAB_SIZE;
+            }
+            let block = self.slab_pos[class];
+            self.slab_pos[class] += block_size;
+            (block as *mut u8, true)
+        } else {
+            (
+                sys_alloc_aligned(block_size, block_size.min(PAGE_SIZE)),
+                true,
+            )
+        }
+    }
+
+    unsafe fn dealloc_small(&mut self, ptr: *mut u8, class: usize) {
+        let block = ptr as *mut FreeBlock;
+        (*block).next = self.free_lists[class];
+        self.free_lists[class] = block;
+    }
+
+    unsafe fn alloc_large(&mut self, layout: &Layout) -> (*mut u8, bool) {
+        let size = large_size(layout);
+        let align = layout.align().max(PAGE_SIZE);
+
+        // First fit
+        let mut link: *mut *mut LargeBlock = &mut self.large_free_list;
+        while !(*link).is_null() {
+            let block = *link;
+            let block_size = (*block).size;
+            if block_size >= size && (block as usize) % align == 0 {
+                *link = (*block).next;
+                if block_size > size {
+                    self.dealloc_large_block((block as *mut u8).add(size), block_size - size);
+                }
+                return (block as *mut u8, false);
+            }
+            link = &mut (*block).next;
+        }
+        (sys_alloc_aligned(size, align), true)
+    }
+
+    unsafe fn dealloc_large_block(&mut self, ptr: *mut u8, size: usize) {
+        let block = ptr as *mut LargeBlock;
+        (*block).next = self.large_free_list;
+        (*block).size = size;
+        self.large_free_list = block;
+    }
+}
+
+unsafe impl GlobalAlloc for FreeListAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_block(layout).0
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let state = &mut *self.state.get();
+        match size_class(&layout) {
+            Some(class) => state.dealloc_small(ptr, class),
+            None => state.dealloc_large_block(ptr, large_size(&layout)),
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let (ptr, fresh) = self.alloc_block(layout);
+        // NOTE: Fresh blocks come from the bump allocator, which never reuses memory, and the zkVM
+        //       memory is zero-initialized.
+        if !fresh {
+            ptr::write_bytes(ptr, 0, layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let class = size_class(&layout);
+        let fits_in_place = match class {
+            Some(_) => class == size_class(&new_layout),
+            None => {
+                size_class(&new_layout).is_none() && large_size(&layout) == large_size(&new_layout)
+            }
+        };
+        if fits_in_place {
+            return ptr;
+        }
+        let new_ptr = self.alloc(new_layout);
+        ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        self.dealloc(ptr, layout);
+        new_ptr
+    }
+}
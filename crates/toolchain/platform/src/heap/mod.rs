@@ -1,5 +1,11 @@
-#[cfg(not(feature = "heap-embedded-alloc"))]
+#[cfg(all(feature = "heap-embedded-alloc", feature = "heap-freelist-alloc"))]
+compile_error!("features `heap-embedded-alloc` and `heap-freelist-alloc` are mutually exclusive");
+
+#[cfg(not(any(feature = "heap-embedded-alloc", feature = "heap-freelist-alloc")))]
 mod bump;
 
 #[cfg(feature = "heap-embedded-alloc")]
 pub mod embedded;
+
+#[cfg(all(feature = "heap-freelist-alloc", not(feature = "heap-embedded-alloc")))]
+pub mod freelist;
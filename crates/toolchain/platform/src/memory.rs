@@ -1,3 +1,5 @@
+use crate::WORD_SIZE;
+
 pub const MEM_BITS: usize = 29;
 pub const MEM_SIZE: usize = 1 << MEM_BITS;
 pub const GUEST_MIN_MEM: usize = 0x0000_0400;
@@ -8,12 +10,121 @@ pub const STACK_TOP: u32 = 0x0020_0400;
 /// Program (text followed by data and then bss) gets loaded in
 /// starting at this location.  HEAP begins right afterwards.
 pub const TEXT_START: u32 = 0x0020_0800;
+/// Exit code of the guest when the heap is exhausted.
+pub const OUT_OF_MEMORY_EXIT_CODE: u8 = 2;
+/// Linker symbol marking the end of all ELF sections, where the heap begins.
+pub const HEAP_START_SYMBOL: &str = "_end";
+/// Symbol of the word holding the next free heap address of the bump allocator
+/// (`sys_alloc_aligned`), or 0 if nothing has been allocated.
+pub const HEAP_POS_SYMBOL: &str = "_openvm_heap_pos";
 
 /// Returns whether `addr` is within guest memory bounds.
 pub fn is_guest_memory(addr: u32) -> bool {
     GUEST_MIN_MEM <= (addr as usize) && (addr as usize) < GUEST_MAX_MEM
 }
 
+/// Layout of guest memory, which can be configured per program. The stack grows down from
+/// `stack_top` towards [GUEST_MIN_MEM], the program is loaded starting at `text_start` and the heap
+/// grows up from the end of the program until the end of guest memory.
+///
+/// The default layout is given by [MEM_BITS], [STACK_TOP] and [TEXT_START].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryLayout {
+    /// Guest memory consists of `2^mem_bits` bytes.
+    pub mem_bits: usize,
+    /// Top of stack.
+    pub stack_top: u32,
+    /// Address where the program is loaded.
+    pub text_start: u32,
+}
+
+impl MemoryLayout {
+    /// Largest supported `mem_bits`, limited by the size of the field used by the VM.
+    pub const MAX_MEM_BITS: usize = 29;
+
+    /// The default layout.
+    pub const DEFAULT: Self = Self {
+        mem_bits: MEM_BITS,
+        stack_top: STACK_TOP,
+        text_start: TEXT_START,
+    };
+
+    /// Layout with a stack of `stack_size` bytes directly above [GUEST_MIN_MEM], followed by the
+    /// program after the same gap as in the default layout. Fails if the addresses overflow.
+    pub const fn with_stack_size(mem_bits: usize, stack_size: u32) -> Result<Self, &'static str> {
+        let Some(stack_top) = (GUEST_MIN_MEM as u32).checked_add(stack_size) else {
+            return Err("stack_size is too large");
+        };
+        let Some(text_start) = stack_top.checked_add(TEXT_START - STACK_TOP) else {
+            return Err("stack_size is too large");
+        };
+        Ok(Self {
+            mem_bits,
+            stack_top,
+            text_start,
+        })
+    }
+
+    /// Size of guest memory in bytes.
+    pub const fn mem_size(&self) -> usize {
+        1 << self.mem_bits
+    }
+
+    /// Size of the stack in bytes.
+    pub const fn stack_size(&self) -> u32 {
+        self.stack_top - GUEST_MIN_MEM as u32
+    }
+
+    /// Checks that the stack and the program fit in guest memory in this order.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.mem_bits > Self::MAX_MEM_BITS {
+            return Err("mem_bits must be at most 29");
+        }
+        if (self.stack_top as usize) <= GUEST_MIN_MEM || self.stack_top % 16 != 0 {
+            return Err("stack_top must be 16-byte aligned and above GUEST_MIN_MEM");
+        }
+        if self.text_start < self.stack_top || self.text_start % WORD_SIZE as u32 != 0 {
+            return Err("text_start must be word aligned and at or above stack_top");
+        }
+        if self.text_start as usize >= self.mem_size() {
+            return Err("text_start must be inside guest memory");
+        }
+        Ok(())
+    }
+}
+
+impl Default for MemoryLayout {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// End of the memory available to the heap, as given by the [MemoryLayout] the program was linked
+/// with.
+pub fn heap_end() -> usize {
+    #[cfg(target_os = "zkvm")]
+    {
+        extern "C" {
+            // Defined by `openvm-build` from the memory layout, with a weak default in the
+            // `openvm` crate.
+            static _openvm_heap_end: u8;
+        }
+        // SAFETY: only the address of the linker symbol is used, it is never dereferenced.
+        unsafe { (&_openvm_heap_end) as *const u8 as usize }
+    }
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        GUEST_MAX_MEM
+    }
+}
+
+// Pointer to next heap address to use, or 0 if the heap has not yet been
+// initialized. Memory is never freed, so the final value is the peak heap pointer. It is exported
+// as [HEAP_POS_SYMBOL] so that the host can read it from the final guest memory.
+#[cfg(feature = "rust-runtime")]
+#[cfg_attr(target_os = "zkvm", export_name = "_openvm_heap_pos")]
+static mut HEAP_POS: usize = 0;
+
 /// # Safety
 ///
 /// This function should be safe to call, but clippy complains if it is not marked as `unsafe`.
@@ -33,10 +144,6 @@ pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u
         static _end: u8;
     }
 
-    // Pointer to next heap address to use, or 0 if the heap has not yet been
-    // initialized.
-    static mut HEAP_POS: usize = 0;
-
     // SAFETY: Single threaded, so nothing else can touch this while we're working.
     let mut heap_pos = unsafe { HEAP_POS };
 
@@ -55,13 +162,13 @@ pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u
     }
 
     match heap_pos.checked_add(bytes) {
-        Some(new_heap_pos) if new_heap_pos <= GUEST_MAX_MEM => {
+        Some(new_heap_pos) if new_heap_pos <= heap_end() => {
             // SAFETY: Single threaded, and non-preemptive so modification is safe.
             unsafe { HEAP_POS = new_heap_pos };
         }
         _ => {
             println("ERROR: Maximum memory exceeded, program terminating.");
-            super::rust_rt::terminate::<1>();
+            super::rust_rt::terminate::<OUT_OF_MEMORY_EXIT_CODE>();
         }
     }
     heap_pos as *mut u8
@@ -16,8 +16,14 @@ use elf::{
 use eyre::{self, bail, ContextCompat};
 #[cfg(feature = "function-span")]
 use openvm_instructions::exe::FnBound;
-use openvm_instructions::{exe::FnBounds, program::MAX_ALLOWED_PC};
-use openvm_platform::WORD_SIZE;
+use openvm_instructions::{
+    exe::{FnBounds, HeapSymbols},
+    program::MAX_ALLOWED_PC,
+};
+use openvm_platform::{
+    memory::{HEAP_POS_SYMBOL, HEAP_START_SYMBOL},
+    WORD_SIZE,
+};
 
 /// RISC-V 32IM ELF (Executable and Linkable Format) File.
 ///
@@ -40,6 +46,8 @@ pub struct Elf {
     pub(crate) memory_image: BTreeMap<u32, u32>,
     /// Debug info for spanning benchmark metrics by function.
     pub(crate) fn_bounds: FnBounds,
+    /// Addresses of the heap symbols, for reporting heap usage.
+    pub(crate) heap_symbols: HeapSymbols,
 }
 
 impl Elf {
@@ -50,6 +58,7 @@ impl Elf {
         pc_base: u32,
         memory_image: BTreeMap<u32, u32>,
         fn_bounds: FnBounds,
+        heap_symbols: HeapSymbols,
     ) -> Self {
         Self {
             instructions,
@@ -57,6 +66,7 @@ impl Elf {
             pc_base,
             memory_image,
             fn_bounds,
+            heap_symbols,
         }
     }
 
@@ -84,6 +94,18 @@ impl Elf {
             bail!("Invalid ELF type, must be executable");
         }
 
+        let mut heap_symbols = HeapSymbols::default();
+        if let Some((symtab, stringtab)) = elf.symbol_table()? {
+            for symbol in symtab.iter() {
+                let addr = Some(symbol.st_value as u32);
+                match stringtab.get(symbol.st_name as usize) {
+                    Ok(HEAP_START_SYMBOL) => heap_symbols.heap_start = addr,
+                    Ok(HEAP_POS_SYMBOL) => heap_symbols.heap_pos = addr,
+                    _ => {}
+                }
+            }
+        }
+
         #[cfg(not(feature = "function-span"))]
         let fn_bounds = Default::default();
 
@@ -232,6 +254,7 @@ impl Elf {
             base_address,
             image,
             fn_bounds,
+            heap_symbols,
         ))
     }
 }
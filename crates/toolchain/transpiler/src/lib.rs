@@ -34,6 +34,7 @@ impl<F: PrimeField32> FromElf for VmExe<F> {
             pc_start: elf.pc_start,
             init_memory,
             fn_bounds: elf.fn_bounds,
+            heap_symbols: elf.heap_symbols,
         })
     }
 }
@@ -0,0 +1,136 @@
+#ifndef OPENVM_VERIFIER_H
+#define OPENVM_VERIFIER_H
+
+/* Generated by cbindgen from crates/verifier-ffi. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of a call through the C ABI.
+ */
+typedef enum OpenvmStatus {
+  OPENVM_STATUS_OK = 0,
+  /**
+   * A required pointer was null.
+   */
+  OPENVM_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * The proof, verifying key or verifier could not be decoded.
+   */
+  OPENVM_STATUS_DECODE_ERROR = 2,
+  /**
+   * The proof is invalid.
+   */
+  OPENVM_STATUS_VERIFICATION_FAILED = 3,
+  /**
+   * The output buffer is too small. The required length is written to the length output.
+   */
+  OPENVM_STATUS_BUFFER_TOO_SMALL = 4,
+  /**
+   * The library was built without support for the requested operation.
+   */
+  OPENVM_STATUS_UNSUPPORTED = 5,
+  /**
+   * The library panicked. This is a bug.
+   */
+  OPENVM_STATUS_PANIC = 6,
+} OpenvmStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the `major.minor` OpenVM version of the proofs this library verifies, as a static
+ * NUL-terminated string.
+ */
+const char *openvm_verifier_version(void);
+
+/**
+ * Copies the message of the last error on this thread into `buf` as a NUL-terminated string,
+ * truncated to `buf_len - 1` bytes, and returns the length of the full message. The message is
+ * empty if the last call succeeded.
+ *
+ * # Safety
+ * `buf` must be null or valid for writes of `buf_len` bytes.
+ */
+size_t openvm_last_error_message(char *buf, size_t buf_len);
+
+/**
+ * Verifies an aggregate STARK proof.
+ *
+ * - `agg_vk` is the aggregation STARK verifying key (`agg_stark.vk` written by
+ *   `cargo openvm setup`).
+ * - `proof` is the JSON encoded `VersionedVmStarkProof` (`.stark.proof` file written by
+ *   `cargo openvm prove stark`).
+ * - `app_exe_commit` and `app_vm_commit` point to the 32 byte big-endian expected commits.
+ *
+ * # Safety
+ * Each pointer must be valid for reads of its length.
+ */
+OpenvmStatus openvm_verify_stark_proof(const uint8_t *agg_vk,
+                                       size_t agg_vk_len,
+                                       const uint8_t *proof,
+                                       size_t proof_len,
+                                       const uint8_t *app_exe_commit,
+                                       const uint8_t *app_vm_commit);
+
+/**
+ * Decodes the user public values of a JSON encoded `VersionedVmStarkProof` without verifying
+ * it. Each public value is a BabyBear field element, written to `out` as a `uint32_t`.
+ *
+ * # Safety
+ * `proof` must be valid for reads of `proof_len` bytes, `out` must be valid for writes of
+ * `out_cap` values and `out_len` must be valid for writes.
+ */
+OpenvmStatus openvm_stark_proof_public_values(const uint8_t *proof,
+                                              size_t proof_len,
+                                              uint32_t *out,
+                                              size_t out_cap,
+                                              size_t *out_len);
+
+/**
+ * Decodes the user public values of a JSON encoded `EvmProof` (`.evm.proof` file written by
+ * `cargo openvm prove evm`) without verifying it.
+ *
+ * # Safety
+ * `proof` must be valid for reads of `proof_len` bytes, `out` must be valid for writes of
+ * `out_cap` bytes and `out_len` must be valid for writes.
+ */
+OpenvmStatus openvm_evm_proof_public_values(const uint8_t *proof,
+                                            size_t proof_len,
+                                            uint8_t *out,
+                                            size_t out_cap,
+                                            size_t *out_len);
+
+/**
+ * Verifies an EVM proof by executing the OpenVM halo2 verifier contract in a local EVM, and
+ * writes the gas cost of verification to `gas_cost` if it is not null.
+ *
+ * - `verifier` is the JSON encoded verifier bytecode (`verifier.bytecode.json` written by
+ *   `cargo openvm setup`).
+ * - `proof` is the JSON encoded `EvmProof` (`.evm.proof` file written by
+ *   `cargo openvm prove evm`).
+ *
+ * Returns [OpenvmStatus::Unsupported] if the library was built without the `evm-verify`
+ * feature.
+ *
+ * # Safety
+ * Each pointer must be valid for reads of its length, and `gas_cost` must be null or valid for
+ * writes.
+ */
+OpenvmStatus openvm_verify_evm_proof(const uint8_t *verifier,
+                                     size_t verifier_len,
+                                     const uint8_t *proof,
+                                     size_t proof_len,
+                                     uint64_t *gas_cost);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OPENVM_VERIFIER_H */
@@ -0,0 +1,329 @@
+//! C ABI for verifying OpenVM proofs from other languages. The header is generated into
+//! `include/openvm_verifier.h` by the build script.
+//!
+//! All functions return an [OpenvmStatus]. On failure, a description of the error can be read
+//! with [openvm_last_error_message] on the same thread. Buffers are passed as a pointer and a
+//! length, and are only borrowed for the duration of the call.
+
+use std::{
+    cell::RefCell,
+    ffi::c_char,
+    panic::{catch_unwind, UnwindSafe},
+    slice,
+};
+
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_verifier::{
+    verify_versioned_stark_proof, AggVerifyingKey, AppExecutionCommit, VerifyError,
+    VersionedVmStarkProof, VmStarkProof, BN254_BYTES,
+};
+use serde::Deserialize;
+
+/// Result of a call through the C ABI.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenvmStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    InvalidArgument = 1,
+    /// The proof, verifying key or verifier could not be decoded.
+    DecodeError = 2,
+    /// The proof is invalid.
+    VerificationFailed = 3,
+    /// The output buffer is too small. The required length is written to the length output.
+    BufferTooSmall = 4,
+    /// The library was built without support for the requested operation.
+    Unsupported = 5,
+    /// The library panicked. This is a bug.
+    Panic = 6,
+}
+
+struct FfiError {
+    status: OpenvmStatus,
+    message: String,
+}
+
+impl FfiError {
+    fn new(status: OpenvmStatus, message: impl ToString) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<VerifyError> for FfiError {
+    fn from(e: VerifyError) -> Self {
+        let status = match e {
+            VerifyError::UnexpectedEof
+            | VerifyError::TrailingBytes(_)
+            | VerifyError::InvalidCodecVersion(_)
+            | VerifyError::UnknownRapPhaseSeqKind(_)
+            | VerifyError::NonCanonicalField(_)
+            | VerifyError::InvalidHex
+            | VerifyError::InvalidVerifyingKey
+            | VerifyError::UnsupportedVersion { .. } => OpenvmStatus::DecodeError,
+            _ => OpenvmStatus::VerificationFailed,
+        };
+        Self::new(status, e)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Runs `f`, records its error message for [openvm_last_error_message] and converts panics into
+/// [OpenvmStatus::Panic], since unwinding across the C ABI is undefined behavior.
+fn ffi_call(f: impl FnOnce() -> Result<(), FfiError> + UnwindSafe) -> OpenvmStatus {
+    let (status, message) = match catch_unwind(f) {
+        Ok(Ok(())) => (OpenvmStatus::Ok, String::new()),
+        Ok(Err(e)) => (e.status, e.message),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            (OpenvmStatus::Panic, format!("panic: {message}"))
+        }
+    };
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+    status
+}
+
+/// # Safety
+/// `ptr` must be null or valid for reads of `len` bytes.
+unsafe fn input<'a>(ptr: *const u8, len: usize, name: &str) -> Result<&'a [u8], FfiError> {
+    if ptr.is_null() {
+        if len == 0 {
+            return Ok(&[]);
+        }
+        return Err(FfiError::new(
+            OpenvmStatus::InvalidArgument,
+            format!("{name} is null"),
+        ));
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+/// Copies `values` to `out` if it has capacity `out_cap`, and writes the length to `out_len`.
+///
+/// # Safety
+/// `out` must be null or valid for writes of `out_cap` values, and `out_len` must be null or
+/// valid for writes.
+unsafe fn output<T: Copy>(
+    values: &[T],
+    out: *mut T,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> Result<(), FfiError> {
+    if out_len.is_null() {
+        return Err(FfiError::new(
+            OpenvmStatus::InvalidArgument,
+            "out_len is null",
+        ));
+    }
+    *out_len = values.len();
+    if values.len() > out_cap {
+        return Err(FfiError::new(
+            OpenvmStatus::BufferTooSmall,
+            format!("output needs {} values, got {out_cap}", values.len()),
+        ));
+    }
+    if !values.is_empty() {
+        if out.is_null() {
+            return Err(FfiError::new(OpenvmStatus::InvalidArgument, "out is null"));
+        }
+        slice::from_raw_parts_mut(out, values.len()).copy_from_slice(values);
+    }
+    Ok(())
+}
+
+fn parse_json<'a, T: Deserialize<'a>>(bytes: &'a [u8], name: &str) -> Result<T, FfiError> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| FfiError::new(OpenvmStatus::DecodeError, format!("invalid {name}: {e}")))
+}
+
+/// Returns the `major.minor` OpenVM version of the proofs this library verifies, as a static
+/// NUL-terminated string.
+#[no_mangle]
+pub extern "C" fn openvm_verifier_version() -> *const c_char {
+    concat!(
+        env!("CARGO_PKG_VERSION_MAJOR"),
+        ".",
+        env!("CARGO_PKG_VERSION_MINOR"),
+        "\0"
+    )
+    .as_ptr() as *const c_char
+}
+
+/// Copies the message of the last error on this thread into `buf` as a NUL-terminated string,
+/// truncated to `buf_len - 1` bytes, and returns the length of the full message. The message is
+/// empty if the last call succeeded.
+///
+/// # Safety
+/// `buf` must be null or valid for writes of `buf_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn openvm_last_error_message(buf: *mut c_char, buf_len: usize) -> usize {
+    LAST_ERROR.with(|last_error| {
+        let message = last_error.borrow();
+        if !buf.is_null() && buf_len > 0 {
+            let len = message.len().min(buf_len - 1);
+            let buf = slice::from_raw_parts_mut(buf as *mut u8, buf_len);
+            buf[..len].copy_from_slice(&message.as_bytes()[..len]);
+            buf[len] = 0;
+        }
+        message.len()
+    })
+}
+
+/// Verifies an aggregate STARK proof.
+///
+/// - `agg_vk` is the aggregation STARK verifying key (`agg_stark.vk` written by `cargo openvm
+///   setup`).
+/// - `proof` is the JSON encoded `VersionedVmStarkProof` (`.stark.proof` file written by `cargo
+///   openvm prove stark`).
+/// - `app_exe_commit` and `app_vm_commit` point to the 32 byte big-endian expected commits.
+///
+/// # Safety
+/// Each pointer must be valid for reads of its length.
+#[no_mangle]
+pub unsafe extern "C" fn openvm_verify_stark_proof(
+    agg_vk: *const u8,
+    agg_vk_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    app_exe_commit: *const u8,
+    app_vm_commit: *const u8,
+) -> OpenvmStatus {
+    ffi_call(|| {
+        let agg_vk = AggVerifyingKey::from_bytes(input(agg_vk, agg_vk_len, "agg_vk")?)?;
+        let proof: VersionedVmStarkProof = parse_json(input(proof, proof_len, "proof")?, "proof")?;
+        let commit = |ptr, name| -> Result<[u8; BN254_BYTES], FfiError> {
+            Ok(input(ptr, BN254_BYTES, name)?.try_into().unwrap())
+        };
+        let app_commit = AppExecutionCommit::new(
+            commit(app_exe_commit, "app_exe_commit")?,
+            commit(app_vm_commit, "app_vm_commit")?,
+        );
+        verify_versioned_stark_proof(&agg_vk, &app_commit, &proof)?;
+        Ok(())
+    })
+}
+
+/// Decodes the user public values of a JSON encoded `VersionedVmStarkProof` without verifying
+/// it. Each public value is a BabyBear field element, written to `out` as a `uint32_t`.
+///
+/// # Safety
+/// `proof` must be valid for reads of `proof_len` bytes, `out` must be valid for writes of
+/// `out_cap` values and `out_len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn openvm_stark_proof_public_values(
+    proof: *const u8,
+    proof_len: usize,
+    out: *mut u32,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> OpenvmStatus {
+    ffi_call(|| {
+        let proof: VersionedVmStarkProof = parse_json(input(proof, proof_len, "proof")?, "proof")?;
+        let proof = VmStarkProof::try_from(&proof)?;
+        let values: Vec<u32> = proof
+            .user_public_values
+            .iter()
+            .map(|x| x.as_canonical_u32())
+            .collect();
+        output(&values, out, out_cap, out_len)
+    })
+}
+
+/// The fields of an `EvmProof` needed to decode its public values.
+#[derive(Deserialize)]
+struct EvmProofPublicValues {
+    user_public_values: String,
+}
+
+/// Decodes the user public values of a JSON encoded `EvmProof` (`.evm.proof` file written by
+/// `cargo openvm prove evm`) without verifying it.
+///
+/// # Safety
+/// `proof` must be valid for reads of `proof_len` bytes, `out` must be valid for writes of
+/// `out_cap` bytes and `out_len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn openvm_evm_proof_public_values(
+    proof: *const u8,
+    proof_len: usize,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> OpenvmStatus {
+    ffi_call(|| {
+        let proof: EvmProofPublicValues = parse_json(input(proof, proof_len, "proof")?, "proof")?;
+        let values = hex::decode(&proof.user_public_values)
+            .map_err(|e| FfiError::new(OpenvmStatus::DecodeError, e))?;
+        output(&values, out, out_cap, out_len)
+    })
+}
+
+/// Verifies an EVM proof by executing the OpenVM halo2 verifier contract in a local EVM, and
+/// writes the gas cost of verification to `gas_cost` if it is not null.
+///
+/// - `verifier` is the JSON encoded verifier bytecode (`verifier.bytecode.json` written by `cargo
+///   openvm setup`).
+/// - `proof` is the JSON encoded `EvmProof` (`.evm.proof` file written by `cargo openvm prove
+///   evm`).
+///
+/// Returns [OpenvmStatus::Unsupported] if the library was built without the `evm-verify`
+/// feature.
+///
+/// # Safety
+/// Each pointer must be valid for reads of its length, and `gas_cost` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn openvm_verify_evm_proof(
+    verifier: *const u8,
+    verifier_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    gas_cost: *mut u64,
+) -> OpenvmStatus {
+    ffi_call(|| {
+        let verifier = input(verifier, verifier_len, "verifier")?;
+        let proof = input(proof, proof_len, "proof")?;
+        let cost = verify_evm_proof(verifier, proof)?;
+        if !gas_cost.is_null() {
+            *gas_cost = cost;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(feature = "evm-verify")]
+fn verify_evm_proof(verifier: &[u8], proof: &[u8]) -> Result<u64, FfiError> {
+    use openvm_native_recursion::halo2::wrapper::EvmVerifierByteCode;
+    use openvm_sdk::{
+        types::{EvmHalo2Verifier, EvmProof},
+        Sdk,
+    };
+
+    let artifact: EvmVerifierByteCode = parse_json(verifier, "verifier")?;
+    let proof: EvmProof = parse_json(proof, "proof")?;
+    // Only the bytecode is used for verification
+    let verifier = EvmHalo2Verifier {
+        halo2_verifier_code: String::new(),
+        openvm_verifier_code: String::new(),
+        openvm_verifier_interface: String::new(),
+        artifact,
+    };
+    Sdk::verify_evm_halo2_proof(&verifier, proof)
+        .map_err(|e| FfiError::new(OpenvmStatus::VerificationFailed, e))
+}
+
+#[cfg(not(feature = "evm-verify"))]
+fn verify_evm_proof(_verifier: &[u8], _proof: &[u8]) -> Result<u64, FfiError> {
+    Err(FfiError::new(
+        OpenvmStatus::Unsupported,
+        "built without the evm-verify feature",
+    ))
+}
@@ -0,0 +1,177 @@
+use std::{ffi::CStr, path::PathBuf, ptr};
+
+use openvm_circuit::utils::test_system_config;
+use openvm_native_compiler::conversion::CompilerOptions;
+use openvm_sdk::{
+    config::{AggregationConfig, AppConfig, SdkSystemConfig, SdkVmConfig},
+    types::VersionedVmStarkProof,
+    Sdk, StdIn,
+};
+use openvm_stark_sdk::{config::FriParameters, openvm_stark_backend::p3_field::PrimeField32};
+use openvm_verifier_ffi::{
+    openvm_evm_proof_public_values, openvm_last_error_message, openvm_stark_proof_public_values,
+    openvm_verifier_version, openvm_verify_stark_proof, OpenvmStatus,
+};
+
+fn last_error() -> String {
+    let mut buf = [0u8; 256];
+    unsafe { openvm_last_error_message(buf.as_mut_ptr() as *mut _, buf.len()) };
+    CStr::from_bytes_until_nul(&buf)
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn test_app_config() -> AppConfig<SdkVmConfig> {
+    let config = test_system_config()
+        .with_max_segment_len(200)
+        .with_public_values(16);
+    AppConfig {
+        app_fri_params: FriParameters::new_for_testing(1).into(),
+        app_vm_config: SdkVmConfig::builder()
+            .system(SdkSystemConfig { config })
+            .rv32i(Default::default())
+            .rv32m(Default::default())
+            .io(Default::default())
+            .build(),
+        leaf_fri_params: FriParameters::new_for_testing(2).into(),
+        compiler_options: CompilerOptions::default(),
+        memory_layout: None,
+    }
+}
+
+#[test]
+fn test_verify_stark_proof_through_ffi() -> eyre::Result<()> {
+    let sdk = Sdk::new(test_app_config())?.with_agg_config(AggregationConfig {
+        max_num_user_public_values: 16,
+        leaf_fri_params: FriParameters::new_for_testing(2),
+        internal_fri_params: FriParameters::new_for_testing(3),
+        root_fri_params: FriParameters::new_for_testing(4),
+        root_max_constraint_degree: (1 << 4) + 1,
+        ..Default::default()
+    });
+    let pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../sdk/guest/fib");
+    let elf = sdk.build(Default::default(), pkg_dir, &Default::default(), None)?;
+    let (proof, app_commit) = sdk.prove(elf, StdIn::default())?;
+    let user_public_values = proof.user_public_values.clone();
+
+    let agg_vk = bitcode::serialize(&sdk.agg_pk().get_agg_vk())?;
+    let proof = serde_json::to_vec(&VersionedVmStarkProof::new(proof)?)?;
+    let app_exe_commit = *app_commit.app_exe_commit.as_slice();
+    let mut app_vm_commit = *app_commit.app_vm_commit.as_slice();
+    let verify = |app_vm_commit: &[u8; 32]| unsafe {
+        openvm_verify_stark_proof(
+            agg_vk.as_ptr(),
+            agg_vk.len(),
+            proof.as_ptr(),
+            proof.len(),
+            app_exe_commit.as_ptr(),
+            app_vm_commit.as_ptr(),
+        )
+    };
+    assert_eq!(verify(&app_vm_commit), OpenvmStatus::Ok);
+    assert!(last_error().is_empty());
+
+    app_vm_commit[31] ^= 1;
+    assert_eq!(verify(&app_vm_commit), OpenvmStatus::VerificationFailed);
+    assert!(last_error().contains("app commit mismatch"));
+
+    let mut out = vec![0u32; user_public_values.len()];
+    let mut out_len = 0;
+    let status = unsafe {
+        openvm_stark_proof_public_values(
+            proof.as_ptr(),
+            proof.len(),
+            ptr::null_mut(),
+            0,
+            &mut out_len,
+        )
+    };
+    assert_eq!(status, OpenvmStatus::BufferTooSmall);
+    assert_eq!(out_len, user_public_values.len());
+    let status = unsafe {
+        openvm_stark_proof_public_values(
+            proof.as_ptr(),
+            proof.len(),
+            out.as_mut_ptr(),
+            out.len(),
+            &mut out_len,
+        )
+    };
+    assert_eq!(status, OpenvmStatus::Ok);
+    let expected: Vec<u32> = user_public_values
+        .iter()
+        .map(|x| x.as_canonical_u32())
+        .collect();
+    assert_eq!(out, expected);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_inputs_through_ffi() {
+    let version = unsafe { CStr::from_ptr(openvm_verifier_version()) };
+    assert_eq!(version.to_str().unwrap(), openvm_sdk::OPENVM_VERSION);
+
+    let commit = [0u8; 32];
+    let status = unsafe {
+        openvm_verify_stark_proof(
+            ptr::null(),
+            1,
+            ptr::null(),
+            0,
+            commit.as_ptr(),
+            commit.as_ptr(),
+        )
+    };
+    assert_eq!(status, OpenvmStatus::InvalidArgument);
+    assert_eq!(last_error(), "agg_vk is null");
+
+    let garbage = b"not a proof";
+    let status = unsafe {
+        openvm_verify_stark_proof(
+            garbage.as_ptr(),
+            garbage.len(),
+            garbage.as_ptr(),
+            garbage.len(),
+            commit.as_ptr(),
+            commit.as_ptr(),
+        )
+    };
+    assert_eq!(status, OpenvmStatus::DecodeError);
+
+    let mut out_len = 0;
+    let status = unsafe {
+        openvm_stark_proof_public_values(
+            garbage.as_ptr(),
+            garbage.len(),
+            ptr::null_mut(),
+            0,
+            &mut out_len,
+        )
+    };
+    assert_eq!(status, OpenvmStatus::DecodeError);
+
+    // The error message is truncated to fit the buffer
+    let mut buf = [0xffu8; 4];
+    let len = unsafe { openvm_last_error_message(buf.as_mut_ptr() as *mut _, buf.len()) };
+    assert!(len > 3);
+    assert_eq!(&buf, b"inv\0");
+}
+
+#[test]
+fn test_evm_proof_public_values_through_ffi() {
+    let proof = br#"{"version":"v1.4","app_exe_commit":"00","app_vm_commit":"00","user_public_values":"01ff","proof_data":{"accumulator":"","proof":""}}"#;
+    let mut out = [0u8; 4];
+    let mut out_len = 0;
+    let status = unsafe {
+        openvm_evm_proof_public_values(
+            proof.as_ptr(),
+            proof.len(),
+            out.as_mut_ptr(),
+            out.len(),
+            &mut out_len,
+        )
+    };
+    assert_eq!(status, OpenvmStatus::Ok);
+    assert_eq!(&out[..out_len], &[0x01, 0xff]);
+}
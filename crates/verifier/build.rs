@@ -0,0 +1,38 @@
+//! Generates the Poseidon2 round constants of `openvm_stark_sdk::config::baby_bear_poseidon2`,
+//! which are derived from the Horizen Labs reference implementation. `zkhash` requires `std`, so
+//! it is only used at build time.
+
+use std::{env, fs, path::PathBuf};
+
+use zkhash::{ark_ff::PrimeField as _, poseidon2::poseidon2_instance_babybear::RC16};
+
+const HALF_FULL_ROUNDS: usize = 4;
+const PARTIAL_ROUNDS: usize = 13;
+
+fn main() {
+    let rounds: Vec<Vec<u64>> = RC16
+        .iter()
+        .map(|round| round.iter().map(|x| x.into_bigint().0[0]).collect())
+        .collect();
+    let p_end = HALF_FULL_ROUNDS + PARTIAL_ROUNDS;
+    let full_rounds = |rounds: &[Vec<u64>]| {
+        let rounds: Vec<String> = rounds.iter().map(|round| format!("{round:?}")).collect();
+        format!("[{}]", rounds.join(", "))
+    };
+    let partial_rounds: Vec<u64> = rounds[HALF_FULL_ROUNDS..p_end]
+        .iter()
+        .map(|round| round[0])
+        .collect();
+
+    let out = format!(
+        "pub(crate) const INITIAL_ROUND_CONSTANTS: [[u32; 16]; {HALF_FULL_ROUNDS}] = {};\n\
+         pub(crate) const PARTIAL_ROUND_CONSTANTS: [u32; {PARTIAL_ROUNDS}] = {:?};\n\
+         pub(crate) const TERMINAL_ROUND_CONSTANTS: [[u32; 16]; {HALF_FULL_ROUNDS}] = {};\n",
+        full_rounds(&rounds[..HALF_FULL_ROUNDS]),
+        partial_rounds,
+        full_rounds(&rounds[p_end..]),
+    );
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join("poseidon2_constants.rs"), out).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+}
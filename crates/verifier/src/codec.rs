@@ -0,0 +1,271 @@
+//! Decoding of proofs in the hardware and language independent format of `openvm_sdk::codec`.
+
+use alloc::vec::Vec;
+
+use openvm_stark_backend::{
+    interaction::{fri_log_up::FriLogUpPartialProof, RapPhaseSeqKind},
+    p3_field::{FieldAlgebra, FieldExtensionAlgebra, PrimeField32},
+    proof::{AdjacentOpenedValues, AirProofData, Commitments, OpenedValues, OpeningProof, Proof},
+};
+use p3_fri::{BatchOpening, CommitPhaseProofStep, FriProof, QueryProof};
+use p3_symmetric::Hash;
+
+use crate::{
+    config::{Challenge, ChallengeMmcs as InnerChallengeMmcs, ValMmcs as InnerValMmcs},
+    VerifyError, DIGEST_SIZE, F, SC,
+};
+
+type InnerBatchOpening = BatchOpening<F, InnerValMmcs>;
+type InnerQueryProof = QueryProof<Challenge, InnerChallengeMmcs, Vec<InnerBatchOpening>>;
+type InnerFriProof = FriProof<Challenge, InnerChallengeMmcs, F, Vec<InnerBatchOpening>>;
+
+/// Codec version of `openvm_sdk::codec`, which changes only when the proof format changes.
+const CODEC_VERSION: u32 = 1;
+
+type Result<T> = core::result::Result<T, VerifyError>;
+
+/// Reads little-endian words from an encoded proof.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        let (&byte, rest) = self.bytes.split_first().ok_or(VerifyError::UnexpectedEof)?;
+        self.bytes = rest;
+        Ok(byte)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        if self.bytes.len() < 4 {
+            return Err(VerifyError::UnexpectedEof);
+        }
+        let (word, rest) = self.bytes.split_at(4);
+        self.bytes = rest;
+        Ok(u32::from_le_bytes(word.try_into().unwrap()))
+    }
+
+    /// Reads a length prefix, rejecting lengths that exceed the remaining input so that malformed
+    /// proofs cannot trigger huge allocations.
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_u32()? as usize;
+        if len > self.bytes.len() {
+            return Err(VerifyError::UnexpectedEof);
+        }
+        Ok(len)
+    }
+
+    pub(crate) fn finish(self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(VerifyError::TrailingBytes(self.bytes.len()));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait Decode: Sized {
+    fn decode(reader: &mut Reader) -> Result<Self>;
+}
+
+impl Decode for Proof<SC> {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let version = reader.read_u32()?;
+        if version != CODEC_VERSION {
+            return Err(VerifyError::InvalidCodecVersion(version));
+        }
+
+        let main_trace = decode_vec(reader)?;
+        let after_challenge = decode_vec(reader)?;
+        let quotient = Hash::decode(reader)?;
+        let commitments = Commitments {
+            main_trace,
+            after_challenge,
+            quotient,
+        };
+
+        let proof = InnerFriProof::decode(reader)?;
+        let values = decode_opened_values(reader)?;
+        let opening = OpeningProof { proof, values };
+
+        let per_air = decode_vec(reader)?;
+
+        let kind = reader.read_u8()?;
+        if kind != RapPhaseSeqKind::FriLogUp as u8 {
+            return Err(VerifyError::UnknownRapPhaseSeqKind(kind));
+        }
+        // `None` is encoded as `u32::MAX`.
+        let logup_pow_witness = reader.read_u32()?;
+        let rap_phase_seq_proof = (logup_pow_witness != u32::MAX).then(|| FriLogUpPartialProof {
+            logup_pow_witness: F::from_canonical_u32(logup_pow_witness),
+        });
+
+        Ok(Proof {
+            commitments,
+            opening,
+            per_air,
+            rap_phase_seq_proof,
+        })
+    }
+}
+
+fn decode_opened_values(reader: &mut Reader) -> Result<OpenedValues<Challenge>> {
+    let preprocessed = decode_vec(reader)?;
+    let main = decode_vec(reader)?;
+    let after_challenge = decode_vec(reader)?;
+    let quotient = decode_vec(reader)?;
+    Ok(OpenedValues {
+        preprocessed,
+        main,
+        after_challenge,
+        quotient,
+    })
+}
+
+impl Decode for AdjacentOpenedValues<Challenge> {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let local = decode_vec(reader)?;
+        let next = decode_vec(reader)?;
+        Ok(AdjacentOpenedValues { local, next })
+    }
+}
+
+impl Decode for AirProofData<F, Challenge> {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let air_id = reader.read_u32()? as usize;
+        let degree = reader.read_u32()? as usize;
+        let exposed_values_after_challenge = decode_vec(reader)?;
+        let public_values = decode_vec(reader)?;
+        Ok(AirProofData {
+            air_id,
+            degree,
+            exposed_values_after_challenge,
+            public_values,
+        })
+    }
+}
+
+impl Decode for InnerFriProof {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let commit_phase_commits = decode_vec(reader)?;
+        let query_proofs = decode_vec(reader)?;
+        let final_poly = decode_vec(reader)?;
+        let pow_witness = F::decode(reader)?;
+        Ok(FriProof {
+            commit_phase_commits,
+            query_proofs,
+            final_poly,
+            pow_witness,
+        })
+    }
+}
+
+impl Decode for InnerQueryProof {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let input_proof = decode_vec(reader)?;
+        let len = reader.read_len()?;
+        let mut commit_phase_openings = Vec::with_capacity(len);
+        for _ in 0..len {
+            let sibling_value = Challenge::decode(reader)?;
+            let opening_proof = decode_vec(reader)?;
+            commit_phase_openings.push(CommitPhaseProofStep {
+                sibling_value,
+                opening_proof,
+            });
+        }
+        Ok(QueryProof {
+            input_proof,
+            commit_phase_openings,
+        })
+    }
+}
+
+impl Decode for InnerBatchOpening {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let opened_values = decode_vec(reader)?;
+        let opening_proof = decode_vec(reader)?;
+        Ok(BatchOpening {
+            opened_values,
+            opening_proof,
+        })
+    }
+}
+
+impl Decode for Hash<F, F, DIGEST_SIZE> {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        Ok(<[F; DIGEST_SIZE]>::decode(reader)?.into())
+    }
+}
+
+impl Decode for [F; DIGEST_SIZE] {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let mut digest = [F::ZERO; DIGEST_SIZE];
+        for elt in &mut digest {
+            *elt = F::decode(reader)?;
+        }
+        Ok(digest)
+    }
+}
+
+impl Decode for Challenge {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let mut base_elements = [F::ZERO; 4];
+        for base_element in &mut base_elements {
+            *base_element = F::decode(reader)?;
+        }
+        Ok(Challenge::from_base_slice(&base_elements))
+    }
+}
+
+impl Decode for F {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let value = reader.read_u32()?;
+        if value >= F::ORDER_U32 {
+            return Err(VerifyError::NonCanonicalField(value));
+        }
+        Ok(F::from_canonical_u32(value))
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        decode_vec(reader)
+    }
+}
+
+pub(crate) fn decode_vec<T: Decode>(reader: &mut Reader) -> Result<Vec<T>> {
+    let len = reader.read_len()?;
+    let mut vec = Vec::with_capacity(len);
+    for _ in 0..len {
+        vec.push(T::decode(reader)?);
+    }
+    Ok(vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use openvm_stark_backend::{p3_field::PrimeField32, proof::Proof};
+
+    use super::{decode_vec, Decode, Reader};
+    use crate::{VerifyError, F, SC};
+
+    #[test]
+    fn test_decode_rejects_malformed_input() {
+        let proof = Proof::<SC>::decode(&mut Reader::new(&[1, 0]));
+        assert!(matches!(proof, Err(VerifyError::UnexpectedEof)));
+
+        // Length prefix larger than the input
+        let bytes = u32::MAX.to_le_bytes();
+        let values = decode_vec::<F>(&mut Reader::new(&bytes));
+        assert!(matches!(values, Err(VerifyError::UnexpectedEof)));
+
+        let bytes = [vec![1, 0, 0, 0], F::ORDER_U32.to_le_bytes().to_vec()].concat();
+        let values = decode_vec::<F>(&mut Reader::new(&bytes));
+        assert!(matches!(values, Err(VerifyError::NonCanonicalField(_))));
+    }
+}
@@ -0,0 +1,171 @@
+//! Commitments to the app executable and App VM, matching `openvm_sdk::commit`.
+
+use alloc::vec::Vec;
+use core::{array::from_fn, fmt};
+
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+use p3_symmetric::Permutation;
+
+use crate::{config::Perm, VerifyError, DIGEST_SIZE, F};
+
+/// Number of bytes of a BN254 scalar.
+pub const BN254_BYTES: usize = 32;
+
+/// Commitments users check against the final proof. Each commitment is the big-endian encoding
+/// of a digest of [DIGEST_SIZE] BabyBear elements, read as an integer in base `F::ORDER`, as in
+/// `openvm_sdk::commit::CommitBytes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AppExecutionCommit {
+    /// Commitment of the program, its initial memory and its starting pc.
+    pub app_exe_commit: [u8; BN254_BYTES],
+    /// Commitment of the leaf verifier program, which commits the App VM config.
+    pub app_vm_commit: [u8; BN254_BYTES],
+}
+
+impl AppExecutionCommit {
+    pub fn new(app_exe_commit: [u8; BN254_BYTES], app_vm_commit: [u8; BN254_BYTES]) -> Self {
+        Self {
+            app_exe_commit,
+            app_vm_commit,
+        }
+    }
+
+    /// Parses the hex encoded commitments of an `app.commit` file written by
+    /// `cargo openvm commit`.
+    pub fn from_hex(app_exe_commit: &str, app_vm_commit: &str) -> Result<Self, VerifyError> {
+        Ok(Self::new(
+            decode_commit_hex(app_exe_commit)?,
+            decode_commit_hex(app_vm_commit)?,
+        ))
+    }
+
+    pub(crate) fn from_field_commit(
+        exe_commit: &[F; DIGEST_SIZE],
+        vm_commit: &[F; DIGEST_SIZE],
+    ) -> Self {
+        Self::new(digest_to_bytes(exe_commit), digest_to_bytes(vm_commit))
+    }
+}
+
+impl fmt::Display for AppExecutionCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "app_exe_commit: {}, app_vm_commit: {}",
+            hex::encode(self.app_exe_commit),
+            hex::encode(self.app_vm_commit)
+        )
+    }
+}
+
+fn decode_commit_hex(commit: &str) -> Result<[u8; BN254_BYTES], VerifyError> {
+    let commit = commit.strip_prefix("0x").unwrap_or(commit);
+    let mut bytes = [0u8; BN254_BYTES];
+    hex::decode_to_slice(commit, &mut bytes).map_err(|_| VerifyError::InvalidHex)?;
+    Ok(bytes)
+}
+
+/// Big-endian bytes of `sum_i digest[i] * F::ORDER^i`, which is less than the BN254 modulus.
+fn digest_to_bytes(digest: &[F; DIGEST_SIZE]) -> [u8; BN254_BYTES] {
+    // Little-endian u32 limbs of the integer, computed with Horner's rule.
+    let mut limbs = [0u32; BN254_BYTES / 4];
+    for x in digest.iter().rev() {
+        let mut carry = x.as_canonical_u32() as u64;
+        for limb in &mut limbs {
+            let value = *limb as u64 * F::ORDER_U32 as u64 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        debug_assert_eq!(carry, 0);
+    }
+    let mut bytes = [0u8; BN254_BYTES];
+    for (chunk, limb) in bytes.chunks_exact_mut(4).zip(limbs.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+/// Poseidon2 compression of the VM, see `openvm_circuit::arch::hasher::poseidon2`.
+pub(crate) struct Poseidon2Hasher {
+    perm: Perm,
+}
+
+impl Poseidon2Hasher {
+    pub fn new(perm: Perm) -> Self {
+        Self { perm }
+    }
+
+    pub fn compress(&self, lhs: &[F; DIGEST_SIZE], rhs: &[F; DIGEST_SIZE]) -> [F; DIGEST_SIZE] {
+        let mut state: [F; 2 * DIGEST_SIZE] = from_fn(|i| {
+            if i < DIGEST_SIZE {
+                lhs[i]
+            } else {
+                rhs[i - DIGEST_SIZE]
+            }
+        });
+        self.perm.permute_mut(&mut state);
+        from_fn(|i| state[i])
+    }
+
+    pub fn hash(&self, values: &[F; DIGEST_SIZE]) -> [F; DIGEST_SIZE] {
+        self.compress(values, &[F::ZERO; DIGEST_SIZE])
+    }
+
+    /// Root of the Merkle tree whose leaves are the hashes of the chunks of `values`. Returns
+    /// `None` unless the number of chunks is a power of two.
+    pub fn merkle_root(&self, values: &[F]) -> Option<[F; DIGEST_SIZE]> {
+        if values.len() % DIGEST_SIZE != 0 || !(values.len() / DIGEST_SIZE).is_power_of_two() {
+            return None;
+        }
+        let mut layer: Vec<_> = values
+            .chunks_exact(DIGEST_SIZE)
+            .map(|chunk| self.hash(chunk.try_into().unwrap()))
+            .collect();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks_exact(2)
+                .map(|pair| self.compress(&pair[0], &pair[1]))
+                .collect();
+        }
+        Some(layer[0])
+    }
+
+    /// Commitment of the app program, initial memory and starting pc, see
+    /// `openvm_circuit::system::program::trace::compute_exe_commit`.
+    pub fn exe_commit(
+        &self,
+        program_commit: &[F; DIGEST_SIZE],
+        init_memory_root: &[F; DIGEST_SIZE],
+        pc_start: F,
+    ) -> [F; DIGEST_SIZE] {
+        let mut padded_pc_start = [F::ZERO; DIGEST_SIZE];
+        padded_pc_start[0] = pc_start;
+        let program_hash = self.hash(program_commit);
+        let memory_hash = self.hash(init_memory_root);
+        let pc_hash = self.hash(&padded_pc_start);
+        self.compress(&self.compress(&program_hash, &memory_hash), &pc_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+
+    use super::digest_to_bytes;
+    use crate::{DIGEST_SIZE, F};
+
+    #[test]
+    fn test_digest_to_bytes() {
+        let mut digest = [F::ZERO; DIGEST_SIZE];
+        digest[0] = F::ONE;
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+        assert_eq!(digest_to_bytes(&digest), expected);
+
+        digest[0] = F::ZERO;
+        digest[1] = F::ONE;
+        expected[31] = 0;
+        expected[28..].copy_from_slice(&F::ORDER_U32.to_be_bytes());
+        assert_eq!(digest_to_bytes(&digest), expected);
+    }
+}
@@ -0,0 +1,93 @@
+//! STARK config of the aggregation proofs, equivalent to
+//! `openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Config` but only built from
+//! `no_std` crates.
+
+use alloc::vec::Vec;
+
+use openvm_stark_backend::{
+    config::StarkConfig,
+    interaction::{fri_log_up::FriLogUpPhase, LogUpSecurityParameters},
+    p3_challenger::DuplexChallenger,
+    p3_commit::ExtensionMmcs,
+    p3_field::{extension::BinomialExtensionField, Field, FieldAlgebra, PrimeField32},
+};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_dft::Radix2DitParallel;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_poseidon2::ExternalLayerConstants;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use serde::{Deserialize, Serialize};
+
+mod constants {
+    include!(concat!(env!("OUT_DIR"), "/poseidon2_constants.rs"));
+}
+
+pub type F = BabyBear;
+pub(crate) type Challenge = BinomialExtensionField<F, 4>;
+pub(crate) type Perm = Poseidon2BabyBear<16>;
+pub(crate) type Hash = PaddingFreeSponge<Perm, 16, 8, 8>;
+pub(crate) type Compress = TruncatedPermutation<Perm, 2, 8, 16>;
+pub(crate) type ValMmcs =
+    MerkleTreeMmcs<<F as Field>::Packing, <F as Field>::Packing, Hash, Compress, 8>;
+pub(crate) type ChallengeMmcs = ExtensionMmcs<F, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<F, Perm, 16, 8>;
+type Dft = Radix2DitParallel<F>;
+type Pcs = TwoAdicFriPcs<F, Dft, ValMmcs, ChallengeMmcs>;
+type RapPhase = FriLogUpPhase<F, Challenge, Challenger>;
+pub type SC = StarkConfig<Pcs, RapPhase, Challenge, Challenger>;
+
+/// FRI parameters of a proof, with the serialized layout of
+/// `openvm_stark_sdk::config::FriParameters`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriParameters {
+    pub log_blowup: usize,
+    pub log_final_poly_len: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+}
+
+/// Poseidon2 permutation of the STARK config and the VM.
+pub(crate) fn default_perm() -> Perm {
+    let to_field = |round: &[u32; 16]| round.map(F::from_canonical_u32);
+    Perm::new(
+        ExternalLayerConstants::new(
+            constants::INITIAL_ROUND_CONSTANTS
+                .iter()
+                .map(to_field)
+                .collect(),
+            constants::TERMINAL_ROUND_CONSTANTS
+                .iter()
+                .map(to_field)
+                .collect(),
+        ),
+        constants::PARTIAL_ROUND_CONSTANTS
+            .iter()
+            .map(|&c| F::from_canonical_u32(c))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// STARK config with the FRI parameters `fri_params` and the LogUp parameters of
+/// `BabyBearPoseidon2Engine`.
+pub(crate) fn stark_config(perm: &Perm, fri_params: FriParameters) -> SC {
+    let val_mmcs = ValMmcs::new(Hash::new(perm.clone()), Compress::new(perm.clone()));
+    let fri_config = FriConfig {
+        log_blowup: fri_params.log_blowup,
+        log_final_poly_len: fri_params.log_final_poly_len,
+        num_queries: fri_params.num_queries,
+        proof_of_work_bits: fri_params.proof_of_work_bits,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    };
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    let log_up_params = LogUpSecurityParameters {
+        max_interaction_count: F::ORDER_U32,
+        log_max_message_length: 7,
+        log_up_pow_bits: 16,
+    };
+    SC::new(pcs, RapPhase::new(log_up_params, fri_params.log_blowup))
+}
+
+pub(crate) fn new_challenger(perm: &Perm) -> Challenger {
+    Challenger::new(perm.clone())
+}
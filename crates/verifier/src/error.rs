@@ -0,0 +1,112 @@
+use core::fmt;
+
+use openvm_stark_backend::verifier::VerificationError;
+
+use crate::AppExecutionCommit;
+
+#[derive(Debug)]
+pub enum VerifyError {
+    /// The proof or key ended before it was fully decoded.
+    UnexpectedEof,
+    TrailingBytes(usize),
+    InvalidCodecVersion(u32),
+    UnknownRapPhaseSeqKind(u8),
+    NonCanonicalField(u32),
+    InvalidHex,
+    InvalidVerifyingKey,
+    UnsupportedVersion {
+        expected: &'static str,
+        actual: alloc::string::String,
+    },
+    NotEnoughAirs(usize),
+    SystemAirMissing {
+        air_id: usize,
+    },
+    UnexpectedPvs {
+        expected: usize,
+        actual: usize,
+    },
+    ProgramCommitMismatch,
+    IsTerminateMismatch,
+    ExitCodeMismatch(u32),
+    InvalidAppFriProfile {
+        num_profiles: usize,
+        actual: u32,
+    },
+    UserPublicValuesCommitMismatch,
+    UnexpectedNumUserPublicValues {
+        expected: usize,
+        actual: usize,
+    },
+    AppCommitMismatch {
+        expected: AppExecutionCommit,
+        actual: AppExecutionCommit,
+    },
+    StarkError(VerificationError),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of input"),
+            Self::TrailingBytes(len) => write!(f, "{len} trailing bytes after the proof"),
+            Self::InvalidCodecVersion(version) => {
+                write!(f, "invalid codec version {version}")
+            }
+            Self::UnknownRapPhaseSeqKind(kind) => write!(f, "unknown RapPhaseSeqKind: {kind}"),
+            Self::NonCanonicalField(value) => {
+                write!(f, "{value} is not a canonical field element")
+            }
+            Self::InvalidHex => write!(f, "invalid hex encoding"),
+            Self::InvalidVerifyingKey => write!(f, "failed to deserialize the verifying key"),
+            Self::UnsupportedVersion { expected, actual } => write!(
+                f,
+                "unsupported proof version {actual}, this verifier supports {expected}"
+            ),
+            Self::NotEnoughAirs(num_airs) => {
+                write!(
+                    f,
+                    "invalid number of AIRs: expected at least 3, got {num_airs}"
+                )
+            }
+            Self::SystemAirMissing { air_id } => write!(f, "missing system AIR with ID {air_id}"),
+            Self::UnexpectedPvs { expected, actual } => write!(
+                f,
+                "AIR has unexpected public values (expected: {expected}, actual: {actual})"
+            ),
+            Self::ProgramCommitMismatch => write!(f, "internal program commit mismatch"),
+            Self::IsTerminateMismatch => write!(f, "the program did not terminate"),
+            Self::ExitCodeMismatch(exit_code) => {
+                write!(f, "the program exited with code {exit_code}")
+            }
+            Self::InvalidAppFriProfile {
+                num_profiles,
+                actual,
+            } => write!(
+                f,
+                "invalid app FRI profile {actual}: only {num_profiles} profiles are accepted"
+            ),
+            Self::UserPublicValuesCommitMismatch => {
+                write!(f, "user public values do not match their commitment")
+            }
+            Self::UnexpectedNumUserPublicValues { expected, actual } => write!(
+                f,
+                "unexpected number of user public values (expected: {expected}, actual: {actual})"
+            ),
+            Self::AppCommitMismatch { expected, actual } => write!(
+                f,
+                "app commit mismatch (expected: {expected}, actual: {actual})"
+            ),
+            Self::StarkError(e) => write!(f, "stark verification error: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+impl From<VerificationError> for VerifyError {
+    fn from(e: VerificationError) -> Self {
+        Self::StarkError(e)
+    }
+}
@@ -0,0 +1,243 @@
+//! Lightweight verifier of OpenVM aggregate STARK proofs.
+//!
+//! This crate verifies the proofs produced by `Sdk::prove` (serialized as a
+//! `VersionedVmStarkProof`) against an `AggVerifyingKey` and the expected [AppExecutionCommit],
+//! performing the same checks as `Sdk::verify_proof`. It only depends on the field, hash and FRI
+//! verification code of the STARK backend, not on the VM circuits or provers, and is `no_std`
+//! with `alloc` when the default `std` feature is disabled, so it can be embedded in light clients
+//! and compiled to `wasm32-unknown-unknown`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+
+use openvm_stark_backend::{
+    config::Com, keygen::types::MultiStarkVerifyingKey, p3_field::PrimeField32, proof::Proof,
+    verifier::MultiTraceStarkVerifier,
+};
+use serde::{Deserialize, Serialize};
+
+mod codec;
+mod commit;
+mod config;
+mod error;
+mod pvs;
+
+pub use commit::{AppExecutionCommit, BN254_BYTES};
+pub use config::{FriParameters, F, SC};
+pub use error::VerifyError;
+
+use crate::{
+    codec::{decode_vec, Decode, Reader},
+    commit::Poseidon2Hasher,
+    config::{default_perm, new_challenger, stark_config},
+    pvs::{InternalVmVerifierExtraPvs, VmVerifierPvs, INTERNAL_VM_VERIFIER_PVS_WIDTH},
+};
+
+pub const DIGEST_SIZE: usize = 8;
+
+/// The `major.minor` OpenVM version of the proofs this crate verifies.
+pub const OPENVM_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION_MAJOR"),
+    ".",
+    env!("CARGO_PKG_VERSION_MINOR")
+);
+
+/// Version tag of [VersionedVmStarkProof]s this crate verifies.
+const PROOF_VERSION: &str = concat!(
+    "v",
+    env!("CARGO_PKG_VERSION_MAJOR"),
+    ".",
+    env!("CARGO_PKG_VERSION_MINOR")
+);
+
+const PROGRAM_AIR_ID: usize = 0;
+const CONNECTOR_AIR_ID: usize = 1;
+const PUBLIC_VALUES_AIR_ID: usize = 2;
+const PROGRAM_CACHED_TRACE_INDEX: usize = 0;
+
+/// Verifying key of the leaf and internal aggregation verifiers. Deserializes from the bitcode
+/// encoding of `openvm_sdk::keygen::AggVerifyingKey`, e.g. the `agg_stark.vk` file written by
+/// `cargo openvm setup`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AggVerifyingKey {
+    pub leaf_fri_params: FriParameters,
+    pub leaf_vk: MultiStarkVerifyingKey<SC>,
+    /// FRI parameters used to generate the last internal proof.
+    pub internal_fri_params: FriParameters,
+    pub internal_vk: MultiStarkVerifyingKey<SC>,
+    pub internal_verifier_program_commit: Com<SC>,
+    /// Number of user public values of the root verifier of the aggregation key.
+    pub num_user_public_values: usize,
+    /// Number of app FRI profiles accepted by the root verifier of the aggregation key.
+    pub num_app_fri_profiles: usize,
+}
+
+impl AggVerifyingKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VerifyError> {
+        bitcode::deserialize(bytes).map_err(|_| VerifyError::InvalidVerifyingKey)
+    }
+}
+
+/// Aggregate STARK proof in the JSON format of `openvm_sdk::types::VersionedVmStarkProof`, with
+/// hex encoded fields.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionedVmStarkProof {
+    pub version: String,
+    pub user_public_values: String,
+    pub proof: String,
+}
+
+/// Decoded aggregate STARK proof.
+#[derive(Clone)]
+pub struct VmStarkProof {
+    pub inner: Proof<SC>,
+    pub user_public_values: Vec<F>,
+}
+
+impl VmStarkProof {
+    /// Decodes the proof and user public values, which are encoded with `openvm_sdk::codec`.
+    pub fn decode(proof: &[u8], user_public_values: &[u8]) -> Result<Self, VerifyError> {
+        let mut reader = Reader::new(proof);
+        let inner = Proof::decode(&mut reader)?;
+        reader.finish()?;
+        let mut reader = Reader::new(user_public_values);
+        let user_public_values = decode_vec(&mut reader)?;
+        reader.finish()?;
+        Ok(Self {
+            inner,
+            user_public_values,
+        })
+    }
+}
+
+impl TryFrom<&VersionedVmStarkProof> for VmStarkProof {
+    type Error = VerifyError;
+
+    fn try_from(proof: &VersionedVmStarkProof) -> Result<Self, VerifyError> {
+        if proof.version != PROOF_VERSION {
+            return Err(VerifyError::UnsupportedVersion {
+                expected: PROOF_VERSION,
+                actual: proof.version.clone(),
+            });
+        }
+        let decode_hex = |s: &str| hex::decode(s).map_err(|_| VerifyError::InvalidHex);
+        Self::decode(
+            &decode_hex(&proof.proof)?,
+            &decode_hex(&proof.user_public_values)?,
+        )
+    }
+}
+
+/// Verifies a versioned aggregate STARK proof. See [verify_stark_proof].
+pub fn verify_versioned_stark_proof(
+    agg_vk: &AggVerifyingKey,
+    expected_app_commit: &AppExecutionCommit,
+    proof: &VersionedVmStarkProof,
+) -> Result<Vec<F>, VerifyError> {
+    let proof = VmStarkProof::try_from(proof)?;
+    verify_stark_proof(agg_vk, expected_app_commit, &proof)?;
+    Ok(proof.user_public_values)
+}
+
+/// Verifies an aggregate STARK proof of a program that terminated with exit code 0, and checks
+/// that it proves the execution committed to by `expected_app_commit` and that the user public
+/// values of `proof` are the ones committed to in the proof.
+pub fn verify_stark_proof(
+    agg_vk: &AggVerifyingKey,
+    expected_app_commit: &AppExecutionCommit,
+    proof: &VmStarkProof,
+) -> Result<(), VerifyError> {
+    let per_air = &proof.inner.per_air;
+    if per_air.len() < 3 {
+        return Err(VerifyError::NotEnoughAirs(per_air.len()));
+    }
+    for (air_proof_data, air_id) in
+        per_air
+            .iter()
+            .zip([PROGRAM_AIR_ID, CONNECTOR_AIR_ID, PUBLIC_VALUES_AIR_ID])
+    {
+        if air_proof_data.air_id != air_id {
+            return Err(VerifyError::SystemAirMissing { air_id });
+        }
+    }
+    let public_values = &per_air[PUBLIC_VALUES_AIR_ID].public_values;
+
+    let program_commit: [F; DIGEST_SIZE] = (*proof
+        .inner
+        .commitments
+        .main_trace
+        .get(PROGRAM_CACHED_TRACE_INDEX)
+        .ok_or(VerifyError::SystemAirMissing {
+            air_id: PROGRAM_AIR_ID,
+        })?)
+    .into();
+    let internal_commit: [F; DIGEST_SIZE] = agg_vk.internal_verifier_program_commit.into();
+
+    // The final proof is an internal proof, unless a single leaf proof was enough.
+    let (fri_params, vk, app_vm_commit) = if program_commit == internal_commit {
+        if public_values.len() != INTERNAL_VM_VERIFIER_PVS_WIDTH {
+            return Err(VerifyError::UnexpectedPvs {
+                expected: INTERNAL_VM_VERIFIER_PVS_WIDTH,
+                actual: public_values.len(),
+            });
+        }
+        let extra_pvs = InternalVmVerifierExtraPvs::from_slice(public_values).unwrap();
+        if extra_pvs.internal_program_commit != internal_commit {
+            return Err(VerifyError::ProgramCommitMismatch);
+        }
+        (
+            agg_vk.internal_fri_params,
+            &agg_vk.internal_vk,
+            extra_pvs.leaf_verifier_commit,
+        )
+    } else {
+        (agg_vk.leaf_fri_params, &agg_vk.leaf_vk, program_commit)
+    };
+    let perm = default_perm();
+    MultiTraceStarkVerifier::new(&stark_config(&perm, fri_params)).verify(
+        &mut new_challenger(&perm),
+        vk,
+        &proof.inner,
+    )?;
+
+    let pvs = VmVerifierPvs::from_slice(public_values).ok_or(VerifyError::UnexpectedPvs {
+        expected: pvs::VM_VERIFIER_PVS_WIDTH,
+        actual: public_values.len(),
+    })?;
+    match pvs.exit_code() {
+        Some(0) => {}
+        Some(exit_code) => return Err(VerifyError::ExitCodeMismatch(exit_code)),
+        None => return Err(VerifyError::IsTerminateMismatch),
+    }
+    let fri_profile = pvs.fri_profile.as_canonical_u32();
+    if fri_profile as usize >= agg_vk.num_app_fri_profiles {
+        return Err(VerifyError::InvalidAppFriProfile {
+            num_profiles: agg_vk.num_app_fri_profiles,
+            actual: fri_profile,
+        });
+    }
+
+    if proof.user_public_values.len() != agg_vk.num_user_public_values {
+        return Err(VerifyError::UnexpectedNumUserPublicValues {
+            expected: agg_vk.num_user_public_values,
+            actual: proof.user_public_values.len(),
+        });
+    }
+    let hasher = Poseidon2Hasher::new(perm);
+    if hasher.merkle_root(&proof.user_public_values) != Some(pvs.public_values_commit) {
+        return Err(VerifyError::UserPublicValuesCommitMismatch);
+    }
+
+    let app_exe_commit =
+        hasher.exe_commit(&pvs.app_commit, &pvs.initial_memory_root, pvs.initial_pc);
+    let app_commit = AppExecutionCommit::from_field_commit(&app_exe_commit, &app_vm_commit);
+    if &app_commit != expected_app_commit {
+        return Err(VerifyError::AppCommitMismatch {
+            expected: *expected_app_commit,
+            actual: app_commit,
+        });
+    }
+    Ok(())
+}
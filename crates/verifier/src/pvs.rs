@@ -0,0 +1,88 @@
+//! Public values of the leaf and internal verifier programs, laid out as in
+//! `openvm_continuations::verifier`.
+
+use core::array::from_fn;
+
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+
+use crate::{DIGEST_SIZE, F};
+
+/// Number of field elements of [VmVerifierPvs].
+pub(crate) const VM_VERIFIER_PVS_WIDTH: usize = 4 * DIGEST_SIZE + 5;
+/// Number of field elements of [InternalVmVerifierPvs].
+pub(crate) const INTERNAL_VM_VERIFIER_PVS_WIDTH: usize = VM_VERIFIER_PVS_WIDTH + 2 * DIGEST_SIZE;
+
+/// Public values of the leaf and internal verifiers, which are a prefix of the public values of
+/// the internal verifier.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VmVerifierPvs {
+    pub app_commit: [F; DIGEST_SIZE],
+    pub initial_pc: F,
+    pub exit_code: F,
+    pub is_terminate: F,
+    pub initial_memory_root: [F; DIGEST_SIZE],
+    pub public_values_commit: [F; DIGEST_SIZE],
+    pub fri_profile: F,
+}
+
+impl VmVerifierPvs {
+    /// Reads the public values from the first [VM_VERIFIER_PVS_WIDTH] elements of `values`.
+    pub fn from_slice(values: &[F]) -> Option<Self> {
+        let mut reader = PvsReader(values.get(..VM_VERIFIER_PVS_WIDTH)?);
+        let app_commit = reader.digest();
+        let initial_pc = reader.next();
+        let _final_pc = reader.next();
+        let exit_code = reader.next();
+        let is_terminate = reader.next();
+        let initial_memory_root = reader.digest();
+        let _final_memory_root = reader.digest();
+        let public_values_commit = reader.digest();
+        let fri_profile = reader.next();
+        Some(Self {
+            app_commit,
+            initial_pc,
+            exit_code,
+            is_terminate,
+            initial_memory_root,
+            public_values_commit,
+            fri_profile,
+        })
+    }
+
+    /// Exit code of the program, or `None` if it did not terminate.
+    pub fn exit_code(&self) -> Option<u32> {
+        (self.is_terminate == F::ONE).then(|| self.exit_code.as_canonical_u32())
+    }
+}
+
+/// Commitments the internal verifier exposes after [VmVerifierPvs].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct InternalVmVerifierExtraPvs {
+    pub leaf_verifier_commit: [F; DIGEST_SIZE],
+    pub internal_program_commit: [F; DIGEST_SIZE],
+}
+
+impl InternalVmVerifierExtraPvs {
+    pub fn from_slice(values: &[F]) -> Option<Self> {
+        let mut reader =
+            PvsReader(values.get(VM_VERIFIER_PVS_WIDTH..INTERNAL_VM_VERIFIER_PVS_WIDTH)?);
+        Some(Self {
+            leaf_verifier_commit: reader.digest(),
+            internal_program_commit: reader.digest(),
+        })
+    }
+}
+
+struct PvsReader<'a>(&'a [F]);
+
+impl PvsReader<'_> {
+    fn next(&mut self) -> F {
+        let (&value, rest) = self.0.split_first().unwrap();
+        self.0 = rest;
+        value
+    }
+
+    fn digest(&mut self) -> [F; DIGEST_SIZE] {
+        from_fn(|_| self.next())
+    }
+}
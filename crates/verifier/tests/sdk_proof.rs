@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use openvm_circuit::utils::test_system_config;
+use openvm_native_compiler::conversion::CompilerOptions;
+use openvm_sdk::{
+    config::{AggregationConfig, AppConfig, SdkSystemConfig, SdkVmConfig},
+    types::VersionedVmStarkProof,
+    Sdk, StdIn,
+};
+use openvm_stark_sdk::{
+    config::FriParameters, openvm_stark_backend::p3_field::PrimeField32, p3_baby_bear::BabyBear,
+};
+use openvm_verifier::{
+    verify_versioned_stark_proof, AggVerifyingKey, AppExecutionCommit, VerifyError, VmStarkProof,
+    DIGEST_SIZE, F,
+};
+
+fn test_app_config() -> AppConfig<SdkVmConfig> {
+    let config = test_system_config()
+        .with_max_segment_len(200)
+        .with_public_values(16);
+    AppConfig {
+        app_fri_params: FriParameters::new_for_testing(1).into(),
+        app_vm_config: SdkVmConfig::builder()
+            .system(SdkSystemConfig { config })
+            .rv32i(Default::default())
+            .rv32m(Default::default())
+            .io(Default::default())
+            .build(),
+        leaf_fri_params: FriParameters::new_for_testing(2).into(),
+        compiler_options: CompilerOptions::default(),
+        memory_layout: None,
+    }
+}
+
+fn test_sdk() -> eyre::Result<Sdk> {
+    Ok(
+        Sdk::new(test_app_config())?.with_agg_config(AggregationConfig {
+            max_num_user_public_values: 16,
+            leaf_fri_params: FriParameters::new_for_testing(2),
+            internal_fri_params: FriParameters::new_for_testing(3),
+            root_fri_params: FriParameters::new_for_testing(4),
+            root_max_constraint_degree: (1 << 4) + 1,
+            ..Default::default()
+        }),
+    )
+}
+
+fn assert_fri_params_eq(actual: openvm_verifier::FriParameters, expected: FriParameters) {
+    assert_eq!(actual.log_blowup, expected.log_blowup);
+    assert_eq!(actual.log_final_poly_len, expected.log_final_poly_len);
+    assert_eq!(actual.num_queries, expected.num_queries);
+    assert_eq!(actual.proof_of_work_bits, expected.proof_of_work_bits);
+}
+
+fn to_u32s<T: PrimeField32>(values: &[T]) -> Vec<u32> {
+    values.iter().map(|x| x.as_canonical_u32()).collect()
+}
+
+/// The verifier mirrors the layout of the SDK's `AggVerifyingKey`, so check that every field of a
+/// key serialized by the SDK decodes to the value the SDK derived it from.
+#[test]
+fn test_decode_sdk_agg_vk() -> eyre::Result<()> {
+    let sdk = test_sdk()?;
+    let agg_pk = sdk.agg_pk();
+    let agg_vk = AggVerifyingKey::from_bytes(&bitcode::serialize(&agg_pk.get_agg_vk())?)?;
+
+    assert_fri_params_eq(agg_vk.leaf_fri_params, agg_pk.leaf_vm_pk.fri_params);
+    assert_eq!(
+        bitcode::serialize(&agg_vk.leaf_vk)?,
+        bitcode::serialize(&agg_pk.leaf_vm_pk.vm_pk.get_vk())?
+    );
+    assert_fri_params_eq(agg_vk.internal_fri_params, agg_pk.internal_vm_pk.fri_params);
+    assert_eq!(
+        bitcode::serialize(&agg_vk.internal_vk)?,
+        bitcode::serialize(&agg_pk.internal_vm_pk.vm_pk.get_vk())?
+    );
+    let internal_commit: [F; DIGEST_SIZE] = agg_vk.internal_verifier_program_commit.into();
+    let expected_internal_commit: [BabyBear; DIGEST_SIZE] =
+        agg_pk.internal_committed_exe.get_program_commit().into();
+    assert_eq!(
+        to_u32s(&internal_commit),
+        to_u32s(&expected_internal_commit)
+    );
+    assert_eq!(
+        agg_vk.num_user_public_values,
+        agg_pk.num_user_public_values()
+    );
+    assert_eq!(agg_vk.num_app_fri_profiles, agg_pk.num_app_fri_profiles);
+    Ok(())
+}
+
+#[test]
+fn test_verify_sdk_stark_proof() -> eyre::Result<()> {
+    let sdk = test_sdk()?;
+    let pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../sdk/guest/fib");
+    let elf = sdk.build(Default::default(), pkg_dir, &Default::default(), None)?;
+    let (proof, app_commit) = sdk.prove(elf, StdIn::default())?;
+    let expected_public_values: Vec<u32> = proof
+        .user_public_values
+        .iter()
+        .map(|x| x.as_canonical_u32())
+        .collect();
+
+    let agg_vk = AggVerifyingKey::from_bytes(&bitcode::serialize(&sdk.agg_pk().get_agg_vk())?)?;
+    let sdk_inner_proof = bitcode::serialize(&proof.inner)?;
+    let proof: openvm_verifier::VersionedVmStarkProof =
+        serde_json::from_value(serde_json::to_value(VersionedVmStarkProof::new(proof)?)?)?;
+
+    // The proof and user public values are decoded with the verifier's copy of the SDK codec.
+    let decoded = VmStarkProof::try_from(&proof)?;
+    assert_eq!(bitcode::serialize(&decoded.inner)?, sdk_inner_proof);
+    assert_eq!(to_u32s(&decoded.user_public_values), expected_public_values);
+    let mut commit = AppExecutionCommit::new(
+        *app_commit.app_exe_commit.as_slice(),
+        *app_commit.app_vm_commit.as_slice(),
+    );
+    let public_values = verify_versioned_stark_proof(&agg_vk, &commit, &proof)?;
+    let public_values: Vec<u32> = public_values.iter().map(|x| x.as_canonical_u32()).collect();
+    assert_eq!(public_values, expected_public_values);
+
+    commit.app_vm_commit[31] ^= 1;
+    assert!(matches!(
+        verify_versioned_stark_proof(&agg_vk, &commit, &proof),
+        Err(VerifyError::AppCommitMismatch { .. })
+    ));
+    Ok(())
+}
@@ -18,6 +18,7 @@ use openvm_stark_backend::{
     p3_util::log2_strict_usize,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
 
 use super::{AnyEnum, VmChipComplex, PUBLIC_VALUES_AIR_ID};
 use crate::{
@@ -198,6 +199,15 @@ impl Default for MemoryConfig {
     }
 }
 
+/// Error returned by [MemoryConfig::set_rv32_memory_bits].
+#[derive(Clone, Debug, Error)]
+#[error("address space {addr_space} has {num_cells} cells, more than the 2^{mem_bits} addressable by the memory layout")]
+pub struct AddressSpaceTooLarge {
+    pub addr_space: u32,
+    pub num_cells: usize,
+    pub mem_bits: usize,
+}
+
 impl MemoryConfig {
     pub fn empty_address_space_configs(num_addr_spaces: usize) -> Vec<AddressSpaceHostConfig> {
         // All except address spaces 0..4 default to native 32-bit field.
@@ -239,6 +249,30 @@ impl MemoryConfig {
         Self::new(3, addr_spaces, POINTER_MAX_BITS, 29, 17, 8)
     }
 
+    /// Sets `pointer_max_bits` to `mem_bits` and sizes the RV32 memory address space to
+    /// `2^mem_bits` cells, matching a guest memory layout. Fails without modifying the config if
+    /// another address space has more than `2^mem_bits` cells, since its pointers would no longer
+    /// fit in `pointer_max_bits`.
+    pub fn set_rv32_memory_bits(&mut self, mem_bits: usize) -> Result<(), AddressSpaceTooLarge> {
+        if let Some((addr_space, config)) =
+            self.addr_spaces
+                .iter()
+                .enumerate()
+                .find(|(addr_space, config)| {
+                    *addr_space != RV32_MEMORY_AS as usize && config.num_cells > 1 << mem_bits
+                })
+        {
+            return Err(AddressSpaceTooLarge {
+                addr_space: addr_space as u32,
+                num_cells: config.num_cells,
+                mem_bits,
+            });
+        }
+        self.pointer_max_bits = mem_bits;
+        self.addr_spaces[RV32_MEMORY_AS as usize].num_cells = 1 << mem_bits;
+        Ok(())
+    }
+
     pub fn min_block_size_bits(&self) -> Vec<u8> {
         self.addr_spaces
             .iter()
@@ -279,6 +313,10 @@ pub struct SystemConfig {
     #[serde(skip, default = "SegmentationLimits::default")]
     #[getset(set = "pub")]
     pub segmentation_limits: SegmentationLimits,
+    /// Guard regions of guest memory checked by guarded execution. See [MemoryGuardConfig]. If
+    /// unset, the SDK derives them from the guest memory layout.
+    #[serde(default)]
+    pub memory_guards: Option<MemoryGuardConfig>,
 }
 
 impl SystemConfig {
@@ -299,6 +337,7 @@ impl SystemConfig {
             num_public_values,
             profiling: false,
             segmentation_limits: SegmentationLimits::default(),
+            memory_guards: None,
         }
     }
 
@@ -341,6 +380,11 @@ impl SystemConfig {
         self
     }
 
+    pub fn with_memory_guards(mut self, memory_guards: MemoryGuardConfig) -> Self {
+        self.memory_guards = Some(memory_guards);
+        self
+    }
+
     pub fn has_public_values_chip(&self) -> bool {
         !self.continuation_enabled && self.num_public_values > 0
     }
@@ -406,6 +450,43 @@ impl AsMut<SystemConfig> for SystemConfig {
 // Default implementation uses no init file
 impl InitFileGenerator for SystemConfig {}
 
+/// A range `[start, end)` of pointers in the RV32 memory address space.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, new)]
+pub struct GuardRegion {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl GuardRegion {
+    /// Returns whether an access of `size` cells starting at `ptr` overlaps the region.
+    #[inline(always)]
+    pub fn overlaps(&self, ptr: u32, size: u32) -> bool {
+        ptr < self.end && ptr.saturating_add(size) > self.start
+    }
+}
+
+/// Guard regions of the RV32 memory address space. Guest memory accesses are only checked against
+/// these regions during guarded execution, so they do not affect the performance of other
+/// execution modes nor the circuit.
+///
+/// The guest stack grows down from `STACK_TOP` towards `GUEST_MIN_MEM` and the heap grows up from
+/// the end of the program towards `GUEST_MAX_MEM` (see `openvm_platform::memory`).
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryGuardConfig {
+    /// Accesses inside this region are reported as [ExecutionError::StackOverflow]. It should lie
+    /// directly below the lowest address the stack is allowed to grow to.
+    ///
+    /// [ExecutionError::StackOverflow]: crate::arch::ExecutionError::StackOverflow
+    #[serde(default)]
+    pub stack_guard: Option<GuardRegion>,
+    /// Accesses inside this region are reported as [ExecutionError::OutOfMemory]. It should lie at
+    /// the end of the memory the heap is allowed to grow into.
+    ///
+    /// [ExecutionError::OutOfMemory]: crate::arch::ExecutionError::OutOfMemory
+    #[serde(default)]
+    pub heap_guard: Option<GuardRegion>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, new)]
 pub struct AddressSpaceHostConfig {
     /// The number of memory cells in each address space, where a memory cell refers to a single
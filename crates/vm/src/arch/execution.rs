@@ -11,7 +11,10 @@ use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{execution_mode::ExecutionCtxTrait, Streams, VmExecState};
+use super::{
+    execution_mode::{ExecutionCtxTrait, GuestBacktrace},
+    Streams, VmExecState,
+};
 #[cfg(feature = "tco")]
 use crate::arch::interpreter::InterpretedInstance;
 #[cfg(feature = "metrics")]
@@ -64,6 +67,20 @@ pub enum ExecutionError {
     DidNotTerminate,
     #[error("program exit code {0}")]
     FailedWithExitCode(u32),
+    #[error("stack overflow at pc {pc}: access to {ptr:#x} is inside the stack guard region, the guest stack grew past its limit\n{guest_backtrace}")]
+    StackOverflow {
+        pc: u32,
+        ptr: u32,
+        guest_backtrace: GuestBacktrace,
+    },
+    #[error("out of memory at pc {pc}: {}\n{guest_backtrace}", out_of_memory_reason(.ptr))]
+    OutOfMemory {
+        pc: u32,
+        /// The accessed pointer inside the heap guard region, or `None` if the guest allocator
+        /// reported that the heap is exhausted.
+        ptr: Option<u32>,
+        guest_backtrace: GuestBacktrace,
+    },
     #[error("trace buffer out of bounds: requested {requested} but capacity is {capacity}")]
     TraceBufferOutOfBounds { requested: usize, capacity: usize },
     #[error("instruction counter overflow: {instret} + {num_insns} > u64::MAX")]
@@ -74,6 +91,15 @@ pub enum ExecutionError {
     Static(#[from] StaticProgramError),
 }
 
+fn out_of_memory_reason(ptr: &Option<u32>) -> String {
+    match ptr {
+        Some(ptr) => format!(
+            "access to {ptr:#x} is inside the heap guard region, the guest heap grew past its limit"
+        ),
+        None => "the guest allocator exhausted the heap".to_string(),
+    }
+}
+
 /// Errors in the program that can be statically analyzed before runtime.
 #[derive(Error, Debug)]
 pub enum StaticProgramError {
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use openvm_instructions::program::DEFAULT_PC_STEP;
+
+use crate::{
+    arch::{execution_mode::ExecutionCtxTrait, interpreter::get_pc_index, VmExecState},
+    system::memory::online::GuestMemory,
+};
+
+/// Map from program counter to the number of times the instruction at that pc was executed.
+/// Only executed pcs are present.
+pub type PcCounts = BTreeMap<u32, u64>;
+
+/// Execution context for pure execution that additionally counts how many times each instruction
+/// is executed. Used to produce guest code coverage reports.
+pub struct CoverageCtx {
+    pub instret_end: u64,
+    /// Execution counts indexed by `pc_index = pc / DEFAULT_PC_STEP`.
+    pub pc_counts: Vec<u64>,
+}
+
+impl CoverageCtx {
+    /// `num_pc_indices` should be at least the largest `pc_index` of the program plus one.
+    pub fn new(instret_end: Option<u64>, num_pc_indices: usize) -> Self {
+        CoverageCtx {
+            instret_end: instret_end.unwrap_or(u64::MAX),
+            pc_counts: vec![0; num_pc_indices],
+        }
+    }
+
+    /// Returns the execution count of every executed pc.
+    pub fn into_pc_counts(self) -> PcCounts {
+        self.pc_counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(pc_idx, count)| (pc_idx as u32 * DEFAULT_PC_STEP, count))
+            .collect()
+    }
+}
+
+impl ExecutionCtxTrait for CoverageCtx {
+    #[inline(always)]
+    fn on_memory_operation(&mut self, _address_space: u32, _ptr: u32, _size: u32) {}
+
+    #[inline(always)]
+    fn should_suspend<F>(
+        instret: u64,
+        pc: u32,
+        instret_end: u64,
+        exec_state: &mut VmExecState<F, GuestMemory, Self>,
+    ) -> bool {
+        if instret >= instret_end {
+            return true;
+        }
+        // `should_suspend` is called exactly once before each instruction is executed, with the
+        // pc of that instruction. Out of bounds pcs are reported by the interpreter.
+        if let Some(count) = exec_state.ctx.pc_counts.get_mut(get_pc_index(pc)) {
+            *count += 1;
+        }
+        false
+    }
+}
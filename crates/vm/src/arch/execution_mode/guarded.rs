@@ -0,0 +1,283 @@
+use std::fmt;
+
+use openvm_instructions::{
+    exe::{FnBound, FnBounds},
+    riscv::RV32_MEMORY_AS,
+};
+
+use crate::{
+    arch::{
+        execution_mode::ExecutionCtxTrait, ExecutionError, GuardRegion, MemoryGuardConfig,
+        VmExecState,
+    },
+    system::memory::online::GuestMemory,
+};
+
+/// Maximum number of frames kept in a [GuestBacktrace].
+const MAX_BACKTRACE_FRAMES: usize = 64;
+/// Maximum depth of the tracked call stack. Deeper calls replace the innermost frame, which bounds
+/// memory usage when a jump to the start of a function is not actually a call.
+const MAX_CALL_STACK_DEPTH: usize = 1 << 20;
+
+/// Call stack of the guest program reconstructed from the function bounds of the `VmExe`.
+/// Frames are ordered from innermost to outermost.
+#[derive(Clone, Debug, Default)]
+pub struct GuestBacktrace {
+    pub frames: Vec<FnBound>,
+    /// Number of outermost frames that were dropped to keep the backtrace short.
+    pub num_omitted_frames: usize,
+}
+
+impl fmt::Display for GuestBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.frames.is_empty() {
+            return write!(
+                f,
+                "guest backtrace unavailable: the program has no function bounds"
+            );
+        }
+        write!(f, "guest backtrace:")?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            write!(f, "\n  {i}: {} at pc {:#x}", frame.name, frame.start)?;
+        }
+        if self.num_omitted_frames > 0 {
+            write!(f, "\n  ... {} more frames", self.num_omitted_frames)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks the guest call stack from the sequence of executed pcs, in the same way as the cycle
+/// tracker of `VmMetrics`: entering the start of a function is a call and leaving the current
+/// function anywhere else is a return to the innermost function containing the new pc.
+#[derive(Clone, Debug, Default)]
+struct ShadowCallStack {
+    fn_bounds: FnBounds,
+    /// Start pcs of the active functions, outermost first.
+    stack: Vec<u32>,
+    /// Bounds of the innermost function, cached to keep the common case to two comparisons.
+    current: Option<(u32, u32)>,
+}
+
+impl ShadowCallStack {
+    fn new(fn_bounds: FnBounds) -> Self {
+        Self {
+            fn_bounds,
+            ..Default::default()
+        }
+    }
+
+    #[inline(always)]
+    fn update(&mut self, pc: u32) {
+        // A jump to the start of the current function is a recursive call.
+        if let Some((start, end)) = self.current {
+            if start < pc && pc <= end {
+                return;
+            }
+        }
+        if self.fn_bounds.is_empty() {
+            return;
+        }
+        self.update_slow(pc);
+    }
+
+    #[cold]
+    fn update_slow(&mut self, pc: u32) {
+        if !self.fn_bounds.contains_key(&pc) {
+            while let Some(start) = self.stack.last() {
+                if pc <= self.fn_bounds[start].end && *start <= pc {
+                    break;
+                }
+                self.stack.pop();
+            }
+        }
+        if self.stack.is_empty() || self.fn_bounds.contains_key(&pc) {
+            let callee = self
+                .fn_bounds
+                .range(..=pc)
+                .next_back()
+                .filter(|(_, func)| pc <= func.end);
+            if let Some((start, _)) = callee {
+                if self.stack.len() == MAX_CALL_STACK_DEPTH {
+                    self.stack.pop();
+                }
+                self.stack.push(*start);
+            }
+        }
+        self.current = self
+            .stack
+            .last()
+            .map(|start| (*start, self.fn_bounds[start].end));
+    }
+
+    fn backtrace(&self) -> GuestBacktrace {
+        let frames = self
+            .stack
+            .iter()
+            .rev()
+            .take(MAX_BACKTRACE_FRAMES)
+            .map(|start| self.fn_bounds[start].clone())
+            .collect();
+        GuestBacktrace {
+            frames,
+            num_omitted_frames: self.stack.len().saturating_sub(MAX_BACKTRACE_FRAMES),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum GuardViolation {
+    Stack { ptr: u32 },
+    Heap { ptr: u32 },
+}
+
+/// Execution context for pure execution that checks every access to the RV32 memory address
+/// space against the guard regions of a [MemoryGuardConfig]. An access inside a guard region stops
+/// execution with [ExecutionError::StackOverflow] or [ExecutionError::OutOfMemory], including the
+/// pc of the offending instruction and the guest backtrace.
+pub struct GuardedCtx {
+    pub instret_end: u64,
+    stack_guard: Option<GuardRegion>,
+    heap_guard: Option<GuardRegion>,
+    /// The pc of the instruction currently being executed.
+    pc: u32,
+    violation: Option<GuardViolation>,
+    call_stack: ShadowCallStack,
+}
+
+impl GuardedCtx {
+    pub fn new(instret_end: Option<u64>, guards: MemoryGuardConfig, fn_bounds: FnBounds) -> Self {
+        Self {
+            instret_end: instret_end.unwrap_or(u64::MAX),
+            stack_guard: guards.stack_guard,
+            heap_guard: guards.heap_guard,
+            pc: 0,
+            violation: None,
+            call_stack: ShadowCallStack::new(fn_bounds),
+        }
+    }
+
+    /// Backtrace of the guest at the current pc.
+    pub fn backtrace(&self) -> GuestBacktrace {
+        self.call_stack.backtrace()
+    }
+
+    /// The pc of the last instruction that started executing.
+    pub fn pc(&self) -> u32 {
+        self.pc
+    }
+
+    fn violation_error(&self, violation: GuardViolation) -> ExecutionError {
+        match violation {
+            GuardViolation::Stack { ptr } => ExecutionError::StackOverflow {
+                pc: self.pc,
+                ptr,
+                guest_backtrace: self.backtrace(),
+            },
+            GuardViolation::Heap { ptr } => ExecutionError::OutOfMemory {
+                pc: self.pc,
+                ptr: Some(ptr),
+                guest_backtrace: self.backtrace(),
+            },
+        }
+    }
+}
+
+impl ExecutionCtxTrait for GuardedCtx {
+    #[inline(always)]
+    fn on_memory_operation(&mut self, address_space: u32, ptr: u32, size: u32) {
+        if address_space != RV32_MEMORY_AS || self.violation.is_some() {
+            return;
+        }
+        if self
+            .stack_guard
+            .is_some_and(|guard| guard.overlaps(ptr, size))
+        {
+            self.violation = Some(GuardViolation::Stack { ptr });
+        } else if self
+            .heap_guard
+            .is_some_and(|guard| guard.overlaps(ptr, size))
+        {
+            self.violation = Some(GuardViolation::Heap { ptr });
+        }
+    }
+
+    #[inline(always)]
+    fn should_suspend<F>(
+        instret: u64,
+        pc: u32,
+        instret_end: u64,
+        exec_state: &mut VmExecState<F, GuestMemory, Self>,
+    ) -> bool {
+        // The violation was caused by the previous instruction, whose pc is still in `ctx.pc`.
+        if let Some(violation) = exec_state.ctx.violation.take() {
+            exec_state.exit_code = Err(exec_state.ctx.violation_error(violation));
+            return true;
+        }
+        if instret >= instret_end {
+            return true;
+        }
+        let ctx = &mut exec_state.ctx;
+        ctx.pc = pc;
+        ctx.call_stack.update(pc);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fn_bound(start: u32, end: u32, name: &str) -> (u32, FnBound) {
+        (
+            start,
+            FnBound {
+                start,
+                end,
+                name: name.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_shadow_call_stack() {
+        let fn_bounds = FnBounds::from([
+            fn_bound(0x100, 0x1fc, "main"),
+            fn_bound(0x200, 0x2fc, "foo"),
+            fn_bound(0x300, 0x3fc, "bar"),
+        ]);
+        let mut call_stack = ShadowCallStack::new(fn_bounds);
+        let names = |call_stack: &ShadowCallStack| {
+            call_stack
+                .backtrace()
+                .frames
+                .into_iter()
+                .map(|frame| frame.name)
+                .collect::<Vec<_>>()
+        };
+
+        // main calls foo, foo calls bar
+        for pc in [0x100, 0x104, 0x200, 0x204, 0x300, 0x304] {
+            call_stack.update(pc);
+        }
+        assert_eq!(names(&call_stack), ["bar", "foo", "main"]);
+        // bar returns to main directly, e.g. after a tail call
+        call_stack.update(0x108);
+        assert_eq!(names(&call_stack), ["main"]);
+        // recursion into foo
+        for pc in [0x200, 0x204, 0x200] {
+            call_stack.update(pc);
+        }
+        assert_eq!(names(&call_stack), ["foo", "foo", "main"]);
+    }
+
+    #[test]
+    fn test_guard_region_overlaps() {
+        let guard = GuardRegion::new(0x400, 0x800);
+        assert!(!guard.overlaps(0x3fc, 4));
+        assert!(guard.overlaps(0x3fe, 4));
+        assert!(guard.overlaps(0x7fc, 4));
+        assert!(!guard.overlaps(0x800, 4));
+        assert!(!guard.overlaps(u32::MAX - 1, 4));
+    }
+}
@@ -6,6 +6,7 @@ use openvm_instructions::riscv::{RV32_IMM_AS, RV32_REGISTER_AS};
 
 use super::{
     memory_ctx::MemoryCtx,
+    memory_profile::{MemoryProfile, MemoryProfileCtx},
     segment_ctx::{Segment, SegmentationCtx},
 };
 use crate::{
@@ -24,6 +25,8 @@ pub struct MeteredCtx<const PAGE_BITS: usize = DEFAULT_PAGE_BITS> {
     pub is_trace_height_constant: Vec<bool>,
     pub memory_ctx: MemoryCtx<PAGE_BITS>,
     pub segmentation_ctx: SegmentationCtx,
+    /// Only tracked if enabled with [`with_memory_profile`](Self::with_memory_profile).
+    pub memory_profile_ctx: Option<MemoryProfileCtx>,
     #[getset(get = "pub", set = "pub", set_with = "pub")]
     suspend_on_segment: bool,
 }
@@ -78,6 +81,7 @@ impl<const PAGE_BITS: usize> MeteredCtx<PAGE_BITS> {
             is_trace_height_constant,
             memory_ctx,
             segmentation_ctx,
+            memory_profile_ctx: None,
             suspend_on_segment: false,
         };
         if !config.continuation_enabled {
@@ -110,6 +114,22 @@ impl<const PAGE_BITS: usize> MeteredCtx<PAGE_BITS> {
         self
     }
 
+    /// Enables collection of the [MemoryProfile] of the execution.
+    pub fn with_memory_profile(mut self, config: &SystemConfig) -> Self {
+        self.memory_profile_ctx = Some(MemoryProfileCtx::new(config, PAGE_BITS));
+        self
+    }
+
+    /// Returns the [MemoryProfile] of the execution so far, if enabled.
+    pub fn memory_profile(&self) -> Option<MemoryProfile> {
+        self.memory_profile_ctx.as_ref().map(|profile_ctx| {
+            profile_ctx.finalize(
+                self.memory_ctx.total_page_loads,
+                &self.segmentation_ctx.widths,
+            )
+        })
+    }
+
     pub fn segments(&self) -> &[Segment] {
         &self.segmentation_ctx.segments
     }
@@ -195,6 +215,9 @@ impl<const PAGE_BITS: usize> ExecutionCtxTrait for MeteredCtx<PAGE_BITS> {
         if address_space != RV32_REGISTER_AS {
             self.memory_ctx
                 .update_boundary_merkle_heights(address_space, ptr, size);
+            if let Some(profile_ctx) = self.memory_profile_ctx.as_mut() {
+                profile_ctx.on_memory_operation(address_space, ptr, size);
+            }
         }
     }
 
@@ -108,6 +108,9 @@ pub struct MemoryCtx<const PAGE_BITS: usize> {
     chunk: u32,
     chunk_bits: u32,
     page_access_count: usize,
+    /// Total number of page accesses resolved by `lazy_update_boundary_heights`, over all
+    /// segments.
+    pub(crate) total_page_loads: u64,
     // Note: 32 is the maximum access adapter size.
     addr_space_access_count: Vec<usize>,
 }
@@ -132,6 +135,7 @@ impl<const PAGE_BITS: usize> MemoryCtx<PAGE_BITS> {
             memory_dimensions,
             continuations_enabled: config.continuation_enabled,
             page_access_count: 0,
+            total_page_loads: 0,
             addr_space_access_count: vec![0; (1 << memory_dimensions.addr_space_height) + 1],
         }
     }
@@ -266,6 +270,7 @@ impl<const PAGE_BITS: usize> MemoryCtx<PAGE_BITS> {
                 *trace_heights.get_unchecked_mut(merkle_tree_idx) += nodes * 2;
             }
         }
+        self.total_page_loads += self.page_access_count as u64;
         self.page_access_count = 0;
 
         for address_space in 0..self.addr_space_access_count.len() {
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use super::memory_ctx::BitSet;
+use crate::arch::SystemConfig;
+
+/// Memory usage of a single address space over an execution.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AddressSpaceUsage {
+    pub address_space: u32,
+    /// Lowest pointer accessed.
+    pub min_ptr: u32,
+    /// One past the highest pointer accessed.
+    pub max_ptr: u32,
+    /// Number of distinct pages accessed.
+    pub touched_pages: usize,
+}
+
+/// Summary of guest memory usage collected during metered execution.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MemoryProfile {
+    /// Number of cells in a page. A page is the unit in which memory is loaded into the memory
+    /// boundary and merkle chips.
+    pub page_size: u32,
+    /// Usage of every address space that was accessed, ordered by address space.
+    pub address_spaces: Vec<AddressSpaceUsage>,
+    /// Number of page loads into the memory boundary and merkle chips. A page touched in several
+    /// segments is loaded once per segment.
+    pub page_loads: u64,
+    /// Upper bound on the trace cells of the memory boundary, merkle and poseidon2 chips caused by
+    /// the page loads.
+    pub boundary_merkle_cells: u64,
+}
+
+impl MemoryProfile {
+    pub fn address_space(&self, address_space: u32) -> Option<&AddressSpaceUsage> {
+        self.address_spaces
+            .iter()
+            .find(|usage| usage.address_space == address_space)
+    }
+}
+
+/// Tracks the pointer range and distinct pages accessed in each address space.
+#[derive(Clone, Debug)]
+pub struct MemoryProfileCtx {
+    /// log2 of the page size in cells.
+    page_bits: u32,
+    /// Number of leaves of the merkle tree under a page, as a power of two.
+    leaf_page_bits: u32,
+    merkle_height: usize,
+    touched_pages: Vec<BitSet>,
+    num_touched_pages: Vec<usize>,
+    min_ptr: Vec<u32>,
+    max_ptr: Vec<u32>,
+    boundary_idx: usize,
+    merkle_tree_idx: Option<usize>,
+}
+
+impl MemoryProfileCtx {
+    /// `leaf_page_bits` is the number of merkle leaves per page, as a power of two, used by the
+    /// segmentation heuristics.
+    pub fn new(config: &SystemConfig, leaf_page_bits: usize) -> Self {
+        let chunk_bits = config.initial_block_size().ilog2();
+        let page_bits = chunk_bits + leaf_page_bits as u32;
+        let memory_dimensions = config.memory_config.memory_dimensions();
+        let num_addr_spaces = config.memory_config.addr_spaces.len();
+        let touched_pages = config
+            .memory_config
+            .addr_spaces
+            .iter()
+            .map(|addr_space| BitSet::new(addr_space.num_cells.div_ceil(1 << page_bits)))
+            .collect();
+        Self {
+            page_bits,
+            leaf_page_bits: leaf_page_bits as u32,
+            merkle_height: memory_dimensions.overall_height(),
+            touched_pages,
+            num_touched_pages: vec![0; num_addr_spaces],
+            min_ptr: vec![u32::MAX; num_addr_spaces],
+            max_ptr: vec![0; num_addr_spaces],
+            boundary_idx: config.memory_boundary_air_id(),
+            merkle_tree_idx: config.memory_merkle_air_id(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn on_memory_operation(&mut self, address_space: u32, ptr: u32, size: u32) {
+        let addr_space = address_space as usize;
+        debug_assert!(addr_space < self.touched_pages.len());
+        let end = ptr + size;
+        if ptr < self.min_ptr[addr_space] {
+            self.min_ptr[addr_space] = ptr;
+        }
+        if end > self.max_ptr[addr_space] {
+            self.max_ptr[addr_space] = end;
+        }
+        let start_page = ptr >> self.page_bits;
+        let end_page = ((end - 1) >> self.page_bits) + 1;
+        for page in start_page..end_page {
+            if self.touched_pages[addr_space].insert(page as usize) {
+                self.num_touched_pages[addr_space] += 1;
+            }
+        }
+    }
+
+    /// Number of distinct pages accessed over all address spaces.
+    pub fn num_touched_pages(&self) -> u64 {
+        self.num_touched_pages.iter().sum::<usize>() as u64
+    }
+
+    /// Summarizes the usage given the total number of `page_loads`, using the AIR `widths` to
+    /// estimate the cost of the memory boundary and merkle chips.
+    pub fn finalize(&self, page_loads: u64, widths: &[usize]) -> MemoryProfile {
+        let address_spaces = (0..self.touched_pages.len())
+            .filter(|&addr_space| self.num_touched_pages[addr_space] > 0)
+            .map(|addr_space| AddressSpaceUsage {
+                address_space: addr_space as u32,
+                min_ptr: self.min_ptr[addr_space],
+                max_ptr: self.max_ptr[addr_space],
+                touched_pages: self.num_touched_pages[addr_space],
+            })
+            .collect();
+
+        // Same accounting as `MemoryCtx::lazy_update_boundary_heights`, except that the merkle
+        // path of every page is counted separately.
+        let leaves = page_loads << self.leaf_page_bits;
+        let mut boundary_merkle_cells = leaves * widths[self.boundary_idx] as u64;
+        if let Some(merkle_tree_idx) = self.merkle_tree_idx {
+            let poseidon2_idx = widths.len() - 2;
+            let nodes_per_page = ((1u64 << self.leaf_page_bits) - 1)
+                + (self.merkle_height as u64 - self.leaf_page_bits as u64);
+            let nodes = page_loads * nodes_per_page;
+            boundary_merkle_cells += 2 * nodes * widths[merkle_tree_idx] as u64;
+            boundary_merkle_cells += 2 * (leaves + nodes) * widths[poseidon2_idx] as u64;
+        }
+
+        MemoryProfile {
+            page_size: 1 << self.page_bits,
+            address_spaces,
+            page_loads,
+            boundary_merkle_cells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_instructions::riscv::RV32_MEMORY_AS;
+
+    use super::*;
+
+    #[test]
+    fn test_memory_profile_touched_pages() {
+        let config = SystemConfig::default();
+        let mut profile_ctx = MemoryProfileCtx::new(&config, 6);
+        let page_size = 1u32 << profile_ctx.page_bits;
+
+        profile_ctx.on_memory_operation(RV32_MEMORY_AS, 0x100, 4);
+        profile_ctx.on_memory_operation(RV32_MEMORY_AS, 0x104, 4);
+        // Crosses a page boundary
+        profile_ctx.on_memory_operation(RV32_MEMORY_AS, 3 * page_size - 4, 8);
+        assert_eq!(profile_ctx.num_touched_pages(), 3);
+
+        let widths = vec![1; config.num_airs()];
+        let profile = profile_ctx.finalize(profile_ctx.num_touched_pages(), &widths);
+        assert_eq!(profile.page_size, page_size);
+        assert_eq!(profile.page_loads, 3);
+        let usage = profile.address_space(RV32_MEMORY_AS).unwrap();
+        assert_eq!(usage.min_ptr, 0x100);
+        assert_eq!(usage.max_ptr, 3 * page_size + 4);
+        assert_eq!(usage.touched_pages, 3);
+        assert!(profile.boundary_merkle_cells >= 3 << 6);
+    }
+}
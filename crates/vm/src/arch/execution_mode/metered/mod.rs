@@ -1,3 +1,4 @@
 pub mod ctx;
 pub mod memory_ctx;
+pub mod memory_profile;
 pub mod segment_ctx;
@@ -1,11 +1,15 @@
 use std::num::NonZero;
 
 use getset::WithSetters;
-use openvm_instructions::riscv::RV32_IMM_AS;
+use openvm_instructions::riscv::{RV32_IMM_AS, RV32_REGISTER_AS};
 
 use crate::{
     arch::{
-        execution_mode::metered::segment_ctx::DEFAULT_MAX_CELLS as DEFAULT_SEGMENT_MAX_CELLS,
+        execution_mode::metered::{
+            ctx::DEFAULT_PAGE_BITS,
+            memory_profile::{MemoryProfile, MemoryProfileCtx},
+            segment_ctx::DEFAULT_MAX_CELLS as DEFAULT_SEGMENT_MAX_CELLS,
+        },
         ExecutionCtxTrait, MeteredExecutionCtxTrait, SystemConfig, VmExecState,
     },
     system::memory::online::GuestMemory,
@@ -71,6 +75,8 @@ pub struct MeteredCostCtx {
     pub max_execution_cost: u64,
     // Cost is number of trace cells (height * width)
     pub cost: u64,
+    /// Only tracked if enabled with [`with_memory_profile`](Self::with_memory_profile).
+    pub memory_profile_ctx: Option<MemoryProfileCtx>,
 }
 
 impl MeteredCostCtx {
@@ -81,9 +87,24 @@ impl MeteredCostCtx {
             access_adapter_ctx,
             max_execution_cost: DEFAULT_MAX_COST,
             cost: 0,
+            memory_profile_ctx: None,
         }
     }
 
+    /// Enables collection of the [MemoryProfile] of the execution.
+    pub fn with_memory_profile(mut self, config: &SystemConfig) -> Self {
+        self.memory_profile_ctx = Some(MemoryProfileCtx::new(config, DEFAULT_PAGE_BITS));
+        self
+    }
+
+    /// Returns the [MemoryProfile] of the execution so far, if enabled. Since cost metering does
+    /// not segment, every touched page is counted as loaded exactly once.
+    pub fn memory_profile(&self) -> Option<MemoryProfile> {
+        self.memory_profile_ctx
+            .as_ref()
+            .map(|profile_ctx| profile_ctx.finalize(profile_ctx.num_touched_pages(), &self.widths))
+    }
+
     #[cold]
     fn panic_cost_exceeded(&self) -> ! {
         panic!(
@@ -96,7 +117,7 @@ impl MeteredCostCtx {
 
 impl ExecutionCtxTrait for MeteredCostCtx {
     #[inline(always)]
-    fn on_memory_operation(&mut self, address_space: u32, _ptr: u32, size: u32) {
+    fn on_memory_operation(&mut self, address_space: u32, ptr: u32, size: u32) {
         debug_assert!(
             address_space != RV32_IMM_AS,
             "address space must not be immediate"
@@ -121,6 +142,12 @@ impl ExecutionCtxTrait for MeteredCostCtx {
             size_bits,
             &self.widths,
         );
+
+        if address_space != RV32_REGISTER_AS {
+            if let Some(profile_ctx) = self.memory_profile_ctx.as_mut() {
+                profile_ctx.on_memory_operation(address_space, ptr, size);
+            }
+        }
     }
 
     #[inline(always)]
@@ -1,11 +1,19 @@
 use crate::{arch::VmExecState, system::memory::online::GuestMemory};
 
+mod coverage;
+mod guarded;
 pub mod metered;
 pub mod metered_cost;
 mod preflight;
 mod pure;
 
-pub use metered::{ctx::MeteredCtx, segment_ctx::Segment};
+pub use coverage::{CoverageCtx, PcCounts};
+pub use guarded::{GuardedCtx, GuestBacktrace};
+pub use metered::{
+    ctx::MeteredCtx,
+    memory_profile::{AddressSpaceUsage, MemoryProfile},
+    segment_ctx::Segment,
+};
 pub use metered_cost::MeteredCostCtx;
 pub use preflight::PreflightCtx;
 pub use pure::ExecutionCtx;
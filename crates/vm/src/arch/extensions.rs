@@ -618,12 +618,16 @@ pub enum ExecutorInventoryError {
     ExecutorExists { opcode: VmOpcode, id: ExecutorId },
     #[error("Phantom discriminant {} already has sub-executor", .discriminant.0)]
     PhantomSubExecutorExists { discriminant: PhantomDiscriminant },
+    #[error("Invalid extension config: {0}")]
+    InvalidConfig(String),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum AirInventoryError {
     #[error("AIR {name} not found")]
     AirNotFound { name: String },
+    #[error("Invalid extension config: {0}")]
+    InvalidConfig(String),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -642,6 +646,8 @@ pub enum ChipInventoryError {
     MissingChip { actual: usize, expected: usize },
     #[error("Missing executor chip. Number of executors with associated chips is {actual}, expected number is {expected}")]
     MissingExecutor { actual: usize, expected: usize },
+    #[error("Invalid extension config: {0}")]
+    InvalidConfig(String),
 }
 
 // ======================= VM Chip Complex Implementation =============================
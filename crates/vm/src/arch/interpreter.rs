@@ -10,7 +10,7 @@ use std::{
 use itertools::Itertools;
 use openvm_circuit_primitives_derive::AlignedBytesBorrow;
 use openvm_instructions::{
-    exe::{SparseMemoryImage, VmExe},
+    exe::{FnBounds, SparseMemoryImage, VmExe},
     instruction::Instruction,
     program::{Program, DEFAULT_PC_STEP},
     LocalOpcode, SystemOpcode,
@@ -23,11 +23,11 @@ use crate::arch::Handler;
 use crate::{
     arch::{
         execution_mode::{
-            ExecutionCtx, ExecutionCtxTrait, MeteredCostCtx, MeteredCtx, MeteredExecutionCtxTrait,
-            Segment,
+            CoverageCtx, ExecutionCtx, ExecutionCtxTrait, GuardedCtx, MemoryProfile,
+            MeteredCostCtx, MeteredCtx, MeteredExecutionCtxTrait, PcCounts, Segment,
         },
-        ExecuteFunc, ExecutionError, Executor, ExecutorInventory, ExitCode, MeteredExecutor,
-        StaticProgramError, Streams, SystemConfig, VmExecState, VmState,
+        ExecuteFunc, ExecutionError, Executor, ExecutorInventory, ExitCode, MemoryGuardConfig,
+        MeteredExecutor, StaticProgramError, Streams, SystemConfig, VmExecState, VmState,
     },
     system::memory::online::GuestMemory,
 };
@@ -394,6 +394,97 @@ where
     }
 }
 
+impl<F> InterpretedInstance<'_, F, CoverageCtx>
+where
+    F: PrimeField32,
+{
+    /// Pure execution for the given `inputs` that additionally records the number of times each
+    /// instruction is executed. Execution begins from the initial state specified by the `VmExe`
+    /// and runs until termination.
+    ///
+    /// Returns the execution count of every executed pc and the final VM state.
+    pub fn execute_with_coverage(
+        &self,
+        inputs: impl Into<Streams<F>>,
+    ) -> Result<(PcCounts, VmState<F, GuestMemory>), ExecutionError> {
+        let vm_state = self.create_initial_vm_state(inputs);
+        #[cfg(not(feature = "tco"))]
+        let num_pc_indices = self.pre_compute_insns.len();
+        #[cfg(feature = "tco")]
+        let num_pc_indices = self.handlers.len();
+        let ctx = CoverageCtx::new(None, num_pc_indices);
+        let mut exec_state = VmExecState::new(vm_state, ctx);
+
+        let instret = exec_state.instret();
+        let pc = exec_state.pc();
+        let instret_end = exec_state.ctx.instret_end;
+        run!(
+            "execute_coverage",
+            self,
+            instret,
+            pc,
+            instret_end,
+            exec_state,
+            CoverageCtx
+        );
+        check_termination(exec_state.exit_code)?;
+        let VmExecState { ctx, vm_state, .. } = exec_state;
+        Ok((ctx.into_pc_counts(), vm_state))
+    }
+}
+
+impl<F> InterpretedInstance<'_, F, GuardedCtx>
+where
+    F: PrimeField32,
+{
+    /// Pure execution for the given `inputs` that checks every guest memory access against the
+    /// guard regions in `guards`. Execution begins from the initial state
+    /// specified by the `VmExe` and runs until termination if `num_insns` is `None` or for exactly
+    /// `num_insns` instructions if `num_insns` is `Some`.
+    ///
+    /// Stack overflows and heap exhaustion are reported as [ExecutionError::StackOverflow] and
+    /// [ExecutionError::OutOfMemory], with a guest backtrace derived from `fn_bounds`.
+    ///
+    /// Returns the final VM state when execution stops.
+    pub fn execute_guarded(
+        &self,
+        inputs: impl Into<Streams<F>>,
+        num_insns: Option<u64>,
+        guards: MemoryGuardConfig,
+        fn_bounds: &FnBounds,
+    ) -> Result<VmState<F, GuestMemory>, ExecutionError> {
+        let vm_state = self.create_initial_vm_state(inputs);
+        let ctx = GuardedCtx::new(num_insns, guards, fn_bounds.clone());
+        let mut exec_state = VmExecState::new(vm_state, ctx);
+
+        let instret = exec_state.instret();
+        let pc = exec_state.pc();
+        let instret_end = exec_state.ctx.instret_end;
+        run!(
+            "execute_guarded",
+            self,
+            instret,
+            pc,
+            instret_end,
+            exec_state,
+            GuardedCtx
+        );
+        if matches!(exec_state.exit_code, Ok(Some(code)) if code == ExitCode::OutOfMemory as u32) {
+            return Err(ExecutionError::OutOfMemory {
+                pc: exec_state.ctx.pc(),
+                ptr: None,
+                guest_backtrace: exec_state.ctx.backtrace(),
+            });
+        }
+        if num_insns.is_some() {
+            check_exit_code(exec_state.exit_code)?;
+        } else {
+            check_termination(exec_state.exit_code)?;
+        }
+        Ok(exec_state.vm_state)
+    }
+}
+
 impl<F> InterpretedInstance<'_, F, MeteredCtx>
 where
     F: PrimeField32,
@@ -424,6 +515,33 @@ where
         from_state: VmState<F, GuestMemory>,
         ctx: MeteredCtx,
     ) -> Result<(Vec<Segment>, VmState<F, GuestMemory>), ExecutionError> {
+        let (ctx, vm_state) = self.execute_metered_to_termination(from_state, ctx)?;
+        Ok((ctx.into_segments(), vm_state))
+    }
+
+    /// Metered execution for the given `inputs` with a [MeteredCtx] built
+    /// [`with_memory_profile`](MeteredCtx::with_memory_profile). This function executes the
+    /// program until termination.
+    ///
+    /// Returns the segmentation boundary data, the memory profile of the execution, and the final
+    /// VM state when execution stops. The memory profile is `None` if it was not enabled in `ctx`.
+    pub fn execute_metered_with_memory_profile(
+        &self,
+        inputs: impl Into<Streams<F>>,
+        ctx: MeteredCtx,
+    ) -> Result<(Vec<Segment>, Option<MemoryProfile>, VmState<F, GuestMemory>), ExecutionError>
+    {
+        let vm_state = self.create_initial_vm_state(inputs);
+        let (ctx, vm_state) = self.execute_metered_to_termination(vm_state, ctx)?;
+        let memory_profile = ctx.memory_profile();
+        Ok((ctx.into_segments(), memory_profile, vm_state))
+    }
+
+    fn execute_metered_to_termination(
+        &self,
+        from_state: VmState<F, GuestMemory>,
+        ctx: MeteredCtx,
+    ) -> Result<(MeteredCtx, VmState<F, GuestMemory>), ExecutionError> {
         let mut exec_state = VmExecState::new(from_state, ctx);
 
         loop {
@@ -438,7 +556,7 @@ where
         }
         check_termination(exec_state.exit_code)?;
         let VmExecState { vm_state, ctx, .. } = exec_state;
-        Ok((ctx.into_segments(), vm_state))
+        Ok((ctx, vm_state))
     }
     /// Executes a metered virtual machine operation starting from a given execution state until
     /// suspension.
@@ -507,6 +625,30 @@ where
         from_state: VmState<F, GuestMemory>,
         ctx: MeteredCostCtx,
     ) -> Result<(u64, VmState<F, GuestMemory>), ExecutionError> {
+        let (ctx, vm_state) = self.execute_metered_cost_to_end(from_state, ctx)?;
+        Ok((ctx.cost, vm_state))
+    }
+
+    /// Metered cost execution for the given `inputs` with a [MeteredCostCtx] built
+    /// [`with_memory_profile`](MeteredCostCtx::with_memory_profile).
+    ///
+    /// Returns the trace cost, the memory profile of the execution, and the final VM state when
+    /// execution stops. The memory profile is `None` if it was not enabled in `ctx`.
+    pub fn execute_metered_cost_with_memory_profile(
+        &self,
+        inputs: impl Into<Streams<F>>,
+        ctx: MeteredCostCtx,
+    ) -> Result<(u64, Option<MemoryProfile>, VmState<F, GuestMemory>), ExecutionError> {
+        let vm_state = self.create_initial_vm_state(inputs);
+        let (ctx, vm_state) = self.execute_metered_cost_to_end(vm_state, ctx)?;
+        Ok((ctx.cost, ctx.memory_profile(), vm_state))
+    }
+
+    fn execute_metered_cost_to_end(
+        &self,
+        from_state: VmState<F, GuestMemory>,
+        ctx: MeteredCostCtx,
+    ) -> Result<(MeteredCostCtx, VmState<F, GuestMemory>), ExecutionError> {
         let mut exec_state = VmExecState::new(from_state, ctx);
 
         let instret = exec_state.instret();
@@ -524,8 +666,7 @@ where
         );
         check_exit_code(exec_state.exit_code)?;
         let VmExecState { ctx, vm_state, .. } = exec_state;
-        let cost = ctx.cost;
-        Ok((cost, vm_state))
+        Ok((ctx, vm_state))
     }
 }
 
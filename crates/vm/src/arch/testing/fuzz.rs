@@ -0,0 +1,496 @@
+//! Soundness fuzzing of the AIR of a chip under test. Starting from a valid set of traces, the
+//! [ConstraintFuzzer] applies one [TraceMutation] at a time, either to the main trace of the
+//! target AIR or to one of the bus messages it sends on a row, and checks whether the verifier
+//! still accepts the proof. Every accepted mutation points at an under-constrained column, at a
+//! row ordering the AIR does not enforce, or at a bus whose messages are not checked.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, iter,
+    sync::Arc,
+};
+
+use itertools::Itertools;
+use openvm_stark_backend::{
+    air_builders::symbolic::{
+        symbolic_variable::Entry, SymbolicConstraintsDag, SymbolicExpressionNode,
+    },
+    config::{StarkGenericConfig, Val},
+    interaction::BusIndex,
+    p3_air::BaseAir,
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::{
+        cpu::{CpuBackend, CpuDevice},
+        types::AirProvingContext,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    AirRef,
+};
+use openvm_stark_sdk::{
+    config::{
+        baby_bear_blake3::{BabyBearBlake3Config, BabyBearBlake3Engine},
+        FriParameters,
+    },
+    dummy_airs::interaction::dummy_interaction_air::DummyInteractionAir,
+    engine::{StarkEngine, StarkFriEngine},
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::VmChipTester;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceMutation {
+    /// Adds `delta` to the cell of column `col` in row `row`.
+    Cell { row: usize, col: usize, delta: u32 },
+    /// Swaps rows `a` and `b`.
+    SwapRows { a: usize, b: usize },
+    /// Adds `delta` to the `target` of the message sent by interaction `interaction` of the AIR on
+    /// row `row`, leaving the trace unchanged.
+    Interaction {
+        row: usize,
+        interaction: usize,
+        target: InteractionTarget,
+        delta: u32,
+    },
+}
+
+/// Part of a bus message changed by [TraceMutation::Interaction].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionTarget {
+    /// Field `i` of the message.
+    Field(usize),
+    /// The multiplicity of the message.
+    Count,
+}
+
+/// A bus message sent by the target AIR on one row. Receives have a negated `count`.
+#[derive(Clone, Debug)]
+struct RowInteraction<F> {
+    bus_index: BusIndex,
+    message: Vec<F>,
+    count: F,
+}
+
+impl TraceMutation {
+    fn apply<F: PrimeField32>(&self, trace: &mut RowMajorMatrix<F>) {
+        let width = trace.width();
+        match *self {
+            Self::Cell { row, col, delta } => {
+                trace.values[row * width + col] += F::from_canonical_u32(delta);
+            }
+            Self::SwapRows { a, b } => {
+                for col in 0..width {
+                    trace.values.swap(a * width + col, b * width + col);
+                }
+            }
+            Self::Interaction { .. } => {}
+        }
+    }
+}
+
+/// An AIR sending the difference between the mutated and the original `interaction`, so that the
+/// bus sees the mutated message instead of the original one.
+fn interaction_mutation_air<SC: StarkGenericConfig>(
+    interaction: &RowInteraction<Val<SC>>,
+    target: InteractionTarget,
+    delta: u32,
+) -> (AirRef<SC>, AirProvingContext<CpuBackend<SC>>)
+where
+    Val<SC>: PrimeField32,
+{
+    let delta = Val::<SC>::from_canonical_u32(delta);
+    let RowInteraction {
+        bus_index,
+        message,
+        count,
+    } = interaction.clone();
+    let sends = match target {
+        InteractionTarget::Field(i) => {
+            let mut mutated = message.clone();
+            mutated[i] += delta;
+            vec![(-count, message), (count, mutated)]
+        }
+        InteractionTarget::Count => vec![(delta, message)],
+    };
+    let width = interaction.message.len() + 1;
+    let trace = RowMajorMatrix::new(
+        sends
+            .into_iter()
+            .flat_map(|(count, message)| iter::once(count).chain(message))
+            .collect(),
+        width,
+    );
+    let air = DummyInteractionAir::new(width - 1, true, bus_index);
+    (
+        Arc::new(air),
+        AirProvingContext::simple_no_pis(Arc::new(trace)),
+    )
+}
+
+/// Evaluates the interactions of `constraints` on row `row` of `main`.
+fn eval_interactions<F: Field>(
+    constraints: &SymbolicConstraintsDag<F>,
+    preprocessed: Option<&RowMajorMatrix<F>>,
+    main: &RowMajorMatrix<F>,
+    public_values: &[F],
+    row: usize,
+) -> Vec<RowInteraction<F>> {
+    let height = main.height();
+    let get = |matrix: &RowMajorMatrix<F>, offset: usize, col: usize| {
+        matrix.get((row + offset) % height, col)
+    };
+    let mut values: Vec<F> = Vec::with_capacity(constraints.constraints.nodes.len());
+    for node in &constraints.constraints.nodes {
+        let value = match node {
+            SymbolicExpressionNode::Variable(var) => match var.entry {
+                Entry::Preprocessed { offset } => get(
+                    preprocessed.expect("interaction uses a missing preprocessed trace"),
+                    offset,
+                    var.index,
+                ),
+                Entry::Main {
+                    part_index: 0,
+                    offset,
+                } => get(main, offset, var.index),
+                Entry::Public => public_values[var.index],
+                entry => panic!("unsupported variable in interaction: {entry:?}"),
+            },
+            SymbolicExpressionNode::IsFirstRow => F::from_bool(row == 0),
+            SymbolicExpressionNode::IsLastRow => F::from_bool(row == height - 1),
+            SymbolicExpressionNode::IsTransition => F::from_bool(row != height - 1),
+            SymbolicExpressionNode::Constant(c) => *c,
+            SymbolicExpressionNode::Add {
+                left_idx,
+                right_idx,
+                ..
+            } => values[*left_idx] + values[*right_idx],
+            SymbolicExpressionNode::Sub {
+                left_idx,
+                right_idx,
+                ..
+            } => values[*left_idx] - values[*right_idx],
+            SymbolicExpressionNode::Neg { idx, .. } => -values[*idx],
+            SymbolicExpressionNode::Mul {
+                left_idx,
+                right_idx,
+                ..
+            } => values[*left_idx] * values[*right_idx],
+        };
+        values.push(value);
+    }
+    constraints
+        .interactions
+        .iter()
+        .map(|interaction| RowInteraction {
+            bus_index: interaction.bus_index,
+            message: interaction.message.iter().map(|&idx| values[idx]).collect(),
+            count: values[interaction.count],
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MutationOutcome {
+    Accepted,
+    Rejected(VerificationError),
+}
+
+#[derive(Debug)]
+pub struct FuzzReport {
+    pub air_name: String,
+    pub width: usize,
+    pub results: Vec<(TraceMutation, MutationOutcome)>,
+}
+
+impl FuzzReport {
+    pub fn accepted(&self) -> impl Iterator<Item = &TraceMutation> {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| *outcome == MutationOutcome::Accepted)
+            .map(|(mutation, _)| mutation)
+    }
+
+    /// Columns for which at least one cell mutation was accepted.
+    pub fn under_constrained_columns(&self) -> Vec<usize> {
+        self.accepted()
+            .filter_map(|mutation| match mutation {
+                TraceMutation::Cell { col, .. } => Some(*col),
+                TraceMutation::SwapRows { .. } | TraceMutation::Interaction { .. } => None,
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Columns whose mutations were all rejected by the interaction check only, i.e. columns that
+    /// are constrained only through the interaction fields they feed.
+    pub fn interaction_only_columns(&self) -> Vec<usize> {
+        let mut by_col = vec![None; self.width];
+        for (mutation, outcome) in &self.results {
+            if let TraceMutation::Cell { col, .. } = mutation {
+                let is_interaction =
+                    *outcome == MutationOutcome::Rejected(VerificationError::ChallengePhaseError);
+                let entry = by_col[*col].get_or_insert(true);
+                *entry &= is_interaction;
+            }
+        }
+        by_col
+            .into_iter()
+            .positions(|is_interaction| is_interaction == Some(true))
+            .collect()
+    }
+
+    /// Panics with the list of accepted mutations if there is any.
+    pub fn assert_sound(&self) {
+        assert!(self.accepted().next().is_none(), "{self}");
+    }
+}
+
+impl fmt::Display for FuzzReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let num_accepted = self.accepted().count();
+        writeln!(
+            f,
+            "{}: {} of {} mutations accepted",
+            self.air_name,
+            num_accepted,
+            self.results.len()
+        )?;
+        if num_accepted > 0 {
+            writeln!(
+                f,
+                "under-constrained columns: {:?}",
+                self.under_constrained_columns()
+            )?;
+            for mutation in self.accepted() {
+                writeln!(f, "  accepted: {mutation:?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mutates the main trace and the bus messages of the air named `air_name`, which must be loaded
+/// exactly once in the [VmChipTester], and records how the verifier handles each mutation.
+///
+/// Only rows that are not entirely zero are mutated, since mutations of padding rows are usually
+/// harmless. By default every such row is mutated, and every pair of consecutive such rows is
+/// swapped; chips which do not constrain the order of their rows should disable row swaps with
+/// `with_row_swaps(0)`.
+#[derive(Clone, Debug)]
+pub struct ConstraintFuzzer {
+    pub air_name: String,
+    /// Maximum number of rows whose cells and messages are mutated, all rows if `None`.
+    pub num_rows: Option<usize>,
+    /// Number of random non-zero deltas tried on each cell, message field and multiplicity.
+    pub deltas_per_cell: usize,
+    /// Number of random pairs of distinct rows to swap, all pairs of consecutive rows if `None`.
+    pub num_row_swaps: Option<usize>,
+    /// Whether the bus messages of the rows are mutated independently of the trace.
+    pub mutate_interactions: bool,
+    pub skip_columns: BTreeSet<usize>,
+    pub seed: u64,
+}
+
+impl ConstraintFuzzer {
+    pub fn new(air_name: impl Into<String>) -> Self {
+        Self {
+            air_name: air_name.into(),
+            num_rows: None,
+            deltas_per_cell: 1,
+            num_row_swaps: None,
+            mutate_interactions: true,
+            skip_columns: BTreeSet::new(),
+            seed: 0,
+        }
+    }
+
+    pub fn with_num_rows(mut self, num_rows: usize) -> Self {
+        self.num_rows = Some(num_rows);
+        self
+    }
+
+    pub fn with_deltas_per_cell(mut self, deltas_per_cell: usize) -> Self {
+        self.deltas_per_cell = deltas_per_cell;
+        self
+    }
+
+    pub fn with_row_swaps(mut self, num_row_swaps: usize) -> Self {
+        self.num_row_swaps = Some(num_row_swaps);
+        self
+    }
+
+    pub fn with_interaction_mutations(mut self, mutate_interactions: bool) -> Self {
+        self.mutate_interactions = mutate_interactions;
+        self
+    }
+
+    /// Columns which are not mutated, e.g. columns that are intentionally left unconstrained.
+    pub fn skip_columns(mut self, cols: impl IntoIterator<Item = usize>) -> Self {
+        self.skip_columns.extend(cols);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Runs every mutation against a fresh engine from `engine_provider`.
+    pub fn run<SC, E, P>(&self, tester: VmChipTester<SC>, engine_provider: P) -> FuzzReport
+    where
+        SC: StarkGenericConfig,
+        Val<SC>: PrimeField32,
+        E: StarkEngine<SC = SC, PB = CpuBackend<SC>, PD = CpuDevice<SC>>,
+        P: Fn() -> E,
+    {
+        assert!(tester.memory.is_none(), "Memory must be finalized");
+        let (airs, ctxs): (Vec<_>, Vec<_>) = tester.air_ctxs.into_iter().unzip();
+        let target = airs
+            .iter()
+            .positions(|air| air.name() == self.air_name)
+            .exactly_one()
+            .unwrap_or_else(|_| panic!("{} must be loaded exactly once", self.air_name));
+        let trace = ctxs[target]
+            .common_main
+            .as_ref()
+            .map(|trace| (**trace).clone())
+            .expect("target air has no common main trace");
+        let width = trace.width();
+        let used_rows = (0..trace.height())
+            .filter(|&row| {
+                trace.values[row * width..(row + 1) * width]
+                    .iter()
+                    .any(|x| !x.is_zero())
+            })
+            .collect_vec();
+        assert!(!used_rows.is_empty(), "target trace has no non-zero rows");
+
+        let pk = {
+            let engine = engine_provider();
+            let mut keygen_builder = engine.keygen_builder();
+            keygen_builder.add_air(airs[target].clone());
+            keygen_builder.generate_pk()
+        };
+        let constraints = &pk.per_air[0].vk.symbolic_constraints;
+        let preprocessed = airs[target].preprocessed_trace();
+        let public_values = &ctxs[target].public_values;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let num_rows = self.num_rows.unwrap_or(used_rows.len());
+        let mut mutations = vec![];
+        let mut interactions = BTreeMap::new();
+        for &row in used_rows.choose_multiple(&mut rng, num_rows).sorted() {
+            for col in (0..width).filter(|col| !self.skip_columns.contains(col)) {
+                for _ in 0..self.deltas_per_cell {
+                    let delta = rng.gen_range(1..Val::<SC>::ORDER_U32);
+                    mutations.push(TraceMutation::Cell { row, col, delta });
+                }
+            }
+            if !self.mutate_interactions {
+                continue;
+            }
+            let row_interactions = eval_interactions(
+                constraints,
+                preprocessed.as_ref(),
+                &trace,
+                public_values,
+                row,
+            );
+            for (i, interaction) in row_interactions.iter().enumerate() {
+                // Changing a message which is not sent has no effect.
+                let fields = if interaction.count.is_zero() {
+                    0
+                } else {
+                    interaction.message.len()
+                };
+                let targets = (0..fields)
+                    .map(InteractionTarget::Field)
+                    .chain(iter::once(InteractionTarget::Count));
+                for target in targets {
+                    for _ in 0..self.deltas_per_cell {
+                        let delta = rng.gen_range(1..Val::<SC>::ORDER_U32);
+                        mutations.push(TraceMutation::Interaction {
+                            row,
+                            interaction: i,
+                            target,
+                            delta,
+                        });
+                    }
+                }
+            }
+            interactions.insert(row, row_interactions);
+        }
+        if used_rows.len() >= 2 {
+            let pairs = match self.num_row_swaps {
+                None => used_rows.iter().copied().tuple_windows().collect_vec(),
+                Some(num_row_swaps) => (0..num_row_swaps)
+                    .map(|_| {
+                        used_rows
+                            .choose_multiple(&mut rng, 2)
+                            .copied()
+                            .collect_tuple()
+                            .unwrap()
+                    })
+                    .collect(),
+            };
+            for (a, b) in pairs {
+                // Swapping equal rows does not change the trace.
+                if trace.values[a * width..(a + 1) * width]
+                    != trace.values[b * width..(b + 1) * width]
+                {
+                    mutations.push(TraceMutation::SwapRows { a, b });
+                }
+            }
+        }
+
+        disable_debug_builder();
+        let results = mutations
+            .into_iter()
+            .map(|mutation| {
+                let mut airs = airs.clone();
+                let mut ctxs = ctxs.clone();
+                if let TraceMutation::Interaction {
+                    row,
+                    interaction,
+                    target,
+                    delta,
+                } = mutation
+                {
+                    let (air, ctx) = interaction_mutation_air::<SC>(
+                        &interactions[&row][interaction],
+                        target,
+                        delta,
+                    );
+                    airs.push(air);
+                    ctxs.push(ctx);
+                } else {
+                    let mut mutated = trace.clone();
+                    mutation.apply(&mut mutated);
+                    ctxs[target].common_main = Some(Arc::new(mutated));
+                }
+                let outcome = match engine_provider().run_test_impl(airs, ctxs) {
+                    Ok(_) => MutationOutcome::Accepted,
+                    Err(err) => MutationOutcome::Rejected(err),
+                };
+                tracing::debug!("{mutation:?}: {outcome:?}");
+                (mutation, outcome)
+            })
+            .collect();
+        FuzzReport {
+            air_name: self.air_name.clone(),
+            width,
+            results,
+        }
+    }
+}
+
+impl VmChipTester<BabyBearBlake3Config> {
+    pub fn simple_fuzz(self, fuzzer: &ConstraintFuzzer) -> FuzzReport {
+        fuzzer.run(self, || {
+            BabyBearBlake3Engine::new(FriParameters::new_for_testing(1))
+        })
+    }
+}
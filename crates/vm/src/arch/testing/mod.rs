@@ -2,6 +2,7 @@ mod cpu;
 #[cfg(feature = "cuda")]
 mod cuda;
 pub mod execution;
+mod fuzz;
 pub mod memory;
 pub mod program;
 mod utils;
@@ -12,6 +13,7 @@ pub use cpu::*;
 #[cfg(feature = "cuda")]
 pub use cuda::*;
 pub use execution::ExecutionTester;
+pub use fuzz::*;
 pub use memory::MemoryTester;
 use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
 use openvm_instructions::instruction::Instruction;
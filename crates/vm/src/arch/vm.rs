@@ -40,7 +40,9 @@ use thiserror::Error;
 use tracing::{info_span, instrument};
 
 use super::{
-    execution_mode::{ExecutionCtx, MeteredCostCtx, MeteredCtx, PreflightCtx, Segment},
+    execution_mode::{
+        CoverageCtx, ExecutionCtx, GuardedCtx, MeteredCostCtx, MeteredCtx, PreflightCtx, Segment,
+    },
     hasher::poseidon2::vm_poseidon2_hasher,
     interpreter::InterpretedInstance,
     interpreter_preflight::PreflightInterpretedInstance,
@@ -166,6 +168,9 @@ where
 pub enum ExitCode {
     Success = 0,
     Error = 1,
+    /// The guest allocator exhausted the heap. Must match
+    /// `openvm_platform::memory::OUT_OF_MEMORY_EXIT_CODE`.
+    OutOfMemory = 2,
     Suspended = -1, // Continuations
 }
 
@@ -233,6 +238,24 @@ where
     ) -> Result<InterpretedInstance<F, ExecutionCtx>, StaticProgramError> {
         InterpretedInstance::new(&self.inventory, exe)
     }
+
+    /// Creates an instance of the interpreter specialized for pure execution of the given `exe`
+    /// that also records per-pc execution counts, for guest code coverage.
+    pub fn coverage_instance(
+        &self,
+        exe: &VmExe<F>,
+    ) -> Result<InterpretedInstance<F, CoverageCtx>, StaticProgramError> {
+        InterpretedInstance::new(&self.inventory, exe)
+    }
+
+    /// Creates an instance of the interpreter specialized for pure execution with guest memory
+    /// guard regions checked. See [InterpretedInstance::execute_guarded].
+    pub fn guarded_instance(
+        &self,
+        exe: &VmExe<F>,
+    ) -> Result<InterpretedInstance<F, GuardedCtx>, StaticProgramError> {
+        InterpretedInstance::new(&self.inventory, exe)
+    }
 }
 
 impl<F, VC> VmExecutor<F, VC>
@@ -321,6 +344,8 @@ pub enum VirtualMachineError {
     ProgramIsNotCommitted,
     #[error("verification error: {0}")]
     Verification(#[from] VmVerificationError),
+    #[error("proving cancelled")]
+    Cancelled,
 }
 
 /// The [VirtualMachine] struct contains the API to generate proofs for _arbitrary_ programs for a
@@ -978,9 +1003,22 @@ where
     ///
     /// The closure `modify_ctx(seg_idx, &mut ctx)` is called sequentially for each segment.
     pub fn prove_continuations(
+        &mut self,
+        input: impl Into<Streams<Val<E::SC>>>,
+        modify_ctx: impl FnMut(usize, &mut ProvingContext<E::PB>),
+    ) -> Result<ContinuationVmProof<E::SC>, VirtualMachineError> {
+        self.prove_continuations_with_progress(input, modify_ctx, |_, _| Ok(()))
+    }
+
+    /// Same as [`prove_continuations`](Self::prove_continuations), but calls
+    /// `on_progress(num_segments_proven, num_segments)` once metered execution has determined the
+    /// segments and after each segment is proven. If `on_progress` returns an error, e.g.
+    /// [VirtualMachineError::Cancelled], proving stops and the error is returned.
+    pub fn prove_continuations_with_progress(
         &mut self,
         input: impl Into<Streams<Val<E::SC>>>,
         mut modify_ctx: impl FnMut(usize, &mut ProvingContext<E::PB>),
+        mut on_progress: impl FnMut(usize, usize) -> Result<(), VirtualMachineError>,
     ) -> Result<ContinuationVmProof<E::SC>, VirtualMachineError> {
         let input = input.into();
         self.reset_state(input.clone());
@@ -988,7 +1026,9 @@ where
         let metered_ctx = vm.build_metered_ctx(&self.exe);
         let metered_interpreter = vm.metered_interpreter(&self.exe)?;
         let (segments, _) = metered_interpreter.execute_metered(input, metered_ctx)?;
-        let mut proofs = Vec::with_capacity(segments.len());
+        let num_segments = segments.len();
+        on_progress(0, num_segments)?;
+        let mut proofs = Vec::with_capacity(num_segments);
         let mut state = self.state.take();
         for (seg_idx, segment) in segments.into_iter().enumerate() {
             let _segment_span = info_span!("prove_segment", segment = seg_idx).entered();
@@ -1018,6 +1058,7 @@ where
             modify_ctx(seg_idx, &mut ctx);
             let proof = vm.engine.prove(vm.pk(), ctx);
             proofs.push(proof);
+            on_progress(seg_idx + 1, num_segments)?;
         }
         let to_state = state.unwrap();
         let final_memory = &to_state.memory.memory;
@@ -0,0 +1,345 @@
+use std::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+};
+
+use num_bigint::BigUint;
+use openvm_algebra_transpiler::Rv32CustomFieldExprOpcode;
+use openvm_circuit::{
+    arch::*,
+    system::memory::{online::GuestMemory, POINTER_MAX_BITS},
+};
+use openvm_circuit_primitives::AlignedBytesBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+};
+use openvm_mod_circuit_builder::{run_field_expression_precomputed, FieldExpr};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::CustomFieldExprExecutor;
+
+#[derive(AlignedBytesBorrow, Clone)]
+#[repr(C)]
+struct CustomFieldExprPreCompute<'a> {
+    expr: &'a FieldExpr,
+    rs_addrs: [u8; 2],
+    a: u8,
+    flag_idx: u8,
+}
+
+impl<'a, const BLOCKS: usize, const BLOCK_SIZE: usize> CustomFieldExprExecutor<BLOCKS, BLOCK_SIZE> {
+    /// Returns whether the instruction is `SETUP_EVAL`.
+    fn pre_compute_impl<F: PrimeField32>(
+        &'a self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut CustomFieldExprPreCompute<'a>,
+    ) -> Result<bool, StaticProgramError> {
+        let Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = inst;
+
+        let a = a.as_canonical_u32();
+        let b = b.as_canonical_u32();
+        let c = c.as_canonical_u32();
+        let d = d.as_canonical_u32();
+        let e = e.as_canonical_u32();
+        if d != RV32_REGISTER_AS || e != RV32_MEMORY_AS {
+            return Err(StaticProgramError::InvalidInstruction(pc));
+        }
+
+        let local_opcode = opcode.local_opcode_idx(self.0.offset);
+        let is_setup = local_opcode == Rv32CustomFieldExprOpcode::SETUP_EVAL as usize;
+        // Setup rows have all flags off.
+        let flag_idx = if is_setup {
+            self.0.expr.num_flags() as u8
+        } else {
+            self.0.opcode_flag_idx[0] as u8
+        };
+
+        *data = CustomFieldExprPreCompute {
+            expr: &self.0.expr,
+            rs_addrs: [b as u8, c as u8],
+            a: a as u8,
+            flag_idx,
+        };
+        Ok(is_setup)
+    }
+}
+
+macro_rules! dispatch {
+    ($execute_impl:ident, $execute_setup_impl:ident, $is_setup:ident) => {
+        if $is_setup {
+            Ok($execute_setup_impl::<_, _, BLOCKS, BLOCK_SIZE>)
+        } else {
+            Ok($execute_impl::<_, _, BLOCKS, BLOCK_SIZE>)
+        }
+    };
+}
+
+impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize> Executor<F>
+    for CustomFieldExprExecutor<BLOCKS, BLOCK_SIZE>
+{
+    #[inline(always)]
+    fn pre_compute_size(&self) -> usize {
+        std::mem::size_of::<CustomFieldExprPreCompute>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn pre_compute<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let pre_compute: &mut CustomFieldExprPreCompute = data.borrow_mut();
+        let is_setup = self.pre_compute_impl(pc, inst, pre_compute)?;
+        dispatch!(execute_e1_handler, execute_e1_setup_handler, is_setup)
+    }
+
+    #[cfg(feature = "tco")]
+    fn handler<Ctx>(
+        &self,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: ExecutionCtxTrait,
+    {
+        let pre_compute: &mut CustomFieldExprPreCompute = data.borrow_mut();
+        let is_setup = self.pre_compute_impl(pc, inst, pre_compute)?;
+        dispatch!(execute_e1_handler, execute_e1_setup_handler, is_setup)
+    }
+}
+
+impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize> MeteredExecutor<F>
+    for CustomFieldExprExecutor<BLOCKS, BLOCK_SIZE>
+{
+    #[inline(always)]
+    fn metered_pre_compute_size(&self) -> usize {
+        std::mem::size_of::<E2PreCompute<CustomFieldExprPreCompute>>()
+    }
+
+    #[cfg(not(feature = "tco"))]
+    fn metered_pre_compute<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<ExecuteFunc<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let pre_compute: &mut E2PreCompute<CustomFieldExprPreCompute> = data.borrow_mut();
+        pre_compute.chip_idx = chip_idx as u32;
+        let is_setup = self.pre_compute_impl(pc, inst, &mut pre_compute.data)?;
+        dispatch!(execute_e2_handler, execute_e2_setup_handler, is_setup)
+    }
+
+    #[cfg(feature = "tco")]
+    fn metered_handler<Ctx>(
+        &self,
+        chip_idx: usize,
+        pc: u32,
+        inst: &Instruction<F>,
+        data: &mut [u8],
+    ) -> Result<Handler<F, Ctx>, StaticProgramError>
+    where
+        Ctx: MeteredExecutionCtxTrait,
+    {
+        let pre_compute: &mut E2PreCompute<CustomFieldExprPreCompute> = data.borrow_mut();
+        pre_compute.chip_idx = chip_idx as u32;
+        let is_setup = self.pre_compute_impl(pc, inst, &mut pre_compute.data)?;
+        dispatch!(execute_e2_handler, execute_e2_setup_handler, is_setup)
+    }
+}
+
+#[inline(always)]
+unsafe fn read_operands<
+    F: PrimeField32,
+    CTX: ExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &CustomFieldExprPreCompute,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) -> [[[u8; BLOCK_SIZE]; BLOCKS]; 2] {
+    let rs_vals = pre_compute
+        .rs_addrs
+        .map(|addr| u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, addr as u32)));
+
+    rs_vals.map(|address| {
+        debug_assert!(address as usize + BLOCK_SIZE * BLOCKS - 1 < (1 << POINTER_MAX_BITS));
+        from_fn(|i| exec_state.vm_read(RV32_MEMORY_AS, address + (i * BLOCK_SIZE) as u32))
+    })
+}
+
+#[inline(always)]
+unsafe fn eval_and_write<
+    F: PrimeField32,
+    CTX: ExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &CustomFieldExprPreCompute,
+    read_data: [[[u8; BLOCK_SIZE]; BLOCKS]; 2],
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let read_data_dyn: DynArray<u8> = read_data.into();
+
+    let writes = run_field_expression_precomputed::<true>(
+        pre_compute.expr,
+        pre_compute.flag_idx as usize,
+        &read_data_dyn.0,
+    );
+
+    let rd_val = u32::from_le_bytes(exec_state.vm_read(RV32_REGISTER_AS, pre_compute.a as u32));
+    debug_assert!(rd_val as usize + BLOCK_SIZE * BLOCKS - 1 < (1 << POINTER_MAX_BITS));
+
+    let data: [[u8; BLOCK_SIZE]; BLOCKS] = writes.into();
+    for (i, block) in data.into_iter().enumerate() {
+        exec_state.vm_write(RV32_MEMORY_AS, rd_val + (i * BLOCK_SIZE) as u32, &block);
+    }
+
+    *pc = pc.wrapping_add(DEFAULT_PC_STEP);
+    *instret += 1;
+}
+
+#[inline(always)]
+unsafe fn execute_e12_impl<
+    F: PrimeField32,
+    CTX: ExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &CustomFieldExprPreCompute,
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let read_data = read_operands::<_, _, BLOCKS, BLOCK_SIZE>(pre_compute, exec_state);
+    eval_and_write::<_, _, BLOCKS, BLOCK_SIZE>(pre_compute, read_data, instret, pc, exec_state);
+}
+
+#[inline(always)]
+unsafe fn execute_e12_setup_impl<
+    F: PrimeField32,
+    CTX: ExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &CustomFieldExprPreCompute,
+    instret: &mut u64,
+    pc: &mut u32,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) -> Result<(), ExecutionError> {
+    let read_data = read_operands::<_, _, BLOCKS, BLOCK_SIZE>(pre_compute, exec_state);
+
+    // The first input should be the prime
+    let num_limbs = pre_compute.expr.canonical_num_limbs();
+    let input_prime = BigUint::from_bytes_le(&read_data[0].as_flattened()[..num_limbs]);
+    if input_prime != pre_compute.expr.prime {
+        let err = ExecutionError::Fail {
+            pc: *pc,
+            msg: "CustomFieldExpr: mismatched prime",
+        };
+        return Err(err);
+    }
+
+    eval_and_write::<_, _, BLOCKS, BLOCK_SIZE>(pre_compute, read_data, instret, pc, exec_state);
+
+    Ok(())
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e1_impl<
+    F: PrimeField32,
+    CTX: ExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _instret_end: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &CustomFieldExprPreCompute = pre_compute.borrow();
+    execute_e12_impl::<_, _, BLOCKS, BLOCK_SIZE>(pre_compute, instret, pc, exec_state);
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e2_impl<
+    F: PrimeField32,
+    CTX: MeteredExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _arg: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) {
+    let pre_compute: &E2PreCompute<CustomFieldExprPreCompute> = pre_compute.borrow();
+    exec_state
+        .ctx
+        .on_height_change(pre_compute.chip_idx as usize, 1);
+    execute_e12_impl::<_, _, BLOCKS, BLOCK_SIZE>(&pre_compute.data, instret, pc, exec_state);
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e1_setup_impl<
+    F: PrimeField32,
+    CTX: ExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _instret_end: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) -> Result<(), ExecutionError> {
+    let pre_compute: &CustomFieldExprPreCompute = pre_compute.borrow();
+    execute_e12_setup_impl::<_, _, BLOCKS, BLOCK_SIZE>(pre_compute, instret, pc, exec_state)
+}
+
+#[create_handler]
+#[inline(always)]
+unsafe fn execute_e2_setup_impl<
+    F: PrimeField32,
+    CTX: MeteredExecutionCtxTrait,
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+>(
+    pre_compute: &[u8],
+    instret: &mut u64,
+    pc: &mut u32,
+    _arg: u64,
+    exec_state: &mut VmExecState<F, GuestMemory, CTX>,
+) -> Result<(), ExecutionError> {
+    let pre_compute: &E2PreCompute<CustomFieldExprPreCompute> = pre_compute.borrow();
+    exec_state
+        .ctx
+        .on_height_change(pre_compute.chip_idx as usize, 1);
+    execute_e12_setup_impl::<_, _, BLOCKS, BLOCK_SIZE>(&pre_compute.data, instret, pc, exec_state)
+}
@@ -0,0 +1,320 @@
+//! Chips for field expressions declared in the VM config instead of in Rust: each
+//! [CustomFieldExprConfig] becomes a [FieldExpr] with its own opcodes, see
+//! [CustomFieldExprExtension](crate::CustomFieldExprExtension).
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use derive_more::derive::{Deref, DerefMut};
+use eyre::{bail, ensure, eyre, Result};
+use num_bigint::BigUint;
+use num_traits::One;
+use openvm_algebra_transpiler::Rv32CustomFieldExprOpcode;
+use openvm_circuit::{
+    arch::ExecutionBridge,
+    system::memory::{offline_checker::MemoryBridge, SharedMemoryHelper},
+};
+use openvm_circuit_derive::PreflightExecutor;
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    var_range::{SharedVariableRangeCheckerChip, VariableRangeCheckerBus},
+};
+use openvm_instructions::riscv::RV32_CELL_BITS;
+use openvm_mod_circuit_builder::{
+    ExprBuilder, ExprBuilderConfig, FieldExpr, FieldExpressionCoreAir, FieldExpressionExecutor,
+    FieldExpressionFiller, FieldVariable,
+};
+use openvm_rv32_adapters::{
+    Rv32VecHeapAdapterAir, Rv32VecHeapAdapterExecutor, Rv32VecHeapAdapterFiller,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::modular_chip::{ModularAir, ModularChip};
+
+mod execution;
+mod parser;
+pub use parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub const CUSTOM_FIELD_EXPR_MAX_INPUTS: usize = 4;
+pub const CUSTOM_FIELD_EXPR_MAX_OUTPUTS: usize = 2;
+
+/// A named expression over the prime field of `modulus`, e.g.
+/// ```toml
+/// [[app_vm_config.custom_field_expr.expressions]]
+/// name = "mul_add2"
+/// modulus = "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+/// inputs = ["a", "b", "c", "d"]
+/// definitions = ["ab = a * b"]
+/// outputs = ["ab + c * d"]
+/// ```
+///
+/// `definitions` are evaluated in order, each of the form `name = expression`, and may use the
+/// inputs and previous definitions. Expressions support `+`, `-`, `*`, `/`, unary minus,
+/// parentheses and integer constants. Division by zero fails execution.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomFieldExprConfig {
+    pub name: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub modulus: BigUint,
+    pub inputs: Vec<String>,
+    #[serde(default)]
+    pub definitions: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+impl CustomFieldExprConfig {
+    /// Number of field elements each of `rs1`, `rs2` and `rd` points to. The inputs are split in
+    /// order between `rs1` and `rs2`, padded with unused elements, and the outputs are written to
+    /// `rd`, padded with copies of the first output.
+    pub fn num_elems(&self) -> usize {
+        if self.inputs.len() <= 2 && self.outputs.len() <= 1 {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Number of bytes of a field element in memory.
+    pub fn num_limbs(&self) -> Result<usize> {
+        let bytes = self.modulus.bits().div_ceil(8);
+        if bytes <= 32 {
+            Ok(32)
+        } else if bytes <= 48 {
+            Ok(48)
+        } else {
+            bail!("Modulus too large")
+        }
+    }
+
+    /// Shape of the heap adapter reading and writing the operands.
+    pub fn adapter_shape(&self) -> Result<CustomFieldExprShape> {
+        let one_elem = self.num_elems() == 1;
+        Ok(if self.num_limbs()? == 32 {
+            if one_elem {
+                CustomFieldExprShape::Rv32_1x32
+            } else {
+                CustomFieldExprShape::Rv32_2x32
+            }
+        } else if one_elem {
+            CustomFieldExprShape::Rv32_3x16
+        } else {
+            CustomFieldExprShape::Rv32_6x16
+        })
+    }
+
+    pub fn builder_config(&self) -> Result<ExprBuilderConfig> {
+        Ok(ExprBuilderConfig {
+            modulus: self.modulus.clone(),
+            num_limbs: self.num_limbs()?,
+            limb_bits: RV32_CELL_BITS,
+        })
+    }
+
+    /// Checks that the expression is well formed.
+    pub fn validate(&self) -> Result<()> {
+        custom_field_expr(self, VariableRangeCheckerBus::new(u16::MAX, 17)).map(|_| ())
+    }
+}
+
+/// `BLOCKS x BLOCK_SIZE` of the heap adapter of a custom field expression chip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CustomFieldExprShape {
+    // 32 limbs prime
+    Rv32_1x32,
+    Rv32_2x32,
+    // 48 limbs prime
+    Rv32_3x16,
+    Rv32_6x16,
+}
+
+impl CustomFieldExprShape {
+    /// `(BLOCKS, BLOCK_SIZE)` of the heap adapter.
+    pub fn block_dims(self) -> (usize, usize) {
+        match self {
+            Self::Rv32_1x32 => (1, 32),
+            Self::Rv32_2x32 => (2, 32),
+            Self::Rv32_3x16 => (3, 16),
+            Self::Rv32_6x16 => (6, 16),
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn lower(
+    ast: &FieldExprAst,
+    env: &HashMap<String, FieldVariable>,
+    builder: &Rc<RefCell<ExprBuilder>>,
+    flag: usize,
+) -> Result<FieldVariable> {
+    let rec = |ast: &FieldExprAst| lower(ast, env, builder, flag);
+    Ok(match ast {
+        FieldExprAst::Var(name) => env
+            .get(name)
+            .cloned()
+            .ok_or_else(|| eyre!("unknown variable `{name}`"))?,
+        FieldExprAst::Const(value) => {
+            let value = value % &builder.borrow().prime;
+            ExprBuilder::new_const(builder.clone(), value)
+        }
+        FieldExprAst::Neg(x) => rec(x)?.int_mul(-1),
+        FieldExprAst::Add(x, y) => rec(x)? + rec(y)?,
+        FieldExprAst::Sub(x, y) => rec(x)? - rec(y)?,
+        FieldExprAst::Mul(x, y) => rec(x)? * rec(y)?,
+        FieldExprAst::Div(x, y) => {
+            // Setup and padding rows have all flags off and zero inputs, so they divide by one.
+            let one = ExprBuilder::new_const(builder.clone(), BigUint::one());
+            rec(x)? / FieldVariable::select(flag, &rec(y)?, &one)
+        }
+    })
+}
+
+/// Builds the field expression of `config`, returning it with the local opcodes and flags of
+/// [FieldExpressionCoreAir].
+pub fn custom_field_expr(
+    config: &CustomFieldExprConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> Result<(FieldExpr, Vec<usize>, Vec<usize>)> {
+    ensure!(
+        is_identifier(&config.name),
+        "`{}` is not a valid name",
+        config.name
+    );
+    ensure!(
+        (1..=CUSTOM_FIELD_EXPR_MAX_INPUTS).contains(&config.inputs.len()),
+        "expected 1 to {CUSTOM_FIELD_EXPR_MAX_INPUTS} inputs, got {}",
+        config.inputs.len()
+    );
+    ensure!(
+        (1..=CUSTOM_FIELD_EXPR_MAX_OUTPUTS).contains(&config.outputs.len()),
+        "expected 1 to {CUSTOM_FIELD_EXPR_MAX_OUTPUTS} outputs, got {}",
+        config.outputs.len()
+    );
+    let builder_config = config.builder_config()?;
+    builder_config.check_valid();
+    let builder = ExprBuilder::new(builder_config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+    let flag = builder.borrow_mut().new_flag();
+    let num_elems = config.num_elems();
+
+    let mut env = HashMap::new();
+    for name in config.inputs.iter() {
+        ensure!(is_identifier(name), "`{name}` is not a valid input name");
+        let input = ExprBuilder::new_input(builder.clone());
+        ensure!(
+            env.insert(name.clone(), input).is_none(),
+            "input `{name}` is declared twice"
+        );
+    }
+    for _ in config.inputs.len()..2 * num_elems {
+        ExprBuilder::new_input(builder.clone());
+    }
+    for definition in config.definitions.iter() {
+        let (name, ast) = parse_definition(definition)?;
+        ensure!(!env.contains_key(&name), "`{name}` is defined twice");
+        let mut var = lower(&ast, &env, &builder, flag)?;
+        var.save();
+        env.insert(name, var);
+    }
+    for output in config.outputs.iter() {
+        let ast = parse_field_expr(output)?;
+        lower(&ast, &env, &builder, flag)?.save_output();
+    }
+    let first_output = builder.borrow().output_indices[0];
+    for _ in config.outputs.len()..num_elems {
+        builder.borrow_mut().output_indices.push(first_output);
+    }
+
+    let mut builder = (*builder).borrow().clone();
+    builder.minimize_columns();
+    let local_opcode_idx = vec![
+        Rv32CustomFieldExprOpcode::EVAL as usize,
+        Rv32CustomFieldExprOpcode::SETUP_EVAL as usize,
+    ];
+    Ok((
+        FieldExpr::new(builder, range_bus, true),
+        local_opcode_idx,
+        vec![flag],
+    ))
+}
+
+pub type CustomFieldExprAir<const BLOCKS: usize, const BLOCK_SIZE: usize> =
+    ModularAir<BLOCKS, BLOCK_SIZE>;
+
+#[derive(Clone, PreflightExecutor, Deref, DerefMut)]
+pub struct CustomFieldExprExecutor<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    FieldExpressionExecutor<Rv32VecHeapAdapterExecutor<2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>>,
+);
+
+pub type CustomFieldExprChip<F, const BLOCKS: usize, const BLOCK_SIZE: usize> =
+    ModularChip<F, BLOCKS, BLOCK_SIZE>;
+
+pub fn get_custom_field_expr_air<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    exec_bridge: ExecutionBridge,
+    mem_bridge: MemoryBridge,
+    config: &CustomFieldExprConfig,
+    range_checker_bus: VariableRangeCheckerBus,
+    bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pointer_max_bits: usize,
+    offset: usize,
+) -> Result<CustomFieldExprAir<BLOCKS, BLOCK_SIZE>> {
+    let (expr, local_opcode_idx, opcode_flag_idx) = custom_field_expr(config, range_checker_bus)?;
+    Ok(CustomFieldExprAir::new(
+        Rv32VecHeapAdapterAir::new(
+            exec_bridge,
+            mem_bridge,
+            bitwise_lookup_bus,
+            pointer_max_bits,
+        ),
+        FieldExpressionCoreAir::new(expr, offset, local_opcode_idx, opcode_flag_idx),
+    ))
+}
+
+pub fn get_custom_field_expr_step<const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    config: &CustomFieldExprConfig,
+    range_checker_bus: VariableRangeCheckerBus,
+    pointer_max_bits: usize,
+    offset: usize,
+) -> Result<CustomFieldExprExecutor<BLOCKS, BLOCK_SIZE>> {
+    let (expr, local_opcode_idx, opcode_flag_idx) = custom_field_expr(config, range_checker_bus)?;
+
+    Ok(CustomFieldExprExecutor(FieldExpressionExecutor::new(
+        Rv32VecHeapAdapterExecutor::new(pointer_max_bits),
+        expr,
+        offset,
+        local_opcode_idx,
+        opcode_flag_idx,
+        "CustomFieldExpr",
+    )))
+}
+
+pub fn get_custom_field_expr_chip<F, const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    config: &CustomFieldExprConfig,
+    mem_helper: SharedMemoryHelper<F>,
+    range_checker: SharedVariableRangeCheckerChip,
+    bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    pointer_max_bits: usize,
+) -> Result<CustomFieldExprChip<F, BLOCKS, BLOCK_SIZE>> {
+    let (expr, local_opcode_idx, opcode_flag_idx) = custom_field_expr(config, range_checker.bus())?;
+    Ok(CustomFieldExprChip::new(
+        FieldExpressionFiller::new(
+            Rv32VecHeapAdapterFiller::new(pointer_max_bits, bitwise_lookup_chip),
+            expr,
+            local_opcode_idx,
+            opcode_flag_idx,
+            range_checker,
+            false,
+        ),
+        mem_helper,
+    ))
+}
@@ -0,0 +1,195 @@
+//! Parser of the textual field expressions used in
+//! [CustomFieldExprConfig](super::CustomFieldExprConfig).
+//!
+//! The grammar is the usual one for arithmetic, where `-` and `/` are left associative and
+//! unary minus binds tighter than `*` and `/`:
+//! ```text
+//! expr  := term (('+' | '-') term)*
+//! term  := unary (('*' | '/') unary)*
+//! unary := '-' unary | atom
+//! atom  := identifier | integer | '(' expr ')'
+//! ```
+//! Integers are either decimal or hexadecimal with a `0x` prefix.
+
+use eyre::{bail, eyre, Result};
+use num_bigint::BigUint;
+use num_traits::Num;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldExprAst {
+    Var(String),
+    Const(BigUint),
+    Neg(Box<FieldExprAst>),
+    Add(Box<FieldExprAst>, Box<FieldExprAst>),
+    Sub(Box<FieldExprAst>, Box<FieldExprAst>),
+    Mul(Box<FieldExprAst>, Box<FieldExprAst>),
+    Div(Box<FieldExprAst>, Box<FieldExprAst>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Int(BigUint),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+    Assign,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            let literal = chars[start..i].iter().collect::<String>();
+            let value = match literal.strip_prefix("0x") {
+                Some(hex) => BigUint::from_str_radix(hex, 16),
+                None => BigUint::from_str_radix(&literal, 10),
+            }
+            .map_err(|_| eyre!("invalid integer literal `{literal}`"))?;
+            tokens.push(Token::Int(value));
+            continue;
+        }
+        tokens.push(match c {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '=' => Token::Assign,
+            _ => bail!("unexpected character `{c}`"),
+        });
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Result<FieldExprAst> {
+        let mut lhs = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    lhs = FieldExprAst::Add(Box::new(lhs), Box::new(self.term()?));
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    lhs = FieldExprAst::Sub(Box::new(lhs), Box::new(self.term()?));
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<FieldExprAst> {
+        let mut lhs = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.pos += 1;
+                    lhs = FieldExprAst::Mul(Box::new(lhs), Box::new(self.unary()?));
+                }
+                Some(Token::Slash) => {
+                    self.pos += 1;
+                    lhs = FieldExprAst::Div(Box::new(lhs), Box::new(self.unary()?));
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<FieldExprAst> {
+        if self.peek() == Some(&Token::Minus) {
+            self.pos += 1;
+            return Ok(FieldExprAst::Neg(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<FieldExprAst> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(FieldExprAst::Var(name)),
+            Some(Token::Int(value)) => Ok(FieldExprAst::Const(value)),
+            Some(Token::LParen) => {
+                let expr = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => bail!("expected `)`"),
+                }
+            }
+            Some(token) => bail!("unexpected token {token:?}"),
+            None => bail!("unexpected end of expression"),
+        }
+    }
+
+    fn finish(&self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => bail!("unexpected token {token:?}"),
+        }
+    }
+}
+
+/// Parses an expression such as `a * b + c * d`.
+pub fn parse_field_expr(s: &str) -> Result<FieldExprAst> {
+    let mut parser = Parser {
+        tokens: tokenize(s)?,
+        pos: 0,
+    };
+    let expr = parser.expr()?;
+    parser.finish()?;
+    Ok(expr)
+}
+
+/// Parses a definition such as `t = a * b`, returning the name and the expression.
+pub fn parse_definition(s: &str) -> Result<(String, FieldExprAst)> {
+    let mut parser = Parser {
+        tokens: tokenize(s)?,
+        pos: 0,
+    };
+    let name = match (parser.next(), parser.next()) {
+        (Some(Token::Ident(name)), Some(Token::Assign)) => name,
+        _ => bail!("expected a definition of the form `name = expression`"),
+    };
+    let expr = parser.expr()?;
+    parser.finish()?;
+    Ok((name, expr))
+}
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use openvm_algebra_guest::CustomFieldExprBaseFunct7;
+use openvm_algebra_transpiler::Rv32CustomFieldExprOpcode;
+use openvm_circuit::arch::{
+    instructions::LocalOpcode,
+    testing::{memory::gen_pointer, TestChipHarness, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+};
+use openvm_circuit_primitives::{
+    bigint::utils::secp256k1_coord_prime,
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    var_range::VariableRangeCheckerBus,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS},
+    VmOpcode,
+};
+use openvm_mod_circuit_builder::{
+    test_utils::generate_random_biguint, utils::biguint_to_limbs_vec,
+};
+use openvm_pairing_guest::{bls12_381::BLS12_381_MODULUS, bn254::BN254_MODULUS};
+use openvm_rv32_adapters::write_ptr_reg;
+use openvm_rv32im_circuit::adapters::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+
+use super::*;
+use crate::CustomFieldExprExtension;
+
+const MAX_INS_CAPACITY: usize = 128;
+type F = BabyBear;
+
+fn var(name: &str) -> Box<FieldExprAst> {
+    Box::new(FieldExprAst::Var(name.to_string()))
+}
+
+fn config(
+    modulus: BigUint,
+    inputs: &[&str],
+    definitions: &[&str],
+    outputs: &[&str],
+) -> CustomFieldExprConfig {
+    CustomFieldExprConfig {
+        name: "test_expr".to_string(),
+        modulus,
+        inputs: inputs.iter().map(|s| s.to_string()).collect(),
+        definitions: definitions.iter().map(|s| s.to_string()).collect(),
+        outputs: outputs.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+fn mul_add2_config(modulus: BigUint) -> CustomFieldExprConfig {
+    config(
+        modulus,
+        &["a", "b", "c", "d"],
+        &["ab = a * b"],
+        &["ab + c * d", "ab - c * d"],
+    )
+}
+
+#[test]
+fn test_parse_precedence() {
+    assert_eq!(
+        parse_field_expr("a - b * -c + d").unwrap(),
+        FieldExprAst::Add(
+            Box::new(FieldExprAst::Sub(
+                var("a"),
+                Box::new(FieldExprAst::Mul(
+                    var("b"),
+                    Box::new(FieldExprAst::Neg(var("c")))
+                ))
+            )),
+            var("d")
+        )
+    );
+    assert_eq!(
+        parse_field_expr("(a + 0x10) / 3").unwrap(),
+        FieldExprAst::Div(
+            Box::new(FieldExprAst::Add(
+                var("a"),
+                Box::new(FieldExprAst::Const(BigUint::from(16u32)))
+            )),
+            Box::new(FieldExprAst::Const(BigUint::from(3u32)))
+        )
+    );
+    assert_eq!(
+        parse_definition("t_1 = x").unwrap(),
+        ("t_1".to_string(), FieldExprAst::Var("x".to_string()))
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(parse_field_expr("a +").is_err());
+    assert!(parse_field_expr("(a * b").is_err());
+    assert!(parse_field_expr("a b").is_err());
+    assert!(parse_field_expr("a % b").is_err());
+    assert!(parse_field_expr("0xg").is_err());
+    assert!(parse_definition("a * b").is_err());
+}
+
+#[test]
+fn test_invalid_configs() {
+    let modulus = BN254_MODULUS.clone();
+    let invalid = [
+        config(modulus.clone(), &["a"], &[], &["a * b"]),
+        config(modulus.clone(), &["a", "a"], &[], &["a"]),
+        config(modulus.clone(), &["a"], &["a = a + 1"], &["a"]),
+        config(modulus.clone(), &["a", "b", "c", "d", "e"], &[], &["a"]),
+        config(modulus.clone(), &["a"], &[], &["a", "a", "a"]),
+        config(modulus.clone(), &["a"], &[], &[]),
+        config(BigUint::one() << 400, &["a"], &[], &["a"]),
+    ];
+    for config in invalid {
+        assert!(config.validate().is_err(), "{config:?}");
+    }
+    assert!(config(BigUint::one() << 400, &["a"], &[], &["a"])
+        .adapter_shape()
+        .is_err());
+    assert!(mul_add2_config(modulus).validate().is_ok());
+}
+
+#[test]
+fn test_custom_field_expr_execute() {
+    let modulus = BN254_MODULUS.clone();
+    let range_bus = VariableRangeCheckerBus::new(1, 17);
+
+    let (expr, _, flags) = custom_field_expr(&mul_add2_config(modulus.clone()), range_bus).unwrap();
+    assert_eq!(expr.builder.num_input, 4);
+    let inputs = (0..4)
+        .map(|_| generate_random_biguint(&modulus))
+        .collect::<Vec<_>>();
+    let ab = &inputs[0] * &inputs[1];
+    let cd = &inputs[2] * &inputs[3];
+    let mut flag_values = vec![false; expr.num_flags()];
+    flag_values[flags[0]] = true;
+    let outputs = expr.execute_with_output(inputs.clone(), flag_values);
+    assert_eq!(
+        outputs,
+        vec![
+            (&ab + &cd) % &modulus,
+            (&ab + &modulus - &cd % &modulus) % &modulus
+        ]
+    );
+
+    // A single input and output fit in one element per operand, the output is a constant
+    // expression of the input.
+    let config = config(modulus.clone(), &["x"], &[], &["-x / 2"]);
+    let (expr, _, flags) = custom_field_expr(&config, range_bus).unwrap();
+    assert_eq!(expr.builder.num_input, 2);
+    let x = generate_random_biguint(&modulus);
+    let mut flag_values = vec![false; expr.num_flags()];
+    flag_values[flags[0]] = true;
+    let outputs = expr.execute_with_output(vec![x.clone(), BigUint::zero()], flag_values);
+    assert_eq!((&outputs[0] * 2u32 + &x) % &modulus, BigUint::zero());
+}
+
+type Harness<const BLOCKS: usize, const BLOCK_SIZE: usize> = TestChipHarness<
+    F,
+    CustomFieldExprExecutor<BLOCKS, BLOCK_SIZE>,
+    CustomFieldExprAir<BLOCKS, BLOCK_SIZE>,
+    CustomFieldExprChip<F, BLOCKS, BLOCK_SIZE>,
+>;
+
+/// Runs `num_ops` random evaluations of `config` after its setup, checking the outputs against
+/// `expected`.
+fn run_custom_field_expr_test<
+    const BLOCKS: usize,
+    const BLOCK_SIZE: usize,
+    const NUM_LIMBS: usize,
+>(
+    config: CustomFieldExprConfig,
+    expected: impl Fn(&[BigUint]) -> Vec<BigUint>,
+    num_ops: usize,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let offset = Rv32CustomFieldExprOpcode::CLASS_OFFSET;
+    let modulus = config.modulus.clone();
+    let num_elems = config.num_elems();
+    assert_eq!(
+        config.adapter_shape().unwrap().block_dims(),
+        (BLOCKS, BLOCK_SIZE)
+    );
+
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+    let air = get_custom_field_expr_air::<BLOCKS, BLOCK_SIZE>(
+        tester.execution_bridge(),
+        tester.memory_bridge(),
+        &config,
+        tester.range_checker().bus(),
+        bitwise_bus,
+        tester.address_bits(),
+        offset,
+    )
+    .unwrap();
+    let executor = get_custom_field_expr_step::<BLOCKS, BLOCK_SIZE>(
+        &config,
+        tester.range_checker().bus(),
+        tester.address_bits(),
+        offset,
+    )
+    .unwrap();
+    let chip = get_custom_field_expr_chip::<F, BLOCKS, BLOCK_SIZE>(
+        &config,
+        tester.memory_helper(),
+        tester.range_checker(),
+        bitwise_chip.clone(),
+        tester.address_bits(),
+    )
+    .unwrap();
+    let mut harness: Harness<BLOCKS, BLOCK_SIZE> =
+        Harness::with_capacity(executor, air, chip, MAX_INS_CAPACITY);
+
+    let ptr_as = RV32_REGISTER_AS as usize;
+    let data_as = RV32_MEMORY_AS as usize;
+    let addr_ptrs = [0, 3, 6].map(|i| i * RV32_REGISTER_NUM_LIMBS);
+    for i in 0..=num_ops {
+        let is_setup = i == 0;
+        let inputs = if is_setup {
+            let mut inputs = vec![BigUint::zero(); 2 * num_elems];
+            inputs[0] = modulus.clone();
+            inputs
+        } else {
+            (0..2 * num_elems)
+                .map(|j| {
+                    if j < config.inputs.len() {
+                        generate_random_biguint(&modulus)
+                    } else {
+                        BigUint::zero()
+                    }
+                })
+                .collect()
+        };
+        let addresses = addr_ptrs.map(|addr_ptr| {
+            let address = gen_pointer(&mut rng, BLOCK_SIZE);
+            write_ptr_reg(&mut tester, ptr_as, addr_ptr, address as u32);
+            address
+        });
+        for (k, operand) in inputs.chunks(num_elems).enumerate() {
+            let limbs = operand
+                .iter()
+                .flat_map(|x| biguint_to_limbs_vec(x, NUM_LIMBS))
+                .map(F::from_canonical_u8)
+                .collect::<Vec<_>>();
+            for (j, block) in limbs.chunks(BLOCK_SIZE).enumerate() {
+                tester.write::<BLOCK_SIZE>(
+                    data_as,
+                    addresses[k] + j * BLOCK_SIZE,
+                    block.try_into().unwrap(),
+                );
+            }
+        }
+
+        let local_opcode = if is_setup {
+            Rv32CustomFieldExprOpcode::SETUP_EVAL
+        } else {
+            Rv32CustomFieldExprOpcode::EVAL
+        };
+        let instruction = Instruction::from_isize(
+            VmOpcode::from_usize(offset + local_opcode.local_usize()),
+            addr_ptrs[2] as isize,
+            addr_ptrs[0] as isize,
+            addr_ptrs[1] as isize,
+            ptr_as as isize,
+            data_as as isize,
+        );
+        tester.execute(&mut harness.executor, &mut harness.arena, &instruction);
+
+        if is_setup {
+            continue;
+        }
+        let mut outputs = expected(&inputs[..config.inputs.len()]);
+        outputs.resize(num_elems, outputs[0].clone());
+        let expected_limbs = outputs
+            .iter()
+            .flat_map(|x| biguint_to_limbs_vec(&(x % &modulus), NUM_LIMBS))
+            .map(F::from_canonical_u8)
+            .collect::<Vec<_>>();
+        for (j, block) in expected_limbs.chunks(BLOCK_SIZE).enumerate() {
+            let read_vals = tester.read::<BLOCK_SIZE>(data_as, addresses[2] + j * BLOCK_SIZE);
+            assert_eq!(read_vals.as_slice(), block);
+        }
+    }
+
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery((bitwise_chip.air, bitwise_chip))
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_custom_field_expr_1x32_secp256k1() {
+    let modulus = secp256k1_coord_prime();
+    let config = config(modulus.clone(), &["x", "y"], &[], &["(x * x + 7) / y"]);
+    run_custom_field_expr_test::<1, 32, 32>(
+        config,
+        |inputs| {
+            let y_inv = inputs[1].modpow(&(&modulus - 2u32), &modulus);
+            vec![(&inputs[0] * &inputs[0] + 7u32) * y_inv]
+        },
+        50,
+    );
+}
+
+#[test]
+fn test_custom_field_expr_2x32_bn254() {
+    let modulus = BN254_MODULUS.clone();
+    run_custom_field_expr_test::<2, 32, 32>(
+        mul_add2_config(modulus.clone()),
+        |inputs| {
+            let ab = &inputs[0] * &inputs[1];
+            let cd = &inputs[2] * &inputs[3] % &modulus;
+            vec![&ab + &cd, &ab + &modulus - &cd]
+        },
+        50,
+    );
+}
+
+#[test]
+fn test_custom_field_expr_6x16_bls12_381() {
+    let modulus = BLS12_381_MODULUS.clone();
+    let config = config(modulus.clone(), &["x", "y", "z"], &[], &["x * y - z"]);
+    run_custom_field_expr_test::<6, 16, 48>(
+        config,
+        |inputs| vec![&inputs[0] * &inputs[1] + &modulus - &inputs[2]],
+        50,
+    );
+}
+
+#[test]
+fn test_custom_field_expr_extension_limits() {
+    let expr = |i: usize| CustomFieldExprConfig {
+        name: format!("expr_{i}"),
+        ..mul_add2_config(BN254_MODULUS.clone())
+    };
+    let max_expressions = CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_EXPRESSIONS;
+    assert_eq!(max_expressions, 64);
+    assert!(CustomFieldExprExtension::new((0..max_expressions).map(expr).collect()).is_ok());
+    assert!(CustomFieldExprExtension::new((0..=max_expressions).map(expr).collect()).is_err());
+    assert!(CustomFieldExprExtension::new(vec![expr(0), expr(0)]).is_err());
+}
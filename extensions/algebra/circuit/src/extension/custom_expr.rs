@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use eyre::ensure;
+use openvm_algebra_guest::CustomFieldExprBaseFunct7;
+use openvm_algebra_transpiler::Rv32CustomFieldExprOpcode;
+use openvm_circuit::{
+    self,
+    arch::{
+        AirInventory, AirInventoryError, ChipInventory, ChipInventoryError, ExecutionBridge,
+        ExecutorInventoryBuilder, ExecutorInventoryError, RowMajorMatrixArena, VmCircuitExtension,
+        VmExecutionExtension, VmProverExtension,
+    },
+    system::{memory::SharedMemoryHelper, SystemPort},
+};
+use openvm_circuit_derive::{AnyEnum, Executor, MeteredExecutor, PreflightExecutor};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{
+        BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+        SharedBitwiseOperationLookupChip,
+    },
+    var_range::VariableRangeCheckerBus,
+};
+use openvm_instructions::{LocalOpcode, VmOpcode};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_field::PrimeField32,
+    prover::cpu::{CpuBackend, CpuDevice},
+};
+use openvm_stark_sdk::engine::StarkEngine;
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+
+use crate::{
+    custom_expr_chip::{
+        get_custom_field_expr_air, get_custom_field_expr_chip, get_custom_field_expr_step,
+        CustomFieldExprAir, CustomFieldExprConfig, CustomFieldExprExecutor, CustomFieldExprShape,
+    },
+    AlgebraCpuProverExt,
+};
+
+/// Field expressions declared in the VM config, each executed by a single instruction. The
+/// `i`-th expression is called from the guest through the function declared by
+/// `field_expr_declare!`, after `field_expr_init!` lists the expression names in config order.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CustomFieldExprExtension {
+    #[serde(default)]
+    pub expressions: Vec<CustomFieldExprConfig>,
+}
+
+impl CustomFieldExprExtension {
+    pub fn new(expressions: Vec<CustomFieldExprConfig>) -> eyre::Result<Self> {
+        let extension = Self { expressions };
+        extension.validate()?;
+        Ok(extension)
+    }
+
+    /// Checks that the expressions fit in the funct7 field of the instructions, have distinct
+    /// names and are well formed.
+    pub fn validate(&self) -> eyre::Result<()> {
+        let max_expressions = CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_EXPRESSIONS;
+        ensure!(
+            self.expressions.len() <= max_expressions,
+            "expected at most {max_expressions} custom field expressions, got {}",
+            self.expressions.len()
+        );
+        for (i, config) in self.expressions.iter().enumerate() {
+            ensure!(
+                self.expressions[..i].iter().all(|c| c.name != config.name),
+                "custom field expression `{}` is declared twice",
+                config.name
+            );
+            config.validate().map_err(|e| {
+                eyre::eyre!("invalid custom field expression `{}`: {e}", config.name)
+            })?;
+        }
+        Ok(())
+    }
+
+    // Generates a call to the field_expr_init! macro with expression names in the correct order
+    pub fn generate_field_expr_init(&self) -> String {
+        let names = self
+            .expressions
+            .iter()
+            .map(|expr| format!("\"{}\"", expr.name))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        format!("openvm_algebra_guest::moduli_macros::field_expr_init! {{ {names} }}")
+    }
+}
+
+#[derive(Clone, AnyEnum, Executor, MeteredExecutor, PreflightExecutor)]
+pub enum CustomFieldExprExtensionExecutor {
+    // 32 limbs prime
+    CustomFieldExprRv32_1x32(CustomFieldExprExecutor<1, 32>),
+    CustomFieldExprRv32_2x32(CustomFieldExprExecutor<2, 32>),
+    // 48 limbs prime
+    CustomFieldExprRv32_3x16(CustomFieldExprExecutor<3, 16>),
+    CustomFieldExprRv32_6x16(CustomFieldExprExecutor<6, 16>),
+}
+
+impl<F: PrimeField32> VmExecutionExtension<F> for CustomFieldExprExtension {
+    type Executor = CustomFieldExprExtensionExecutor;
+
+    fn extend_execution(
+        &self,
+        inventory: &mut ExecutorInventoryBuilder<F, CustomFieldExprExtensionExecutor>,
+    ) -> Result<(), ExecutorInventoryError> {
+        let invalid = |e: eyre::Report| ExecutorInventoryError::InvalidConfig(e.to_string());
+        self.validate().map_err(invalid)?;
+        let pointer_max_bits = inventory.pointer_max_bits();
+        // TODO: somehow get the range checker bus from `ExecutorInventory`
+        let dummy_range_checker_bus = VariableRangeCheckerBus::new(u16::MAX, 16);
+        for (i, config) in self.expressions.iter().enumerate() {
+            let start_offset =
+                Rv32CustomFieldExprOpcode::CLASS_OFFSET + i * Rv32CustomFieldExprOpcode::COUNT;
+            let executor = match config.adapter_shape().map_err(invalid)? {
+                CustomFieldExprShape::Rv32_1x32 => get_custom_field_expr_step(
+                    config,
+                    dummy_range_checker_bus,
+                    pointer_max_bits,
+                    start_offset,
+                )
+                .map(CustomFieldExprExtensionExecutor::CustomFieldExprRv32_1x32),
+                CustomFieldExprShape::Rv32_2x32 => get_custom_field_expr_step(
+                    config,
+                    dummy_range_checker_bus,
+                    pointer_max_bits,
+                    start_offset,
+                )
+                .map(CustomFieldExprExtensionExecutor::CustomFieldExprRv32_2x32),
+                CustomFieldExprShape::Rv32_3x16 => get_custom_field_expr_step(
+                    config,
+                    dummy_range_checker_bus,
+                    pointer_max_bits,
+                    start_offset,
+                )
+                .map(CustomFieldExprExtensionExecutor::CustomFieldExprRv32_3x16),
+                CustomFieldExprShape::Rv32_6x16 => get_custom_field_expr_step(
+                    config,
+                    dummy_range_checker_bus,
+                    pointer_max_bits,
+                    start_offset,
+                )
+                .map(CustomFieldExprExtensionExecutor::CustomFieldExprRv32_6x16),
+            }
+            .map_err(invalid)?;
+            inventory.add_executor(
+                executor,
+                ((Rv32CustomFieldExprOpcode::EVAL as usize)
+                    ..=(Rv32CustomFieldExprOpcode::SETUP_EVAL as usize))
+                    .map(|x| VmOpcode::from_usize(x + start_offset)),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<SC: StarkGenericConfig> VmCircuitExtension<SC> for CustomFieldExprExtension {
+    fn extend_circuit(&self, inventory: &mut AirInventory<SC>) -> Result<(), AirInventoryError> {
+        let invalid = |e: eyre::Report| AirInventoryError::InvalidConfig(e.to_string());
+        self.validate().map_err(invalid)?;
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = inventory.system().port();
+
+        let exec_bridge = ExecutionBridge::new(execution_bus, program_bus);
+        let range_checker_bus = inventory.range_checker().bus;
+        let pointer_max_bits = inventory.pointer_max_bits();
+
+        let bitwise_lu = {
+            // A trick to get around Rust's borrow rules
+            let existing_air = inventory.find_air::<BitwiseOperationLookupAir<8>>().next();
+            if let Some(air) = existing_air {
+                air.bus
+            } else {
+                let bus = BitwiseOperationLookupBus::new(inventory.new_bus_idx());
+                let air = BitwiseOperationLookupAir::<8>::new(bus);
+                inventory.add_air(air);
+                air.bus
+            }
+        };
+        for (i, config) in self.expressions.iter().enumerate() {
+            let start_offset =
+                Rv32CustomFieldExprOpcode::CLASS_OFFSET + i * Rv32CustomFieldExprOpcode::COUNT;
+            match config.adapter_shape().map_err(invalid)? {
+                CustomFieldExprShape::Rv32_1x32 => inventory.add_air(
+                    get_custom_field_expr_air::<1, 32>(
+                        exec_bridge,
+                        memory_bridge,
+                        config,
+                        range_checker_bus,
+                        bitwise_lu,
+                        pointer_max_bits,
+                        start_offset,
+                    )
+                    .map_err(invalid)?,
+                ),
+                CustomFieldExprShape::Rv32_2x32 => inventory.add_air(
+                    get_custom_field_expr_air::<2, 32>(
+                        exec_bridge,
+                        memory_bridge,
+                        config,
+                        range_checker_bus,
+                        bitwise_lu,
+                        pointer_max_bits,
+                        start_offset,
+                    )
+                    .map_err(invalid)?,
+                ),
+                CustomFieldExprShape::Rv32_3x16 => inventory.add_air(
+                    get_custom_field_expr_air::<3, 16>(
+                        exec_bridge,
+                        memory_bridge,
+                        config,
+                        range_checker_bus,
+                        bitwise_lu,
+                        pointer_max_bits,
+                        start_offset,
+                    )
+                    .map_err(invalid)?,
+                ),
+                CustomFieldExprShape::Rv32_6x16 => inventory.add_air(
+                    get_custom_field_expr_air::<6, 16>(
+                        exec_bridge,
+                        memory_bridge,
+                        config,
+                        range_checker_bus,
+                        bitwise_lu,
+                        pointer_max_bits,
+                        start_offset,
+                    )
+                    .map_err(invalid)?,
+                ),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// This implementation is specific to CpuBackend because the lookup chips (VariableRangeChecker,
+// BitwiseOperationLookupChip) are specific to CpuBackend.
+impl<E, SC, RA> VmProverExtension<E, RA, CustomFieldExprExtension> for AlgebraCpuProverExt
+where
+    SC: StarkGenericConfig,
+    E: StarkEngine<SC = SC, PB = CpuBackend<SC>, PD = CpuDevice<SC>>,
+    RA: RowMajorMatrixArena<Val<SC>>,
+    Val<SC>: PrimeField32,
+{
+    fn extend_prover(
+        &self,
+        extension: &CustomFieldExprExtension,
+        inventory: &mut ChipInventory<SC, RA, CpuBackend<SC>>,
+    ) -> Result<(), ChipInventoryError> {
+        let invalid = |e: eyre::Report| ChipInventoryError::InvalidConfig(e.to_string());
+        let range_checker = inventory.range_checker()?.clone();
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+        let mem_helper = SharedMemoryHelper::new(range_checker.clone(), timestamp_max_bits);
+        let bitwise_lu = {
+            let existing_chip = inventory
+                .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+                .next();
+            if let Some(chip) = existing_chip {
+                chip.clone()
+            } else {
+                let air: &BitwiseOperationLookupAir<8> = inventory.next_air()?;
+                let chip = Arc::new(BitwiseOperationLookupChip::new(air.bus));
+                inventory.add_periphery_chip(chip.clone());
+                chip
+            }
+        };
+        for config in extension.expressions.iter() {
+            match config.adapter_shape().map_err(invalid)? {
+                CustomFieldExprShape::Rv32_1x32 => {
+                    inventory.next_air::<CustomFieldExprAir<1, 32>>()?;
+                    let chip = get_custom_field_expr_chip::<Val<SC>, 1, 32>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(chip);
+                }
+                CustomFieldExprShape::Rv32_2x32 => {
+                    inventory.next_air::<CustomFieldExprAir<2, 32>>()?;
+                    let chip = get_custom_field_expr_chip::<Val<SC>, 2, 32>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(chip);
+                }
+                CustomFieldExprShape::Rv32_3x16 => {
+                    inventory.next_air::<CustomFieldExprAir<3, 16>>()?;
+                    let chip = get_custom_field_expr_chip::<Val<SC>, 3, 16>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(chip);
+                }
+                CustomFieldExprShape::Rv32_6x16 => {
+                    inventory.next_air::<CustomFieldExprAir<6, 16>>()?;
+                    let chip = get_custom_field_expr_chip::<Val<SC>, 6, 16>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(chip);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
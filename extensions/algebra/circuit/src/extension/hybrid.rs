@@ -31,9 +31,11 @@ use openvm_stark_backend::{p3_air::BaseAir, prover::types::AirProvingContext, Ch
 use strum::EnumCount;
 
 use crate::{
+    custom_expr_chip::{get_custom_field_expr_chip, CustomFieldExprAir, CustomFieldExprShape},
     fp2_chip::{get_fp2_addsub_chip, get_fp2_muldiv_chip, Fp2Air, Fp2Chip},
     modular_chip::*,
-    AlgebraRecord, Fp2Extension, ModularExtension, Rv32ModularConfig, Rv32ModularWithFp2Config,
+    AlgebraRecord, CustomFieldExprExtension, Fp2Extension, ModularExtension, Rv32ModularConfig,
+    Rv32ModularWithFp2Config,
 };
 
 #[derive(derive_new::new)]
@@ -383,6 +385,82 @@ impl VmProverExtension<GpuBabyBearPoseidon2Engine, DenseRecordArena, Fp2Extensio
     }
 }
 
+impl VmProverExtension<GpuBabyBearPoseidon2Engine, DenseRecordArena, CustomFieldExprExtension>
+    for AlgebraHybridProverExt
+{
+    fn extend_prover(
+        &self,
+        extension: &CustomFieldExprExtension,
+        inventory: &mut ChipInventory<SC, DenseRecordArena, GpuBackend>,
+    ) -> Result<(), ChipInventoryError> {
+        let invalid = |e: eyre::Report| ChipInventoryError::InvalidConfig(e.to_string());
+        let range_checker_gpu = get_inventory_range_checker(inventory);
+        let timestamp_max_bits = inventory.timestamp_max_bits();
+        let pointer_max_bits = inventory.airs().pointer_max_bits();
+        let range_checker = range_checker_gpu.cpu_chip.clone().unwrap();
+        let mem_helper = SharedMemoryHelper::new(range_checker.clone(), timestamp_max_bits);
+        let bitwise_lu_gpu = get_or_create_bitwise_op_lookup(inventory)?;
+        let bitwise_lu = bitwise_lu_gpu.cpu_chip.clone().unwrap();
+
+        // Custom field expressions have the same layout as the modular chips, so their CPU trace
+        // generation is reused through `HybridModularChip`.
+        for config in extension.expressions.iter() {
+            match config.adapter_shape().map_err(invalid)? {
+                CustomFieldExprShape::Rv32_1x32 => {
+                    inventory.next_air::<CustomFieldExprAir<1, 32>>()?;
+                    let chip = get_custom_field_expr_chip::<F, 1, 32>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(HybridModularChip::new(chip));
+                }
+                CustomFieldExprShape::Rv32_2x32 => {
+                    inventory.next_air::<CustomFieldExprAir<2, 32>>()?;
+                    let chip = get_custom_field_expr_chip::<F, 2, 32>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(HybridModularChip::new(chip));
+                }
+                CustomFieldExprShape::Rv32_3x16 => {
+                    inventory.next_air::<CustomFieldExprAir<3, 16>>()?;
+                    let chip = get_custom_field_expr_chip::<F, 3, 16>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(HybridModularChip::new(chip));
+                }
+                CustomFieldExprShape::Rv32_6x16 => {
+                    inventory.next_air::<CustomFieldExprAir<6, 16>>()?;
+                    let chip = get_custom_field_expr_chip::<F, 6, 16>(
+                        config,
+                        mem_helper.clone(),
+                        range_checker.clone(),
+                        bitwise_lu.clone(),
+                        pointer_max_bits,
+                    )
+                    .map_err(invalid)?;
+                    inventory.add_executor_chip(HybridModularChip::new(chip));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// This builder will do tracegen for the RV32IM extensions on GPU but the modular extensions on
 /// CPU.
 #[derive(Clone)]
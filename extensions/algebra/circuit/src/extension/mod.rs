@@ -24,6 +24,8 @@ mod modular;
 pub use modular::*;
 mod fp2;
 pub use fp2::*;
+mod custom_expr;
+pub use custom_expr::*;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "cuda")] {
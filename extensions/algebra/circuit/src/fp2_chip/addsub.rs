@@ -25,6 +25,21 @@ pub fn fp2_addsub_expr(
     config: ExprBuilderConfig,
     range_bus: VariableRangeCheckerBus,
 ) -> (FieldExpr, usize, usize) {
+    let (mut builder, is_add_flag, is_sub_flag) = fp2_addsub_builder(config, range_bus);
+    builder.minimize_columns();
+    (
+        FieldExpr::new(builder, range_bus, true),
+        is_add_flag,
+        is_sub_flag,
+    )
+}
+
+/// The expression of [fp2_addsub_expr] before [ExprBuilder::minimize_columns], with its two opcode
+/// flags.
+pub fn fp2_addsub_builder(
+    config: ExprBuilderConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> (ExprBuilder, usize, usize) {
     config.check_valid();
     let builder = ExprBuilder::new(config, range_bus.range_max_bits);
     let builder = Rc::new(RefCell::new(builder));
@@ -41,11 +56,7 @@ pub fn fp2_addsub_expr(
     z.save_output();
 
     let builder = builder.borrow().clone();
-    (
-        FieldExpr::new(builder, range_bus, true),
-        is_add_flag,
-        is_sub_flag,
-    )
+    (builder, is_add_flag, is_sub_flag)
 }
 
 // Input: Fp2 * 2
@@ -16,6 +16,7 @@ use openvm_circuit_primitives::{
         BitwiseOperationLookupAir, BitwiseOperationLookupBus, BitwiseOperationLookupChip,
         SharedBitwiseOperationLookupChip,
     },
+    var_range::VariableRangeCheckerBus,
 };
 use openvm_instructions::{
     instruction::Instruction,
@@ -23,7 +24,9 @@ use openvm_instructions::{
     LocalOpcode, VmOpcode,
 };
 use openvm_mod_circuit_builder::{
-    test_utils::generate_random_biguint, utils::biguint_to_limbs_vec, ExprBuilderConfig,
+    test_utils::{assert_minimized_expr, generate_random_biguint},
+    utils::biguint_to_limbs_vec,
+    ExprBuilderConfig,
 };
 use openvm_pairing_guest::{bls12_381::BLS12_381_MODULUS, bn254::BN254_MODULUS};
 use openvm_stark_backend::p3_field::FieldAlgebra;
@@ -32,8 +35,9 @@ use rand::{rngs::StdRng, Rng};
 use test_case::test_case;
 
 use crate::fp2_chip::{
-    get_fp2_addsub_air, get_fp2_addsub_chip, get_fp2_addsub_step, get_fp2_muldiv_air,
-    get_fp2_muldiv_chip, get_fp2_muldiv_step, Fp2Air, Fp2Chip, Fp2Executor,
+    fp2_addsub_builder, fp2_addsub_expr, fp2_muldiv_builder, fp2_muldiv_expr, get_fp2_addsub_air,
+    get_fp2_addsub_chip, get_fp2_addsub_step, get_fp2_muldiv_air, get_fp2_muldiv_chip,
+    get_fp2_muldiv_step, Fp2Air, Fp2Chip, Fp2Executor,
 };
 
 const LIMB_BITS: usize = 8;
@@ -571,3 +575,23 @@ mod cuda_tests {
             .unwrap();
     }
 }
+
+#[test_case(secp256k1_coord_prime(), 32)]
+#[test_case(BN254_MODULUS.clone(), 32)]
+#[test_case(BLS12_381_MODULUS.clone(), 48)]
+fn test_fp2_minimized(modulus: BigUint, num_limbs: usize) {
+    let range_bus = VariableRangeCheckerBus::new(1, 17);
+    let config = ExprBuilderConfig {
+        modulus,
+        num_limbs,
+        limb_bits: LIMB_BITS,
+    };
+
+    let (builder, _, _) = fp2_addsub_builder(config.clone(), range_bus);
+    let (expr, _, _) = fp2_addsub_expr(config.clone(), range_bus);
+    assert_minimized_expr(builder, &expr);
+
+    let (builder, _, _) = fp2_muldiv_builder(config.clone(), range_bus);
+    let (expr, _, _) = fp2_muldiv_expr(config, range_bus);
+    assert_minimized_expr(builder, &expr);
+}
@@ -12,6 +12,7 @@ use {
     openvm_rv32_adapters::Rv32VecHeapAdapterRecord,
 };
 
+pub mod custom_expr_chip;
 pub mod fp2_chip;
 pub mod modular_chip;
 
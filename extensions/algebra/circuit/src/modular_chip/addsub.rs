@@ -25,6 +25,21 @@ pub fn addsub_expr(
     config: ExprBuilderConfig,
     range_bus: VariableRangeCheckerBus,
 ) -> (FieldExpr, usize, usize) {
+    let (mut builder, is_add_flag, is_sub_flag) = addsub_builder(config, range_bus);
+    builder.minimize_columns();
+    (
+        FieldExpr::new(builder, range_bus, true),
+        is_add_flag,
+        is_sub_flag,
+    )
+}
+
+/// The expression of [addsub_expr] before [ExprBuilder::minimize_columns], with its two opcode
+/// flags.
+pub fn addsub_builder(
+    config: ExprBuilderConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> (ExprBuilder, usize, usize) {
     config.check_valid();
     let builder = ExprBuilder::new(config, range_bus.range_max_bits);
     let builder = Rc::new(RefCell::new(builder));
@@ -39,12 +54,7 @@ pub fn addsub_expr(
     let mut x6 = FieldVariable::select(is_add_flag, &x3, &x5);
     x6.save_output();
     let builder = (*builder).borrow().clone();
-
-    (
-        FieldExpr::new(builder, range_bus, true),
-        is_add_flag,
-        is_sub_flag,
-    )
+    (builder, is_add_flag, is_sub_flag)
 }
 
 fn gen_base_expr(
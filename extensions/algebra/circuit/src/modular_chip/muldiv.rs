@@ -25,6 +25,21 @@ pub fn muldiv_expr(
     config: ExprBuilderConfig,
     range_bus: VariableRangeCheckerBus,
 ) -> (FieldExpr, usize, usize) {
+    let (mut builder, is_mul_flag, is_div_flag) = muldiv_builder(config, range_bus);
+    builder.minimize_columns();
+    (
+        FieldExpr::new(builder, range_bus, true),
+        is_mul_flag,
+        is_div_flag,
+    )
+}
+
+/// The expression of [muldiv_expr] before [ExprBuilder::minimize_columns], with its two opcode
+/// flags.
+pub fn muldiv_builder(
+    config: ExprBuilderConfig,
+    range_bus: VariableRangeCheckerBus,
+) -> (ExprBuilder, usize, usize) {
     config.check_valid();
     let builder = ExprBuilder::new(config, range_bus.range_max_bits);
     let builder = Rc::new(RefCell::new(builder));
@@ -56,12 +71,7 @@ pub fn muldiv_expr(
     z.save_output();
 
     let builder = (*builder).borrow().clone();
-
-    (
-        FieldExpr::new(builder, range_bus, true),
-        is_mul_flag,
-        is_div_flag,
-    )
+    (builder, is_mul_flag, is_div_flag)
 }
 
 fn gen_base_expr(
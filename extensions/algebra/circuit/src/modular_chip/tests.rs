@@ -1213,3 +1213,47 @@ mod is_equal_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod minimize_tests {
+    use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+    use openvm_mod_circuit_builder::test_utils::assert_minimized_expr;
+
+    use super::*;
+    use crate::modular_chip::{addsub_builder, addsub_expr, muldiv_builder, muldiv_expr};
+
+    fn configs() -> Vec<ExprBuilderConfig> {
+        [
+            (secp256k1_coord_prime(), 32),
+            (BN254_MODULUS.clone(), 32),
+            (BLS12_381_MODULUS.clone(), 48),
+        ]
+        .into_iter()
+        .map(|(modulus, num_limbs)| ExprBuilderConfig {
+            modulus,
+            num_limbs,
+            limb_bits: LIMB_BITS,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_modular_addsub_minimized() {
+        let range_bus = VariableRangeCheckerBus::new(1, 17);
+        for config in configs() {
+            let (builder, _, _) = addsub_builder(config.clone(), range_bus);
+            let (expr, _, _) = addsub_expr(config, range_bus);
+            assert_minimized_expr(builder, &expr);
+        }
+    }
+
+    #[test]
+    fn test_modular_muldiv_minimized() {
+        let range_bus = VariableRangeCheckerBus::new(1, 17);
+        for config in configs() {
+            let (builder, _, _) = muldiv_builder(config.clone(), range_bus);
+            let (expr, _, _) = muldiv_expr(config, range_bus);
+            assert_minimized_expr(builder, &expr);
+        }
+    }
+}
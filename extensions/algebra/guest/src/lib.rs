@@ -5,6 +5,7 @@ extern crate self as openvm_algebra_guest;
 pub const OPCODE: u8 = 0x2b;
 pub const MODULAR_ARITHMETIC_FUNCT3: u8 = 0b000;
 pub const COMPLEX_EXT_FIELD_FUNCT3: u8 = 0b010;
+pub const CUSTOM_FIELD_EXPR_FUNCT3: u8 = 0b100;
 
 /// Modular arithmetic is configurable.
 /// The funct7 field equals `mod_idx * MODULAR_ARITHMETIC_MAX_KINDS + base_funct7`.
@@ -41,6 +42,22 @@ impl ComplexExtFieldBaseFunct7 {
     pub const COMPLEX_EXT_FIELD_MAX_KINDS: u8 = 8;
 }
 
+/// Custom field expressions are configurable.
+/// The funct7 field equals `expr_idx * CUSTOM_FIELD_EXPR_MAX_KINDS + base_funct7`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum CustomFieldExprBaseFunct7 {
+    Eval = 0,
+    Setup,
+}
+
+impl CustomFieldExprBaseFunct7 {
+    pub const CUSTOM_FIELD_EXPR_MAX_KINDS: u8 = 2;
+    /// Number of expressions whose instructions fit in the 7-bit funct7 field.
+    pub const CUSTOM_FIELD_EXPR_MAX_EXPRESSIONS: usize =
+        (1 << 7) / Self::CUSTOM_FIELD_EXPR_MAX_KINDS as usize;
+}
+
 /// Modular arithmetic traits for use with OpenVM intrinsics.
 extern crate alloc;
 
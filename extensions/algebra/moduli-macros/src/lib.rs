@@ -1177,3 +1177,236 @@ pub fn moduli_init(input: TokenStream) -> TokenStream {
         }
     })
 }
+
+/// This macro declares functions evaluating the custom field expressions of the VM config, see
+/// `CustomFieldExprExtension`. Usage:
+/// ```
+/// field_expr_declare! {
+///     mul_add2 { mod_type = Bn254Fp, inputs = 4, outputs = 2 },
+/// }
+/// ```
+/// This creates `pub fn mul_add2(x0: &Bn254Fp, x1: &Bn254Fp, x2: &Bn254Fp, x3: &Bn254Fp) ->
+/// (Bn254Fp, Bn254Fp)`, where `mod_type` is a type declared by [moduli_declare!] with the same
+/// modulus as the expression. Functions with a single output return the element itself.
+///
+/// The formula of the expression is only known to the VM, so the functions panic when not
+/// running in the zkVM.
+#[proc_macro]
+pub fn field_expr_declare(input: TokenStream) -> TokenStream {
+    let MacroArgs { items } = parse_macro_input!(input as MacroArgs);
+
+    let mut output = Vec::new();
+
+    let span = proc_macro::Span::call_site();
+
+    for item in items {
+        let name = item.name.clone();
+        let mut mod_type: Option<syn::Path> = None;
+        let mut num_inputs: Option<usize> = None;
+        let mut num_outputs: Option<usize> = None;
+        for param in item.params {
+            match param.name.to_string().as_str() {
+                "mod_type" => {
+                    if let syn::Expr::Path(path) = param.value {
+                        mod_type = Some(path.path);
+                    } else {
+                        return syn::Error::new_spanned(
+                            param.value,
+                            "Expected a type for macro argument `mod_type`",
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                }
+                "inputs" | "outputs" => {
+                    let value = if let syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(value),
+                        ..
+                    }) = &param.value
+                    {
+                        value.base10_parse::<usize>().ok()
+                    } else {
+                        None
+                    };
+                    let Some(value) = value else {
+                        return syn::Error::new_spanned(
+                            param.value,
+                            format!(
+                                "Expected an integer literal for macro argument `{}`",
+                                param.name
+                            ),
+                        )
+                        .to_compile_error()
+                        .into();
+                    };
+                    if param.name == "inputs" {
+                        num_inputs = Some(value);
+                    } else {
+                        num_outputs = Some(value);
+                    }
+                }
+                _ => {
+                    panic!("Unknown parameter {}", param.name);
+                }
+            }
+        }
+
+        // Parsing the parameters is over at this point
+
+        let mod_type = mod_type.expect("mod_type parameter is required");
+        let num_inputs = num_inputs.expect("inputs parameter is required");
+        let num_outputs = num_outputs.expect("outputs parameter is required");
+        assert!(
+            (1..=4).contains(&num_inputs),
+            "a custom field expression has 1 to 4 inputs"
+        );
+        assert!(
+            (1..=2).contains(&num_outputs),
+            "a custom field expression has 1 to 2 outputs"
+        );
+        // Number of field elements pointed to by each of `rs1`, `rs2` and `rd`.
+        let num_elems = if num_inputs <= 2 && num_outputs <= 1 {
+            1
+        } else {
+            2
+        };
+
+        let eval_extern_func = format_ident!("field_expr_eval_extern_func_{}", name);
+        let setup_extern_func = format_ident!("field_expr_setup_extern_func_{}", name);
+
+        let inputs = (0..num_inputs)
+            .map(|i| format_ident!("x{}", i))
+            .collect::<Vec<_>>();
+        let operand = |range: std::ops::Range<usize>| {
+            let elems = range.map(|i| {
+                if i < num_inputs {
+                    let input = &inputs[i];
+                    quote::quote! { #input.clone() }
+                } else {
+                    quote::quote! { <#mod_type as ::openvm_algebra_guest::IntMod>::ZERO }
+                }
+            });
+            quote::quote! { [#(#elems),*] }
+        };
+        let rs1 = operand(0..num_elems);
+        let rs2 = operand(num_elems..2 * num_elems);
+        let zeros = (1..num_elems)
+            .map(|_| quote::quote! { <#mod_type as ::openvm_algebra_guest::IntMod>::ZERO })
+            .collect::<Vec<_>>();
+        let outputs = (0..num_elems)
+            .map(|i| format_ident!("y{}", i))
+            .collect::<Vec<_>>();
+        let (output_type, output_value) = if num_outputs == 1 {
+            let y0 = &outputs[0];
+            (quote::quote! { #mod_type }, quote::quote! { #y0 })
+        } else {
+            (
+                quote::quote! { (#mod_type, #mod_type) },
+                quote::quote! { (#(#outputs),*) },
+            )
+        };
+
+        output.push(TokenStream::from(quote::quote_spanned! { span.into() =>
+            #[allow(unused_variables)]
+            pub fn #name(#(#inputs: &#mod_type),*) -> #output_type {
+                #[cfg(not(target_os = "zkvm"))]
+                {
+                    panic!(concat!("custom field expression `", stringify!(#name), "` can only be evaluated in the zkVM"));
+                }
+                #[cfg(target_os = "zkvm")]
+                {
+                    extern "C" {
+                        fn #eval_extern_func(rd: usize, rs1: usize, rs2: usize);
+                        fn #setup_extern_func(rd: usize, rs1: usize, rs2: usize);
+                    }
+
+                    static is_setup: ::openvm_algebra_guest::once_cell::race::OnceBool = ::openvm_algebra_guest::once_cell::race::OnceBool::new();
+                    is_setup.get_or_init(|| {
+                        // The setup reads the modulus as its first input and zeros otherwise.
+                        let rs1: [#mod_type; #num_elems] = [
+                            <#mod_type as ::openvm_algebra_guest::IntMod>::from_repr(<#mod_type as ::openvm_algebra_guest::IntMod>::MODULUS),
+                            #(#zeros),*
+                        ];
+                        let rs2: [#mod_type; #num_elems] = [<#mod_type as ::openvm_algebra_guest::IntMod>::ZERO, #(#zeros),*];
+                        let mut uninit: core::mem::MaybeUninit<[#mod_type; #num_elems]> = core::mem::MaybeUninit::uninit();
+                        unsafe {
+                            #setup_extern_func(
+                                uninit.as_mut_ptr() as usize,
+                                rs1.as_ptr() as usize,
+                                rs2.as_ptr() as usize,
+                            );
+                        }
+                        true
+                    });
+
+                    let rs1: [#mod_type; #num_elems] = #rs1;
+                    let rs2: [#mod_type; #num_elems] = #rs2;
+                    let mut uninit: core::mem::MaybeUninit<[#mod_type; #num_elems]> = core::mem::MaybeUninit::uninit();
+                    unsafe {
+                        #eval_extern_func(
+                            uninit.as_mut_ptr() as usize,
+                            rs1.as_ptr() as usize,
+                            rs2.as_ptr() as usize,
+                        );
+                    }
+                    let [#(#outputs),*] = unsafe { uninit.assume_init() };
+                    #output_value
+                }
+            }
+        }));
+    }
+
+    TokenStream::from_iter(output)
+}
+
+/// This macro generates the intrinsics of the custom field expressions of the VM config. The
+/// names must be listed in the order of the config. It is generated by `cargo openvm` together with
+/// [moduli_init!]. Usage:
+/// ```
+/// field_expr_init! { "mul_add2", "line_eval" }
+/// ```
+#[proc_macro]
+pub fn field_expr_init(input: TokenStream) -> TokenStream {
+    let ModuliDefine { items } = parse_macro_input!(input as ModuliDefine);
+
+    let mut externs = Vec::new();
+
+    let span = proc_macro::Span::call_site();
+    let num_exprs = items.len();
+
+    for (expr_idx, item) in items.into_iter().enumerate() {
+        let name = item.value();
+        for (op_type, base_funct7) in [("eval", "Eval"), ("setup", "Setup")] {
+            let func_name = syn::Ident::new(
+                &format!("field_expr_{}_extern_func_{}", op_type, name),
+                span.into(),
+            );
+            let base_funct7 = syn::Ident::new(base_funct7, span.into());
+            externs.push(quote::quote_spanned! { span.into() =>
+                #[no_mangle]
+                extern "C" fn #func_name(rd: usize, rs1: usize, rs2: usize) {
+                    openvm::platform::custom_insn_r!(
+                        opcode = ::openvm_algebra_guest::OPCODE,
+                        funct3 = ::openvm_algebra_guest::CUSTOM_FIELD_EXPR_FUNCT3 as usize,
+                        funct7 = ::openvm_algebra_guest::CustomFieldExprBaseFunct7::#base_funct7 as usize + #expr_idx * (::openvm_algebra_guest::CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_KINDS as usize),
+                        rd = In rd,
+                        rs1 = In rs1,
+                        rs2 = In rs2
+                    )
+                }
+            });
+        }
+    }
+
+    TokenStream::from(quote::quote_spanned! { span.into() =>
+        #[allow(non_snake_case)]
+        #[cfg(target_os = "zkvm")]
+        mod openvm_intrinsics_ffi_field_expr {
+            const _: () = assert!(
+                #num_exprs <= ::openvm_algebra_guest::CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_EXPRESSIONS,
+                "too many custom field expressions"
+            );
+            #(#externs)*
+        }
+    })
+}
@@ -1,6 +1,6 @@
 use openvm_algebra_guest::{
-    ComplexExtFieldBaseFunct7, ModArithBaseFunct7, COMPLEX_EXT_FIELD_FUNCT3,
-    MODULAR_ARITHMETIC_FUNCT3, OPCODE,
+    ComplexExtFieldBaseFunct7, CustomFieldExprBaseFunct7, ModArithBaseFunct7,
+    COMPLEX_EXT_FIELD_FUNCT3, CUSTOM_FIELD_EXPR_FUNCT3, MODULAR_ARITHMETIC_FUNCT3, OPCODE,
 };
 use openvm_instructions::{
     instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode, PhantomDiscriminant,
@@ -51,12 +51,28 @@ pub enum Fp2Opcode {
     SETUP_MULDIV,
 }
 
+/// Opcodes of a custom field expression. Each expression configured in the VM gets its own copy
+/// of these opcodes, the `i`-th expression being shifted by `i * COUNT`.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x800]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32CustomFieldExprOpcode {
+    EVAL,
+    SETUP_EVAL,
+}
+
 #[derive(Default)]
 pub struct ModularTranspilerExtension;
 
 #[derive(Default)]
 pub struct Fp2TranspilerExtension;
 
+#[derive(Default)]
+pub struct CustomFieldExprTranspilerExtension;
+
 impl<F: PrimeField32> TranspilerExtension<F> for ModularTranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
         if instruction_stream.is_empty() {
@@ -232,3 +248,61 @@ impl<F: PrimeField32> TranspilerExtension<F> for Fp2TranspilerExtension {
         instruction.map(TranspilerOutput::one_to_one)
     }
 }
+
+impl<F: PrimeField32> TranspilerExtension<F> for CustomFieldExprTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if opcode != OPCODE {
+            return None;
+        }
+        if funct3 != CUSTOM_FIELD_EXPR_FUNCT3 {
+            return None;
+        }
+
+        let instruction = {
+            assert!(
+                Rv32CustomFieldExprOpcode::COUNT
+                    <= CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_KINDS as usize
+            );
+            let dec_insn = RType::new(instruction_u32);
+            let base_funct7 =
+                (dec_insn.funct7 as u8) % CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_KINDS;
+            let expr_idx_shift = ((dec_insn.funct7 as u8)
+                / CustomFieldExprBaseFunct7::CUSTOM_FIELD_EXPR_MAX_KINDS)
+                as usize
+                * Rv32CustomFieldExprOpcode::COUNT;
+
+            match CustomFieldExprBaseFunct7::from_repr(base_funct7) {
+                Some(CustomFieldExprBaseFunct7::Setup) => Some(Instruction::new(
+                    VmOpcode::from_usize(
+                        Rv32CustomFieldExprOpcode::SETUP_EVAL
+                            .global_opcode()
+                            .as_usize()
+                            + expr_idx_shift,
+                    ),
+                    F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+                    F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs1),
+                    F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rs2),
+                    F::ONE, // d_as = 1
+                    F::TWO, // e_as = 2
+                    F::ZERO,
+                    F::ZERO,
+                )),
+                Some(CustomFieldExprBaseFunct7::Eval) => {
+                    let global_opcode = Rv32CustomFieldExprOpcode::EVAL as usize
+                        + Rv32CustomFieldExprOpcode::CLASS_OFFSET
+                        + expr_idx_shift;
+                    Some(from_r_type(global_opcode, 2, &dec_insn, true))
+                }
+                None => None,
+            }
+        };
+        instruction.map(TranspilerOutput::one_to_one)
+    }
+}
@@ -35,6 +35,16 @@ pub fn ec_add_ne_expr(
     config: ExprBuilderConfig, // The coordinate field.
     range_bus: VariableRangeCheckerBus,
 ) -> FieldExpr {
+    let mut builder = ec_add_ne_builder(config, range_bus);
+    builder.minimize_columns();
+    FieldExpr::new(builder, range_bus, true)
+}
+
+/// The expression of [ec_add_ne_expr] before [ExprBuilder::minimize_columns].
+pub fn ec_add_ne_builder(
+    config: ExprBuilderConfig, // The coordinate field.
+    range_bus: VariableRangeCheckerBus,
+) -> ExprBuilder {
     config.check_valid();
     let builder = ExprBuilder::new(config, range_bus.range_max_bits);
     let builder = Rc::new(RefCell::new(builder));
@@ -50,7 +60,7 @@ pub fn ec_add_ne_expr(
     y3.save_output();
 
     let builder = (*builder).borrow().clone();
-    FieldExpr::new(builder, range_bus, true)
+    builder
 }
 
 /// BLOCK_SIZE: how many cells do we read at a time, must be a power of 2.
@@ -36,13 +36,24 @@ pub fn ec_double_ne_expr(
     range_bus: VariableRangeCheckerBus,
     a_biguint: BigUint,
 ) -> FieldExpr {
+    let mut builder = ec_double_ne_builder(config, range_bus, a_biguint.clone());
+    builder.minimize_columns();
+    FieldExpr::new_with_setup_values(builder, range_bus, true, vec![a_biguint])
+}
+
+/// The expression of [ec_double_ne_expr] before [ExprBuilder::minimize_columns].
+pub fn ec_double_ne_builder(
+    config: ExprBuilderConfig, // The coordinate field.
+    range_bus: VariableRangeCheckerBus,
+    a_biguint: BigUint,
+) -> ExprBuilder {
     config.check_valid();
     let builder = ExprBuilder::new(config, range_bus.range_max_bits);
     let builder = Rc::new(RefCell::new(builder));
 
     let mut x1 = ExprBuilder::new_input(builder.clone());
     let mut y1 = ExprBuilder::new_input(builder.clone());
-    let a = ExprBuilder::new_const(builder.clone(), a_biguint.clone());
+    let a = ExprBuilder::new_const(builder.clone(), a_biguint);
     let is_double_flag = (*builder).borrow_mut().new_flag();
     // We need to prevent divide by zero when not double flag
     // (equivalently, when it is the setup opcode)
@@ -58,7 +69,7 @@ pub fn ec_double_ne_expr(
     y3.save_output();
 
     let builder = (*builder).borrow().clone();
-    FieldExpr::new_with_setup_values(builder, range_bus, true, vec![a_biguint])
+    builder
 }
 
 /// BLOCK_SIZE: how many cells do we read at a time, must be a power of 2.
@@ -1113,3 +1113,50 @@ mod ec_double_tests {
         assert_eq!(r[2], expected_double_y);
     }
 }
+
+mod minimize_tests {
+    use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+    use openvm_mod_circuit_builder::test_utils::assert_minimized_expr;
+
+    use super::*;
+    use crate::{ec_add_ne_builder, ec_add_ne_expr, ec_double_ne_builder, ec_double_ne_expr};
+
+    fn curves() -> Vec<(ExprBuilderConfig, BigUint)> {
+        let secp256r1_a = BigUint::from_bytes_le(&(-secp256r1::Fp::from(3)).to_bytes());
+        [
+            (secp256k1_coord_prime(), 32, BigUint::zero()),
+            (secp256r1_coord_prime(), 32, secp256r1_a),
+            (BLS12_381_MODULUS.clone(), 48, BigUint::zero()),
+        ]
+        .into_iter()
+        .map(|(modulus, num_limbs, a)| {
+            let config = ExprBuilderConfig {
+                modulus,
+                num_limbs,
+                limb_bits: LIMB_BITS,
+            };
+            (config, a)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_ec_add_ne_minimized() {
+        let range_bus = VariableRangeCheckerBus::new(1, 17);
+        for (config, _) in curves() {
+            let builder = ec_add_ne_builder(config.clone(), range_bus);
+            let expr = ec_add_ne_expr(config, range_bus);
+            assert_minimized_expr(builder, &expr);
+        }
+    }
+
+    #[test]
+    fn test_ec_double_minimized() {
+        let range_bus = VariableRangeCheckerBus::new(1, 17);
+        for (config, a) in curves() {
+            let builder = ec_double_ne_builder(config.clone(), range_bus, a.clone());
+            let expr = ec_double_ne_expr(config, range_bus, a);
+            assert_minimized_expr(builder, &expr);
+        }
+    }
+}
@@ -281,6 +281,7 @@ fn test_vm_initial_memory() {
         pc_start: 0,
         init_memory,
         fn_bounds: Default::default(),
+        heap_symbols: Default::default(),
     };
     air_test(NativeBuilder::default(), config, exe);
 }
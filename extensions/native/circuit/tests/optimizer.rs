@@ -0,0 +1,131 @@
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::{AsmBuilder, AsmConfig},
+    conversion::CompilerOptions,
+    ir::{optimize, Builder, Ext, ExtConst, Felt, OptimizationOptions, Var},
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, FieldAlgebra};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+/// Builds a program with constant expressions, repeated subexpressions, copies, dead values and
+/// loop-invariant arithmetic, mixed with loop counters and branches that must not be touched.
+fn build_program(builder: &mut Builder<AsmConfig<F, EF>>) {
+    let n: Var<_> = builder.eval(F::from_canonical_u32(16));
+    let two: Felt<_> = builder.eval(F::TWO);
+    let three: Felt<_> = builder.eval(F::from_canonical_u32(3));
+
+    // Constant expressions and common subexpressions.
+    let a: Felt<_> = builder.eval(two * three + F::ONE);
+    let b: Felt<_> = builder.eval(two * three + F::ONE);
+    builder.assert_felt_eq(a, b);
+    builder.assert_felt_eq(a, F::from_canonical_u32(7));
+    let quotient: Felt<_> = builder.eval(a / two);
+    builder.assert_felt_eq(quotient * F::TWO, F::from_canonical_u32(7));
+
+    // A dead value.
+    let _unused: Felt<_> = builder.eval(a * b + three);
+
+    // Copies.
+    let copy: Felt<_> = builder.eval(a);
+    let copy_of_copy: Felt<_> = builder.eval(copy);
+    builder.assert_felt_eq(copy_of_copy, a);
+
+    // Extension field arithmetic.
+    let x: Ext<_, _> = builder.eval(EF::from_canonical_u32(5).cons());
+    let y: Ext<_, _> = builder.eval(x * x + x);
+    let z: Ext<_, _> = builder.eval(x * x + x);
+    builder.assert_ext_eq(y, z);
+    builder.assert_ext_eq(y, EF::from_canonical_u32(30).cons());
+
+    // Loop-invariant arithmetic next to a loop-carried accumulator.
+    let sum: Felt<_> = builder.eval(F::ZERO);
+    let count: Var<_> = builder.eval(F::ZERO);
+    builder.range(0, n).for_each(|_, builder| {
+        let scale: Felt<_> = builder.eval(two * three);
+        builder.assign(&sum, sum + scale);
+        builder.assign(&count, count + F::ONE);
+    });
+    builder.assert_felt_eq(sum, F::from_canonical_u32(96));
+    builder.assert_var_eq(count, n);
+
+    // Values assigned in branches.
+    let flag: Var<_> = builder.eval(F::ONE);
+    let chosen: Felt<_> = builder.uninit();
+    builder.if_eq(flag, F::ONE).then_or_else(
+        |builder| builder.assign(&chosen, a + F::ONE),
+        |builder| builder.assign(&chosen, a),
+    );
+    builder.assert_felt_eq(chosen, F::from_canonical_u32(8));
+
+    builder.halt();
+}
+
+#[test]
+fn test_optimizer_preserves_semantics() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    build_program(&mut builder);
+    let baseline = builder.clone().compile_isa();
+    let optimized = builder.compile_isa_with_options(
+        CompilerOptions::default().with_optimizations(OptimizationOptions::all()),
+    );
+    assert!(optimized.len() < baseline.len());
+
+    execute_program(baseline, vec![]);
+    execute_program(optimized, vec![]);
+}
+
+#[test]
+fn test_optimizer_passes() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    build_program(&mut builder);
+
+    let mut operations = builder.operations.clone();
+    let stats = optimize(&mut operations, &OptimizationOptions::all());
+    assert!(stats.constants_folded > 0);
+    assert!(stats.copies_propagated > 0);
+    assert!(stats.dead_instructions_removed > 0);
+    assert!(stats.subexpressions_eliminated > 0);
+    assert!(stats.loop_invariants_hoisted > 0);
+    assert!(operations.vec.len() < builder.operations.vec.len());
+
+    let disabled = optimize(
+        &mut builder.operations.clone(),
+        &OptimizationOptions::default(),
+    );
+    assert_eq!(disabled, Default::default());
+}
+
+#[test]
+fn test_optimizer_single_pass() {
+    for options in [
+        OptimizationOptions {
+            constant_folding: true,
+            ..Default::default()
+        },
+        OptimizationOptions {
+            copy_propagation: true,
+            ..Default::default()
+        },
+        OptimizationOptions {
+            dead_code_elimination: true,
+            ..Default::default()
+        },
+        OptimizationOptions {
+            common_subexpression_elimination: true,
+            ..Default::default()
+        },
+        OptimizationOptions {
+            loop_invariant_hoisting: true,
+            ..Default::default()
+        },
+    ] {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        build_program(&mut builder);
+        let program = builder
+            .compile_isa_with_options(CompilerOptions::default().with_optimizations(options));
+        execute_program(program, vec![]);
+    }
+}
@@ -0,0 +1,134 @@
+use openvm_instructions::{program::Program, LocalOpcode};
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    ir::{Felt, Var},
+    text::{assemble, disassemble, AssembleErrorKind},
+    FieldArithmeticOpcode, NativeBranchEqualOpcode,
+};
+use openvm_rv32im_transpiler::BranchEqualOpcode;
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, FieldAlgebra};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+const COUNTDOWN: &str = "
+; counts mem[8] down from 10 to 0
+    ADDF 8, 0, 10, 4, 0      ; set counter
+loop:
+    SUBF 8, 8, 1, 4, 4, 0
+    BNE 8, 0, loop, 4, 0
+    TERMINATE
+";
+
+#[test]
+fn test_assemble() {
+    let program = assemble::<F>(COUNTDOWN).unwrap();
+    assert_eq!(program.pc_base, 0);
+    assert_eq!(program.len(), 4);
+
+    let instructions = program.defined_instructions();
+    assert_eq!(
+        instructions[0].opcode,
+        FieldArithmeticOpcode::ADD.global_opcode()
+    );
+    assert_eq!(
+        instructions[2].opcode,
+        NativeBranchEqualOpcode(BranchEqualOpcode::BNE).global_opcode()
+    );
+    assert_eq!(instructions[2].c, -F::from_canonical_u32(4));
+    let debug_info = program.instructions_and_debug_infos[0]
+        .as_ref()
+        .unwrap()
+        .1
+        .as_ref()
+        .unwrap();
+    assert_eq!(debug_info.dsl_instruction, "set counter");
+    assert!(program.instructions_and_debug_infos[1]
+        .as_ref()
+        .unwrap()
+        .1
+        .is_none());
+
+    execute_program(program, vec![]);
+}
+
+#[test]
+fn test_disassemble_roundtrip() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let n: Var<_> = builder.eval(F::from_canonical_u32(10));
+    let sum: Felt<_> = builder.eval(F::ZERO);
+    builder.range(0, n).for_each(|_, builder| {
+        builder.assign(&sum, sum + F::TWO);
+    });
+    builder.if_eq(n, F::from_canonical_u32(10)).then(|builder| {
+        builder.assert_felt_eq(sum, F::from_canonical_u32(20));
+    });
+    builder.halt();
+    let program = builder.compile_isa();
+
+    let text = disassemble(&program);
+    assert!(text.contains("TERMINATE"));
+    assert!(text.lines().any(|line| line.starts_with('L')));
+
+    let assembled = assemble::<F>(&text).unwrap();
+    assert_eq!(
+        assembled.defined_instructions(),
+        program.defined_instructions()
+    );
+    assert_eq!(disassemble(&assembled), text);
+    execute_program(assembled, vec![]);
+}
+
+#[test]
+fn test_pc_base_and_skip() {
+    let source = ".pc_base 0x100\n.skip 2\nend:\n    JAL 8, end, 0, 4\n.skip 1\n";
+    let program = assemble::<F>(source).unwrap();
+    assert_eq!(program.pc_base, 0x100);
+    assert_eq!(program.len(), 4);
+    assert!(program.instructions_and_debug_infos[0].is_none());
+    assert_eq!(program.defined_instructions()[0].b, F::ZERO);
+
+    let roundtrip: Program<F> = assemble(&disassemble(&program)).unwrap();
+    assert_eq!(roundtrip.pc_base, program.pc_base);
+    assert_eq!(roundtrip.len(), program.len());
+    assert_eq!(
+        roundtrip.defined_instructions(),
+        program.defined_instructions()
+    );
+}
+
+#[test]
+fn test_assemble_errors() {
+    let cases = [
+        ("FOO 1, 2", AssembleErrorKind::UnknownOpcode("FOO".into())),
+        (
+            "ADDF 1, 2, 3, 4, 5, 6, 7, 8",
+            AssembleErrorKind::TooManyOperands(8),
+        ),
+        ("ADDF 1, , 3", AssembleErrorKind::InvalidOperand("".into())),
+        (
+            "ADDF 2013265921",
+            AssembleErrorKind::InvalidOperand("2013265921".into()),
+        ),
+        (
+            "JAL 0, nowhere",
+            AssembleErrorKind::UndefinedLabel("nowhere".into()),
+        ),
+        (
+            "a:\na:\nTERMINATE",
+            AssembleErrorKind::DuplicateLabel("a".into()),
+        ),
+        (
+            ".align 4",
+            AssembleErrorKind::UnknownDirective(".align".into()),
+        ),
+        ("TERMINATE\n.pc_base 4", AssembleErrorKind::MisplacedPcBase),
+    ];
+    for (source, kind) in cases {
+        let err = assemble::<F>(source).unwrap_err();
+        assert_eq!(err.kind, kind, "{source}");
+    }
+    assert_eq!(assemble::<F>("\n\nFOO").unwrap_err().line, 3);
+}
@@ -0,0 +1,458 @@
+//! BabyBear and its degree 4 extension emulated in R1CS over BN254.
+//!
+//! This follows the halo2 [BabyBearChip](crate::constraints::halo2::baby_bear::BabyBearChip):
+//! an element is a signed integer represented as a BN254 element, with a bound on its bit size.
+//! Arithmetic is lazy, and elements are only reduced modulo the BabyBear prime when the bound
+//! would get too close to the BN254 modulus.
+
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::SynthesisError;
+use itertools::Itertools;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use openvm_stark_backend::p3_field::{
+    extension::{BinomialExtensionField, BinomiallyExtendable},
+    Field, FieldAlgebra, FieldExtensionAlgebra, PrimeField32,
+};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+use super::r1cs::{bigint_to_fr, biguint_to_fr, fr_to_bigint, AssignedValue, R1csContext};
+
+pub(crate) const BABYBEAR_MAX_BITS: usize = 31;
+// bits reserved so that if we do lazy range checking, we still have a valid result
+// the first reserved bit is so that we can represent negative numbers
+// the second is to accommodate lazy range checking
+const RESERVED_HIGH_BITS: usize = 2;
+/// Largest bit size of the absolute value of an unreduced element.
+const MAX_BITS: usize = Fr::MODULUS_BIT_SIZE as usize - 1 - RESERVED_HIGH_BITS;
+
+type Result<T> = std::result::Result<T, SynthesisError>;
+
+#[derive(Clone, Debug)]
+pub struct AssignedBabyBear {
+    /// Logically `value` is a signed integer represented as a BN254 element, with
+    /// `|value| < 2^max_bits`.
+    pub value: AssignedValue,
+    pub max_bits: usize,
+}
+
+impl AssignedBabyBear {
+    pub fn to_baby_bear(&self) -> BabyBear {
+        let b_int = fr_to_bigint(&self.value.value).mod_floor(&BigInt::from(BabyBear::ORDER_U32));
+        BabyBear::from_canonical_u32(b_int.try_into().unwrap())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BabyBearChip;
+
+impl BabyBearChip {
+    pub fn load_witness(&self, ctx: &R1csContext, value: BabyBear) -> Result<AssignedBabyBear> {
+        let value = ctx.witness(Fr::from(value.as_canonical_u32()))?;
+        ctx.range_check(&value, BABYBEAR_MAX_BITS)?;
+        Ok(AssignedBabyBear {
+            value,
+            max_bits: BABYBEAR_MAX_BITS,
+        })
+    }
+
+    pub fn load_constant(&self, value: BabyBear) -> AssignedBabyBear {
+        let value = value.as_canonical_u32();
+        AssignedBabyBear {
+            value: AssignedValue::constant(Fr::from(value)),
+            max_bits: (u32::BITS - value.leading_zeros()) as usize,
+        }
+    }
+
+    /// Returns the canonical representative of `a`, i.e. an element in `[0, p)`.
+    pub fn reduce(&self, ctx: &R1csContext, a: &AssignedBabyBear) -> Result<AssignedBabyBear> {
+        let (_, r) = signed_div_mod(ctx, &a.value, a.max_bits)?;
+        let r = AssignedBabyBear {
+            value: r,
+            max_bits: BABYBEAR_MAX_BITS,
+        };
+        debug_assert_eq!(a.to_baby_bear(), r.to_baby_bear());
+        Ok(r)
+    }
+
+    /// Reduce max_bits if possible. This function doesn't guarantee that the actual value is within
+    /// BabyBear.
+    pub fn reduce_max_bits(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBear> {
+        if a.max_bits > BABYBEAR_MAX_BITS {
+            self.reduce(ctx, a)
+        } else {
+            Ok(a.clone())
+        }
+    }
+
+    /// Reduces `a` and then `b` until `a.max_bits + b.max_bits + extra_bits` fits.
+    fn fit_product(
+        &self,
+        ctx: &R1csContext,
+        a: &mut AssignedBabyBear,
+        b: &mut AssignedBabyBear,
+        extra_bits: usize,
+    ) -> Result<()> {
+        if a.max_bits < b.max_bits {
+            std::mem::swap(a, b);
+        }
+        if a.max_bits + b.max_bits + extra_bits > MAX_BITS {
+            *a = self.reduce(ctx, a)?;
+            if a.max_bits + b.max_bits + extra_bits > MAX_BITS {
+                *b = self.reduce(ctx, b)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reduces `a` and then `b` until their sum fits.
+    fn fit_sum(
+        &self,
+        ctx: &R1csContext,
+        a: &mut AssignedBabyBear,
+        b: &mut AssignedBabyBear,
+    ) -> Result<()> {
+        if a.max_bits.max(b.max_bits) + 1 > MAX_BITS {
+            *a = self.reduce(ctx, a)?;
+            if a.max_bits.max(b.max_bits) + 1 > MAX_BITS {
+                *b = self.reduce(ctx, b)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBear,
+        b: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBear> {
+        let (mut a, mut b) = (a.clone(), b.clone());
+        self.fit_sum(ctx, &mut a, &mut b)?;
+        let c = AssignedBabyBear {
+            value: ctx.add(&a.value, &b.value)?,
+            max_bits: a.max_bits.max(b.max_bits) + 1,
+        };
+        debug_assert_eq!(c.to_baby_bear(), a.to_baby_bear() + b.to_baby_bear());
+        Ok(c)
+    }
+
+    pub fn neg(&self, ctx: &R1csContext, a: &AssignedBabyBear) -> Result<AssignedBabyBear> {
+        Ok(AssignedBabyBear {
+            value: ctx.neg(&a.value)?,
+            max_bits: a.max_bits,
+        })
+    }
+
+    pub fn sub(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBear,
+        b: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBear> {
+        let (mut a, mut b) = (a.clone(), b.clone());
+        self.fit_sum(ctx, &mut a, &mut b)?;
+        let c = AssignedBabyBear {
+            value: ctx.sub(&a.value, &b.value)?,
+            max_bits: a.max_bits.max(b.max_bits) + 1,
+        };
+        debug_assert_eq!(c.to_baby_bear(), a.to_baby_bear() - b.to_baby_bear());
+        Ok(c)
+    }
+
+    pub fn mul(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBear,
+        b: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBear> {
+        let (mut a, mut b) = (a.clone(), b.clone());
+        self.fit_product(ctx, &mut a, &mut b, 0)?;
+        let c = AssignedBabyBear {
+            value: ctx.mul(&a.value, &b.value)?,
+            max_bits: a.max_bits + b.max_bits,
+        };
+        debug_assert_eq!(c.to_baby_bear(), a.to_baby_bear() * b.to_baby_bear());
+        Ok(c)
+    }
+
+    pub fn div(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBear,
+        b: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBear> {
+        let b_inv = b.to_baby_bear().try_inverse().unwrap();
+        let c = self.load_witness(ctx, a.to_baby_bear() * b_inv)?;
+        // constraint a = b * c (mod p)
+        let prod = self.mul(ctx, b, &c)?;
+        self.assert_equal(ctx, a, &prod)?;
+        Ok(c)
+    }
+
+    pub fn select(
+        &self,
+        ctx: &R1csContext,
+        cond: &AssignedValue,
+        a: &AssignedBabyBear,
+        b: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBear> {
+        Ok(AssignedBabyBear {
+            value: ctx.select(cond, &a.value, &b.value)?,
+            max_bits: a.max_bits.max(b.max_bits),
+        })
+    }
+
+    pub fn assert_zero(&self, ctx: &R1csContext, a: &AssignedBabyBear) -> Result<()> {
+        debug_assert_eq!(a.to_baby_bear(), BabyBear::ZERO);
+        let b = BigInt::from(BabyBear::ORDER_U32);
+        let (div, _) = fr_to_bigint(&a.value.value).div_mod_floor(&b);
+        let div = ctx.witness(bigint_to_fr(&div))?;
+        let prod = ctx.scale(&div, Fr::from(BabyBear::ORDER_U32))?;
+        ctx.enforce_equal(&a.value, &prod)?;
+        range_check_signed(ctx, &div, a.max_bits)
+    }
+
+    pub fn assert_equal(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBear,
+        b: &AssignedBabyBear,
+    ) -> Result<()> {
+        let diff = self.sub(ctx, a, b)?;
+        self.assert_zero(ctx, &diff)
+    }
+}
+
+/// Constrains and returns `(c, r)` such that `a = BabyBear::ORDER_U32 * c + r` and `0 <= r < p`.
+/// `|a|` must be less than `2^a_num_bits`. See the halo2 version for the proof of soundness.
+fn signed_div_mod(
+    ctx: &R1csContext,
+    a: &AssignedValue,
+    a_num_bits: usize,
+) -> Result<(AssignedValue, AssignedValue)> {
+    let b = BigInt::from(BabyBear::ORDER_U32);
+    let a_val = fr_to_bigint(&a.value);
+    assert!(a_val.bits() <= a_num_bits as u64);
+    let (div, rem) = a_val.div_mod_floor(&b);
+    let div = ctx.witness(bigint_to_fr(&div))?;
+    let rem = ctx.witness(bigint_to_fr(&rem))?;
+    let recomposed = ctx.linear_combination(
+        &[
+            (Fr::from(BabyBear::ORDER_U32), &div),
+            (Fr::from(1u64), &rem),
+        ],
+        Fr::from(0u64),
+    )?;
+    ctx.enforce_equal(&recomposed, a)?;
+    range_check_signed(ctx, &div, a_num_bits)?;
+    let rem_bits = ctx.to_bits(&rem, BABYBEAR_MAX_BITS)?;
+    ctx.enforce_bits_at_most(&rem_bits, &BigUint::from(BabyBear::ORDER_U32 - 1))?;
+    Ok((div, rem))
+}
+
+/// Constrains `|div| <= 2^a_num_bits / p`, where `div` is the quotient of a division by `p`.
+fn range_check_signed(ctx: &R1csContext, div: &AssignedValue, a_num_bits: usize) -> Result<()> {
+    let bound = (BigUint::from(1u32) << a_num_bits) / BigUint::from(BabyBear::ORDER_U32);
+    let shifted_div = ctx.add_constant(div, biguint_to_fr(&bound))?;
+    ctx.range_check(&shifted_div, (bound * 2u32 + 1u32).bits() as usize)
+}
+
+// irred poly is x^4 - 11
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BabyBearExt4Chip {
+    pub base: BabyBearChip,
+}
+
+#[derive(Clone, Debug)]
+pub struct AssignedBabyBearExt4(pub [AssignedBabyBear; 4]);
+pub type BabyBearExt4 = BinomialExtensionField<BabyBear, 4>;
+
+impl AssignedBabyBearExt4 {
+    pub fn to_extension_field(&self) -> BabyBearExt4 {
+        let b_val = (0..4).map(|i| self.0[i].to_baby_bear()).collect_vec();
+        BabyBearExt4::from_base_slice(&b_val)
+    }
+}
+
+impl BabyBearExt4Chip {
+    fn map(
+        a: &AssignedBabyBearExt4,
+        mut f: impl FnMut(&AssignedBabyBear) -> Result<AssignedBabyBear>,
+    ) -> Result<AssignedBabyBearExt4> {
+        let coeffs = a.0.iter().map(&mut f).collect::<Result<Vec<_>>>()?;
+        Ok(AssignedBabyBearExt4(coeffs.try_into().unwrap()))
+    }
+
+    fn zip(
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+        mut f: impl FnMut(&AssignedBabyBear, &AssignedBabyBear) -> Result<AssignedBabyBear>,
+    ) -> Result<AssignedBabyBearExt4> {
+        let coeffs =
+            a.0.iter()
+                .zip(b.0.iter())
+                .map(|(a, b)| f(a, b))
+                .collect::<Result<Vec<_>>>()?;
+        Ok(AssignedBabyBearExt4(coeffs.try_into().unwrap()))
+    }
+
+    pub fn load_witness(
+        &self,
+        ctx: &R1csContext,
+        value: BabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        let coeffs = value
+            .as_base_slice()
+            .iter()
+            .map(|x| self.base.load_witness(ctx, *x))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AssignedBabyBearExt4(coeffs.try_into().unwrap()))
+    }
+
+    pub fn load_constant(&self, value: BabyBearExt4) -> AssignedBabyBearExt4 {
+        AssignedBabyBearExt4(
+            value
+                .as_base_slice()
+                .iter()
+                .map(|x| self.base.load_constant(*x))
+                .collect_vec()
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    pub fn add(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        Self::zip(a, b, |a, b| self.base.add(ctx, a, b))
+    }
+
+    pub fn neg(&self, ctx: &R1csContext, a: &AssignedBabyBearExt4) -> Result<AssignedBabyBearExt4> {
+        Self::map(a, |x| self.base.neg(ctx, x))
+    }
+
+    pub fn sub(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        Self::zip(a, b, |a, b| self.base.sub(ctx, a, b))
+    }
+
+    pub fn scalar_mul(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBear,
+    ) -> Result<AssignedBabyBearExt4> {
+        Self::map(a, |x| self.base.mul(ctx, x, b))
+    }
+
+    pub fn select(
+        &self,
+        ctx: &R1csContext,
+        cond: &AssignedValue,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        Self::zip(a, b, |a, b| self.base.select(ctx, cond, a, b))
+    }
+
+    pub fn assert_zero(&self, ctx: &R1csContext, a: &AssignedBabyBearExt4) -> Result<()> {
+        for x in a.0.iter() {
+            self.base.assert_zero(ctx, x)?;
+        }
+        Ok(())
+    }
+
+    pub fn assert_equal(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+    ) -> Result<()> {
+        for (a, b) in a.0.iter().zip(b.0.iter()) {
+            self.base.assert_equal(ctx, a, b)?;
+        }
+        Ok(())
+    }
+
+    pub fn mul(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        // Each coefficient of the result sums at most 7 products, 3 of them multiplied by W < 2^4.
+        const EXTRA_BITS: usize = 7;
+        let (mut a, mut b) = (a.clone(), b.clone());
+        let max_bits = |x: &AssignedBabyBearExt4| x.0.iter().map(|x| x.max_bits).max().unwrap();
+        while max_bits(&a) + max_bits(&b) + EXTRA_BITS > MAX_BITS {
+            let x = if max_bits(&a) >= max_bits(&b) {
+                &mut a
+            } else {
+                &mut b
+            };
+            let i = (0..4).max_by_key(|&i| x.0[i].max_bits).unwrap();
+            x.0[i] = self.base.reduce(ctx, &x.0[i])?;
+        }
+        let w = Fr::from(<BabyBear as BinomiallyExtendable<4>>::W.as_canonical_u32());
+        let mut terms: [Vec<(Fr, AssignedValue)>; 4] = Default::default();
+        for i in 0..4 {
+            for j in 0..4 {
+                let prod = ctx.mul(&a.0[i].value, &b.0[j].value)?;
+                let coeff = if i + j < 4 { Fr::from(1u64) } else { w };
+                terms[(i + j) % 4].push((coeff, prod));
+            }
+        }
+        let max_bits = max_bits(&a) + max_bits(&b) + EXTRA_BITS;
+        let coeffs = terms
+            .iter()
+            .map(|terms| {
+                let terms = terms.iter().map(|(c, x)| (*c, x)).collect_vec();
+                Ok(AssignedBabyBear {
+                    value: ctx.linear_combination(&terms, Fr::from(0u64))?,
+                    max_bits,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let c = AssignedBabyBearExt4(coeffs.try_into().unwrap());
+        debug_assert_eq!(
+            c.to_extension_field(),
+            a.to_extension_field() * b.to_extension_field()
+        );
+        Ok(c)
+    }
+
+    pub fn div(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+        b: &AssignedBabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        let b_inv = b.to_extension_field().try_inverse().unwrap();
+        let c = self.load_witness(ctx, a.to_extension_field() * b_inv)?;
+        // constraint a = b * c
+        let prod = self.mul(ctx, b, &c)?;
+        self.assert_equal(ctx, a, &prod)?;
+        Ok(c)
+    }
+
+    pub fn reduce_max_bits(
+        &self,
+        ctx: &R1csContext,
+        a: &AssignedBabyBearExt4,
+    ) -> Result<AssignedBabyBearExt4> {
+        Self::map(a, |x| self.base.reduce_max_bits(ctx, x))
+    }
+}
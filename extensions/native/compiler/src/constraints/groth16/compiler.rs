@@ -0,0 +1,471 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::LazyLock,
+};
+
+use ark_bn254::Fr;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use itertools::Itertools;
+use num_bigint::BigUint;
+use openvm_stark_backend::p3_field::{Field, FieldAlgebra, PrimeField};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, p3_bn254_fr::Bn254Fr};
+
+use crate::{
+    constraints::groth16::{
+        baby_bear::{
+            AssignedBabyBear, AssignedBabyBearExt4, BabyBearChip, BabyBearExt4, BabyBearExt4Chip,
+            BABYBEAR_MAX_BITS,
+        },
+        poseidon2_perm::{Poseidon2Params, Poseidon2State},
+        r1cs::{biguint_to_fr, AssignedValue, R1csContext},
+    },
+    ir::{Config, DslIr, TracedVec, Witness},
+};
+
+const POSEIDON2_T: usize = 3;
+static POSEIDON2_PARAMS: LazyLock<Poseidon2Params<POSEIDON2_T>> = LazyLock::new(|| {
+    use zkhash::{
+        ark_ff::{BigInteger, PrimeField as _},
+        fields::bn256::FpBN256 as ark_FpBN256,
+        poseidon2::poseidon2_instance_bn256::{MAT_DIAG3_M_1, RC3},
+    };
+
+    fn convert_fr(input: ark_FpBN256) -> Fr {
+        biguint_to_fr(&BigUint::from_bytes_le(&input.into_bigint().to_bytes_le()))
+    }
+    const T: usize = 3;
+    let rounds_f = 8;
+    let rounds_p = 56;
+    let mut round_constants: Vec<[Fr; T]> = RC3
+        .iter()
+        .map(|vec| {
+            vec.iter()
+                .cloned()
+                .map(convert_fr)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        })
+        .collect();
+
+    let rounds_f_beginning = rounds_f / 2;
+    let p_end = rounds_f_beginning + rounds_p;
+    let internal_round_constants = round_constants
+        .drain(rounds_f_beginning..p_end)
+        .map(|vec| vec[0])
+        .collect::<Vec<_>>();
+    let external_round_constants = round_constants;
+    Poseidon2Params {
+        rounds_f,
+        rounds_p,
+        mat_internal_diag_m_1: MAT_DIAG3_M_1
+            .iter()
+            .copied()
+            .map(convert_fr)
+            .collect_vec()
+            .try_into()
+            .unwrap(),
+        external_rc: external_round_constants,
+        internal_rc: internal_round_constants,
+    }
+});
+
+/// The backend for the Groth16 constraint compiler. It compiles the same DSL programs as the
+/// [Halo2ConstraintCompiler](crate::constraints::halo2::compiler::Halo2ConstraintCompiler), into
+/// a rank-1 constraint system over BN254.
+#[derive(Debug, Clone)]
+pub struct Groth16ConstraintCompiler<C: Config> {
+    pub num_public_values: usize,
+    pub phantom: PhantomData<C>,
+}
+
+impl<C: Config + Debug> Groth16ConstraintCompiler<C> {
+    pub fn new(num_public_values: usize) -> Self {
+        Self {
+            num_public_values,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds the constraints of `operations` to `cs`, assigning the witness along the way.
+    ///
+    /// The public inputs of the constraint system are the values published with
+    /// `CircuitPublish`, in index order. Unpublished indices are zero.
+    pub fn constrain_r1cs(
+        &self,
+        cs: ConstraintSystemRef<Fr>,
+        operations: TracedVec<DslIr<C>>,
+        witness: &Witness<C>,
+    ) -> Result<(), SynthesisError>
+    where
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
+    {
+        let ctx = R1csContext::new(cs);
+        let f_chip = BabyBearChip;
+        let ext_chip = BabyBearExt4Chip { base: f_chip };
+        let mut public_values = vec![AssignedValue::zero(); self.num_public_values];
+
+        // Local variables for referencing during the course of constraint building
+        let mut vars = HashMap::<u32, AssignedValue>::new();
+        let mut felts = HashMap::<u32, AssignedBabyBear>::new();
+        let mut exts = HashMap::<u32, AssignedBabyBearExt4>::new();
+
+        for (instruction, mut backtrace) in operations {
+            let res = catch_unwind(AssertUnwindSafe(|| -> Result<(), SynthesisError> {
+                match instruction {
+                    DslIr::ImmV(a, b) => {
+                        vars.insert(a.0, AssignedValue::constant(convert_fr(&b)));
+                    }
+                    DslIr::ImmF(a, b) => {
+                        felts.insert(a.0, f_chip.load_constant(b));
+                    }
+                    DslIr::ImmE(a, b) => {
+                        exts.insert(a.0, ext_chip.load_constant(b));
+                    }
+                    DslIr::AddV(a, b, c) => {
+                        let x = ctx.add(&vars[&b.0], &vars[&c.0])?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::AddVI(a, b, c) => {
+                        let x = ctx.add_constant(&vars[&b.0], convert_fr(&c))?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::AddF(a, b, c) => {
+                        let x = f_chip.add(&ctx, &felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::AddFI(a, b, c) => {
+                        let x = if c.is_zero() {
+                            felts[&b.0].clone()
+                        } else {
+                            f_chip.add(&ctx, &felts[&b.0], &f_chip.load_constant(c))?
+                        };
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::AddE(a, b, c) => {
+                        let x = ext_chip.add(&ctx, &exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEF(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        x.0[0] = f_chip.add(&ctx, &x.0[0], &felts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEFI(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        if !c.is_zero() {
+                            x.0[0] = f_chip.add(&ctx, &x.0[0], &f_chip.load_constant(c))?;
+                        }
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEI(a, b, c) => {
+                        let x = if c.is_zero() {
+                            exts[&b.0].clone()
+                        } else {
+                            ext_chip.add(&ctx, &exts[&b.0], &ext_chip.load_constant(c))?
+                        };
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::AddEFFI(a, b, c) => {
+                        let mut x = ext_chip.load_constant(c);
+                        x.0[0] = f_chip.add(&ctx, &x.0[0], &felts[&b.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubV(a, b, c) => {
+                        let x = ctx.sub(&vars[&b.0], &vars[&c.0])?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::SubF(a, b, c) => {
+                        let x = f_chip.sub(&ctx, &felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::SubE(a, b, c) => {
+                        let x = ext_chip.sub(&ctx, &exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubEF(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        x.0[0] = f_chip.sub(&ctx, &x.0[0], &felts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubEI(a, b, c) => {
+                        let x = if c.is_zero() {
+                            exts[&b.0].clone()
+                        } else {
+                            ext_chip.sub(&ctx, &exts[&b.0], &ext_chip.load_constant(c))?
+                        };
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubVIN(a, b, c) => {
+                        let x = ctx.sub(&AssignedValue::constant(convert_fr(&b)), &vars[&c.0])?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::SubEIN(a, b, c) => {
+                        let x = ext_chip.sub(&ctx, &ext_chip.load_constant(b), &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::SubEFI(a, b, c) => {
+                        let mut x = exts[&b.0].clone();
+                        if !c.is_zero() {
+                            x.0[0] = f_chip.sub(&ctx, &x.0[0], &f_chip.load_constant(c))?;
+                        }
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulV(a, b, c) => {
+                        let x = ctx.mul(&vars[&b.0], &vars[&c.0])?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::MulVI(a, b, c) => {
+                        let x = ctx.scale(&vars[&b.0], convert_fr(&c))?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::MulF(a, b, c) => {
+                        let x = f_chip.mul(&ctx, &felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::MulFI(a, b, c) => {
+                        let x = if c.is_one() {
+                            felts[&b.0].clone()
+                        } else {
+                            f_chip.mul(&ctx, &felts[&b.0], &f_chip.load_constant(c))?
+                        };
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::MulE(a, b, c) => {
+                        let x = ext_chip.mul(&ctx, &exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulEI(a, b, c) => {
+                        let x = if c.is_one() {
+                            exts[&b.0].clone()
+                        } else {
+                            ext_chip.mul(&ctx, &exts[&b.0], &ext_chip.load_constant(c))?
+                        };
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulEF(a, b, c) => {
+                        let x = ext_chip.scalar_mul(&ctx, &exts[&b.0], &felts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::MulEFI(a, b, c) => {
+                        let x = if c.is_one() {
+                            exts[&b.0].clone()
+                        } else {
+                            ext_chip.scalar_mul(&ctx, &exts[&b.0], &f_chip.load_constant(c))?
+                        };
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::DivF(a, b, c) => {
+                        let x = f_chip.div(&ctx, &felts[&b.0], &felts[&c.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::DivFIN(a, b, c) => {
+                        // a = b / c
+                        let tmp = f_chip.load_constant(b);
+                        let x = if b.is_zero() {
+                            tmp
+                        } else {
+                            f_chip.div(&ctx, &tmp, &felts[&c.0])?
+                        };
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::DivE(a, b, c) => {
+                        let x = ext_chip.div(&ctx, &exts[&b.0], &exts[&c.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::DivEIN(a, b, c) => {
+                        let tmp = ext_chip.load_constant(b);
+                        let x = if b.is_zero() {
+                            tmp
+                        } else {
+                            ext_chip.div(&ctx, &tmp, &exts[&c.0])?
+                        };
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::NegE(a, b) => {
+                        let x = ext_chip.neg(&ctx, &exts[&b.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::CastFV(a, b) => {
+                        let reduced_felt = f_chip.reduce(&ctx, &felts[&b.0])?;
+                        vars.insert(a.0, reduced_felt.value);
+                    }
+                    DslIr::CircuitNum2BitsF(value, output) => {
+                        let val = f_chip.reduce(&ctx, &felts[&value.0])?;
+                        let x = ctx.to_bits(&val.value, 32)?;
+                        assert!(output.len() <= x.len());
+                        for (o, x) in output.into_iter().zip(x) {
+                            vars.insert(o.0, x);
+                        }
+                    }
+                    DslIr::CircuitVarTo64BitsF(value, output) => {
+                        let limbs = var_to_u64_limbs(&ctx, &vars[&value.0])?;
+                        for (o, l) in output.into_iter().zip(limbs) {
+                            felts.insert(o.0, l);
+                        }
+                    }
+                    DslIr::CircuitPoseidon2Permute(state_vars) => {
+                        let mut state = Poseidon2State::<POSEIDON2_T>::new(
+                            state_vars.map(|x| vars[&x.0].clone()),
+                        );
+                        state.permutation(&ctx, &POSEIDON2_PARAMS)?;
+                        for (x, s) in state_vars.iter().zip(state.s) {
+                            vars.insert(x.0, s);
+                        }
+                    }
+                    DslIr::CircuitSelectV(cond, a, b, out) => {
+                        let x = ctx.select(&vars[&cond.0], &vars[&a.0], &vars[&b.0])?;
+                        vars.insert(out.0, x);
+                    }
+                    DslIr::CircuitSelectF(cond, a, b, out) => {
+                        let x = f_chip.select(&ctx, &vars[&cond.0], &felts[&a.0], &felts[&b.0])?;
+                        felts.insert(out.0, x);
+                    }
+                    DslIr::CircuitSelectE(cond, a, b, out) => {
+                        let x = ext_chip.select(&ctx, &vars[&cond.0], &exts[&a.0], &exts[&b.0])?;
+                        exts.insert(out.0, x);
+                    }
+                    DslIr::CircuitExt2Felt(a, b) => {
+                        for (i, x) in a.iter().enumerate() {
+                            felts.insert(x.0, exts[&b.0].0[i].clone());
+                        }
+                    }
+                    DslIr::AssertEqV(a, b) => {
+                        ctx.enforce_equal(&vars[&a.0], &vars[&b.0])?;
+                    }
+                    DslIr::AssertEqVI(a, b) => {
+                        ctx.enforce_equal(&vars[&a.0], &AssignedValue::constant(convert_fr(&b)))?;
+                    }
+                    DslIr::AssertEqF(a, b) => {
+                        f_chip.assert_equal(&ctx, &felts[&a.0], &felts[&b.0])?;
+                    }
+                    DslIr::AssertEqFI(a, b) => {
+                        if b.is_zero() {
+                            f_chip.assert_zero(&ctx, &felts[&a.0])?;
+                        } else {
+                            f_chip.assert_equal(&ctx, &felts[&a.0], &f_chip.load_constant(b))?;
+                        }
+                    }
+                    DslIr::AssertEqE(a, b) => {
+                        ext_chip.assert_equal(&ctx, &exts[&a.0], &exts[&b.0])?;
+                    }
+                    DslIr::AssertEqEI(a, b) => {
+                        if b.is_zero() {
+                            ext_chip.assert_zero(&ctx, &exts[&a.0])?;
+                        } else {
+                            ext_chip.assert_equal(&ctx, &exts[&a.0], &ext_chip.load_constant(b))?;
+                        }
+                    }
+                    DslIr::PrintV(a) => {
+                        println!("PrintV: {}", vars[&a.0].value);
+                    }
+                    DslIr::PrintF(a) => {
+                        println!("PrintF: {:?}", felts[&a.0].to_baby_bear());
+                    }
+                    DslIr::PrintE(a) => {
+                        println!("PrintE:");
+                        for x in exts[&a.0].0.iter() {
+                            println!("{:?}", x.to_baby_bear());
+                        }
+                    }
+                    DslIr::WitnessVar(a, b) => {
+                        let x = ctx.witness(convert_fr(&witness.vars[b as usize]))?;
+                        vars.insert(a.0, x);
+                    }
+                    DslIr::WitnessFelt(a, b) => {
+                        let x = f_chip.load_witness(&ctx, witness.felts[b as usize])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::WitnessExt(a, b) => {
+                        let x = ext_chip.load_witness(&ctx, witness.exts[b as usize])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::CircuitFelts2Ext(a, b) => {
+                        let x = AssignedBabyBearExt4(
+                            a.iter()
+                                .map(|a| felts[&a.0].clone())
+                                .collect_vec()
+                                .try_into()
+                                .unwrap(),
+                        );
+                        exts.insert(b.0, x);
+                    }
+                    DslIr::CircuitFeltReduce(a) => {
+                        let x = f_chip.reduce_max_bits(&ctx, &felts[&a.0])?;
+                        felts.insert(a.0, x);
+                    }
+                    DslIr::CircuitExtReduce(a) => {
+                        let x = ext_chip.reduce_max_bits(&ctx, &exts[&a.0])?;
+                        exts.insert(a.0, x);
+                    }
+                    DslIr::CircuitLessThan(a, b) => {
+                        let (a, b) = (&vars[&a.0], &vars[&b.0]);
+                        ctx.range_check(a, BABYBEAR_MAX_BITS)?;
+                        ctx.range_check(b, BABYBEAR_MAX_BITS)?;
+                        // b - a - 1 is negative, hence not in range, if a >= b.
+                        let diff = ctx.linear_combination(
+                            &[(Fr::from(1u64), b), (-Fr::from(1u64), a)],
+                            -Fr::from(1u64),
+                        )?;
+                        ctx.range_check(&diff, BABYBEAR_MAX_BITS)?;
+                    }
+                    DslIr::CycleTrackerStart(_) | DslIr::CycleTrackerEnd(_) => {}
+                    DslIr::CircuitPublish(val, index) => {
+                        public_values[index] = vars[&val.0].clone();
+                    }
+                    _ => panic!("unsupported {:?}", instruction),
+                }
+                Ok(())
+            }));
+            if res.is_err() {
+                if let Some(backtrace) = backtrace.as_mut() {
+                    backtrace.resolve();
+                    eprintln!("openvm circuit failure; backtrace:\n{:?}", backtrace);
+                }
+            }
+            res.unwrap()?;
+        }
+
+        for value in public_values {
+            let input = ctx.public_input(value.value)?;
+            ctx.enforce_equal(&input, &value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assumes F is Bn254 Fr and converts to arkworks Fr type
+pub fn convert_fr<F: PrimeField>(a: &F) -> Fr {
+    biguint_to_fr(&a.as_canonical_biguint())
+}
+
+/// Decomposes `x` into 4 little-endian limbs of 64 bits, the last one having 62 bits, and
+/// constrains the decomposition to be the canonical one.
+fn var_to_u64_limbs(
+    ctx: &R1csContext,
+    x: &AssignedValue,
+) -> Result<[AssignedBabyBear; 4], SynthesisError> {
+    let num_bits = Bn254Fr::bits();
+    let bits = ctx.to_bits(x, num_bits)?;
+    // The decomposition is unique only if it is less than the modulus.
+    let modulus_minus_one = (Bn254Fr::ZERO - Bn254Fr::ONE).as_canonical_biguint();
+    ctx.enforce_bits_at_most(&bits, &modulus_minus_one)?;
+    let limbs = bits
+        .chunks(64)
+        .map(|chunk| {
+            let terms = chunk
+                .iter()
+                .enumerate()
+                .map(|(i, bit)| (biguint_to_fr(&(BigUint::from(1u32) << i)), bit))
+                .collect_vec();
+            Ok(AssignedBabyBear {
+                value: ctx.linear_combination(&terms, Fr::from(0u64))?,
+                max_bits: chunk.len(),
+            })
+        })
+        .collect::<Result<Vec<_>, SynthesisError>>()?;
+    Ok(limbs.try_into().unwrap())
+}
@@ -0,0 +1,6 @@
+//! R1CS helper functions for the Groth16 constraint compiler
+
+pub mod baby_bear;
+pub mod compiler;
+pub mod poseidon2_perm;
+pub mod r1cs;
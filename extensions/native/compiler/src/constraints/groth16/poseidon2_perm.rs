@@ -0,0 +1,131 @@
+//! R1CS implementation of poseidon2 perm for Bn254Fr
+//! sbox degree 5
+
+use ark_bn254::Fr;
+use ark_ff::Field;
+use ark_relations::r1cs::SynthesisError;
+
+use super::r1cs::{AssignedValue, R1csContext};
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2State<const T: usize> {
+    pub s: [AssignedValue; T],
+}
+
+#[derive(Debug, Clone)]
+pub struct Poseidon2Params<const T: usize> {
+    /// Number of full rounds
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    pub mat_internal_diag_m_1: [Fr; T],
+    pub external_rc: Vec<[Fr; T]>,
+    pub internal_rc: Vec<Fr>,
+}
+
+impl<const T: usize> Poseidon2Params<T> {
+    pub fn new(
+        rounds_f: usize,
+        rounds_p: usize,
+        mat_internal_diag_m_1: [Fr; T],
+        external_rc: Vec<[Fr; T]>,
+        internal_rc: Vec<Fr>,
+    ) -> Self {
+        Self {
+            rounds_f,
+            rounds_p,
+            mat_internal_diag_m_1,
+            external_rc,
+            internal_rc,
+        }
+    }
+}
+
+impl<const T: usize> Poseidon2State<T> {
+    pub fn new(state: [AssignedValue; T]) -> Self {
+        Self { s: state }
+    }
+
+    /// Perform permutation on this state.
+    pub fn permutation(
+        &mut self,
+        ctx: &R1csContext,
+        params: &Poseidon2Params<T>,
+    ) -> Result<(), SynthesisError> {
+        let rounds_f_beginning = params.rounds_f / 2;
+
+        // First half of the full round
+        self.matmul_external(ctx)?;
+        for r in 0..rounds_f_beginning {
+            self.add_rc(ctx, params.external_rc[r])?;
+            self.sbox(ctx)?;
+            self.matmul_external(ctx)?;
+        }
+
+        for r in 0..params.rounds_p {
+            self.s[0] = ctx.add_constant(&self.s[0], params.internal_rc[r])?;
+            self.s[0] = Self::x_power5(ctx, &self.s[0])?;
+            self.matmul_internal(ctx, params.mat_internal_diag_m_1)?;
+        }
+
+        for r in rounds_f_beginning..params.rounds_f {
+            self.add_rc(ctx, params.external_rc[r])?;
+            self.sbox(ctx)?;
+            self.matmul_external(ctx)?;
+        }
+        Ok(())
+    }
+
+    fn x_power5(ctx: &R1csContext, x: &AssignedValue) -> Result<AssignedValue, SynthesisError> {
+        let x2 = ctx.mul(x, x)?;
+        let x4 = ctx.mul(&x2, &x2)?;
+        ctx.mul(x, &x4)
+    }
+
+    fn sbox(&mut self, ctx: &R1csContext) -> Result<(), SynthesisError> {
+        for x in self.s.iter_mut() {
+            *x = Self::x_power5(ctx, x)?;
+        }
+        Ok(())
+    }
+
+    fn matmul_external(&mut self, ctx: &R1csContext) -> Result<(), SynthesisError> {
+        // Only doing T = 3 case
+        assert_eq!(T, 3);
+
+        // Matrix is circ(2, 1, 1)
+        let sum = self.sum(ctx)?;
+        for x in self.s.iter_mut() {
+            *x = ctx.add(x, &sum)?;
+        }
+        Ok(())
+    }
+
+    fn add_rc(
+        &mut self,
+        ctx: &R1csContext,
+        round_constants: [Fr; T],
+    ) -> Result<(), SynthesisError> {
+        for (x, rc) in self.s.iter_mut().zip(round_constants.iter()) {
+            *x = ctx.add_constant(x, *rc)?;
+        }
+        Ok(())
+    }
+
+    fn matmul_internal(
+        &mut self,
+        ctx: &R1csContext,
+        mat_internal_diag_m_1: [Fr; T],
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(T, 3);
+        let sum = self.sum(ctx)?;
+        for (x, diag) in self.s.iter_mut().zip(mat_internal_diag_m_1) {
+            *x = ctx.linear_combination(&[(diag, x), (Fr::ONE, &sum)], Fr::ZERO)?;
+        }
+        Ok(())
+    }
+
+    fn sum(&self, ctx: &R1csContext) -> Result<AssignedValue, SynthesisError> {
+        let terms = self.s.iter().map(|x| (Fr::ONE, x)).collect::<Vec<_>>();
+        ctx.linear_combination(&terms, Fr::ZERO)
+    }
+}
@@ -0,0 +1,275 @@
+//! Thin layer over an arkworks constraint system which keeps the value of every linear
+//! combination next to it, so that gadgets can compute their witnesses while adding constraints.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_relations::r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// Linear combinations with more terms are replaced by a new witness, so that long chains of
+/// additions don't produce quadratically many terms.
+const MAX_LC_TERMS: usize = 32;
+
+/// A linear combination of variables of the constraint system, together with its value.
+#[derive(Clone, Debug)]
+pub struct AssignedValue {
+    pub lc: LinearCombination<Fr>,
+    pub value: Fr,
+}
+
+impl AssignedValue {
+    pub fn constant(value: Fr) -> Self {
+        let lc = if value.is_zero() {
+            LinearCombination(vec![])
+        } else {
+            LinearCombination(vec![(value, Variable::One)])
+        };
+        Self { lc, value }
+    }
+
+    pub fn zero() -> Self {
+        Self::constant(Fr::ZERO)
+    }
+
+    pub fn one() -> Self {
+        Self::constant(Fr::ONE)
+    }
+
+    /// Returns the value if this is a constant.
+    pub fn as_constant(&self) -> Option<Fr> {
+        self.lc
+            .0
+            .iter()
+            .all(|(_, var)| *var == Variable::One)
+            .then_some(self.value)
+    }
+}
+
+pub struct R1csContext {
+    pub cs: ConstraintSystemRef<Fr>,
+}
+
+impl R1csContext {
+    pub fn new(cs: ConstraintSystemRef<Fr>) -> Self {
+        Self { cs }
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.cs.num_constraints()
+    }
+
+    pub fn witness(&self, value: Fr) -> Result<AssignedValue, SynthesisError> {
+        let var = self.cs.new_witness_variable(|| Ok(value))?;
+        Ok(AssignedValue {
+            lc: LinearCombination(vec![(Fr::ONE, var)]),
+            value,
+        })
+    }
+
+    pub fn public_input(&self, value: Fr) -> Result<AssignedValue, SynthesisError> {
+        let var = self.cs.new_input_variable(|| Ok(value))?;
+        Ok(AssignedValue {
+            lc: LinearCombination(vec![(Fr::ONE, var)]),
+            value,
+        })
+    }
+
+    pub fn add(
+        &self,
+        a: &AssignedValue,
+        b: &AssignedValue,
+    ) -> Result<AssignedValue, SynthesisError> {
+        self.linear_combination(&[(Fr::ONE, a), (Fr::ONE, b)], Fr::ZERO)
+    }
+
+    pub fn sub(
+        &self,
+        a: &AssignedValue,
+        b: &AssignedValue,
+    ) -> Result<AssignedValue, SynthesisError> {
+        self.linear_combination(&[(Fr::ONE, a), (-Fr::ONE, b)], Fr::ZERO)
+    }
+
+    pub fn neg(&self, a: &AssignedValue) -> Result<AssignedValue, SynthesisError> {
+        self.linear_combination(&[(-Fr::ONE, a)], Fr::ZERO)
+    }
+
+    pub fn add_constant(&self, a: &AssignedValue, c: Fr) -> Result<AssignedValue, SynthesisError> {
+        self.linear_combination(&[(Fr::ONE, a)], c)
+    }
+
+    pub fn scale(&self, a: &AssignedValue, c: Fr) -> Result<AssignedValue, SynthesisError> {
+        self.linear_combination(&[(c, a)], Fr::ZERO)
+    }
+
+    /// Returns `constant + sum(coeff * value)` without adding constraints, unless the result has
+    /// too many terms.
+    pub fn linear_combination(
+        &self,
+        terms: &[(Fr, &AssignedValue)],
+        constant: Fr,
+    ) -> Result<AssignedValue, SynthesisError> {
+        let mut lc = Vec::new();
+        let mut value = constant;
+        if !constant.is_zero() {
+            lc.push((constant, Variable::One));
+        }
+        for (coeff, x) in terms {
+            if coeff.is_zero() {
+                continue;
+            }
+            lc.extend(x.lc.0.iter().map(|(c, var)| (*c * coeff, *var)));
+            value += x.value * coeff;
+        }
+        let mut lc = LinearCombination(lc);
+        lc.compactify();
+        lc.0.retain(|(c, _)| !c.is_zero());
+        let x = AssignedValue { lc, value };
+        if x.lc.0.len() > MAX_LC_TERMS {
+            self.materialize(&x)
+        } else {
+            Ok(x)
+        }
+    }
+
+    /// Returns a single witness variable equal to `a`.
+    pub fn materialize(&self, a: &AssignedValue) -> Result<AssignedValue, SynthesisError> {
+        let x = self.witness(a.value)?;
+        self.enforce_equal(&x, a)?;
+        Ok(x)
+    }
+
+    pub fn mul(
+        &self,
+        a: &AssignedValue,
+        b: &AssignedValue,
+    ) -> Result<AssignedValue, SynthesisError> {
+        if let Some(c) = a.as_constant() {
+            return self.scale(b, c);
+        }
+        if let Some(c) = b.as_constant() {
+            return self.scale(a, c);
+        }
+        let c = self.witness(a.value * b.value)?;
+        self.cs
+            .enforce_constraint(a.lc.clone(), b.lc.clone(), c.lc.clone())?;
+        Ok(c)
+    }
+
+    pub fn enforce_equal(
+        &self,
+        a: &AssignedValue,
+        b: &AssignedValue,
+    ) -> Result<(), SynthesisError> {
+        let diff = self.sub(a, b)?;
+        self.enforce_zero(&diff)
+    }
+
+    pub fn enforce_zero(&self, a: &AssignedValue) -> Result<(), SynthesisError> {
+        self.cs.enforce_constraint(
+            a.lc.clone(),
+            LinearCombination(vec![(Fr::ONE, Variable::One)]),
+            LinearCombination(vec![]),
+        )
+    }
+
+    pub fn enforce_bool(&self, a: &AssignedValue) -> Result<(), SynthesisError> {
+        let a_minus_one = self.add_constant(a, -Fr::ONE)?;
+        self.cs
+            .enforce_constraint(a.lc.clone(), a_minus_one.lc, LinearCombination(vec![]))
+    }
+
+    /// Returns `b + cond * (a - b)`. `cond` must be boolean.
+    pub fn select(
+        &self,
+        cond: &AssignedValue,
+        a: &AssignedValue,
+        b: &AssignedValue,
+    ) -> Result<AssignedValue, SynthesisError> {
+        let diff = self.sub(a, b)?;
+        let t = self.mul(cond, &diff)?;
+        self.add(b, &t)
+    }
+
+    /// Decomposes `a` into `num_bits` little-endian bits. This also constrains `a < 2^num_bits`,
+    /// as long as `num_bits` is less than the modulus bit size.
+    pub fn to_bits(
+        &self,
+        a: &AssignedValue,
+        num_bits: usize,
+    ) -> Result<Vec<AssignedValue>, SynthesisError> {
+        let a_bits = a.value.into_bigint().to_bits_le();
+        let bits = (0..num_bits)
+            .map(|i| {
+                let bit = self.witness(Fr::from(a_bits.get(i).copied().unwrap_or(false)))?;
+                self.enforce_bool(&bit)?;
+                Ok(bit)
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+        let mut sum = Vec::with_capacity(num_bits + a.lc.0.len());
+        let mut power = Fr::ONE;
+        for bit in &bits {
+            sum.extend(bit.lc.0.iter().map(|(c, var)| (*c * power, *var)));
+            power.double_in_place();
+        }
+        sum.extend(a.lc.0.iter().map(|(c, var)| (-*c, *var)));
+        self.cs.enforce_constraint(
+            LinearCombination(sum),
+            LinearCombination(vec![(Fr::ONE, Variable::One)]),
+            LinearCombination(vec![]),
+        )?;
+        Ok(bits)
+    }
+
+    /// Constrains `a < 2^num_bits`.
+    pub fn range_check(&self, a: &AssignedValue, num_bits: usize) -> Result<(), SynthesisError> {
+        self.to_bits(a, num_bits).map(|_| ())
+    }
+
+    /// Constrains that the little-endian `bits` encode an integer which is at most `bound`.
+    pub fn enforce_bits_at_most(
+        &self,
+        bits: &[AssignedValue],
+        bound: &BigUint,
+    ) -> Result<(), SynthesisError> {
+        assert!(bound.bits() as usize <= bits.len());
+        // Scanning from the most significant bit, `equal` is whether the bits read so far equal
+        // those of `bound`.
+        let mut equal = AssignedValue::one();
+        for i in (0..bits.len()).rev() {
+            if bound.bit(i as u64) {
+                equal = self.mul(&equal, &bits[i])?;
+            } else {
+                // While equal, a set bit where `bound` has none would exceed it.
+                let t = self.mul(&equal, &bits[i])?;
+                self.enforce_zero(&t)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Interprets `x` as a signed integer in `(-r/2, r/2]`.
+pub fn fr_to_bigint(x: &Fr) -> BigInt {
+    let value = BigUint::from_bytes_le(&x.into_bigint().to_bytes_le());
+    let modulus = BigUint::from_bytes_le(&Fr::MODULUS.to_bytes_le());
+    if value > &modulus >> 1 {
+        BigInt::from_biguint(Sign::Plus, value) - BigInt::from_biguint(Sign::Plus, modulus)
+    } else {
+        BigInt::from_biguint(Sign::Plus, value)
+    }
+}
+
+pub fn bigint_to_fr(x: &BigInt) -> Fr {
+    let (sign, magnitude) = x.to_bytes_le();
+    let magnitude = Fr::from_le_bytes_mod_order(&magnitude);
+    if sign == Sign::Minus {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+pub fn biguint_to_fr(x: &BigUint) -> Fr {
+    Fr::from_le_bytes_mod_order(&x.to_bytes_le())
+}
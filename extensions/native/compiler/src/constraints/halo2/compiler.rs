@@ -31,6 +31,7 @@ use crate::{
             AssignedBabyBear, AssignedBabyBearExt4, BabyBearChip, BabyBearExt4, BabyBearExt4Chip,
         },
         poseidon2_perm::{Poseidon2Params, Poseidon2State},
+        profile::{ConstraintCost, ConstraintProfile},
     },
     ir::{Config, DslIr, TracedVec, Witness},
 };
@@ -134,6 +135,33 @@ impl<C: Config + Debug> Halo2ConstraintCompiler<C> {
     pub fn constrain_halo2(&self, halo2_state: &mut Halo2State<C>, operations: TracedVec<DslIr<C>>)
     where
         C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
+    {
+        self.constrain_halo2_impl(halo2_state, operations, None);
+    }
+
+    /// Same as [constrain_halo2](Self::constrain_halo2), but also reports the advice cells,
+    /// lookups and copy constraints generated by each DSL instruction. Copy constraints are only
+    /// recorded when the circuit builder is not in witness generation only mode.
+    pub fn profile_halo2(
+        &self,
+        halo2_state: &mut Halo2State<C>,
+        operations: TracedVec<DslIr<C>>,
+    ) -> ConstraintProfile
+    where
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
+    {
+        let mut profile = ConstraintProfile::default();
+        self.constrain_halo2_impl(halo2_state, operations, Some(&mut profile));
+        profile
+    }
+
+    fn constrain_halo2_impl(
+        &self,
+        halo2_state: &mut Halo2State<C>,
+        operations: TracedVec<DslIr<C>>,
+        mut profile: Option<&mut ConstraintProfile>,
+    ) where
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
     {
         #[cfg(feature = "metrics")]
         let mut cell_tracker = CycleTracker::new();
@@ -151,11 +179,14 @@ impl<C: Config + Debug> Halo2ConstraintCompiler<C> {
 
         #[cfg(feature = "metrics")]
         let mut old_stats = stats_snapshot(ctx, range.clone());
-        for (instruction, backtrace) in operations {
+        for (instruction, mut backtrace) in operations {
             #[cfg(feature = "metrics")]
             if self.profiling {
                 old_stats = stats_snapshot(ctx, range.clone());
             }
+            let profile_start = profile
+                .is_some()
+                .then(|| (instruction.to_string(), cost_snapshot(ctx, &range)));
             let res = catch_unwind(AssertUnwindSafe(|| {
                 match instruction {
                     DslIr::ImmV(a, b) => {
@@ -506,12 +537,15 @@ impl<C: Config + Debug> Halo2ConstraintCompiler<C> {
                 }
             }));
             if res.is_err() {
-                if let Some(mut backtrace) = backtrace {
+                if let Some(backtrace) = backtrace.as_mut() {
                     backtrace.resolve();
                     eprintln!("openvm circuit failure; backtrace:\n{:?}", backtrace);
                 }
                 res.unwrap();
             }
+            if let (Some(profile), Some((name, start))) = (profile.as_deref_mut(), profile_start) {
+                profile.record(name, backtrace.as_ref(), cost_snapshot(ctx, &range) - start);
+            }
             #[cfg(feature = "metrics")]
             if self.profiling {
                 let mut new_stats = stats_snapshot(ctx, range.clone());
@@ -549,6 +583,16 @@ fn stats_snapshot(ctx: &Context<Fr>, range_chip: Arc<RangeChip<Fr>>) -> Halo2Sta
     }
 }
 
+fn cost_snapshot(ctx: &Context<Fr>, range_chip: &RangeChip<Fr>) -> ConstraintCost {
+    let copy_manager = ctx.copy_manager.lock().unwrap();
+    ConstraintCost {
+        advice_cells: ctx.advice.len(),
+        lookup_cells: range_chip.lookup_manager()[0].total_rows(),
+        copy_constraints: copy_manager.advice_equalities.len()
+            + copy_manager.constant_equalities.len(),
+    }
+}
+
 #[allow(dead_code)]
 fn is_babybear_ir<C: Config>(ir: &DslIr<C>) -> bool {
     matches!(
@@ -3,4 +3,5 @@
 pub mod baby_bear;
 pub mod compiler;
 pub mod poseidon2_perm;
+pub mod profile;
 pub mod stats;
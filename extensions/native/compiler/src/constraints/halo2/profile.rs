@@ -0,0 +1,212 @@
+//! Attribution of halo2 constraints to DSL instructions and the Rust code that emitted them.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    ops::{AddAssign, Sub},
+    path::Path,
+};
+
+use backtrace::Backtrace;
+
+/// Name used for the call site of instructions without a recorded backtrace.
+const UNKNOWN_CALL_SITE: &str = "<unknown>";
+
+/// Halo2 resources spent on some constraints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConstraintCost {
+    pub advice_cells: usize,
+    pub lookup_cells: usize,
+    /// Equality constraints between advice cells, and between advice cells and constants.
+    pub copy_constraints: usize,
+}
+
+impl ConstraintCost {
+    pub fn get(&self, metric: ProfileMetric) -> usize {
+        match metric {
+            ProfileMetric::AdviceCells => self.advice_cells,
+            ProfileMetric::LookupCells => self.lookup_cells,
+            ProfileMetric::CopyConstraints => self.copy_constraints,
+        }
+    }
+}
+
+impl AddAssign for ConstraintCost {
+    fn add_assign(&mut self, rhs: Self) {
+        self.advice_cells += rhs.advice_cells;
+        self.lookup_cells += rhs.lookup_cells;
+        self.copy_constraints += rhs.copy_constraints;
+    }
+}
+
+impl Sub for ConstraintCost {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            advice_cells: self.advice_cells - rhs.advice_cells,
+            lookup_cells: self.lookup_cells - rhs.lookup_cells,
+            copy_constraints: self.copy_constraints - rhs.copy_constraints,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileMetric {
+    AdviceCells,
+    LookupCells,
+    CopyConstraints,
+}
+
+/// Halo2 constraints generated by the Halo2 constraint compiler, grouped by the call stack which
+/// pushed each DSL instruction and by the [DslIr](crate::ir::DslIr) variant.
+///
+/// Call stacks come from the backtraces recorded by [TracedVec](crate::ir::TracedVec), so
+/// `RUST_BACKTRACE` must be set while the DSL program is built. Without a backtrace, an
+/// instruction is only attributed to its variant.
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintProfile {
+    /// Function names from the outermost frame, followed by the `file:line` of the call site and
+    /// the DSL instruction variant.
+    stacks: BTreeMap<Vec<String>, ConstraintCost>,
+    /// Resolved function names and locations by instruction pointer.
+    symbols: HashMap<usize, Vec<(String, Option<String>)>>,
+}
+
+impl ConstraintProfile {
+    pub(crate) fn record(
+        &mut self,
+        instruction: String,
+        backtrace: Option<&Backtrace>,
+        cost: ConstraintCost,
+    ) {
+        let mut stack = backtrace.map_or_else(Vec::new, |backtrace| self.call_stack(backtrace));
+        stack.push(instruction);
+        *self.stacks.entry(stack).or_default() += cost;
+    }
+
+    /// Returns the frames of `backtrace` outside of the standard library and this crate, from the
+    /// outermost one, followed by the location of the innermost one.
+    fn call_stack(&mut self, backtrace: &Backtrace) -> Vec<String> {
+        let mut frames = Vec::new();
+        for frame in backtrace.frames() {
+            let ip = frame.ip() as usize;
+            let symbols = self.symbols.entry(ip).or_insert_with(|| {
+                // Backtraces deserialized from another process keep their resolved symbols, but
+                // their instruction pointers are meaningless here.
+                if !frame.symbols().is_empty() {
+                    return frame
+                        .symbols()
+                        .iter()
+                        .filter_map(|symbol| {
+                            let name = symbol.name()?;
+                            Some((
+                                format!("{name:#}"),
+                                location(symbol.filename(), symbol.lineno()),
+                            ))
+                        })
+                        .collect();
+                }
+                let mut symbols = Vec::new();
+                backtrace::resolve(frame.ip(), |symbol| {
+                    if let Some(name) = symbol.name() {
+                        let location = location(symbol.filename(), symbol.lineno());
+                        symbols.push((format!("{name:#}"), location));
+                    }
+                });
+                symbols
+            });
+            frames.extend(
+                symbols
+                    .iter()
+                    .filter(|(name, _)| !is_internal_frame(name))
+                    .cloned(),
+            );
+        }
+        let call_site = frames
+            .first()
+            .and_then(|(_, location)| location.clone())
+            .unwrap_or_else(|| UNKNOWN_CALL_SITE.to_string());
+        let mut stack: Vec<String> = frames.into_iter().rev().map(|(name, _)| name).collect();
+        stack.push(call_site);
+        stack
+    }
+
+    /// Total cost of all instructions.
+    pub fn total(&self) -> ConstraintCost {
+        let mut total = ConstraintCost::default();
+        for cost in self.stacks.values() {
+            total += *cost;
+        }
+        total
+    }
+
+    /// Cost of each DSL instruction variant, from the most to the least advice cells.
+    pub fn by_instruction(&self) -> Vec<(String, ConstraintCost)> {
+        self.group_by(|stack| stack[stack.len() - 1].clone())
+    }
+
+    /// Cost of each call site, from the most to the least advice cells.
+    pub fn by_call_site(&self) -> Vec<(String, ConstraintCost)> {
+        self.group_by(|stack| {
+            if stack.len() < 2 {
+                UNKNOWN_CALL_SITE.to_string()
+            } else {
+                stack[stack.len() - 2].clone()
+            }
+        })
+    }
+
+    fn group_by(&self, key: impl Fn(&[String]) -> String) -> Vec<(String, ConstraintCost)> {
+        let mut groups = BTreeMap::<String, ConstraintCost>::new();
+        for (stack, cost) in &self.stacks {
+            *groups.entry(key(stack)).or_default() += *cost;
+        }
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by(|(_, a), (_, b)| b.advice_cells.cmp(&a.advice_cells));
+        groups
+    }
+
+    /// Writes `metric` in the folded stack format read by flamegraph tools such as `inferno` and
+    /// `flamegraph.pl`: one `frame;frame;...;instruction count` line per call stack.
+    pub fn write_folded(&self, mut writer: impl Write, metric: ProfileMetric) -> io::Result<()> {
+        for (stack, cost) in &self.stacks {
+            let count = cost.get(metric);
+            if count == 0 {
+                continue;
+            }
+            let stack = stack
+                .iter()
+                .map(|frame| frame.replace(';', ":"))
+                .collect::<Vec<_>>();
+            writeln!(writer, "{} {count}", stack.join(";"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Frames of the standard library, the test harness, backtrace capture and the DSL builder.
+fn is_internal_frame(name: &str) -> bool {
+    const PREFIXES: [&str; 7] = [
+        "std::",
+        "core::",
+        "alloc::",
+        "test::",
+        "backtrace::",
+        "openvm_native_compiler::",
+        "<openvm_native_compiler::",
+    ];
+    name.starts_with('_') || PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+fn location(file: Option<&Path>, line: Option<u32>) -> Option<String> {
+    let file = file?.to_string_lossy();
+    Some(format!("{}:{}", short_path(&file), line?))
+}
+
+/// Shortens a source path to start at its crate directory, e.g. `recursion/src/fri/mod.rs`.
+fn short_path(path: &str) -> &str {
+    path.rfind("/src/")
+        .and_then(|src| path[..src].rfind('/'))
+        .map_or(path, |start| &path[start + 1..])
+}
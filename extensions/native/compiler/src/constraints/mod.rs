@@ -10,6 +10,8 @@ use crate::{
     prelude::TracedVec,
 };
 
+#[cfg(feature = "groth16-compiler")]
+pub mod groth16;
 #[cfg(feature = "halo2-compiler")]
 pub mod halo2;
 
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     asm::{AsmInstruction, AssemblyCode},
+    ir::OptimizationOptions,
     FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
     NativeJalOpcode, NativeLoadStore4Opcode, NativeLoadStoreOpcode, NativePhantom,
     NativeRangeCheckOpcode, Poseidon2Opcode, VerifyBatchOpcode,
@@ -20,6 +21,9 @@ pub struct CompilerOptions {
     // The compiler will ensure that the heap pointer is aligned to be a multiple of `word_size`.
     pub word_size: usize,
     pub enable_cycle_tracker: bool,
+    /// Optimization passes run on the DSL program before it is compiled.
+    #[serde(default)]
+    pub optimizations: OptimizationOptions,
 }
 
 impl Default for CompilerOptions {
@@ -27,6 +31,7 @@ impl Default for CompilerOptions {
         CompilerOptions {
             word_size: 8,
             enable_cycle_tracker: false,
+            optimizations: OptimizationOptions::default(),
         }
     }
 }
@@ -40,6 +45,10 @@ impl CompilerOptions {
         self.enable_cycle_tracker = true;
         self
     }
+    pub fn with_optimizations(mut self, optimizations: OptimizationOptions) -> Self {
+        self.optimizations = optimizations;
+        self
+    }
 }
 
 fn inst<F: PrimeField64>(opcode: VmOpcode, a: F, b: F, c: F, d: AS, e: AS) -> Instruction<F> {
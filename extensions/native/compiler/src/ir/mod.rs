@@ -2,6 +2,7 @@ pub use builder::*;
 pub use collections::*;
 pub use instructions::*;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField, PrimeField32, TwoAdicField};
+pub use optimize::{optimize, OptimizationOptions, OptimizationStats};
 pub use poseidon::{DIGEST_SIZE, PERMUTATION_WIDTH};
 pub use ptr::*;
 pub use select::*;
@@ -15,6 +16,7 @@ mod builder;
 mod collections;
 mod fri;
 mod instructions;
+mod optimize;
 mod poseidon;
 mod ptr;
 mod select;
@@ -0,0 +1,1101 @@
+//! Optimization passes over the DSL IR.
+//!
+//! The passes rewrite the [TracedVec] of [DslIr] built by a [Builder](super::Builder) before it is
+//! compiled to assembly. Only pure arithmetic instructions (immediates, additions, subtractions,
+//! multiplications, divisions and negations) are folded, merged, moved or removed. Every other
+//! instruction is left in place.
+//!
+//! The IR is not in SSA form: the same variable may be assigned several times, in loops and in
+//! branches. A variable is therefore only rewritten when it is *stable*, i.e. it has exactly one
+//! static definition, it is not a loop variable, and every read of it comes after that definition
+//! in a block enclosing the read. A stable variable holds the same value everywhere it is read.
+
+use std::collections::{HashMap, HashSet};
+
+use openvm_stark_backend::p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
+use serde::{Deserialize, Serialize};
+
+use super::{Array, Config, DslIr, Ext, Felt, MemIndex, Ptr, RVar, TracedVec, Usize, Var};
+
+/// Upper bound on the number of times the pass pipeline is repeated.
+const MAX_ROUNDS: usize = 8;
+
+/// Selects the optimization passes run on a DSL program before it is compiled.
+///
+/// All passes are disabled by default, so that programs compile to the same instructions unless
+/// optimizations are explicitly requested.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizationOptions {
+    /// Evaluate arithmetic with constant operands at compile time.
+    pub constant_folding: bool,
+    /// Replace a variable assigned from another variable by the original.
+    pub copy_propagation: bool,
+    /// Remove arithmetic whose result is never read.
+    pub dead_code_elimination: bool,
+    /// Reuse the result of an identical earlier computation.
+    pub common_subexpression_elimination: bool,
+    /// Move arithmetic that does not depend on the loop out of loop bodies.
+    pub loop_invariant_hoisting: bool,
+}
+
+impl OptimizationOptions {
+    /// Enables every pass.
+    pub fn all() -> Self {
+        Self {
+            constant_folding: true,
+            copy_propagation: true,
+            dead_code_elimination: true,
+            common_subexpression_elimination: true,
+            loop_invariant_hoisting: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.constant_folding
+            || self.copy_propagation
+            || self.dead_code_elimination
+            || self.common_subexpression_elimination
+            || self.loop_invariant_hoisting
+    }
+}
+
+/// Number of DSL instructions rewritten by each pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationStats {
+    /// Instructions rewritten to use immediates or replaced by an immediate.
+    pub constants_folded: usize,
+    /// Copies removed.
+    pub copies_propagated: usize,
+    /// Instructions removed because their result was unused.
+    pub dead_instructions_removed: usize,
+    /// Instructions removed because an identical computation was available.
+    pub subexpressions_eliminated: usize,
+    /// Instructions moved out of loop bodies.
+    pub loop_invariants_hoisted: usize,
+}
+
+impl OptimizationStats {
+    /// Total number of DSL instructions removed from the program.
+    pub fn instructions_removed(&self) -> usize {
+        self.copies_propagated + self.dead_instructions_removed + self.subexpressions_eliminated
+    }
+}
+
+/// Runs the passes enabled in `options` on `operations` until none of them makes progress.
+pub fn optimize<C: Config>(
+    operations: &mut TracedVec<DslIr<C>>,
+    options: &OptimizationOptions,
+) -> OptimizationStats {
+    let mut stats = OptimizationStats::default();
+    for _ in 0..MAX_ROUNDS {
+        let before = stats;
+        if options.constant_folding {
+            stats.constants_folded += fold_constants(operations);
+        }
+        if options.copy_propagation {
+            stats.copies_propagated += propagate_copies(operations);
+        }
+        if options.common_subexpression_elimination {
+            stats.subexpressions_eliminated += eliminate_common_subexpressions(operations);
+        }
+        if options.loop_invariant_hoisting {
+            stats.loop_invariants_hoisted += hoist_loop_invariants(operations);
+        }
+        if options.dead_code_elimination {
+            stats.dead_instructions_removed += eliminate_dead_code(operations);
+        }
+        if stats == before {
+            break;
+        }
+    }
+    stats
+}
+
+// =================================================================================================
+// Operands
+// =================================================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum VarId {
+    Var(u32),
+    Felt(u32),
+    Ext(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+}
+
+enum Slot<'a, C: Config> {
+    Var(&'a mut Var<C::N>),
+    Felt(&'a mut Felt<C::F>),
+    Ext(&'a mut Ext<C::F, C::EF>),
+    /// A variable held by a compile-time array. The array is shared with the builder, so the
+    /// variable can never be renamed.
+    Pinned(VarId),
+}
+
+impl<C: Config> Slot<'_, C> {
+    fn id(&self) -> VarId {
+        match self {
+            Slot::Var(v) => VarId::Var(v.0),
+            Slot::Felt(f) => VarId::Felt(f.0),
+            Slot::Ext(e) => VarId::Ext(e.0),
+            Slot::Pinned(id) => *id,
+        }
+    }
+
+    fn rename(self, renames: &HashMap<VarId, VarId>) {
+        let Some(&to) = renames.get(&self.id()) else {
+            return;
+        };
+        match (self, to) {
+            (Slot::Var(v), VarId::Var(idx)) => v.0 = idx,
+            (Slot::Felt(f), VarId::Felt(idx)) => f.0 = idx,
+            (Slot::Ext(e), VarId::Ext(idx)) => e.0 = idx,
+            _ => unreachable!("variables are only renamed to variables of the same kind"),
+        }
+    }
+}
+
+type Visitor<'v, C> = &'v mut dyn FnMut(Slot<'_, C>, Access);
+
+fn var<C: Config>(v: &mut Var<C::N>, access: Access, f: Visitor<'_, C>) {
+    f(Slot::Var(v), access);
+}
+
+fn felt<C: Config>(v: &mut Felt<C::F>, access: Access, f: Visitor<'_, C>) {
+    f(Slot::Felt(v), access);
+}
+
+fn ext<C: Config>(v: &mut Ext<C::F, C::EF>, access: Access, f: Visitor<'_, C>) {
+    f(Slot::Ext(v), access);
+}
+
+fn rvar<C: Config>(v: &mut RVar<C::N>, f: Visitor<'_, C>) {
+    if let RVar::Val(v) = v {
+        f(Slot::Var(v), Access::Read);
+    }
+}
+
+fn ptr<C: Config>(p: &mut Ptr<C::N>, access: Access, f: Visitor<'_, C>) {
+    f(Slot::Var(&mut p.address), access);
+}
+
+fn usize_var<C: Config>(u: &mut Usize<C::N>, f: Visitor<'_, C>) {
+    if let Usize::Var(v) = u {
+        f(Slot::Var(v), Access::Read);
+    }
+}
+
+fn mem_index<C: Config>(index: &mut MemIndex<C::N>, f: Visitor<'_, C>) {
+    rvar(&mut index.index, f);
+}
+
+fn dim<C: Config>(u: &mut Usize<C::F>, _: Access, f: Visitor<'_, C>) {
+    if let Usize::Var(v) = u {
+        f(Slot::Pinned(VarId::Var(v.0)), Access::Read);
+    }
+}
+
+fn array<C: Config, T: Clone>(
+    arr: &mut Array<C, T>,
+    access: Access,
+    f: Visitor<'_, C>,
+    elem: fn(&mut T, Access, Visitor<'_, C>),
+) {
+    match arr {
+        Array::Fixed(values) => {
+            for value in values.borrow().iter().flatten() {
+                let mut value = value.clone();
+                elem(
+                    &mut value,
+                    access,
+                    &mut |slot: Slot<'_, C>, access: Access| f(Slot::Pinned(slot.id()), access),
+                );
+            }
+        }
+        Array::Dyn(p, len) => {
+            ptr(p, Access::Read, f);
+            usize_var(len, f);
+        }
+    }
+}
+
+fn felt_array<C: Config>(arr: &mut Array<C, Felt<C::F>>, access: Access, f: Visitor<'_, C>) {
+    array(arr, access, f, felt::<C>);
+}
+
+fn ext_array<C: Config>(arr: &mut Array<C, Ext<C::F, C::EF>>, access: Access, f: Visitor<'_, C>) {
+    array(arr, access, f, ext::<C>);
+}
+
+/// Calls `f` on every variable operand of `op`, reads before writes. Nested blocks are not
+/// visited.
+fn visit_operands<C: Config>(op: &mut DslIr<C>, f: Visitor<'_, C>) {
+    use Access::{Read, Write};
+    match op {
+        DslIr::ImmV(dst, _) => var(dst, Write, f),
+        DslIr::ImmF(dst, _) => felt(dst, Write, f),
+        DslIr::ImmE(dst, _) => ext(dst, Write, f),
+        DslIr::AddV(dst, lhs, rhs) | DslIr::SubV(dst, lhs, rhs) | DslIr::MulV(dst, lhs, rhs) => {
+            var(lhs, Read, f);
+            var(rhs, Read, f);
+            var(dst, Write, f);
+        }
+        DslIr::AddVI(dst, src, _)
+        | DslIr::SubVI(dst, src, _)
+        | DslIr::SubVIN(dst, _, src)
+        | DslIr::MulVI(dst, src, _)
+        | DslIr::NegV(dst, src) => {
+            var(src, Read, f);
+            var(dst, Write, f);
+        }
+        DslIr::AddF(dst, lhs, rhs)
+        | DslIr::SubF(dst, lhs, rhs)
+        | DslIr::MulF(dst, lhs, rhs)
+        | DslIr::DivF(dst, lhs, rhs) => {
+            felt(lhs, Read, f);
+            felt(rhs, Read, f);
+            felt(dst, Write, f);
+        }
+        DslIr::AddFI(dst, src, _)
+        | DslIr::SubFI(dst, src, _)
+        | DslIr::SubFIN(dst, _, src)
+        | DslIr::MulFI(dst, src, _)
+        | DslIr::DivFI(dst, src, _)
+        | DslIr::DivFIN(dst, _, src)
+        | DslIr::NegF(dst, src) => {
+            felt(src, Read, f);
+            felt(dst, Write, f);
+        }
+        DslIr::AddE(dst, lhs, rhs)
+        | DslIr::SubE(dst, lhs, rhs)
+        | DslIr::MulE(dst, lhs, rhs)
+        | DslIr::DivE(dst, lhs, rhs) => {
+            ext(lhs, Read, f);
+            ext(rhs, Read, f);
+            ext(dst, Write, f);
+        }
+        DslIr::AddEI(dst, src, _)
+        | DslIr::SubEI(dst, src, _)
+        | DslIr::SubEIN(dst, _, src)
+        | DslIr::MulEI(dst, src, _)
+        | DslIr::DivEI(dst, src, _)
+        | DslIr::DivEIN(dst, _, src)
+        | DslIr::AddEFI(dst, src, _)
+        | DslIr::SubEFI(dst, src, _)
+        | DslIr::MulEFI(dst, src, _)
+        | DslIr::DivEFI(dst, src, _)
+        | DslIr::NegE(dst, src) => {
+            ext(src, Read, f);
+            ext(dst, Write, f);
+        }
+        DslIr::AddEF(dst, lhs, rhs)
+        | DslIr::SubEF(dst, lhs, rhs)
+        | DslIr::MulEF(dst, lhs, rhs)
+        | DslIr::DivEF(dst, lhs, rhs) => {
+            ext(lhs, Read, f);
+            felt(rhs, Read, f);
+            ext(dst, Write, f);
+        }
+        DslIr::AddEFFI(dst, src, _) => {
+            felt(src, Read, f);
+            ext(dst, Write, f);
+        }
+        DslIr::CastFV(dst, src) => {
+            felt(src, Read, f);
+            var(dst, Write, f);
+        }
+        DslIr::UnsafeCastVF(dst, src) => {
+            var(src, Read, f);
+            felt(dst, Write, f);
+        }
+        DslIr::ZipFor(starts, end0, _, loop_vars, _) => {
+            for start in starts {
+                rvar(start, f);
+            }
+            rvar(end0, f);
+            for loop_var in loop_vars {
+                var(loop_var, Write, f);
+            }
+        }
+        DslIr::IfEq(lhs, rhs, _, _) | DslIr::IfNe(lhs, rhs, _, _) => {
+            var(lhs, Read, f);
+            var(rhs, Read, f);
+        }
+        DslIr::IfEqI(lhs, _, _, _) | DslIr::IfNeI(lhs, _, _, _) => var(lhs, Read, f),
+        DslIr::AssertEqV(lhs, rhs) => {
+            var(lhs, Read, f);
+            var(rhs, Read, f);
+        }
+        DslIr::AssertEqF(lhs, rhs) => {
+            felt(lhs, Read, f);
+            felt(rhs, Read, f);
+        }
+        DslIr::AssertEqE(lhs, rhs) => {
+            ext(lhs, Read, f);
+            ext(rhs, Read, f);
+        }
+        DslIr::AssertEqVI(lhs, _) => var(lhs, Read, f),
+        DslIr::AssertEqFI(lhs, _) => felt(lhs, Read, f),
+        DslIr::AssertEqEI(lhs, _) => ext(lhs, Read, f),
+        DslIr::AssertNonZero(u) => usize_var(u, f),
+        DslIr::Alloc(p, len, _) => {
+            rvar(len, f);
+            ptr(p, Write, f);
+        }
+        DslIr::LoadV(dst, p, index) => {
+            ptr(p, Read, f);
+            mem_index(index, f);
+            var(dst, Write, f);
+        }
+        DslIr::LoadF(dst, p, index) => {
+            ptr(p, Read, f);
+            mem_index(index, f);
+            felt(dst, Write, f);
+        }
+        DslIr::LoadE(dst, p, index) => {
+            ptr(p, Read, f);
+            mem_index(index, f);
+            ext(dst, Write, f);
+        }
+        DslIr::LoadHeapPtr(p) => ptr(p, Write, f),
+        DslIr::StoreV(src, p, index) => {
+            var(src, Read, f);
+            ptr(p, Read, f);
+            mem_index(index, f);
+        }
+        DslIr::StoreF(src, p, index) => {
+            felt(src, Read, f);
+            ptr(p, Read, f);
+            mem_index(index, f);
+        }
+        DslIr::StoreE(src, p, index) => {
+            ext(src, Read, f);
+            ptr(p, Read, f);
+            mem_index(index, f);
+        }
+        DslIr::StoreHeapPtr(p) => ptr(p, Read, f),
+        DslIr::CircuitNum2BitsF(src, bits) => {
+            felt(src, Read, f);
+            for bit in bits {
+                var(bit, Write, f);
+            }
+        }
+        DslIr::CircuitVarTo64BitsF(src, limbs) => {
+            var(src, Read, f);
+            for limb in limbs {
+                felt(limb, Write, f);
+            }
+        }
+        DslIr::Poseidon2PermuteBabyBear(dst, src) => {
+            felt_array(src, Read, f);
+            felt_array(dst, Write, f);
+        }
+        DslIr::Poseidon2CompressBabyBear(result, left, right) => {
+            felt_array(left, Read, f);
+            felt_array(right, Read, f);
+            felt_array(result, Write, f);
+        }
+        DslIr::CircuitPoseidon2Permute(state) => {
+            for v in state.iter_mut() {
+                var(v, Read, f);
+            }
+            for v in state.iter_mut() {
+                var(v, Write, f);
+            }
+        }
+        DslIr::PrintV(v) => var(v, Read, f),
+        DslIr::PrintF(v) => felt(v, Read, f),
+        DslIr::PrintE(v) => ext(v, Read, f),
+        DslIr::HintBitsF(v, _) => felt(v, Read, f),
+        DslIr::StoreHintWord(p, index) => {
+            ptr(p, Read, f);
+            mem_index(index, f);
+        }
+        DslIr::WitnessVar(v, _) => var(v, Write, f),
+        DslIr::WitnessFelt(v, _) => felt(v, Write, f),
+        DslIr::WitnessExt(v, _) => ext(v, Write, f),
+        DslIr::Publish(val, index) => {
+            felt(val, Read, f);
+            var(index, Read, f);
+        }
+        DslIr::CircuitPublish(v, _) => var(v, Read, f),
+        DslIr::CircuitSelectV(cond, a, b, out) => {
+            var(cond, Read, f);
+            var(a, Read, f);
+            var(b, Read, f);
+            var(out, Write, f);
+        }
+        DslIr::CircuitSelectF(cond, a, b, out) => {
+            var(cond, Read, f);
+            felt(a, Read, f);
+            felt(b, Read, f);
+            felt(out, Write, f);
+        }
+        DslIr::CircuitSelectE(cond, a, b, out) => {
+            var(cond, Read, f);
+            ext(a, Read, f);
+            ext(b, Read, f);
+            ext(out, Write, f);
+        }
+        DslIr::CircuitExt2Felt(felts, src) => {
+            ext(src, Read, f);
+            for v in felts {
+                felt(v, Write, f);
+            }
+        }
+        DslIr::CircuitFelts2Ext(felts, dst) => {
+            for v in felts {
+                felt(v, Read, f);
+            }
+            ext(dst, Write, f);
+        }
+        DslIr::CircuitFeltReduce(v) => {
+            felt(v, Read, f);
+            felt(v, Write, f);
+        }
+        DslIr::CircuitExtReduce(v) => {
+            ext(v, Read, f);
+            ext(v, Write, f);
+        }
+        DslIr::CircuitLessThan(lhs, rhs) => {
+            var(lhs, Read, f);
+            var(rhs, Read, f);
+        }
+        DslIr::FriReducedOpening(alpha, hint_id, is_init, at_x, at_z, result) => {
+            ext(alpha, Read, f);
+            var(hint_id, Read, f);
+            var(is_init, Read, f);
+            felt_array(at_x, Read, f);
+            ext_array(at_z, Read, f);
+            ext(result, Read, f);
+            ext(result, Write, f);
+        }
+        DslIr::VerifyBatchFelt(dims, opened, proof_id, index, commit) => {
+            array(dims, Read, f, dim::<C>);
+            array(opened, Read, f, felt_array::<C>);
+            var(proof_id, Read, f);
+            array(index, Read, f, var::<C>);
+            felt_array(commit, Read, f);
+        }
+        DslIr::VerifyBatchExt(dims, opened, proof_id, index, commit) => {
+            array(dims, Read, f, dim::<C>);
+            array(opened, Read, f, ext_array::<C>);
+            var(proof_id, Read, f);
+            array(index, Read, f, var::<C>);
+            felt_array(commit, Read, f);
+        }
+        DslIr::RangeCheckV(v, _) => var(v, Read, f),
+        DslIr::Error()
+        | DslIr::HintInputVec()
+        | DslIr::HintFelt()
+        | DslIr::HintLoad()
+        | DslIr::Halt
+        | DslIr::CycleTrackerStart(_)
+        | DslIr::CycleTrackerEnd(_) => {}
+    }
+}
+
+/// The blocks nested in a control flow instruction.
+fn nested_blocks<C: Config>(op: &mut DslIr<C>) -> Vec<&mut TracedVec<DslIr<C>>> {
+    match op {
+        DslIr::ZipFor(_, _, _, _, body) => vec![body],
+        DslIr::IfEq(_, _, then_block, else_block)
+        | DslIr::IfNe(_, _, then_block, else_block)
+        | DslIr::IfEqI(_, _, then_block, else_block)
+        | DslIr::IfNeI(_, _, then_block, else_block) => vec![then_block, else_block],
+        _ => vec![],
+    }
+}
+
+fn rename_operands<C: Config>(op: &mut DslIr<C>, renames: &HashMap<VarId, VarId>) {
+    if !renames.is_empty() {
+        visit_operands(op, &mut |slot: Slot<'_, C>, _| slot.rename(renames));
+    }
+}
+
+fn collect_writes<C: Config>(ops: &mut TracedVec<DslIr<C>>, writes: &mut HashSet<VarId>) {
+    for op in ops.vec.iter_mut() {
+        visit_operands(op, &mut |slot: Slot<'_, C>, access| {
+            if access == Access::Write {
+                writes.insert(slot.id());
+            }
+        });
+        for block in nested_blocks(op) {
+            collect_writes(block, writes);
+        }
+    }
+}
+
+// =================================================================================================
+// Analysis
+// =================================================================================================
+
+/// Definitions and reads of every variable in a program.
+#[derive(Default)]
+struct Usage {
+    defs: HashMap<VarId, usize>,
+    reads: HashMap<VarId, usize>,
+    /// Loop variables and variables read before their definition or outside of its block.
+    unstable: HashSet<VarId>,
+    /// Variables held by compile-time arrays.
+    pinned: HashSet<VarId>,
+}
+
+/// Tracks the block of each definition and the blocks enclosing the current instruction.
+struct Blocks {
+    defined_in: HashMap<VarId, usize>,
+    open: Vec<usize>,
+    next: usize,
+}
+
+impl Usage {
+    fn analyze<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> Self {
+        let mut usage = Self::default();
+        let mut blocks = Blocks {
+            defined_in: HashMap::new(),
+            open: vec![0],
+            next: 1,
+        };
+        usage.walk(ops, &mut blocks);
+        usage
+    }
+
+    fn walk<C: Config>(&mut self, ops: &mut TracedVec<DslIr<C>>, blocks: &mut Blocks) {
+        for op in ops.vec.iter_mut() {
+            let mut operands = Vec::new();
+            visit_operands(op, &mut |slot: Slot<'_, C>, access| {
+                operands.push((slot.id(), access, matches!(slot, Slot::Pinned(_))));
+            });
+            for &(id, access, pinned) in &operands {
+                if pinned {
+                    self.pinned.insert(id);
+                }
+                if access == Access::Read {
+                    *self.reads.entry(id).or_default() += 1;
+                    let dominated = blocks
+                        .defined_in
+                        .get(&id)
+                        .is_some_and(|block| blocks.open.contains(block));
+                    if !dominated {
+                        self.unstable.insert(id);
+                    }
+                }
+            }
+            for &(id, access, _) in &operands {
+                if access == Access::Write {
+                    *self.defs.entry(id).or_default() += 1;
+                    blocks.defined_in.insert(id, *blocks.open.last().unwrap());
+                }
+            }
+            if let DslIr::ZipFor(_, _, _, loop_vars, _) = op {
+                self.unstable
+                    .extend(loop_vars.iter().map(|loop_var| VarId::Var(loop_var.0)));
+            }
+            for block in nested_blocks(op) {
+                blocks.open.push(blocks.next);
+                blocks.next += 1;
+                self.walk(block, blocks);
+                blocks.open.pop();
+            }
+        }
+    }
+
+    /// Whether `id` holds the same value wherever it is read.
+    fn is_stable(&self, id: VarId) -> bool {
+        self.defs.get(&id) == Some(&1) && !self.unstable.contains(&id)
+    }
+
+    /// Whether every occurrence of `id` can be replaced by another variable.
+    fn is_renamable(&self, id: VarId) -> bool {
+        self.is_stable(id) && !self.pinned.contains(&id)
+    }
+}
+
+// =================================================================================================
+// Pure instructions
+// =================================================================================================
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Operand<N, F, EF> {
+    Id(VarId),
+    N(N),
+    F(F),
+    EF(EF),
+}
+
+type Key<C> = (
+    String,
+    Vec<Operand<<C as Config>::N, <C as Config>::F, <C as Config>::EF>>,
+);
+
+/// A pure arithmetic instruction.
+struct PureOp<C: Config> {
+    dst: VarId,
+    /// The instruction name and its operands, which identify the computed value.
+    key: Key<C>,
+    /// Whether the instruction can fail at runtime, i.e. it divides by a variable or by zero.
+    may_fail: bool,
+}
+
+impl<C: Config> PureOp<C> {
+    fn operand_ids(&self) -> impl Iterator<Item = VarId> + '_ {
+        self.key.1.iter().filter_map(|operand| match operand {
+            Operand::Id(id) => Some(*id),
+            _ => None,
+        })
+    }
+}
+
+fn pure_op<C: Config>(op: &DslIr<C>) -> Option<PureOp<C>> {
+    use Operand as O;
+    let v = |x: &Var<C::N>| O::Id(VarId::Var(x.0));
+    let f = |x: &Felt<C::F>| O::Id(VarId::Felt(x.0));
+    let e = |x: &Ext<C::F, C::EF>| O::Id(VarId::Ext(x.0));
+    let (dst, mut operands, commutative, may_fail) = match op {
+        DslIr::ImmV(d, c) => (v(d), vec![O::N(*c)], false, false),
+        DslIr::ImmF(d, c) => (f(d), vec![O::F(*c)], false, false),
+        DslIr::ImmE(d, c) => (e(d), vec![O::EF(*c)], false, false),
+        DslIr::AddV(d, a, b) | DslIr::MulV(d, a, b) => (v(d), vec![v(a), v(b)], true, false),
+        DslIr::SubV(d, a, b) => (v(d), vec![v(a), v(b)], false, false),
+        DslIr::AddVI(d, a, c) | DslIr::SubVI(d, a, c) | DslIr::MulVI(d, a, c) => {
+            (v(d), vec![v(a), O::N(*c)], false, false)
+        }
+        DslIr::SubVIN(d, c, b) => (v(d), vec![O::N(*c), v(b)], false, false),
+        DslIr::NegV(d, a) => (v(d), vec![v(a)], false, false),
+        DslIr::AddF(d, a, b) | DslIr::MulF(d, a, b) => (f(d), vec![f(a), f(b)], true, false),
+        DslIr::SubF(d, a, b) => (f(d), vec![f(a), f(b)], false, false),
+        DslIr::DivF(d, a, b) => (f(d), vec![f(a), f(b)], false, true),
+        DslIr::AddFI(d, a, c) | DslIr::SubFI(d, a, c) | DslIr::MulFI(d, a, c) => {
+            (f(d), vec![f(a), O::F(*c)], false, false)
+        }
+        DslIr::DivFI(d, a, c) => (f(d), vec![f(a), O::F(*c)], false, c.is_zero()),
+        DslIr::SubFIN(d, c, b) => (f(d), vec![O::F(*c), f(b)], false, false),
+        DslIr::DivFIN(d, c, b) => (f(d), vec![O::F(*c), f(b)], false, true),
+        DslIr::NegF(d, a) => (f(d), vec![f(a)], false, false),
+        DslIr::AddE(d, a, b) | DslIr::MulE(d, a, b) => (e(d), vec![e(a), e(b)], true, false),
+        DslIr::SubE(d, a, b) => (e(d), vec![e(a), e(b)], false, false),
+        DslIr::DivE(d, a, b) => (e(d), vec![e(a), e(b)], false, true),
+        DslIr::AddEI(d, a, c) | DslIr::SubEI(d, a, c) | DslIr::MulEI(d, a, c) => {
+            (e(d), vec![e(a), O::EF(*c)], false, false)
+        }
+        DslIr::DivEI(d, a, c) => (e(d), vec![e(a), O::EF(*c)], false, c.is_zero()),
+        DslIr::SubEIN(d, c, b) => (e(d), vec![O::EF(*c), e(b)], false, false),
+        DslIr::DivEIN(d, c, b) => (e(d), vec![O::EF(*c), e(b)], false, true),
+        DslIr::AddEFI(d, a, c) | DslIr::SubEFI(d, a, c) | DslIr::MulEFI(d, a, c) => {
+            (e(d), vec![e(a), O::F(*c)], false, false)
+        }
+        DslIr::DivEFI(d, a, c) => (e(d), vec![e(a), O::F(*c)], false, c.is_zero()),
+        DslIr::AddEF(d, a, b) | DslIr::SubEF(d, a, b) | DslIr::MulEF(d, a, b) => {
+            (e(d), vec![e(a), f(b)], false, false)
+        }
+        DslIr::DivEF(d, a, b) => (e(d), vec![e(a), f(b)], false, true),
+        DslIr::AddEFFI(d, a, c) => (e(d), vec![f(a), O::EF(*c)], false, false),
+        DslIr::NegE(d, a) => (e(d), vec![e(a)], false, false),
+        _ => return None,
+    };
+    if commutative {
+        if let [O::Id(a), O::Id(b)] = &mut operands[..] {
+            if a > b {
+                std::mem::swap(a, b);
+            }
+        }
+    }
+    let O::Id(dst) = dst else { unreachable!() };
+    Some(PureOp {
+        dst,
+        key: (op.to_string(), operands),
+        may_fail,
+    })
+}
+
+/// If `op` copies one variable into another, returns `(dst, src)`.
+fn as_copy<C: Config>(op: &DslIr<C>) -> Option<(VarId, VarId)> {
+    let (dst, src) = match op {
+        DslIr::AddVI(d, s, c) | DslIr::SubVI(d, s, c) if c.is_zero() => {
+            (VarId::Var(d.0), VarId::Var(s.0))
+        }
+        DslIr::MulVI(d, s, c) if *c == C::N::ONE => (VarId::Var(d.0), VarId::Var(s.0)),
+        DslIr::AddFI(d, s, c) | DslIr::SubFI(d, s, c) if c.is_zero() => {
+            (VarId::Felt(d.0), VarId::Felt(s.0))
+        }
+        DslIr::MulFI(d, s, c) | DslIr::DivFI(d, s, c) if *c == C::F::ONE => {
+            (VarId::Felt(d.0), VarId::Felt(s.0))
+        }
+        DslIr::AddEI(d, s, c) | DslIr::SubEI(d, s, c) if c.is_zero() => {
+            (VarId::Ext(d.0), VarId::Ext(s.0))
+        }
+        DslIr::MulEI(d, s, c) | DslIr::DivEI(d, s, c) if *c == C::EF::ONE => {
+            (VarId::Ext(d.0), VarId::Ext(s.0))
+        }
+        DslIr::AddEFI(d, s, c) | DslIr::SubEFI(d, s, c) if c.is_zero() => {
+            (VarId::Ext(d.0), VarId::Ext(s.0))
+        }
+        DslIr::MulEFI(d, s, c) | DslIr::DivEFI(d, s, c) if *c == C::F::ONE => {
+            (VarId::Ext(d.0), VarId::Ext(s.0))
+        }
+        _ => return None,
+    };
+    (dst != src).then_some((dst, src))
+}
+
+// =================================================================================================
+// Constant folding
+// =================================================================================================
+
+struct Constants<C: Config> {
+    vars: HashMap<u32, C::N>,
+    felts: HashMap<u32, C::F>,
+    exts: HashMap<u32, C::EF>,
+}
+
+impl<C: Config> Constants<C> {
+    fn var(&self, v: Var<C::N>) -> Option<C::N> {
+        self.vars.get(&v.0).copied()
+    }
+
+    fn felt(&self, v: Felt<C::F>) -> Option<C::F> {
+        self.felts.get(&v.0).copied()
+    }
+
+    fn ext(&self, v: Ext<C::F, C::EF>) -> Option<C::EF> {
+        self.exts.get(&v.0).copied()
+    }
+
+    fn record(&mut self, op: &DslIr<C>, usage: &Usage) {
+        match op {
+            DslIr::ImmV(d, c) if usage.is_stable(VarId::Var(d.0)) => {
+                self.vars.insert(d.0, *c);
+            }
+            DslIr::ImmF(d, c) if usage.is_stable(VarId::Felt(d.0)) => {
+                self.felts.insert(d.0, *c);
+            }
+            DslIr::ImmE(d, c) if usage.is_stable(VarId::Ext(d.0)) => {
+                self.exts.insert(d.0, *c);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn nonzero<T: Field>(x: Option<T>) -> Option<T> {
+    x.filter(|x| !x.is_zero())
+}
+
+/// Rewrites `op` using the known constant operands, or returns `None` if nothing changes.
+/// Divisions by a known zero are left alone so that they still fail at runtime.
+fn fold<C: Config>(op: &DslIr<C>, k: &Constants<C>) -> Option<DslIr<C>> {
+    let base = <C::EF as FieldExtensionAlgebra<C::F>>::from_base;
+    let folded = match *op {
+        DslIr::AddV(d, a, b) => match (k.var(a), k.var(b)) {
+            (Some(x), Some(y)) => DslIr::ImmV(d, x + y),
+            (Some(x), None) => DslIr::AddVI(d, b, x),
+            (None, Some(y)) => DslIr::AddVI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::AddVI(d, a, c) => DslIr::ImmV(d, k.var(a)? + c),
+        DslIr::AddF(d, a, b) => match (k.felt(a), k.felt(b)) {
+            (Some(x), Some(y)) => DslIr::ImmF(d, x + y),
+            (Some(x), None) => DslIr::AddFI(d, b, x),
+            (None, Some(y)) => DslIr::AddFI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::AddFI(d, a, c) => DslIr::ImmF(d, k.felt(a)? + c),
+        DslIr::AddE(d, a, b) => match (k.ext(a), k.ext(b)) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x + y),
+            (Some(x), None) => DslIr::AddEI(d, b, x),
+            (None, Some(y)) => DslIr::AddEI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::AddEI(d, a, c) => DslIr::ImmE(d, k.ext(a)? + c),
+        DslIr::AddEF(d, a, b) => match (k.ext(a), k.felt(b)) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x + base(y)),
+            (Some(x), None) => DslIr::AddEFFI(d, b, x),
+            (None, Some(y)) => DslIr::AddEFI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::AddEFI(d, a, c) => DslIr::ImmE(d, k.ext(a)? + base(c)),
+        DslIr::AddEFFI(d, a, c) => DslIr::ImmE(d, base(k.felt(a)?) + c),
+        DslIr::SubV(d, a, b) => match (k.var(a), k.var(b)) {
+            (Some(x), Some(y)) => DslIr::ImmV(d, x - y),
+            (Some(x), None) => DslIr::SubVIN(d, x, b),
+            (None, Some(y)) => DslIr::SubVI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::SubVI(d, a, c) => DslIr::ImmV(d, k.var(a)? - c),
+        DslIr::SubVIN(d, c, b) => DslIr::ImmV(d, c - k.var(b)?),
+        DslIr::SubF(d, a, b) => match (k.felt(a), k.felt(b)) {
+            (Some(x), Some(y)) => DslIr::ImmF(d, x - y),
+            (Some(x), None) => DslIr::SubFIN(d, x, b),
+            (None, Some(y)) => DslIr::SubFI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::SubFI(d, a, c) => DslIr::ImmF(d, k.felt(a)? - c),
+        DslIr::SubFIN(d, c, b) => DslIr::ImmF(d, c - k.felt(b)?),
+        DslIr::SubE(d, a, b) => match (k.ext(a), k.ext(b)) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x - y),
+            (Some(x), None) => DslIr::SubEIN(d, x, b),
+            (None, Some(y)) => DslIr::SubEI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::SubEI(d, a, c) => DslIr::ImmE(d, k.ext(a)? - c),
+        DslIr::SubEIN(d, c, b) => DslIr::ImmE(d, c - k.ext(b)?),
+        DslIr::SubEFI(d, a, c) => DslIr::ImmE(d, k.ext(a)? - base(c)),
+        DslIr::SubEF(d, a, b) => match (k.ext(a), k.felt(b)) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x - base(y)),
+            (None, Some(y)) => DslIr::SubEFI(d, a, y),
+            _ => return None,
+        },
+        DslIr::MulV(d, a, b) => match (k.var(a), k.var(b)) {
+            (Some(x), Some(y)) => DslIr::ImmV(d, x * y),
+            (Some(x), None) => DslIr::MulVI(d, b, x),
+            (None, Some(y)) => DslIr::MulVI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::MulVI(d, a, c) => DslIr::ImmV(d, k.var(a)? * c),
+        DslIr::MulF(d, a, b) => match (k.felt(a), k.felt(b)) {
+            (Some(x), Some(y)) => DslIr::ImmF(d, x * y),
+            (Some(x), None) => DslIr::MulFI(d, b, x),
+            (None, Some(y)) => DslIr::MulFI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::MulFI(d, a, c) => DslIr::ImmF(d, k.felt(a)? * c),
+        DslIr::MulE(d, a, b) => match (k.ext(a), k.ext(b)) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x * y),
+            (Some(x), None) => DslIr::MulEI(d, b, x),
+            (None, Some(y)) => DslIr::MulEI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::MulEI(d, a, c) => DslIr::ImmE(d, k.ext(a)? * c),
+        DslIr::MulEFI(d, a, c) => DslIr::ImmE(d, k.ext(a)? * base(c)),
+        DslIr::MulEF(d, a, b) => match (k.ext(a), k.felt(b)) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x * base(y)),
+            (None, Some(y)) => DslIr::MulEFI(d, a, y),
+            _ => return None,
+        },
+        DslIr::DivF(d, a, b) => match (k.felt(a), k.felt(b)) {
+            (_, Some(y)) if y.is_zero() => return None,
+            (Some(x), Some(y)) => DslIr::ImmF(d, x / y),
+            (Some(x), None) => DslIr::DivFIN(d, x, b),
+            (None, Some(y)) => DslIr::DivFI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::DivFI(d, a, c) if !c.is_zero() => DslIr::ImmF(d, k.felt(a)? / c),
+        DslIr::DivFIN(d, c, b) => DslIr::ImmF(d, c / nonzero(k.felt(b))?),
+        DslIr::DivE(d, a, b) => match (k.ext(a), k.ext(b)) {
+            (_, Some(y)) if y.is_zero() => return None,
+            (Some(x), Some(y)) => DslIr::ImmE(d, x / y),
+            (Some(x), None) => DslIr::DivEIN(d, x, b),
+            (None, Some(y)) => DslIr::DivEI(d, a, y),
+            (None, None) => return None,
+        },
+        DslIr::DivEI(d, a, c) if !c.is_zero() => DslIr::ImmE(d, k.ext(a)? / c),
+        DslIr::DivEIN(d, c, b) => DslIr::ImmE(d, c / nonzero(k.ext(b))?),
+        DslIr::DivEFI(d, a, c) if !c.is_zero() => DslIr::ImmE(d, k.ext(a)? / base(c)),
+        DslIr::DivEF(d, a, b) => match (k.ext(a), nonzero(k.felt(b))) {
+            (Some(x), Some(y)) => DslIr::ImmE(d, x / base(y)),
+            (None, Some(y)) => DslIr::DivEFI(d, a, y),
+            _ => return None,
+        },
+        DslIr::NegV(d, a) => DslIr::ImmV(d, -k.var(a)?),
+        DslIr::NegF(d, a) => DslIr::ImmF(d, -k.felt(a)?),
+        DslIr::NegE(d, a) => DslIr::ImmE(d, -k.ext(a)?),
+        _ => return None,
+    };
+    Some(folded)
+}
+
+fn fold_constants<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> usize {
+    let usage = Usage::analyze(ops);
+    let mut constants = Constants {
+        vars: HashMap::new(),
+        felts: HashMap::new(),
+        exts: HashMap::new(),
+    };
+    fold_block(ops, &usage, &mut constants)
+}
+
+fn fold_block<C: Config>(
+    ops: &mut TracedVec<DslIr<C>>,
+    usage: &Usage,
+    constants: &mut Constants<C>,
+) -> usize {
+    let mut folded = 0;
+    for op in ops.vec.iter_mut() {
+        if let Some(new_op) = fold(op, constants) {
+            *op = new_op;
+            folded += 1;
+        }
+        constants.record(op, usage);
+        for block in nested_blocks(op) {
+            folded += fold_block(block, usage, constants);
+        }
+    }
+    folded
+}
+
+// =================================================================================================
+// Copy propagation
+// =================================================================================================
+
+fn propagate_copies<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> usize {
+    let usage = Usage::analyze(ops);
+    copy_block(ops, &usage, &mut HashMap::new())
+}
+
+fn copy_block<C: Config>(
+    ops: &mut TracedVec<DslIr<C>>,
+    usage: &Usage,
+    renames: &mut HashMap<VarId, VarId>,
+) -> usize {
+    let mut removed = 0;
+    let mut kept = TracedVec::new();
+    for (mut op, trace) in std::mem::take(ops) {
+        rename_operands(&mut op, renames);
+        if let Some((dst, src)) = as_copy(&op) {
+            if usage.is_renamable(dst) && usage.is_stable(src) {
+                renames.insert(dst, src);
+                removed += 1;
+                continue;
+            }
+        }
+        for block in nested_blocks(&mut op) {
+            removed += copy_block(block, usage, renames);
+        }
+        kept.extend([(op, trace)]);
+    }
+    *ops = kept;
+    removed
+}
+
+// =================================================================================================
+// Common subexpression elimination
+// =================================================================================================
+
+fn eliminate_common_subexpressions<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> usize {
+    let usage = Usage::analyze(ops);
+    cse_block(ops, &usage, &mut vec![], &mut HashMap::new())
+}
+
+fn cse_block<C: Config>(
+    ops: &mut TracedVec<DslIr<C>>,
+    usage: &Usage,
+    scopes: &mut Vec<HashMap<Key<C>, VarId>>,
+    renames: &mut HashMap<VarId, VarId>,
+) -> usize {
+    let mut removed = 0;
+    let mut kept = TracedVec::new();
+    scopes.push(HashMap::new());
+    for (mut op, trace) in std::mem::take(ops) {
+        rename_operands(&mut op, renames);
+        if let Some(pure) = pure_op(&op) {
+            if usage.is_stable(pure.dst) && pure.operand_ids().all(|id| usage.is_stable(id)) {
+                let available = scopes.iter().rev().find_map(|scope| scope.get(&pure.key));
+                match available {
+                    Some(&prev) if usage.is_renamable(pure.dst) => {
+                        renames.insert(pure.dst, prev);
+                        removed += 1;
+                        continue;
+                    }
+                    Some(_) => {}
+                    None => {
+                        scopes.last_mut().unwrap().insert(pure.key, pure.dst);
+                    }
+                }
+            }
+        }
+        for block in nested_blocks(&mut op) {
+            removed += cse_block(block, usage, scopes, renames);
+        }
+        kept.extend([(op, trace)]);
+    }
+    scopes.pop();
+    *ops = kept;
+    removed
+}
+
+// =================================================================================================
+// Loop-invariant hoisting
+// =================================================================================================
+
+fn hoist_loop_invariants<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> usize {
+    let usage = Usage::analyze(ops);
+    hoist_block(ops, &usage)
+}
+
+fn hoist_block<C: Config>(ops: &mut TracedVec<DslIr<C>>, usage: &Usage) -> usize {
+    let mut hoisted = 0;
+    let mut kept = TracedVec::new();
+    for (mut op, trace) in std::mem::take(ops) {
+        for block in nested_blocks(&mut op) {
+            hoisted += hoist_block(block, usage);
+        }
+        if let DslIr::ZipFor(_, _, _, loop_vars, body) = &mut op {
+            // Variables whose value may change from one iteration to the next.
+            let mut variant: HashSet<VarId> = loop_vars.iter().map(|v| VarId::Var(v.0)).collect();
+            collect_writes(body, &mut variant);
+            let mut body_kept = TracedVec::new();
+            for (inner, inner_trace) in std::mem::take(body) {
+                let invariant = pure_op(&inner).filter(|pure| {
+                    !pure.may_fail
+                        && usage.is_stable(pure.dst)
+                        && pure.operand_ids().all(|id| !variant.contains(&id))
+                });
+                if let Some(pure) = invariant {
+                    variant.remove(&pure.dst);
+                    kept.extend([(inner, inner_trace)]);
+                    hoisted += 1;
+                } else {
+                    body_kept.extend([(inner, inner_trace)]);
+                }
+            }
+            *body = body_kept;
+        }
+        kept.extend([(op, trace)]);
+    }
+    *ops = kept;
+    hoisted
+}
+
+// =================================================================================================
+// Dead code elimination
+// =================================================================================================
+
+fn eliminate_dead_code<C: Config>(ops: &mut TracedVec<DslIr<C>>) -> usize {
+    let mut removed = 0;
+    loop {
+        let usage = Usage::analyze(ops);
+        let removed_now = dce_block(ops, &usage);
+        if removed_now == 0 {
+            return removed;
+        }
+        removed += removed_now;
+    }
+}
+
+fn dce_block<C: Config>(ops: &mut TracedVec<DslIr<C>>, usage: &Usage) -> usize {
+    let mut removed = 0;
+    let mut kept = TracedVec::new();
+    for (mut op, trace) in std::mem::take(ops) {
+        let dead = pure_op(&op).is_some_and(|pure| {
+            !pure.may_fail
+                && !usage.reads.contains_key(&pure.dst)
+                && !usage.pinned.contains(&pure.dst)
+        });
+        if dead {
+            removed += 1;
+            continue;
+        }
+        for block in nested_blocks(&mut op) {
+            removed += dce_block(block, usage);
+        }
+        kept.extend([(op, trace)]);
+    }
+    *ops = kept;
+    removed
+}
@@ -14,6 +14,7 @@ pub mod asm;
 pub mod constraints;
 pub mod conversion;
 pub mod ir;
+pub mod text;
 
 pub mod prelude {
     pub use openvm_native_compiler_derive::DslVariable;
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use openvm_instructions::{
+    instruction::{DebugInfo, Instruction, NUM_OPERANDS},
+    program::{Program, DEFAULT_PC_STEP},
+    VmOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use thiserror::Error;
+
+use super::opcode_from_name;
+
+/// An error in the assembly source, with the 1-based line it occurred on.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("line {line}: {kind}")]
+pub struct AssembleError {
+    pub line: usize,
+    pub kind: AssembleErrorKind,
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum AssembleErrorKind {
+    #[error("unknown opcode `{0}`")]
+    UnknownOpcode(String),
+    #[error("invalid operand `{0}`")]
+    InvalidOperand(String),
+    #[error("{0} operands given, an instruction has at most {NUM_OPERANDS}")]
+    TooManyOperands(usize),
+    #[error("invalid label name `{0}`")]
+    InvalidLabel(String),
+    #[error("label `{0}` is defined more than once")]
+    DuplicateLabel(String),
+    #[error("undefined label `{0}`")]
+    UndefinedLabel(String),
+    #[error("unknown directive `{0}`")]
+    UnknownDirective(String),
+    #[error("invalid argument `{1}` to `{0}`")]
+    InvalidDirectiveArgument(&'static str, String),
+    #[error("`.pc_base` must come before all instructions")]
+    MisplacedPcBase,
+}
+
+enum Operand<'a, F> {
+    Value(F),
+    Label(&'a str),
+}
+
+struct ParsedInstruction<'a, F> {
+    line: usize,
+    opcode: VmOpcode,
+    operands: Vec<Operand<'a, F>>,
+    debug_info: Option<DebugInfo>,
+}
+
+/// Parses a program in the textual assembly format.
+pub fn assemble<F: PrimeField32>(source: &str) -> Result<Program<F>, AssembleError> {
+    let mut pc_base = 0;
+    let mut slots: Vec<Option<ParsedInstruction<F>>> = Vec::new();
+    let mut labels: HashMap<&str, usize> = HashMap::new();
+
+    for (line_idx, raw_line) in source.lines().enumerate() {
+        let line = line_idx + 1;
+        let err = |kind| AssembleError { line, kind };
+        let (mut code, comment) = match raw_line.split_once(';') {
+            Some((code, comment)) => (code.trim(), Some(comment.trim())),
+            None => (raw_line.trim(), None),
+        };
+
+        while let Some((name, rest)) = code.split_once(':') {
+            let name = name.trim();
+            if !is_label(name) {
+                return Err(err(AssembleErrorKind::InvalidLabel(name.to_string())));
+            }
+            if labels.insert(name, slots.len()).is_some() {
+                return Err(err(AssembleErrorKind::DuplicateLabel(name.to_string())));
+            }
+            code = rest.trim();
+        }
+        if code.is_empty() {
+            continue;
+        }
+
+        let (head, rest) = code
+            .split_once(char::is_whitespace)
+            .map_or((code, ""), |(head, rest)| (head, rest.trim()));
+        if let Some(directive) = head.strip_prefix('.') {
+            match directive {
+                "pc_base" => {
+                    if !slots.is_empty() {
+                        return Err(err(AssembleErrorKind::MisplacedPcBase));
+                    }
+                    pc_base = parse_u32(rest).ok_or_else(|| {
+                        err(AssembleErrorKind::InvalidDirectiveArgument(
+                            ".pc_base",
+                            rest.to_string(),
+                        ))
+                    })?;
+                }
+                "skip" => {
+                    let n = parse_u32(rest).ok_or_else(|| {
+                        err(AssembleErrorKind::InvalidDirectiveArgument(
+                            ".skip",
+                            rest.to_string(),
+                        ))
+                    })?;
+                    slots.extend((0..n).map(|_| None));
+                }
+                _ => return Err(err(AssembleErrorKind::UnknownDirective(head.to_string()))),
+            }
+            continue;
+        }
+
+        let opcode = opcode_from_name(head)
+            .or_else(|| parse_u32(head).map(|x| VmOpcode::from_usize(x as usize)))
+            .ok_or_else(|| err(AssembleErrorKind::UnknownOpcode(head.to_string())))?;
+        let operands = if rest.is_empty() {
+            vec![]
+        } else {
+            rest.split(',')
+                .map(|operand| parse_operand(operand.trim()).map_err(err))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        if operands.len() > NUM_OPERANDS {
+            return Err(err(AssembleErrorKind::TooManyOperands(operands.len())));
+        }
+        let debug_info = comment.map(|comment| DebugInfo::new(comment.to_string(), None));
+        slots.push(Some(ParsedInstruction {
+            line,
+            opcode,
+            operands,
+            debug_info,
+        }));
+    }
+
+    let pc_of = |index: usize| pc_base + index as u32 * DEFAULT_PC_STEP;
+    let instructions_and_debug_infos = slots
+        .into_iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            let Some(parsed) = slot else {
+                return Ok(None);
+            };
+            let mut operands = [F::ZERO; NUM_OPERANDS];
+            for (value, operand) in operands.iter_mut().zip(parsed.operands) {
+                *value = match operand {
+                    Operand::Value(x) => x,
+                    Operand::Label(name) => {
+                        let target = labels.get(name).ok_or_else(|| AssembleError {
+                            line: parsed.line,
+                            kind: AssembleErrorKind::UndefinedLabel(name.to_string()),
+                        })?;
+                        F::from_canonical_u32(pc_of(*target)) - F::from_canonical_u32(pc_of(index))
+                    }
+                };
+            }
+            let [a, b, c, d, e, f, g] = operands;
+            let instruction = Instruction::new(parsed.opcode, a, b, c, d, e, f, g);
+            Ok(Some((instruction, parsed.debug_info)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Program {
+        instructions_and_debug_infos,
+        pc_base,
+    })
+}
+
+fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+fn parse_operand<F: PrimeField32>(operand: &str) -> Result<Operand<'_, F>, AssembleErrorKind> {
+    if is_label(operand) {
+        return Ok(Operand::Label(operand));
+    }
+    let invalid = || AssembleErrorKind::InvalidOperand(operand.to_string());
+    let (negative, magnitude) = match operand.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, operand),
+    };
+    let x = parse_u32(magnitude)
+        .filter(|&x| x < F::ORDER_U32)
+        .ok_or_else(invalid)?;
+    let x = F::from_canonical_u32(x);
+    Ok(Operand::Value(if negative { -x } else { x }))
+}
+
+fn parse_u32(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
@@ -0,0 +1,114 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use openvm_instructions::{
+    program::{Program, DEFAULT_PC_STEP},
+    VmOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{jump_operand, opcode_name};
+
+/// Writes `program` in the textual assembly format.
+///
+/// Jump and branch targets that land on a defined instruction are written as labels. Debug infos
+/// are kept as comments, but their backtraces are dropped.
+pub fn disassemble<F: PrimeField32>(program: &Program<F>) -> String {
+    let labels = jump_targets(program);
+    let mut out = String::new();
+    if program.pc_base != 0 {
+        writeln!(out, ".pc_base {}", program.pc_base).unwrap();
+    }
+    let mut skipped = 0;
+    for (index, slot) in program.instructions_and_debug_infos.iter().enumerate() {
+        let Some((instruction, debug_info)) = slot else {
+            skipped += 1;
+            continue;
+        };
+        if skipped > 0 {
+            writeln!(out, ".skip {skipped}").unwrap();
+            skipped = 0;
+        }
+        let pc = program.pc_base + index as u32 * DEFAULT_PC_STEP;
+        if labels.contains(&pc) {
+            writeln!(out, "{}:", label_name(pc)).unwrap();
+        }
+
+        let mut line = format!("    {}", mnemonic(instruction.opcode));
+        let operands = instruction.operands();
+        let label_operand = jump_operand(instruction.opcode)
+            .filter(|&i| labels.contains(&jump_target(pc, operands[i])));
+        let len = operands
+            .iter()
+            .rposition(|x| !x.is_zero())
+            .map_or(0, |i| i + 1)
+            .max(label_operand.map_or(0, |i| i + 1));
+        for (i, &operand) in operands[..len].iter().enumerate() {
+            line.push_str(if i == 0 { " " } else { ", " });
+            if label_operand == Some(i) {
+                line.push_str(&label_name(jump_target(pc, operand)));
+            } else {
+                line.push_str(&format_field(operand));
+            }
+        }
+        if let Some(debug_info) = debug_info {
+            line.push_str(" ;");
+            if !debug_info.dsl_instruction.is_empty() {
+                line.push(' ');
+                // Comments end at the end of the line.
+                line.push_str(&debug_info.dsl_instruction.replace(['\n', '\r'], " "));
+            }
+        }
+        writeln!(out, "{line}").unwrap();
+    }
+    if skipped > 0 {
+        writeln!(out, ".skip {skipped}").unwrap();
+    }
+    out
+}
+
+fn mnemonic(opcode: VmOpcode) -> String {
+    opcode_name(opcode).map_or_else(|| opcode.as_usize().to_string(), str::to_string)
+}
+
+fn label_name(pc: u32) -> String {
+    format!("L{pc}")
+}
+
+/// Writes elements in the upper half of the field as negative numbers, so that pc offsets and
+/// other small negative values stay readable.
+fn format_field<F: PrimeField32>(x: F) -> String {
+    let x = x.as_canonical_u32();
+    if x > F::ORDER_U32 / 2 {
+        format!("-{}", F::ORDER_U32 - x)
+    } else {
+        x.to_string()
+    }
+}
+
+fn jump_target<F: PrimeField32>(pc: u32, offset: F) -> u32 {
+    (F::from_canonical_u32(pc) + offset).as_canonical_u32()
+}
+
+/// The pcs of defined instructions that some jump or branch targets.
+fn jump_targets<F: PrimeField32>(program: &Program<F>) -> BTreeSet<u32> {
+    let is_defined = |pc: u32| {
+        pc >= program.pc_base
+            && (pc - program.pc_base) % DEFAULT_PC_STEP == 0
+            && program
+                .instructions_and_debug_infos
+                .get(((pc - program.pc_base) / DEFAULT_PC_STEP) as usize)
+                .is_some_and(Option::is_some)
+    };
+    program
+        .instructions_and_debug_infos
+        .iter()
+        .enumerate()
+        .filter_map(|(index, slot)| {
+            let (instruction, _) = slot.as_ref()?;
+            let pc = program.pc_base + index as u32 * DEFAULT_PC_STEP;
+            let offset = instruction.operands()[jump_operand(instruction.opcode)?];
+            let target = jump_target(pc, offset);
+            is_defined(target).then_some(target)
+        })
+        .collect()
+}
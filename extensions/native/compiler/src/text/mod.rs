@@ -0,0 +1,119 @@
+//! Textual assembly format for native [Program](openvm_instructions::program::Program)s.
+//!
+//! A program is written one instruction per line:
+//!
+//! ```text
+//! ; comment lines are ignored
+//! .pc_base 0
+//! L8:
+//!     ADDF 12, 12, 1, 4, 4, 0   ; AddVI
+//!     BNE 12, 16, L8, 4, 0      ; ZipFor
+//!     TERMINATE
+//! ```
+//!
+//! - An instruction is an opcode name followed by up to 7 comma separated operands `a, b, c, d, e,
+//!   f, g`. Missing trailing operands are zero. Opcode names are the ISA instruction names of the
+//!   system and native opcodes (see [opcode_name]). Other opcodes are written as their number.
+//! - Operands are field elements, written in decimal or in hexadecimal with a `0x` prefix. A
+//!   negative number `-x` stands for the field element `p - x`.
+//! - `name:` defines a label at the next instruction. A label used as an operand stands for the
+//!   offset from the instruction's pc to the label, which is how native jumps and branches encode
+//!   their targets.
+//! - Text after `;` is a comment. The comment on an instruction line is the instruction's debug
+//!   info, i.e. the DSL instruction it was compiled from.
+//! - `.pc_base <pc>` sets the pc of the first instruction and must come first. `.skip <n>` leaves
+//!   `n` instruction slots undefined.
+
+use openvm_instructions::{LocalOpcode, PublishOpcode, SystemOpcode, VmOpcode};
+use openvm_rv32im_transpiler::BranchEqualOpcode;
+
+pub use self::{
+    assembler::{assemble, AssembleError, AssembleErrorKind},
+    disassembler::disassemble,
+};
+use crate::{
+    CastfOpcode, FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
+    NativeJalOpcode, NativeLoadStore4Opcode, NativeLoadStoreOpcode, NativeRangeCheckOpcode,
+    Poseidon2Opcode, VerifyBatchOpcode,
+};
+
+mod assembler;
+mod disassembler;
+
+/// ISA names of the opcodes a native program may use.
+fn opcode_names() -> [(&'static str, VmOpcode); 26] {
+    use NativeLoadStoreOpcode::*;
+    [
+        ("TERMINATE", SystemOpcode::TERMINATE.global_opcode()),
+        ("PHANTOM", SystemOpcode::PHANTOM.global_opcode()),
+        ("PUBLISH", PublishOpcode::PUBLISH.global_opcode()),
+        ("LOADW", LOADW.global_opcode()),
+        ("STOREW", STOREW.global_opcode()),
+        ("HINT_STOREW", HINT_STOREW.global_opcode()),
+        ("LOADW4", NativeLoadStore4Opcode(LOADW).global_opcode()),
+        ("STOREW4", NativeLoadStore4Opcode(STOREW).global_opcode()),
+        (
+            "HINT_STOREW4",
+            NativeLoadStore4Opcode(HINT_STOREW).global_opcode(),
+        ),
+        (
+            "BEQ",
+            NativeBranchEqualOpcode(BranchEqualOpcode::BEQ).global_opcode(),
+        ),
+        (
+            "BNE",
+            NativeBranchEqualOpcode(BranchEqualOpcode::BNE).global_opcode(),
+        ),
+        ("JAL", NativeJalOpcode::JAL.global_opcode()),
+        (
+            "RANGE_CHECK",
+            NativeRangeCheckOpcode::RANGE_CHECK.global_opcode(),
+        ),
+        ("CASTF", CastfOpcode::CASTF.global_opcode()),
+        ("ADDF", FieldArithmeticOpcode::ADD.global_opcode()),
+        ("SUBF", FieldArithmeticOpcode::SUB.global_opcode()),
+        ("MULF", FieldArithmeticOpcode::MUL.global_opcode()),
+        ("DIVF", FieldArithmeticOpcode::DIV.global_opcode()),
+        ("FE4ADD", FieldExtensionOpcode::FE4ADD.global_opcode()),
+        ("FE4SUB", FieldExtensionOpcode::FE4SUB.global_opcode()),
+        ("BBE4MUL", FieldExtensionOpcode::BBE4MUL.global_opcode()),
+        ("BBE4DIV", FieldExtensionOpcode::BBE4DIV.global_opcode()),
+        ("PERM_POS2", Poseidon2Opcode::PERM_POS2.global_opcode()),
+        ("COMP_POS2", Poseidon2Opcode::COMP_POS2.global_opcode()),
+        (
+            "FRI_REDUCED_OPENING",
+            FriOpcode::FRI_REDUCED_OPENING.global_opcode(),
+        ),
+        (
+            "VERIFY_BATCH",
+            VerifyBatchOpcode::VERIFY_BATCH.global_opcode(),
+        ),
+    ]
+}
+
+/// Returns the ISA name of a system or native opcode.
+pub fn opcode_name(opcode: VmOpcode) -> Option<&'static str> {
+    opcode_names()
+        .into_iter()
+        .find_map(|(name, op)| (op == opcode).then_some(name))
+}
+
+/// Returns the opcode with the given ISA name, ignoring case.
+pub fn opcode_from_name(name: &str) -> Option<VmOpcode> {
+    opcode_names()
+        .into_iter()
+        .find_map(|(n, op)| n.eq_ignore_ascii_case(name).then_some(op))
+}
+
+/// Index of the operand holding the pc offset of the jump target, for jumps and branches.
+fn jump_operand(opcode: VmOpcode) -> Option<usize> {
+    if opcode == NativeJalOpcode::JAL.global_opcode() {
+        Some(1)
+    } else if opcode == NativeBranchEqualOpcode(BranchEqualOpcode::BEQ).global_opcode()
+        || opcode == NativeBranchEqualOpcode(BranchEqualOpcode::BNE).global_opcode()
+    {
+        Some(2)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,237 @@
+//! Groth16 wrapper over BN254: an alternative to the Halo2 static verifier and wrapper circuits
+//! for verifying a DSL program on-chain with a single pairing check.
+
+pub mod solidity;
+#[cfg(test)]
+mod tests;
+
+use ark_bn254::{Bn254, Fq, Fr};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use openvm_native_compiler::{
+    constraints::groth16::compiler::Groth16ConstraintCompiler, ir::Witness,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "evm-prove")]
+use crate::halo2::wrapper::{EvmVerifierByteCode, FallbackEvmVerifier};
+use crate::{config::outer::OuterConfig, halo2::DslOperations};
+
+/// A DSL program and its witness, as an arkworks circuit.
+pub struct Groth16Circuit {
+    pub dsl_operations: DslOperations<OuterConfig>,
+    pub witness: Witness<OuterConfig>,
+}
+
+impl ConstraintSynthesizer<Fr> for Groth16Circuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        Groth16ConstraintCompiler::<OuterConfig>::new(self.dsl_operations.num_public_values)
+            .constrain_r1cs(cs, self.dsl_operations.operations, &self.witness)
+    }
+}
+
+/// Proving key of the Groth16 wrapper, together with the DSL program it proves.
+#[derive(Clone, Debug)]
+pub struct Groth16ProvingKey {
+    pub pk: ProvingKey<Bn254>,
+    pub dsl_ops: DslOperations<OuterConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Groth16Proof {
+    pub public_values: Vec<Fr>,
+    pub proof: Proof<Bn254>,
+}
+
+impl Groth16ProvingKey {
+    /// Runs a circuit specific setup whose randomness is derived from `seed`.
+    ///
+    /// ATTENTION: anyone who knows `seed` can forge proofs. This is only meant for testing and
+    /// benchmarking; production keys must come from a trusted setup ceremony.
+    pub fn keygen_with_test_setup(
+        dsl_ops: DslOperations<OuterConfig>,
+        witness: Witness<OuterConfig>,
+        seed: u64,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let circuit = Groth16Circuit {
+            dsl_operations: dsl_ops.clone(),
+            witness,
+        };
+        let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(circuit, &mut rng).unwrap();
+        Self { pk, dsl_ops }
+    }
+
+    pub fn prove(&self, witness: Witness<OuterConfig>) -> Groth16Proof {
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+        let cs = ark_relations::r1cs::ConstraintSystem::new_ref();
+        Groth16Circuit {
+            dsl_operations: self.dsl_ops.clone(),
+            witness: witness.clone(),
+        }
+        .generate_constraints(cs.clone())
+        .unwrap();
+        assert!(
+            cs.is_satisfied().unwrap(),
+            "witness does not satisfy the circuit"
+        );
+        // The first instance variable is the constant one.
+        let public_values = cs.borrow().unwrap().instance_assignment[1..].to_vec();
+
+        let circuit = Groth16Circuit {
+            dsl_operations: self.dsl_ops.clone(),
+            witness,
+        };
+        let mut rng = StdRng::from_entropy();
+        let proof = Groth16::<Bn254>::prove(&self.pk, circuit, &mut rng).unwrap();
+        #[cfg(feature = "metrics")]
+        metrics::gauge!("total_proof_time_ms").set(start.elapsed().as_millis() as f64);
+        Groth16Proof {
+            public_values,
+            proof,
+        }
+    }
+
+    pub fn verify(&self, proof: &Groth16Proof) -> bool {
+        Groth16::<Bn254>::verify(&self.pk.vk, &proof.public_values, &proof.proof).unwrap_or(false)
+    }
+
+    /// Returns the Solidity code of a contract verifying proofs of this circuit. The contract
+    /// reverts on invalid proofs and expects [Groth16Proof::calldata] as calldata.
+    pub fn generate_evm_verifier_sol(&self) -> String {
+        solidity::generate_verifier_sol(&self.pk.vk)
+    }
+
+    #[cfg(feature = "evm-prove")]
+    /// Return deployment code for EVM verifier which can verify proofs of this circuit.
+    pub fn generate_evm_verifier(&self) -> FallbackEvmVerifier {
+        let sol_code = self.generate_evm_verifier_sol();
+        let bytecode = snark_verifier_sdk::snark_verifier::loader::evm::compile_solidity(&sol_code);
+        FallbackEvmVerifier {
+            sol_code,
+            artifact: EvmVerifierByteCode {
+                sol_compiler_version: "0.8.19".to_string(),
+                sol_compiler_options: "".to_string(),
+                bytecode,
+            },
+        }
+    }
+
+    #[cfg(feature = "evm-verify")]
+    /// A helper function for testing to verify a proof with the EVM verifier. Returns the gas
+    /// used.
+    pub fn evm_verify(
+        evm_verifier: &FallbackEvmVerifier,
+        proof: &Groth16Proof,
+    ) -> Result<u64, String> {
+        snark_verifier_sdk::snark_verifier::loader::evm::deploy_and_call(
+            evm_verifier.artifact.bytecode.clone(),
+            proof.calldata(),
+        )
+    }
+}
+
+impl Groth16Proof {
+    /// Return bytes calldata to be passed to the verifier contract: the public values followed
+    /// by the points `A`, `B` and `C` of the proof, as 32-byte big-endian words. Coordinates in
+    /// `Fq2` are encoded as `(c1, c0)`, as expected by the pairing precompile.
+    pub fn calldata(&self) -> Vec<u8> {
+        let mut words = self
+            .public_values
+            .iter()
+            .map(|x| x.into_bigint().to_bytes_be())
+            .collect::<Vec<_>>();
+        let fq = |x: &Fq| x.into_bigint().to_bytes_be();
+        let (a, b, c) = (&self.proof.a, &self.proof.b, &self.proof.c);
+        words.extend([fq(&a.x), fq(&a.y)]);
+        words.extend([fq(&b.x.c1), fq(&b.x.c0), fq(&b.y.c1), fq(&b.y.c0)]);
+        words.extend([fq(&c.x), fq(&c.y)]);
+        words.concat()
+    }
+}
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+fn from_bytes<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    bytes: &[u8],
+) -> Result<T, D::Error> {
+    T::deserialize_compressed(bytes)
+        .map_err(|e| de::Error::custom(format!("invalid bytes for groth16 object: {}", e)))
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGroth16ProvingKey {
+    pk_bytes: Vec<u8>,
+    dsl_ops: DslOperations<OuterConfig>,
+}
+
+impl Serialize for Groth16ProvingKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let serialized = SerializedGroth16ProvingKey {
+            pk_bytes: to_bytes(&self.pk),
+            dsl_ops: self.dsl_ops.clone(),
+        };
+        serialized.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Groth16ProvingKey {
+    fn deserialize<D>(deserializer: D) -> Result<Groth16ProvingKey, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let SerializedGroth16ProvingKey { pk_bytes, dsl_ops } =
+            SerializedGroth16ProvingKey::deserialize(deserializer)?;
+        Ok(Groth16ProvingKey {
+            pk: from_bytes::<_, D>(&pk_bytes)?,
+            dsl_ops,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGroth16Proof {
+    public_values_bytes: Vec<u8>,
+    proof_bytes: Vec<u8>,
+}
+
+impl Serialize for Groth16Proof {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let serialized = SerializedGroth16Proof {
+            public_values_bytes: to_bytes(&self.public_values),
+            proof_bytes: to_bytes(&self.proof),
+        };
+        serialized.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Groth16Proof {
+    fn deserialize<D>(deserializer: D) -> Result<Groth16Proof, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let SerializedGroth16Proof {
+            public_values_bytes,
+            proof_bytes,
+        } = SerializedGroth16Proof::deserialize(deserializer)?;
+        Ok(Groth16Proof {
+            public_values: from_bytes::<_, D>(&public_values_bytes)?,
+            proof: from_bytes::<_, D>(&proof_bytes)?,
+        })
+    }
+}
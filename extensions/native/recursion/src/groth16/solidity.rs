@@ -0,0 +1,139 @@
+//! Solidity verifier for Groth16 proofs over BN254, using the `ecAdd`, `ecMul` and pairing
+//! precompiles.
+
+use std::fmt::Write;
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use ark_groth16::VerifyingKey;
+
+/// Returns the code of a contract whose fallback function verifies a proof encoded as
+/// [Groth16Proof::calldata](super::Groth16Proof::calldata). The call reverts if the proof is
+/// invalid.
+pub fn generate_verifier_sol(vk: &VerifyingKey<Bn254>) -> String {
+    let num_public_values = vk.gamma_abc_g1.len() - 1;
+    let mut constants = String::new();
+    write_g1(&mut constants, "ALPHA", &vk.alpha_g1);
+    write_g2(&mut constants, "BETA", &vk.beta_g2);
+    write_g2(&mut constants, "GAMMA", &vk.gamma_g2);
+    write_g2(&mut constants, "DELTA", &vk.delta_g2);
+    for (i, ic) in vk.gamma_abc_g1.iter().enumerate() {
+        write_g1(&mut constants, &format!("IC{i}"), ic);
+    }
+
+    let mut accumulate = String::new();
+    for i in 0..num_public_values {
+        writeln!(
+            accumulate,
+            "        mulAcc(vkX, IC{}_X, IC{}_Y, publicValue({i}));",
+            i + 1,
+            i + 1
+        )
+        .unwrap();
+    }
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity 0.8.19;
+
+contract Groth16Verifier {{
+    // Scalar field modulus
+    uint256 constant R = {r};
+    // Base field modulus
+    uint256 constant Q = {q};
+    uint256 constant NUM_PUBLIC_VALUES = {num_public_values};
+
+{constants}
+    fallback(bytes calldata) external returns (bytes memory) {{
+        require(msg.data.length == 32 * (NUM_PUBLIC_VALUES + 8), "invalid calldata length");
+
+        uint256[2] memory vkX = [IC0_X, IC0_Y];
+{accumulate}
+        uint256 p = 32 * NUM_PUBLIC_VALUES;
+        uint256 aY = word(p + 32);
+        // e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+        uint256[24] memory input;
+        input[0] = word(p);
+        input[1] = aY == 0 ? 0 : Q - (aY % Q);
+        for (uint256 i = 0; i < 4; i++) {{
+            input[2 + i] = word(p + 64 + 32 * i);
+        }}
+        input[6] = ALPHA_X;
+        input[7] = ALPHA_Y;
+        input[8] = BETA_X1;
+        input[9] = BETA_X0;
+        input[10] = BETA_Y1;
+        input[11] = BETA_Y0;
+        input[12] = vkX[0];
+        input[13] = vkX[1];
+        input[14] = GAMMA_X1;
+        input[15] = GAMMA_X0;
+        input[16] = GAMMA_Y1;
+        input[17] = GAMMA_Y0;
+        input[18] = word(p + 192);
+        input[19] = word(p + 224);
+        input[20] = DELTA_X1;
+        input[21] = DELTA_X0;
+        input[22] = DELTA_Y1;
+        input[23] = DELTA_Y0;
+        uint256[1] memory out;
+        bool ok;
+        assembly {{
+            ok := staticcall(gas(), 0x08, input, 768, out, 32)
+        }}
+        require(ok && out[0] == 1, "invalid proof");
+        return "";
+    }}
+
+    function word(uint256 offset) internal pure returns (uint256) {{
+        return uint256(bytes32(msg.data[offset:offset + 32]));
+    }}
+
+    function publicValue(uint256 i) internal pure returns (uint256 value) {{
+        value = word(32 * i);
+        require(value < R, "public value out of range");
+    }}
+
+    // acc += s * (x, y)
+    function mulAcc(uint256[2] memory acc, uint256 x, uint256 y, uint256 s) internal view {{
+        uint256[3] memory mulInput = [x, y, s];
+        uint256[4] memory addInput;
+        bool ok;
+        assembly {{
+            ok := staticcall(gas(), 0x07, mulInput, 96, add(addInput, 64), 64)
+        }}
+        require(ok, "ecMul failed");
+        addInput[0] = acc[0];
+        addInput[1] = acc[1];
+        assembly {{
+            ok := staticcall(gas(), 0x06, addInput, 128, acc, 64)
+        }}
+        require(ok, "ecAdd failed");
+    }}
+}}
+"#,
+        r = Fr::MODULUS,
+        q = Fq::MODULUS,
+    )
+}
+
+fn write_constant(sol: &mut String, name: &str, value: &Fq) {
+    writeln!(
+        sol,
+        "    uint256 constant {name} = {};",
+        value.into_bigint()
+    )
+    .unwrap();
+}
+
+fn write_g1(sol: &mut String, name: &str, p: &G1Affine) {
+    write_constant(sol, &format!("{name}_X"), &p.x);
+    write_constant(sol, &format!("{name}_Y"), &p.y);
+}
+
+fn write_g2(sol: &mut String, name: &str, p: &G2Affine) {
+    write_constant(sol, &format!("{name}_X1"), &p.x.c1);
+    write_constant(sol, &format!("{name}_X0"), &p.x.c0);
+    write_constant(sol, &format!("{name}_Y1"), &p.y.c1);
+    write_constant(sol, &format!("{name}_Y0"), &p.y.c0);
+}
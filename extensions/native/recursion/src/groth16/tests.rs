@@ -0,0 +1,155 @@
+use ark_bn254::Fr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use openvm_native_compiler::{
+    constraints::groth16::compiler::convert_fr,
+    ir::{Builder, Ext, ExtConst, Felt, Var, Witness},
+};
+use openvm_stark_backend::{
+    p3_challenger::{CanObserve, CanSample},
+    p3_field::{
+        extension::BinomialExtensionField, FieldAlgebra, FieldExtensionAlgebra, PrimeField,
+    },
+};
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2_root::root_perm, p3_baby_bear::BabyBear, p3_bn254_fr::Bn254Fr,
+};
+use p3_symmetric::Permutation;
+
+use crate::{
+    challenger::multi_field32::MultiField32ChallengerVariable,
+    config::outer::{OuterChallenger, OuterConfig},
+    groth16::{Groth16Circuit, Groth16Proof, Groth16ProvingKey},
+    halo2::DslOperations,
+    outer_poseidon2::Poseidon2CircuitBuilder,
+};
+
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+const SEED: u64 = 42;
+
+/// A program using every kind of DSL instruction supported by the compiler, with its witness.
+fn test_program() -> (DslOperations<OuterConfig>, Witness<OuterConfig>, Bn254Fr) {
+    let felts = [1345237507, 1000001].map(BabyBear::from_canonical_u32);
+    let ext = EF::from_base_slice(&[1, 2, 3, 2013265920].map(BabyBear::from_canonical_u32));
+    let var = Bn254Fr::ZERO - Bn254Fr::from_canonical_u32(7);
+
+    let mut p2_state = [var, Bn254Fr::ONE, Bn254Fr::TWO];
+    root_perm().permute_mut(&mut p2_state);
+    let mut challenger = OuterChallenger::new(root_perm()).unwrap();
+    challenger.observe(felts[0]);
+    challenger.observe(felts[1]);
+    let sample: BabyBear = challenger.sample();
+
+    let mut builder = Builder::<OuterConfig>::default();
+    builder.flags.static_only = true;
+    let a: Felt<_> = builder.witness_felt();
+    let b: Felt<_> = builder.witness_felt();
+    let e: Ext<_, _> = builder.witness_ext();
+    let v: Var<_> = builder.witness_var();
+
+    let c: Felt<_> = builder.eval(a * b - a / b + BabyBear::from_canonical_u32(5));
+    builder.assert_felt_eq(
+        c,
+        felts[0] * felts[1] - felts[0] / felts[1] + BabyBear::from_canonical_u32(5),
+    );
+    let f: Ext<_, _> = builder.eval(e * e - e / e + c);
+    let expected_f = ext * ext - EF::ONE + felts[0] * felts[1] - felts[0] / felts[1]
+        + BabyBear::from_canonical_u32(5);
+    builder.assert_ext_eq(f, expected_f.cons());
+    let f_felts = builder.ext2felt_circuit(f);
+    builder.felt_reduce_circuit(f_felts[3]);
+    let bits = builder.num2bits_f_circuit(f_felts[0]);
+    let recomposed = builder.bits2num_v_circuit(&bits);
+    let reduced = builder.cast_felt_to_var(f_felts[0]);
+    builder.assert_var_eq(recomposed, reduced);
+
+    let limbs = builder.var_to_64bits_f_circuit(v);
+    let var_limbs = var.as_canonical_biguint().to_u64_digits();
+    for (limb, expected) in limbs.into_iter().zip(var_limbs) {
+        let expected: Felt<_> = builder.eval(BabyBear::from_wrapped_u64(expected));
+        builder.assert_felt_eq(limb, expected);
+    }
+
+    let state: [Var<_>; 3] = [
+        builder.eval(v),
+        builder.eval(Bn254Fr::ONE),
+        builder.eval(Bn254Fr::TWO),
+    ];
+    builder.p2_permute_mut(state);
+    for (x, expected) in state.into_iter().zip(p2_state) {
+        builder.assert_var_eq(x, expected);
+    }
+
+    let mut challenger = MultiField32ChallengerVariable::new(&mut builder);
+    challenger.observe(&mut builder, a);
+    challenger.observe(&mut builder, b);
+    let result = challenger.sample(&mut builder);
+    builder.assert_felt_eq(result, sample);
+
+    builder.static_commit_public_value(0, state[0]);
+    builder.static_commit_public_value(1, reduced);
+
+    let mut witness = Witness::default();
+    witness.felts.extend(felts);
+    witness.exts.push(ext);
+    witness.vars.push(var);
+    let operations = DslOperations {
+        operations: builder.operations,
+        num_public_values: 2,
+    };
+    (operations, witness, p2_state[0])
+}
+
+#[test]
+fn test_groth16_constraints() {
+    let (operations, witness, public_value) = test_program();
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    Groth16Circuit {
+        dsl_operations: operations,
+        witness,
+    }
+    .generate_constraints(cs.clone())
+    .unwrap();
+    assert!(cs.is_satisfied().unwrap());
+    let instances = cs.borrow().unwrap().instance_assignment.clone();
+    assert_eq!(instances.len(), 3);
+    assert_eq!(instances[1], convert_fr(&public_value));
+}
+
+#[test]
+fn test_groth16_prove_verify() {
+    let (operations, witness, public_value) = test_program();
+    let pk = Groth16ProvingKey::keygen_with_test_setup(operations, witness.clone(), SEED);
+    let proof = pk.prove(witness);
+    assert_eq!(proof.public_values[0], convert_fr(&public_value));
+    assert!(pk.verify(&proof));
+
+    let mut wrong_proof = proof.clone();
+    wrong_proof.public_values[0] += Fr::from(1u64);
+    assert!(!pk.verify(&wrong_proof));
+
+    let pk: Groth16ProvingKey = bitcode::deserialize(&bitcode::serialize(&pk).unwrap()).unwrap();
+    let proof: Groth16Proof = bitcode::deserialize(&bitcode::serialize(&proof).unwrap()).unwrap();
+    assert!(pk.verify(&proof));
+
+    // The setup is deterministic.
+    let (operations, witness, _) = test_program();
+    let pk2 = Groth16ProvingKey::keygen_with_test_setup(operations, witness, SEED);
+    assert_eq!(pk.pk.vk, pk2.pk.vk);
+}
+
+#[cfg(feature = "evm-verify")]
+#[test]
+fn test_groth16_evm_verify() {
+    let (operations, witness, _) = test_program();
+    let pk = Groth16ProvingKey::keygen_with_test_setup(operations, witness.clone(), SEED);
+    let proof = pk.prove(witness);
+    let evm_verifier = pk.generate_evm_verifier();
+    let gas_cost = Groth16ProvingKey::evm_verify(&evm_verifier, &proof).unwrap();
+    // Four pairings and a scalar multiplication per public input, plus the transaction cost.
+    assert!(gas_cost < 400_000, "gas cost {gas_cost}");
+
+    let mut wrong_proof = proof;
+    wrong_proof.public_values[1] += Fr::from(1u64);
+    assert!(Groth16ProvingKey::evm_verify(&evm_verifier, &wrong_proof).is_err());
+}
@@ -10,7 +10,10 @@ use std::fmt::Debug;
 
 use itertools::Itertools;
 use openvm_native_compiler::{
-    constraints::halo2::compiler::{Halo2ConstraintCompiler, Halo2State},
+    constraints::halo2::{
+        compiler::{Halo2ConstraintCompiler, Halo2State},
+        profile::ConstraintProfile,
+    },
     ir::{Config, DslIr, TracedVec, Witness},
 };
 use openvm_stark_backend::p3_field::extension::BinomialExtensionField;
@@ -127,6 +130,24 @@ impl Halo2Prover {
         state.builder
     }
 
+    /// Builds the circuit in keygen mode and reports the advice cells, lookups and copy
+    /// constraints generated by each DSL instruction and call site. See [ConstraintProfile].
+    pub fn profile<
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BinomialExtensionField<BabyBear, 4>> + Debug,
+    >(
+        k: usize,
+        dsl_operations: DslOperations<C>,
+        witness: Witness<C>,
+    ) -> ConstraintProfile {
+        let mut state = Halo2State {
+            builder: Self::builder(CircuitBuilderStage::Keygen, k),
+            ..Default::default()
+        };
+        state.load_witness(witness);
+        Halo2ConstraintCompiler::<C>::new(dsl_operations.num_public_values)
+            .profile_halo2(&mut state, dsl_operations.operations)
+    }
+
     /// Executes the prover in testing mode with a circuit definition and witness.
     ///
     /// Returns the public instances.
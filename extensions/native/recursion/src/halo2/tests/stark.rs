@@ -1,4 +1,7 @@
-use openvm_native_compiler::ir::Witness;
+use openvm_native_compiler::{
+    constraints::halo2::profile::{ConstraintCost, ProfileMetric},
+    ir::Witness,
+};
 use openvm_stark_sdk::{
     config::{
         baby_bear_poseidon2_root::{BabyBearPoseidon2RootConfig, BabyBearPoseidon2RootEngine},
@@ -10,8 +13,8 @@ use openvm_stark_sdk::{
 use tracing::Level;
 
 use crate::{
-    config::outer::new_from_outer_multi_vk,
-    halo2::Halo2Prover,
+    config::outer::{new_from_outer_multi_vk, OuterConfig},
+    halo2::{DslOperations, Halo2Prover},
     stark::outer::build_circuit_verify_operations,
     tests::{fibonacci_test_proof_input, interaction_test_proof_input},
     witness::Witnessable,
@@ -29,8 +32,48 @@ fn test_interactions() {
     run_recursive_test(interaction_test_proof_input::<BabyBearPoseidon2RootConfig>())
 }
 
-fn run_recursive_test(mut test_proof_input: ProofInputForTest<BabyBearPoseidon2RootConfig>) {
-    setup_tracing_with_log_level(Level::WARN);
+#[test]
+fn test_fibonacci_constraint_profile() {
+    let (operations, witness) = verify_operations(fibonacci_test_proof_input::<
+        BabyBearPoseidon2RootConfig,
+    >(16));
+    let num_operations = operations.operations.vec.len();
+    let profile = Halo2Prover::profile(20, operations, witness);
+
+    let total = profile.total();
+    assert!(total.advice_cells > 0);
+    assert!(total.lookup_cells > 0);
+    assert!(total.copy_constraints > 0);
+    let by_instruction = profile.by_instruction();
+    assert!(by_instruction.len() > 1 && by_instruction.len() <= num_operations);
+    assert!(by_instruction
+        .windows(2)
+        .all(|pair| pair[0].1.advice_cells >= pair[1].1.advice_cells));
+    let mut instruction_total = ConstraintCost::default();
+    for (_, cost) in &by_instruction {
+        instruction_total += *cost;
+    }
+    assert_eq!(instruction_total, total);
+
+    for metric in [
+        ProfileMetric::AdviceCells,
+        ProfileMetric::LookupCells,
+        ProfileMetric::CopyConstraints,
+    ] {
+        let mut folded = Vec::new();
+        profile.write_folded(&mut folded, metric).unwrap();
+        let sum: usize = String::from_utf8(folded)
+            .unwrap()
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().1.parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(sum, total.get(metric));
+    }
+}
+
+fn verify_operations(
+    mut test_proof_input: ProofInputForTest<BabyBearPoseidon2RootConfig>,
+) -> (DslOperations<OuterConfig>, Witness<OuterConfig>) {
     test_proof_input.sort_chips();
     let vparams = test_proof_input
         .run_test(&BabyBearPoseidon2RootEngine::new(
@@ -43,5 +86,11 @@ fn run_recursive_test(mut test_proof_input: ProofInputForTest<BabyBearPoseidon2R
     let mut witness = Witness::default();
     proof.write(&mut witness);
     let operations = build_circuit_verify_operations(advice, &vparams.fri_params, &proof);
+    (operations, witness)
+}
+
+fn run_recursive_test(test_proof_input: ProofInputForTest<BabyBearPoseidon2RootConfig>) {
+    setup_tracing_with_log_level(Level::WARN);
+    let (operations, witness) = verify_operations(test_proof_input);
     Halo2Prover::mock(20, operations, witness);
 }
@@ -1,4 +1,4 @@
-use openvm_native_compiler::ir::Witness;
+use openvm_native_compiler::{constraints::halo2::profile::ConstraintProfile, ir::Witness};
 use openvm_stark_backend::proof::Proof;
 use openvm_stark_sdk::config::{
     baby_bear_poseidon2_root::BabyBearPoseidon2RootConfig, FriParameters,
@@ -57,6 +57,16 @@ impl Halo2VerifierProvingKey {
     }
     // TODO: Add verify method
 
+    /// Reports the advice cells, lookups and copy constraints generated by each DSL instruction
+    /// of the verifier circuit for `witness`.
+    pub fn profile(&self, witness: Witness<OuterConfig>) -> ConstraintProfile {
+        Halo2Prover::profile(
+            self.pinning.metadata.config_params.k,
+            self.dsl_ops.clone(),
+            witness,
+        )
+    }
+
     /// Generate a dummy snark for wrapper keygen.
     pub fn generate_dummy_snark(&self, reader: &impl Halo2ParamsReader) -> Snark {
         self.pinning.generate_dummy_snark(reader)
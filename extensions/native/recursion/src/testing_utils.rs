@@ -55,6 +55,15 @@ pub mod inner {
     pub fn run_recursive_test(
         test_proof_input: ProofInputForTest<BabyBearPoseidon2Config>,
         fri_params: FriParameters,
+    ) {
+        run_recursive_test_with_options(test_proof_input, fri_params, CompilerOptions::default());
+    }
+
+    /// Same as [run_recursive_test], compiling the verifier program with `compiler_options`.
+    pub fn run_recursive_test_with_options(
+        test_proof_input: ProofInputForTest<BabyBearPoseidon2Config>,
+        fri_params: FriParameters,
+        compiler_options: CompilerOptions,
     ) {
         let vparams = test_proof_input
             .run_test(&BabyBearPoseidon2Engine::new(
@@ -62,7 +71,6 @@ pub mod inner {
             ))
             .unwrap();
 
-        let compiler_options = CompilerOptions::default();
         let (program, witness_stream) = build_verification_program(vparams, compiler_options);
         air_test_impl::<BabyBearPoseidon2Engine, _>(
             fri_params,
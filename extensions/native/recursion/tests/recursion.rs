@@ -9,8 +9,14 @@ use openvm_circuit::{
 use openvm_native_circuit::{
     execute_program_with_config, test_native_config, NativeBuilder, NativeConfig,
 };
-use openvm_native_compiler::{asm::AsmBuilder, ir::Felt};
-use openvm_native_recursion::testing_utils::inner::run_recursive_test;
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    conversion::CompilerOptions,
+    ir::{Felt, OptimizationOptions},
+};
+use openvm_native_recursion::testing_utils::inner::{
+    build_verification_program, run_recursive_test, run_recursive_test_with_options,
+};
 use openvm_stark_backend::{
     config::{Domain, StarkGenericConfig, Val},
     p3_commit::PolynomialSpace,
@@ -145,6 +151,43 @@ fn test_fibonacci_program_verify() {
     run_recursive_test(fib_program_stark, FriParameters::new_for_testing(3));
 }
 
+#[test]
+fn test_fibonacci_program_verify_optimized() {
+    let fib_program_stark = || {
+        fibonacci_program_test_proof_input::<
+            BabyBearPoseidon2Config,
+            TestStarkEngine,
+            BabyBearPoseidon2Engine,
+            1,
+        >(0, 1, 32)
+    };
+    let optimized_options =
+        CompilerOptions::default().with_optimizations(OptimizationOptions::all());
+
+    // Compare the size of the verifier program with and without optimizations.
+    let engine = BabyBearPoseidon2Engine::new(FriParameters::new_for_testing(1));
+    let program_len = |compiler_options| {
+        let vparams = fib_program_stark().run_test(&engine).unwrap();
+        build_verification_program(vparams, compiler_options)
+            .0
+            .len()
+    };
+    let baseline = program_len(CompilerOptions::default());
+    let optimized = program_len(optimized_options);
+    assert!(optimized < baseline);
+    println!(
+        "verifier program instructions: {baseline} -> {optimized} ({} saved)",
+        baseline - optimized
+    );
+
+    // The optimized verifier program must still accept the proof.
+    run_recursive_test_with_options(
+        fib_program_stark(),
+        FriParameters::new_for_testing(3),
+        optimized_options,
+    );
+}
+
 #[cfg(all(feature = "static-verifier", not(feature = "cuda")))]
 #[test]
 #[ignore = "needs params files"]
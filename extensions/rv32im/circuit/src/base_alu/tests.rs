@@ -2,7 +2,10 @@ use std::{array, borrow::BorrowMut, sync::Arc};
 
 use openvm_circuit::{
     arch::{
-        testing::{TestBuilder, TestChipHarness, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+        testing::{
+            ConstraintFuzzer, TestBuilder, TestChipHarness, TraceMutation, VmChipTestBuilder,
+            BITWISE_OP_LOOKUP_BUS,
+        },
         Arena, ExecutionBridge, PreflightExecutor,
     },
     system::memory::{offline_checker::MemoryBridge, SharedMemoryHelper},
@@ -20,6 +23,7 @@ use openvm_stark_backend::{
         dense::{DenseMatrix, RowMajorMatrix},
         Matrix,
     },
+    rap::get_air_name,
     utils::disable_debug_builder,
 };
 use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
@@ -412,6 +416,43 @@ fn rv32_alu_adapter_unconstrained_rs2_read_test() {
     );
 }
 
+// Mutates every cell of a few rows. Operands are registers, since with an immediate `c` the
+// auxiliary columns of the rs2 read are unused and mutating them is harmless.
+#[test_case(ADD)]
+#[test_case(XOR)]
+fn rv32_alu_fuzz_test(opcode: BaseAluOpcode) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut harness, bitwise) = create_harness(&tester);
+
+    for _ in 0..4 {
+        set_and_execute(
+            &mut tester,
+            &mut harness.executor,
+            &mut harness.arena,
+            &mut rng,
+            opcode,
+            None,
+            Some(false),
+            None,
+        );
+    }
+
+    // The rows of the ALU chip are not ordered.
+    let fuzzer = ConstraintFuzzer::new(get_air_name(&harness.air)).with_row_swaps(0);
+    let tester = tester
+        .build()
+        .load(harness)
+        .load_periphery(bitwise)
+        .finalize();
+    let report = tester.simple_fuzz(&fuzzer);
+    assert!(report
+        .results
+        .iter()
+        .any(|(mutation, _)| matches!(mutation, TraceMutation::Interaction { .. })));
+    report.assert_sound();
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /// SANITY TESTS
 ///
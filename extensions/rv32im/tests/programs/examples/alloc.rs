@@ -0,0 +1,66 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+use alloc::{boxed::Box, collections::BTreeMap, vec, vec::Vec};
+use core::alloc::Layout;
+
+openvm::entry!(main);
+
+#[repr(align(8192))]
+struct PageAligned([u8; 16]);
+
+pub fn main() {
+    // Freed blocks are reused and must be zeroed by `alloc_zeroed`.
+    for i in 0..64usize {
+        let mut small = vec![0u8; 24 + i];
+        assert!(small.iter().all(|&b| b == 0));
+        small.fill(0xff);
+    }
+    for i in 0..4usize {
+        let mut large = vec![0u32; (1 << 18) + i];
+        assert!(large.iter().step_by(1021).all(|&b| b == 0));
+        assert_eq!(large[(1 << 18) + i - 1], 0);
+        large.iter_mut().step_by(1021).for_each(|b| *b = 1);
+        *large.last_mut().unwrap() = 1;
+    }
+
+    // Growing across size classes keeps the contents.
+    let mut v = Vec::new();
+    for i in 0..10_000u32 {
+        v.push(i);
+    }
+    assert!(v.iter().copied().eq(0..10_000));
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert_eq!(v, (0..10).collect::<Vec<_>>());
+
+    // Alignment is respected for both size classes and large blocks.
+    for size in [1usize, 7, 100, 5000, 1 << 21] {
+        for align in [1usize, 8, 64, 4096, 1 << 16] {
+            let layout = Layout::from_size_align(size, align).unwrap();
+            unsafe {
+                let ptr = alloc::alloc::alloc(layout);
+                assert!(!ptr.is_null());
+                assert_eq!(ptr as usize % align, 0);
+                ptr.write_bytes(0xab, size);
+                alloc::alloc::dealloc(ptr, layout);
+            }
+        }
+    }
+    let boxed = Box::new(PageAligned([1; 16]));
+    assert_eq!(&*boxed as *const _ as usize % 8192, 0);
+    assert_eq!(boxed.0, [1; 16]);
+
+    // Many live allocations of different sizes do not overlap.
+    let mut map = BTreeMap::new();
+    for i in 0..500u32 {
+        map.insert(i, vec![i as u8; (i % 37) as usize]);
+        if i % 3 == 0 {
+            map.remove(&(i / 2));
+        }
+    }
+    for (k, v) in map {
+        assert!(v.iter().all(|&b| b == k as u8));
+        assert_eq!(v.len(), (k % 37) as usize);
+    }
+}
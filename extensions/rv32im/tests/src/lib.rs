@@ -259,13 +259,14 @@ mod tests {
         Ok(())
     }
 
-    #[test]
-    fn test_tiny_mem_test() -> Result<()> {
+    #[test_case("heap-embedded-alloc")]
+    #[test_case("heap-freelist-alloc")]
+    fn test_tiny_mem_test(heap_feature: &str) -> Result<()> {
         let config = test_rv32im_config();
         let elf = build_example_program_at_path_with_features(
             get_programs_dir!(),
             "tiny-mem-test",
-            ["heap-embedded-alloc"],
+            [heap_feature],
             &config,
         )?;
         let exe = VmExe::from_elf(
@@ -279,6 +280,30 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(&[])]
+    #[test_case(&["heap-embedded-alloc"])]
+    #[test_case(&["heap-freelist-alloc"])]
+    fn test_alloc(features: &[&str]) -> Result<()> {
+        let config = test_rv32im_config();
+        let elf = build_example_program_at_path_with_features(
+            get_programs_dir!(),
+            "alloc",
+            features,
+            &config,
+        )?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension),
+        )?;
+        let executor = VmExecutor::new(config)?;
+        let instance = executor.instance(&exe)?;
+        instance.execute(vec![], None)?;
+        Ok(())
+    }
+
     #[test]
     #[should_panic]
     fn test_load_x0() {
@@ -0,0 +1,20 @@
+extern crate alloc;
+
+use openvm::io::read;
+use openvm_verify_stark::{define_verify_openvm_stark, ivc::begin_step};
+
+define_verify_openvm_stark!(
+    verify_openvm_stark,
+    env!("CARGO_MANIFEST_DIR"),
+    "root_verifier.asm"
+);
+
+/// Each step adds its input to a counter starting from 0.
+pub fn main() {
+    let step = begin_step(verify_openvm_stark);
+    let counter = step.prev_state.as_ref().map_or(0, |state| {
+        u32::from_le_bytes(state[..4].try_into().unwrap())
+    });
+    let delta: u32 = read();
+    step.finish(&(counter + delta).to_le_bytes());
+}
@@ -0,0 +1,87 @@
+//! Guest side of incremental verifiable computation (IVC).
+//!
+//! An IVC step program verifies the proof of the previous step, which is a proof of the same
+//! program, and carries its state forward. The user public values of every step are an
+//! [IVC_HEADER_BYTES] bytes header followed by the state:
+//! - the `app_exe_commit` of the step program, as 8 little-endian u32s,
+//! - the `app_vm_commit` of the step program, as 8 little-endian u32s,
+//! - the number of steps proven so far, as a little-endian u32.
+//!
+//! A step program cannot contain its own commitments, so they are provided as inputs and exposed in
+//! the header. The verifier of the last proof checks that the header commitments are the
+//! commitments of the proven program, which by induction holds for the proofs of all previous
+//! steps.
+//!
+//! The inputs of a step are written by `openvm_sdk::ivc::IvcProver`, followed by the inputs of the
+//! user.
+
+use alloc::vec::Vec;
+
+use openvm::io::{read, reveal_u32};
+
+/// Number of bytes of the IVC header in the user public values.
+pub const IVC_HEADER_BYTES: usize = 68;
+
+/// State of an IVC step between [begin_step] and [IvcStep::finish].
+pub struct IvcStep {
+    pub app_exe_commit: [u32; 8],
+    pub app_vm_commit: [u32; 8],
+    /// Number of steps proven including this one.
+    pub num_steps: u32,
+    /// State revealed by the previous step, including its zero padding up to the number of user
+    /// public values. `None` for the first step.
+    pub prev_state: Option<Vec<u8>>,
+}
+
+/// Reads the IVC inputs of this step and verifies the proof of the previous step, if any.
+/// `verify_prev` must be a function defined by
+/// [define_verify_openvm_stark](crate::define_verify_openvm_stark).
+pub fn begin_step(verify_prev: fn(&[u32; 8], &[u32; 8], &[u8])) -> IvcStep {
+    let app_exe_commit: [u32; 8] = read();
+    let app_vm_commit: [u32; 8] = read();
+    let prev_pvs: Option<Vec<u8>> = read();
+    let Some(mut prev_pvs) = prev_pvs else {
+        return IvcStep {
+            app_exe_commit,
+            app_vm_commit,
+            num_steps: 1,
+            prev_state: None,
+        };
+    };
+    assert!(prev_pvs.len() >= IVC_HEADER_BYTES);
+    let word = |i: usize| u32::from_le_bytes(prev_pvs[4 * i..4 * i + 4].try_into().unwrap());
+    // The previous step must be a step of the same program.
+    for i in 0..8 {
+        assert_eq!(word(i), app_exe_commit[i]);
+        assert_eq!(word(8 + i), app_vm_commit[i]);
+    }
+    let num_steps = word(16) + 1;
+    verify_prev(&app_exe_commit, &app_vm_commit, &prev_pvs);
+    let prev_state = prev_pvs.split_off(IVC_HEADER_BYTES);
+    IvcStep {
+        app_exe_commit,
+        app_vm_commit,
+        num_steps,
+        prev_state: Some(prev_state),
+    }
+}
+
+impl IvcStep {
+    /// Reveals the IVC header and the new `state`, which is zero padded to a multiple of 4 bytes.
+    /// The state must fit in the user public values after the header.
+    pub fn finish(self, state: &[u8]) {
+        let header = self
+            .app_exe_commit
+            .into_iter()
+            .chain(self.app_vm_commit)
+            .chain([self.num_steps]);
+        for (i, x) in header.enumerate() {
+            reveal_u32(x, i);
+        }
+        for (i, chunk) in state.chunks(4).enumerate() {
+            let mut bytes = [0u8; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            reveal_u32(u32::from_le_bytes(bytes), IVC_HEADER_BYTES / 4 + i);
+        }
+    }
+}
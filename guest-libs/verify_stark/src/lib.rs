@@ -1,11 +1,24 @@
+extern crate alloc;
+
 #[cfg(not(target_os = "zkvm"))]
 pub mod host;
+pub mod ivc;
+
+/// Filename of the root verifier ASM generated by `cargo openvm build`. It is the filename in the
+/// hint key of functions defined without an explicit ASM file.
+pub const ROOT_VERIFIER_ASM_FILENAME: &str = "root_verifier.asm";
 
 /// Define a function that verifies an OpenVM Stark proof.
 /// To define this function, users need to specify the function name and an ASM file containing the
 /// assembly code for the verification(this ASM file can be generated by
 /// `Sdk.generate_root_verifier_asm` function). To specify the ASM file, users need to provide the
 /// parent folder and filename of the ASM file.
+///
+/// Alternatively, only the function name is specified and the ASM generated by `cargo openvm build`
+/// from the local aggregation keys is used, e.g. `define_verify_openvm_stark!(verify_stark)`. The
+/// filename in the hint key is then [ROOT_VERIFIER_ASM_FILENAME]. Passing
+/// `expected_hash = "<hash>"` additionally fails the build if the local aggregation keys are not
+/// the ones with the given hash, which is printed by `cargo openvm build`.
 /// To call this function:
 /// 1. users need to provide `app_exe_commit`/`app_vm_commit`/`user_pvs`(user public values) as the
 ///    arguments. CAREFUL: `app_exe_commit`/`app_vm_commit` are in u32 and are interpreted as native
@@ -17,6 +30,35 @@ pub mod host;
 ///    `openvm::host::compute_hint_key_for_verify_openvm_stark` to compute the hint key.
 #[macro_export]
 macro_rules! define_verify_openvm_stark {
+    ($fn_name: ident) => {
+        $crate::define_verify_openvm_stark!(
+            $fn_name,
+            env!(
+                "OPENVM_ROOT_VERIFIER_ASM_DIR",
+                "No root verifier ASM was generated for this build. Build the guest with `cargo openvm build` after generating the aggregation keys with `cargo openvm setup`, or pass an ASM folder and filename to `define_verify_openvm_stark!`."
+            ),
+            "root_verifier.asm"
+        );
+    };
+    ($fn_name: ident, expected_hash = $hash: literal) => {
+        const _: () = assert!(
+            $crate::__str_eq(
+                env!(
+                    "OPENVM_ROOT_VERIFIER_HASH",
+                    "No root verifier ASM was generated for this build. Build the guest with `cargo openvm build` after generating the aggregation keys with `cargo openvm setup`."
+                ),
+                $hash
+            ),
+            concat!(
+                "The guest expects the root verifier with hash ",
+                $hash,
+                ", but the local aggregation keys generate the root verifier with hash ",
+                env!("OPENVM_ROOT_VERIFIER_HASH"),
+                ". Build with the aggregation keys the guest was written for, or update the expected hash."
+            )
+        );
+        $crate::define_verify_openvm_stark!($fn_name);
+    };
     ($fn_name: ident, $asm_folder: expr, $asm_filename: literal) => {
         pub fn $fn_name(app_exe_commit: &[u32; 8], app_vm_commit: &[u32; 8], user_pvs: &[u8]) {
             // The memory location for the start of the heap.
@@ -56,3 +98,19 @@ macro_rules! define_verify_openvm_stark {
         }
     };
 }
+
+#[doc(hidden)]
+pub const fn __str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -9,14 +9,31 @@ use openvm_sdk::{
     Sdk, StdIn,
 };
 use openvm_stark_sdk::config::FriParameters;
-use openvm_verify_stark::host::{
-    compute_hint_key_for_verify_openvm_stark, encode_proof_to_kv_store_value,
+use openvm_verify_stark::{
+    host::{compute_hint_key_for_verify_openvm_stark, encode_proof_to_kv_store_value},
+    ivc::IVC_HEADER_BYTES,
 };
 
 const LEAF_LOG_BLOWUP: usize = 2;
 const INTERNAL_LOG_BLOWUP: usize = 3;
 const ROOT_LOG_BLOWUP: usize = 4;
 
+fn agg_config_for_test(max_num_user_public_values: usize) -> AggregationConfig {
+    AggregationConfig {
+        max_num_user_public_values,
+        leaf_fri_params: FriParameters::new_for_testing(LEAF_LOG_BLOWUP),
+        internal_fri_params: FriParameters::new_for_testing(INTERNAL_LOG_BLOWUP),
+        root_fri_params: FriParameters::new_for_testing(ROOT_LOG_BLOWUP),
+        profiling: false,
+        compiler_options: CompilerOptions {
+            enable_cycle_tracker: true,
+            ..Default::default()
+        },
+        root_max_constraint_degree: (1 << ROOT_LOG_BLOWUP) + 1,
+        extra_app_fri_params: vec![],
+    }
+}
+
 #[test]
 fn test_verify_openvm_stark_e2e() -> Result<()> {
     const ASM_FILENAME: &str = "root_verifier.asm";
@@ -44,18 +61,7 @@ fn test_verify_openvm_stark_e2e() -> Result<()> {
     let exe_commit = app_commit.app_exe_commit.to_u32_digest();
     let vm_commit = app_commit.app_vm_commit.to_u32_digest();
 
-    let agg_pk = AggProvingKey::keygen(AggregationConfig {
-        max_num_user_public_values: DEFAULT_MAX_NUM_PUBLIC_VALUES,
-        leaf_fri_params: FriParameters::new_for_testing(LEAF_LOG_BLOWUP),
-        internal_fri_params: FriParameters::new_for_testing(INTERNAL_LOG_BLOWUP),
-        root_fri_params: FriParameters::new_for_testing(ROOT_LOG_BLOWUP),
-        profiling: false,
-        compiler_options: CompilerOptions {
-            enable_cycle_tracker: true,
-            ..Default::default()
-        },
-        root_max_constraint_degree: (1 << ROOT_LOG_BLOWUP) + 1,
-    })?;
+    let agg_pk = AggProvingKey::keygen(agg_config_for_test(DEFAULT_MAX_NUM_PUBLIC_VALUES))?;
     let _ = sdk.set_agg_pk(agg_pk);
     let asm = sdk.generate_root_verifier_asm();
     let asm_path = format!(
@@ -90,3 +96,39 @@ fn test_verify_openvm_stark_e2e() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_ivc_counter_e2e() -> Result<()> {
+    const ASM_FILENAME: &str = "root_verifier.asm";
+    const NUM_PUBLIC_VALUES: usize = IVC_HEADER_BYTES + 4;
+    let vm_config = SdkVmConfig::builder()
+        .system(SdkSystemConfig {
+            config: SystemConfig::default().with_public_values(NUM_PUBLIC_VALUES),
+        })
+        .rv32i(Default::default())
+        .rv32m(Default::default())
+        .io(Default::default())
+        .native(Default::default())
+        .build();
+    let fri_params = FriParameters::new_for_testing(LEAF_LOG_BLOWUP);
+    let app_config = AppConfig::new_with_leaf_fri_params(fri_params, vm_config, fri_params);
+    let sdk = Sdk::new(app_config)?.with_agg_config(agg_config_for_test(NUM_PUBLIC_VALUES));
+
+    let mut pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    pkg_dir.push("examples/ivc_counter");
+    std::fs::write(pkg_dir.join(ASM_FILENAME), sdk.generate_root_verifier_asm())?;
+    let step_elf = sdk.build(Default::default(), &pkg_dir, &None, None)?;
+
+    let mut prover = sdk.ivc_prover(step_elf, ASM_FILENAME)?;
+    for delta in [3u32, 4, 5] {
+        let mut stdin = StdIn::default();
+        stdin.write(&delta);
+        prover.prove_step(stdin)?;
+    }
+    let proof = prover.last_proof().unwrap();
+    let pvs = Sdk::verify_ivc_proof(&sdk.agg_pk().get_agg_vk(), prover.app_commit(), proof)?;
+    assert_eq!(pvs.num_steps, 3);
+    assert_eq!(pvs.state[..4], 12u32.to_le_bytes());
+
+    Ok(())
+}